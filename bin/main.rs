@@ -21,6 +21,11 @@ use exclusion_zone::tile_type::lava::LavaSettings;
 
 mod visualizer;
 
+// measures the memory of each generation stage
+#[cfg(feature = "memory_stats")]
+#[global_allocator]
+static GLOBAL: exclusion_zone::generator::memory::TrackingAllocator = exclusion_zone::generator::memory::TrackingAllocator;
+
 fn main() {
     struct MyRobot(Robot);
 
@@ -77,6 +82,9 @@ fn main() {
     let world = generator.gen();
 
     visualizer::save_world_image(&world.0, (0, 0), "img.png", 4);
+    if let Err(e) = visualizer::annotations::save_annotations(&world.0, world.1, "img.png", 4) {
+        println!("{e}");
+    }
 
     // match generator.save("world", world) {
     //     Ok(_) => {}
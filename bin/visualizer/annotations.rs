@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+use robotics_lib::world::tile::*;
+
+use exclusion_zone::generator::Position;
+
+/// A notable feature of the world, placed at the center of its tile in the exported image
+struct Marker {
+    kind: &'static str,
+    row: usize,
+    col: usize,
+}
+
+/// Saves, next to the image saved by `save_world_image`, a JSON file with the pixel coordinates of the notable features
+/// of the world: the robot spawn, the banks, the markets, the teleports and the city centers, so that external viewers
+/// can draw markers over the image.
+///
+/// The file has the name of the image with the `.json` extension and looks like:
+///
+/// ```json
+/// {"image":"img.png","tile_size":4,"width":4000,"height":4000,"markers":[{"kind":"robot_spawn","x":2,"y":6,"row":1,"col":0}]}
+/// ```
+///
+/// Each street network is a city, its center is the street tile nearest to the mean position of the network.
+///
+/// The robot spawn is the `(row, col)` spawn point of the `GenResult`.
+pub fn save_annotations(tiles: &[Vec<Tile>], bot_position: (usize, usize), image_file_name: &str, tile_size: usize) -> Result<(), String> {
+    let image = Path::new(image_file_name);
+    let spawn = Position::from_spawn(bot_position);
+    let mut markers = vec![Marker {
+        kind: "robot_spawn",
+        row: spawn.row(),
+        col: spawn.col(),
+    }];
+
+    for (row, tiles_row) in tiles.iter().enumerate() {
+        for (col, tile) in tiles_row.iter().enumerate() {
+            let kind = match (&tile.tile_type, &tile.content) {
+                | (TileType::Teleport(_), _) => "teleport",
+                | (_, Content::Bank(_)) => "bank",
+                | (_, Content::Market(_)) => "market",
+                | _ => continue,
+            };
+            markers.push(Marker { kind, row, col });
+        }
+    }
+    markers.extend(city_centers(tiles));
+
+    let side = tiles.len() * tile_size;
+    let center = tile_size / 2;
+    let markers: Vec<String> = markers
+        .iter()
+        .map(|m| {
+            format!(
+                r#"{{"kind":"{}","x":{},"y":{},"row":{},"col":{}}}"#,
+                m.kind,
+                m.col * tile_size + center,
+                m.row * tile_size + center,
+                m.row,
+                m.col
+            )
+        })
+        .collect();
+    let image_name = image.file_name().and_then(|name| name.to_str()).unwrap_or(image_file_name).replace('\\', "\\\\").replace('"', "\\\"");
+    let json = format!(r#"{{"image":"{image_name}","tile_size":{tile_size},"width":{side},"height":{side},"markers":[{}]}}"#, markers.join(","));
+
+    let file_name = image.with_extension("json");
+    fs::write(&file_name, json).map_err(|e| format!("Unable to save the annotations {}: {e}", file_name.display()))
+}
+
+// the street tile nearest to the mean position of each street network
+fn city_centers(tiles: &[Vec<Tile>]) -> Vec<Marker> {
+    let size = tiles.len();
+    let mut visited = vec![vec![false; size]; size];
+    let mut centers = Vec::new();
+
+    for row in 0..size {
+        for col in 0..size {
+            if visited[row][col] || tiles[row][col].tile_type != TileType::Street {
+                continue;
+            }
+
+            // collect the network with a breadth first search over the streets sharing a side
+            let mut network = Vec::new();
+            let mut queue = VecDeque::from([(row, col)]);
+            visited[row][col] = true;
+            while let Some((r, c)) = queue.pop_front() {
+                network.push((r, c));
+                for (nr, nc) in [(r.wrapping_sub(1), c), (r + 1, c), (r, c.wrapping_sub(1)), (r, c + 1)] {
+                    if nr < size && nc < size && !visited[nr][nc] && tiles[nr][nc].tile_type == TileType::Street {
+                        visited[nr][nc] = true;
+                        queue.push_back((nr, nc));
+                    }
+                }
+            }
+
+            let mean_row = network.iter().map(|(r, _)| r).sum::<usize>() as f64 / network.len() as f64;
+            let mean_col = network.iter().map(|(_, c)| c).sum::<usize>() as f64 / network.len() as f64;
+            let distance = |&(r, c): &(usize, usize)| (r as f64 - mean_row).powi(2) + (c as f64 - mean_col).powi(2);
+            if let Some(&(row, col)) = network.iter().min_by(|a, b| distance(a).total_cmp(&distance(b))) {
+                centers.push(Marker {
+                    kind: "city_center",
+                    row,
+                    col,
+                });
+            }
+        }
+    }
+    centers
+}
@@ -58,3 +58,105 @@ pub(crate) mod content {
     /// Crate color (birch wood light brown)
     pub(crate) const CRATE: Rgb<u8> = Rgb([228, 199, 148]);
 }
+
+pub(crate) mod biome {
+    use image::Rgb;
+
+    /// Swamp grass color (murky olive green)
+    pub(crate) const SWAMP_GRASS: Rgb<u8> = Rgb([85, 107, 47]);
+    /// Swamp hill color (wet peat brown)
+    pub(crate) const SWAMP_HILL: Rgb<u8> = Rgb([92, 74, 48]);
+    /// Tundra grass color (lichen sage green)
+    pub(crate) const TUNDRA_GRASS: Rgb<u8> = Rgb([156, 175, 136]);
+    /// Tundra hill color (frozen soil grey brown)
+    pub(crate) const TUNDRA_HILL: Rgb<u8> = Rgb([139, 126, 102]);
+}
+
+pub(crate) mod palette {
+    use image::Rgb;
+
+    /// Winter deep water color (cold navy)
+    pub(crate) const WINTER_DEEP_WATER: Rgb<u8> = Rgb([16, 38, 78]);
+    /// Winter shallow water color (pale ice blue)
+    pub(crate) const WINTER_SHALLOW_WATER: Rgb<u8> = Rgb([168, 204, 224]);
+    /// Winter sand color (frosted beige)
+    pub(crate) const WINTER_SAND: Rgb<u8> = Rgb([222, 218, 204]);
+    /// Winter grass color (snow covered white)
+    pub(crate) const WINTER_GRASS: Rgb<u8> = Rgb([232, 238, 242]);
+    /// Winter hill color (frozen soil grey)
+    pub(crate) const WINTER_HILL: Rgb<u8> = Rgb([176, 168, 160]);
+    /// Winter tree color (snowy pine green)
+    pub(crate) const WINTER_TREE: Rgb<u8> = Rgb([38, 72, 62]);
+    /// Winter bush color (frosted sage)
+    pub(crate) const WINTER_BUSH: Rgb<u8> = Rgb([92, 112, 104]);
+
+    /// Autumn deep water color (dark teal)
+    pub(crate) const AUTUMN_DEEP_WATER: Rgb<u8> = Rgb([18, 44, 72]);
+    /// Autumn shallow water color (muddy blue)
+    pub(crate) const AUTUMN_SHALLOW_WATER: Rgb<u8> = Rgb([70, 108, 128]);
+    /// Autumn sand color (damp ochre)
+    pub(crate) const AUTUMN_SAND: Rgb<u8> = Rgb([214, 188, 128]);
+    /// Autumn grass color (dry golden)
+    pub(crate) const AUTUMN_GRASS: Rgb<u8> = Rgb([188, 152, 64]);
+    /// Autumn hill color (rust brown)
+    pub(crate) const AUTUMN_HILL: Rgb<u8> = Rgb([138, 80, 36]);
+    /// Autumn tree color (maple orange)
+    pub(crate) const AUTUMN_TREE: Rgb<u8> = Rgb([196, 78, 20]);
+    /// Autumn bush color (burgundy)
+    pub(crate) const AUTUMN_BUSH: Rgb<u8> = Rgb([122, 38, 36]);
+
+    /// Night color (midnight blue), blended over every color
+    pub(crate) const NIGHT: Rgb<u8> = Rgb([8, 12, 42]);
+}
+
+pub(crate) mod decor {
+    use image::Rgb;
+
+    /// Tufts color (dark meadow green)
+    pub(crate) const TUFTS: Rgb<u8> = Rgb([72, 140, 40]);
+    /// Rubble color (concrete grey)
+    pub(crate) const RUBBLE: Rgb<u8> = Rgb([120, 115, 105]);
+    /// Cracks color (scorched black brown)
+    pub(crate) const CRACKS: Rgb<u8> = Rgb([45, 35, 30]);
+}
+
+pub(crate) mod trace {
+    use image::Rgb;
+
+    /// Path color (magenta, visible on every tile type)
+    pub(crate) const PATH: Rgb<u8> = Rgb([255, 0, 255]);
+    /// Start color (signal green)
+    pub(crate) const START: Rgb<u8> = Rgb([0, 200, 0]);
+    /// End color (signal red)
+    pub(crate) const END: Rgb<u8> = Rgb([220, 0, 0]);
+}
+
+pub(crate) mod heatmap {
+    use image::Rgb;
+
+    /// Background color the world is faded to (charcoal)
+    pub(crate) const BACKGROUND: Rgb<u8> = Rgb([40, 40, 40]);
+    /// Accepted color, the content was placed on the tile (signal green)
+    pub(crate) const ACCEPTED: Rgb<u8> = Rgb([0, 230, 0]);
+    /// Considered color, the tile could take the content but it was not placed (amber)
+    pub(crate) const CONSIDERED: Rgb<u8> = Rgb([255, 191, 0]);
+    /// Rejected color, every attempt on the tile was refused (signal red)
+    pub(crate) const REJECTED: Rgb<u8> = Rgb([230, 0, 0]);
+}
+
+pub(crate) mod elevation {
+    use image::Rgb;
+
+    /// Hypsometric tints, the color at each elevation between the lowest, 0, and the highest, 1, blended in between:
+    /// the deep blue of the depths, the light blue of the shallows, the sand of the coast, the green of the plains, the
+    /// ochre of the hills, the brown of the mountains and the white of the peaks
+    pub(crate) const HYPSOMETRIC: [(f64, Rgb<u8>); 7] = [
+        (0.0, Rgb([8, 48, 107])),
+        (0.2, Rgb([107, 174, 214])),
+        (0.3, Rgb([238, 214, 175])),
+        (0.45, Rgb([86, 148, 60])),
+        (0.65, Rgb([196, 164, 84])),
+        (0.82, Rgb([120, 80, 50])),
+        (1.0, Rgb([250, 249, 246])),
+    ];
+}
@@ -0,0 +1,78 @@
+use chrono::Utc;
+use debug_print::debug_println;
+use image::{ImageFormat, Rgb, RgbImage};
+
+use super::{blend, colors, RenderError, MAX_IMAGE_BYTES};
+
+/// The colors the elevation is drawn with
+#[allow(dead_code)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Colormap {
+    /// from black, the lowest elevation, to white, the highest
+    #[default]
+    Grayscale,
+    /// the tints of the relief maps, from the deep blue of the lowest elevation through the green of the plains and
+    /// the brown of the mountains to the white of the highest peaks
+    Hypsometric,
+}
+
+/// Saves the raw elevation as a PNG image, one pixel per sample, to inspect the heightfield while tuning the
+/// `NoiseSettings` without classifying it in tile types first.
+///
+/// The elevation is stretched between its lowest and its highest value, so the whole range of colors is used whatever
+/// the noise; it can be the `elevation_map` of the generation artifacts or a `preview_elevation`.
+///
+/// # Errors
+///
+/// Returns an error if the elevation is empty, or if the image is too large or cannot be saved.
+///
+/// # Examples
+///
+/// ```ignore
+/// let elevation = preview_elevation(&NoiseSettings::from_seed(42), 512);
+/// save_elevation_image(&elevation, "elevation.png", Colormap::Hypsometric)?;
+/// ```
+#[allow(dead_code)]
+pub fn save_elevation_image<T: Copy + Into<f64>>(elevation: &[Vec<T>], file_name: &str, colormap: Colormap) -> Result<(), RenderError> {
+    debug_println!("Start: saving elevation as png");
+    let start = Utc::now();
+    let side = elevation.len();
+    if side == 0 {
+        return Err(RenderError::Empty);
+    }
+    let bytes = (side * side * 3) as u64;
+    if bytes > MAX_IMAGE_BYTES {
+        return Err(RenderError::TooLarge { side, bytes });
+    }
+
+    let (min, max) = elevation
+        .iter()
+        .flatten()
+        .fold((f64::MAX, f64::MIN), |(min, max), &value| (min.min(value.into()), max.max(value.into())));
+    let range = (max - min).max(f64::EPSILON);
+    let img = RgbImage::from_fn(side as u32, side as u32, |x, y| {
+        let value = elevation[y as usize].get(x as usize).map_or(min, |&value| value.into());
+        elevation_color((value - min) / range, colormap)
+    });
+
+    img.save_with_format(file_name, ImageFormat::Png).map_err(|e| RenderError::Save(e.to_string()))?;
+    debug_println!("Done: saving elevation as png {}ms", (Utc::now() - start).num_milliseconds());
+    Ok(())
+}
+
+// the color of the elevation, between 0 for the lowest and 1 for the highest
+#[inline(always)]
+fn elevation_color(level: f64, colormap: Colormap) -> Rgb<u8> {
+    match colormap {
+        | Colormap::Grayscale => {
+            let gray = (level * 255.0).round() as u8;
+            Rgb([gray, gray, gray])
+        }
+        | Colormap::Hypsometric => {
+            let stops = &colors::elevation::HYPSOMETRIC;
+            let upper = stops.iter().position(|(stop, _)| *stop >= level).unwrap_or(stops.len() - 1).max(1);
+            let ((low, low_color), (high, high_color)) = (stops[upper - 1], stops[upper]);
+            blend(low_color, high_color, ((level - low) / (high - low)).clamp(0.0, 1.0) as f32)
+        }
+    }
+}
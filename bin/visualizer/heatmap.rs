@@ -0,0 +1,86 @@
+use chrono::Utc;
+use debug_print::debug_println;
+use image::{ImageFormat, Rgb};
+
+use robotics_lib::world::tile::*;
+
+use exclusion_zone::generator::artifacts::GenerationArtifacts;
+use exclusion_zone::generator::spawn_heatmap::PlacementAttempts;
+use exclusion_zone::generator::Spawnables;
+
+use super::{blend, colors, render_world, RenderError, RenderOptions, RenderedImage};
+
+// how much the world is faded to the background, so that the attempts stand out
+const FADE: f32 = 0.6;
+// the opacity of the tiles considered once, the most considered tile is opaque
+const MIN_ALPHA: f32 = 0.35;
+
+/// Saves the world as a PNG image like `save_world_image_with_options`, faded, with the placement attempts of the
+/// spawn stage of the given spawnable drawn over it, to see why a content is missing from a part of the world while
+/// tuning its settings.
+///
+/// Each tile the spawner looked at is colored by the outcome of its attempts:
+/// - green, the content was placed on the tile;
+/// - amber, the tile could take the content but the spawner did not place it there, it was left out by chance;
+/// - red, every attempt was refused, the tile type cannot hold the content, the placement masks rule the tile out or
+///   the tile already held content kept by the conflict policy.
+///
+/// The more attempts on a tile, the more opaque its color; the tiles never looked at are left faded. The heatmaps
+/// are recorded only by a generator built with the `spawn_telemetry` feature.
+///
+/// # Errors
+///
+/// Returns an error if the artifacts hold no heatmap of the spawnable, or if the image is too large, empty or cannot
+/// be saved.
+///
+/// # Examples
+///
+/// ```ignore
+/// // with exclusion_zone_world_generator = { features = ["spawn_telemetry"] }
+/// let (world, artifacts) = WorldGenerator::default(500).gen_with_artifacts();
+/// render_spawn_heatmap(&world.0, &artifacts, Spawnables::Bank, "banks.png", &RenderOptions::default())?;
+/// ```
+#[allow(dead_code)]
+pub fn render_spawn_heatmap(tiles: &[Vec<Tile>], artifacts: &GenerationArtifacts, spawnable: Spawnables, file_name: &str, options: &RenderOptions) -> Result<RenderedImage, RenderError> {
+    debug_println!("Start: saving spawn heatmap of {:?} as png", spawnable);
+    let start = Utc::now();
+    let heatmap = match artifacts.spawn_heatmaps.iter().find(|(other, _)| *other == spawnable) {
+        | Some((_, heatmap)) => heatmap,
+        | None => return Err(RenderError::NoHeatmap(spawnable)),
+    };
+
+    let (mut img, rendered) = render_world(tiles, (0, 0), options)?;
+    let size = tiles.len();
+    // the overlay of each tile, computed once for all its pixels
+    let peak = (heatmap.peaks().considered as f32).ln_1p();
+    let overlays: Vec<Option<(Rgb<u8>, f32)>> = (0..size * size).map(|i| overlay(heatmap.get(i / size, i % size), peak)).collect();
+
+    let tile_of = |pixel: u32| (((pixel as f64 + 0.5) / rendered.tile_size) as usize).min(size.saturating_sub(1));
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let faded = blend(*pixel, colors::heatmap::BACKGROUND, FADE);
+        *pixel = match overlays[tile_of(y) * size + tile_of(x)] {
+            | Some((color, alpha)) => blend(faded, color, alpha),
+            | None => faded,
+        };
+    }
+
+    img.save_with_format(file_name, ImageFormat::Png).map_err(|e| RenderError::Save(e.to_string()))?;
+    debug_println!("Done: saving spawn heatmap as png {}ms", (Utc::now() - start).num_milliseconds());
+    Ok(rendered)
+}
+
+// the color and the opacity of the attempts on a tile, None if the spawner never looked at it
+fn overlay(attempts: PlacementAttempts, peak: f32) -> Option<(Rgb<u8>, f32)> {
+    if attempts.is_untouched() {
+        return None;
+    }
+    let color = if attempts.accepted > 0 {
+        colors::heatmap::ACCEPTED
+    } else if attempts.rejected == attempts.considered {
+        colors::heatmap::REJECTED
+    } else {
+        colors::heatmap::CONSIDERED
+    };
+    let share = if peak > 0.0 { (attempts.considered as f32).ln_1p() / peak } else { 1.0 };
+    Some((color, MIN_ALPHA + (1.0 - MIN_ALPHA) * share))
+}
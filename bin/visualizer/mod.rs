@@ -2,9 +2,19 @@ use chrono::Utc;
 use debug_print::debug_println;
 use image::{ImageFormat, Rgb, RgbImage};
 
+use robotics_lib::world::coordinates::Coordinate;
+use robotics_lib::world::environmental_conditions::{EnvironmentalConditions, WeatherType};
 use robotics_lib::world::tile::*;
 
+use exclusion_zone::generator::decor::{Decor, DecorLayer};
+use exclusion_zone::generator::hex::{HexCoordinate, HexWorld};
+use exclusion_zone::generator::Spawnables;
+
+pub mod annotations;
 mod colors;
+pub mod elevation;
+pub mod heatmap;
+pub mod trace;
 
 /// Fill random pixels or all based on number of content with the appropriate color
 #[inline(always)]
@@ -21,16 +31,16 @@ fn checkerboard_pattern(p: &mut Vec<Vec<Rgb<u8>>>, c: Rgb<u8>) {
     }
 }
 
-/// Associates each tile with its color
+/// Associates each tile with its color, the water, the sand, the grass and the hills take the colors of the palette
 #[inline(always)]
-fn choose_tile_color(t: &TileType) -> Rgb<u8> {
+fn choose_tile_color(t: &TileType, palette: &Palette) -> Rgb<u8> {
     match *t {
-        | TileType::DeepWater => colors::tile::DEEP_WATER,
-        | TileType::ShallowWater => colors::tile::SHALLOW_WATER,
-        | TileType::Sand => colors::tile::SAND,
-        | TileType::Grass => colors::tile::GRASS,
+        | TileType::DeepWater => palette.deep_water,
+        | TileType::ShallowWater => palette.shallow_water,
+        | TileType::Sand => palette.sand,
+        | TileType::Grass => palette.grass,
         | TileType::Street => colors::tile::STREET,
-        | TileType::Hill => colors::tile::HILL,
+        | TileType::Hill => palette.hill,
         | TileType::Mountain => colors::tile::MOUNTAIN,
         | TileType::Snow => colors::tile::SNOW,
         | TileType::Lava => colors::tile::LAVA,
@@ -39,61 +49,548 @@ fn choose_tile_color(t: &TileType) -> Rgb<u8> {
     }
 }
 
-/// Associates each tile content with its color
+/// The color of the tile type, Grass and Hill take the colors of the biome if any, over the ones of the palette
+#[inline(always)]
+fn biome_tile_color(t: &TileType, biome: Option<&BiomeColors>, palette: &Palette) -> Rgb<u8> {
+    match (t, biome) {
+        | (TileType::Grass, Some(biome)) => biome.grass,
+        | (TileType::Hill, Some(biome)) => biome.hill,
+        | _ => choose_tile_color(t, palette),
+    }
+}
+
+/// Associates each tile content with its color, the trees, the bushes and the water take the colors of the palette
 #[inline(always)]
-fn set_content_color(c: &Content, p: &mut Vec<Vec<Rgb<u8>>>) {
+fn content_color(c: &Content, palette: &Palette) -> Rgb<u8> {
     match *c {
-        | Content::Rock(_) => checkerboard_pattern(p, colors::content::ROCK),
-        | Content::Tree(_) => checkerboard_pattern(p, colors::content::TREE),
-        | Content::Garbage(_) => checkerboard_pattern(p, colors::BLACK),
-        | Content::Fire => checkerboard_pattern(p, colors::content::FIRE),
-        | Content::Coin(_) => checkerboard_pattern(p, colors::content::COIN),
-        | Content::Bin(_) => checkerboard_pattern(p, colors::content::BIN),
-        | Content::Crate(_) => checkerboard_pattern(p, colors::content::CRATE),
-        | Content::Bank(_) => checkerboard_pattern(p, colors::content::BANK),
-        | Content::Water(_) => checkerboard_pattern(p, colors::tile::SHALLOW_WATER),
-        | Content::Market(_) => checkerboard_pattern(p, colors::content::MARKET),
-        | Content::Fish(_) => checkerboard_pattern(p, colors::content::FISH),
-        | Content::Building => checkerboard_pattern(p, colors::content::BUILDING),
-        | Content::Bush(_) => checkerboard_pattern(p, colors::content::BUSH),
-        | Content::JollyBlock(_) => checkerboard_pattern(p, colors::content::JOLLYBLOCK),
-        | Content::Scarecrow => checkerboard_pattern(p, colors::content::SCARECROW),
-        | _ => checkerboard_pattern(p, colors::BLACK),
-    }
-}
-
-fn create_image_from_tiles(tiles: &[Vec<Tile>], _bot_position: (usize, usize), tile_size: usize) -> RgbImage {
+        | Content::Rock(_) => colors::content::ROCK,
+        | Content::Tree(_) => palette.tree,
+        | Content::Garbage(_) => colors::BLACK,
+        | Content::Fire => colors::content::FIRE,
+        | Content::Coin(_) => colors::content::COIN,
+        | Content::Bin(_) => colors::content::BIN,
+        | Content::Crate(_) => colors::content::CRATE,
+        | Content::Bank(_) => colors::content::BANK,
+        | Content::Water(_) => palette.shallow_water,
+        | Content::Market(_) => colors::content::MARKET,
+        | Content::Fish(_) => colors::content::FISH,
+        | Content::Building => colors::content::BUILDING,
+        | Content::Bush(_) => palette.bush,
+        | Content::JollyBlock(_) => colors::content::JOLLYBLOCK,
+        | Content::Scarecrow => colors::content::SCARECROW,
+        | _ => colors::BLACK,
+    }
+}
+
+/// Fills the tile with the checkerboard of the color of its content
+#[inline(always)]
+fn set_content_color(c: &Content, p: &mut Vec<Vec<Rgb<u8>>>, palette: &Palette) {
+    checkerboard_pattern(p, content_color(c, palette));
+}
+
+/// Draws the marks of the decor over the tile, leaving most of the tile color visible
+#[inline(always)]
+fn set_decor_pattern(decor: Decor, p: &mut [Vec<Rgb<u8>>]) {
+    for (row, pixels) in p.iter_mut().enumerate() {
+        for (col, pixel) in pixels.iter_mut().enumerate() {
+            let (marked, color) = match decor {
+                | Decor::Tufts => ((row * 3 + col) % 5 == 0, colors::decor::TUFTS),
+                | Decor::Rubble => ((row * 3 + col * 7) % 5 == 0, colors::decor::RUBBLE),
+                | Decor::Cracks => (row == col, colors::decor::CRACKS),
+            };
+            if marked {
+                *pixel = color;
+            }
+        }
+    }
+}
+
+/// The color of the tile seen from afar, the mean of its tile type and of its content
+#[inline(always)]
+fn mean_tile_color(tile: &Tile, biome: Option<&BiomeColors>, palette: &Palette) -> [f32; 3] {
+    let base = biome_tile_color(&tile.tile_type, biome, palette).0;
+    let content = match tile.content {
+        | Content::None => base,
+        | _ => content_color(&tile.content, palette).0,
+    };
+    [0, 1, 2].map(|i| (base[i] as f32 + content[i] as f32) / 2.0)
+}
+
+// the largest image the encoder accepts, in bytes of RGB pixels
+const MAX_IMAGE_BYTES: u64 = i32::MAX as u64;
+
+/// The colors of the tile types which change with the biome
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BiomeColors {
+    /// the color of the Grass tiles
+    pub grass: Rgb<u8>,
+    /// the color of the Hill tiles
+    pub hill: Rgb<u8>,
+}
+
+#[allow(dead_code)]
+impl BiomeColors {
+    /// The colors of the world without biomes
+    pub const MEADOW: BiomeColors = BiomeColors {
+        grass: colors::tile::GRASS,
+        hill: colors::tile::HILL,
+    };
+    /// Murky greens and browns
+    pub const SWAMP: BiomeColors = BiomeColors {
+        grass: colors::biome::SWAMP_GRASS,
+        hill: colors::biome::SWAMP_HILL,
+    };
+    /// Pale, cold greens and browns
+    pub const TUNDRA: BiomeColors = BiomeColors {
+        grass: colors::biome::TUNDRA_GRASS,
+        hill: colors::biome::TUNDRA_HILL,
+    };
+}
+
+/// The colors of the terrain, the water and the vegetation, themed after a season or the time of the day so that the
+/// image of a world matches its weather; the other tile types and content keep their colors
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    /// the color of the DeepWater tiles
+    pub deep_water: Rgb<u8>,
+    /// the color of the ShallowWater tiles and of the Water content
+    pub shallow_water: Rgb<u8>,
+    /// the color of the Sand tiles
+    pub sand: Rgb<u8>,
+    /// the color of the Grass tiles, unless a biome colors them
+    pub grass: Rgb<u8>,
+    /// the color of the Hill tiles, unless a biome colors them
+    pub hill: Rgb<u8>,
+    /// the color of the trees
+    pub tree: Rgb<u8>,
+    /// the color of the bushes
+    pub bush: Rgb<u8>,
+    /// the color blended over every pixel of the image and its opacity, between 0 and 1, `None` to keep the colors
+    pub tint: Option<(Rgb<u8>, f32)>,
+}
+
+impl Default for Palette {
+    /// The colors of the summer, the ones of the images without a theme
+    fn default() -> Self {
+        Palette {
+            deep_water: colors::tile::DEEP_WATER,
+            shallow_water: colors::tile::SHALLOW_WATER,
+            sand: colors::tile::SAND,
+            grass: colors::tile::GRASS,
+            hill: colors::tile::HILL,
+            tree: colors::content::TREE,
+            bush: colors::content::BUSH,
+            tint: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Palette {
+    /// Snow covered grass, frozen water and dark pines
+    pub fn winter() -> Self {
+        Palette {
+            deep_water: colors::palette::WINTER_DEEP_WATER,
+            shallow_water: colors::palette::WINTER_SHALLOW_WATER,
+            sand: colors::palette::WINTER_SAND,
+            grass: colors::palette::WINTER_GRASS,
+            hill: colors::palette::WINTER_HILL,
+            tree: colors::palette::WINTER_TREE,
+            bush: colors::palette::WINTER_BUSH,
+            tint: None,
+        }
+    }
+
+    /// Golden grass, muddy water and orange foliage
+    pub fn autumn() -> Self {
+        Palette {
+            deep_water: colors::palette::AUTUMN_DEEP_WATER,
+            shallow_water: colors::palette::AUTUMN_SHALLOW_WATER,
+            sand: colors::palette::AUTUMN_SAND,
+            grass: colors::palette::AUTUMN_GRASS,
+            hill: colors::palette::AUTUMN_HILL,
+            tree: colors::palette::AUTUMN_TREE,
+            bush: colors::palette::AUTUMN_BUSH,
+            tint: None,
+        }
+    }
+
+    /// The colors of the summer darkened by a midnight blue, the content included
+    pub fn night() -> Self {
+        Palette {
+            tint: Some((colors::palette::NIGHT, 0.55)),
+            ..Palette::default()
+        }
+    }
+
+    /// Returns the palette of the current weather of the environmental conditions, the one the robot starts with in a
+    /// generated world: the winter for the snow, the autumn for the rain and the fog, the summer otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (world, spawn, environmental_conditions, ..) = generator.gen();
+    /// let options = RenderOptions {
+    ///     palette: Palette::from_conditions(&environmental_conditions),
+    ///     ..RenderOptions::default()
+    /// };
+    /// save_world_image_with_options(&world, spawn, "world.png", &options)?;
+    /// ```
+    pub fn from_conditions(conditions: &EnvironmentalConditions) -> Self {
+        match conditions.get_weather_condition() {
+            | WeatherType::TrentinoSnow => Palette::winter(),
+            | WeatherType::Rainy | WeatherType::Foggy => Palette::autumn(),
+            | WeatherType::Sunny | WeatherType::TropicalMonsoon => Palette::default(),
+        }
+    }
+
+    // the color of the pixel with the tint of the palette blended over it
+    #[inline(always)]
+    fn shade(&self, pixel: Rgb<u8>) -> Rgb<u8> {
+        self.tint.map_or(pixel, |(tint, alpha)| blend(pixel, tint, alpha))
+    }
+}
+
+/// The biome of each tile, to color the Grass and Hill tiles of each biome differently
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeMap {
+    /// the biome id of each tile, indexed as `ids[row][col]`, the index of the colors of the biome
+    pub ids: Vec<Vec<u16>>,
+    /// the colors of each biome, the ids without colors keep the default ones
+    pub colors: Vec<BiomeColors>,
+}
+
+#[allow(dead_code)]
+impl BiomeMap {
+    /// Creates a new instance of `BiomeMap` from the biome id of each tile and the colors of each id
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // a swamp in the west and a tundra in the east
+    /// let ids = (0..size).map(|_| (0..size).map(|col| if col < size / 2 { 0 } else { 1 }).collect()).collect();
+    /// let options = RenderOptions {
+    ///     biomes: Some(BiomeMap::new(ids, vec![BiomeColors::SWAMP, BiomeColors::TUNDRA])),
+    ///     ..RenderOptions::default()
+    /// };
+    /// save_world_image_with_options(&world, spawn, "biomes.png", &options)?;
+    /// ```
+    pub fn new(ids: Vec<Vec<u16>>, colors: Vec<BiomeColors>) -> Self {
+        BiomeMap { ids, colors }
+    }
+
+    /// Returns the colors of the biome of the tile, `None` out of the map or for an id without colors
+    pub fn colors_at(&self, row: usize, col: usize) -> Option<&BiomeColors> {
+        let id = *self.ids.get(row)?.get(col)?;
+        self.colors.get(id as usize)
+    }
+}
+
+/// Options of the rendering of the world image
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct RenderOptions {
+    /// the side, in pixels, of the square drawn for each tile
+    pub tile_size: usize,
+    /// the max side, in pixels, of the image, 16384 by default, `None` for no limit.
+    ///
+    /// When the world drawn with `tile_size` is larger, the tile size is reduced to fit; when even a pixel per tile
+    /// does not fit, the tiles are downsampled bilinearly.
+    pub max_side: Option<usize>,
+    /// the biome of each tile, `None` draws every biome with the same colors
+    pub biomes: Option<BiomeMap>,
+    /// the decor generated with the world, drawn under the content of the tiles at least 3 pixels wide, `None` for
+    /// plain tiles
+    pub decor: Option<DecorLayer>,
+    /// the colors of the terrain, the water and the vegetation, the ones of the summer by default
+    pub palette: Palette,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            tile_size: 4,
+            max_side: Some(16_384),
+            biomes: None,
+            decor: None,
+            palette: Palette::default(),
+        }
+    }
+}
+
+/// The scale the world image has been rendered at
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderedImage {
+    /// the side of the image, in pixels
+    pub side: usize,
+    /// the side of a tile, in pixels, less than 1 when the tiles have been downsampled
+    pub tile_size: f64,
+}
+
+/// Why the world image could not be rendered
+#[derive(Debug)]
+pub enum RenderError {
+    /// the image would take more memory than the encoder accepts, use a smaller `tile_size` or set a `max_side`
+    TooLarge {
+        side: usize,
+        bytes: u64,
+    },
+    /// the tile size or the max side is 0
+    Empty,
+    /// the tile is out of the world
+    OutOfWorld {
+        row: usize,
+        col: usize,
+    },
+    /// the generation recorded no heatmap for the spawnable, it is not in the spawn order or the generator was built
+    /// without the `spawn_telemetry` feature
+    NoHeatmap(Spawnables),
+    /// the image could not be saved
+    Save(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | RenderError::TooLarge { side, bytes } => write!(f, "the image of {side}x{side} pixels would take {bytes} bytes, more than the {MAX_IMAGE_BYTES} allowed"),
+            | RenderError::Empty => write!(f, "the image has no pixels, the tile size and the max side must be greater than 0"),
+            | RenderError::OutOfWorld { row, col } => write!(f, "the tile ({row}, {col}) is out of the world"),
+            | RenderError::NoHeatmap(spawnable) => write!(f, "no spawn heatmap of {spawnable:?}, enable the spawn_telemetry feature and spawn it"),
+            | RenderError::Save(e) => write!(f, "Error saving the image, {e}"),
+        }
+    }
+}
+
+// the color over the pixel with the given opacity
+#[inline(always)]
+fn blend(pixel: Rgb<u8>, color: Rgb<u8>, alpha: f32) -> Rgb<u8> {
+    Rgb([0, 1, 2].map(|i| (pixel[i] as f32 * (1.0 - alpha) + color[i] as f32 * alpha).round() as u8))
+}
+
+/// Draws the tile in the given position of the image
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+fn draw_tile(img: &mut RgbImage, y: usize, x: usize, tile: &Tile, tile_size: usize, biomes: Option<&BiomeMap>, decor: Option<&DecorLayer>, palette: &Palette) {
+    // set the base tile color as tile type color
+    let biome = biomes.and_then(|biomes| biomes.colors_at(y, x));
+    let mut pixels: Vec<Vec<Rgb<u8>>> = vec![vec![biome_tile_color(&tile.tile_type, biome, palette); tile_size]; tile_size];
+
+    // set the decor under the content, on smaller tiles it would hide the tile color
+    if let Some(decor) = decor.and_then(|decor| decor.get(y, x)).filter(|_| tile_size >= 3) {
+        set_decor_pattern(decor, &mut pixels);
+    }
+
+    // set the content color as checkerboard of the tile
+    if tile.content != Content::None {
+        set_content_color(&tile.content, &mut pixels, palette);
+    }
+
+    for my in 0..tile_size {
+        for mx in 0..tile_size {
+            img.put_pixel((x * tile_size + mx) as u32, (y * tile_size + my) as u32, palette.shade(pixels[my][mx]));
+        }
+    }
+}
+
+fn create_image_from_tiles(tiles: &[Vec<Tile>], _bot_position: (usize, usize), tile_size: usize, biomes: Option<&BiomeMap>, decor: Option<&DecorLayer>, palette: &Palette) -> RgbImage {
     // get the image final size
     let size: u32 = (tile_size * tiles.len()) as u32;
     let mut img: RgbImage = RgbImage::new(size, size);
 
     for (y, row) in tiles.iter().enumerate() {
         for (x, tile) in row.iter().enumerate() {
-            // set the base tile color as tile type color
-            let mut pixels: Vec<Vec<Rgb<u8>>> = vec![vec![choose_tile_color(&tile.tile_type); tile_size]; tile_size];
+            draw_tile(&mut img, y, x, tile, tile_size, biomes, decor, palette);
+        }
+    }
+    img
+}
 
-            // set the content color as checkerboard of the tile
-            if tile.content != Content::None {
-                set_content_color(&tile.content, &mut pixels);
-            }
+// draws each pixel as the bilinear interpolation of the colors of the tiles around its center, for worlds with more
+// tiles per side than the image has pixels
+fn create_downsampled_image(tiles: &[Vec<Tile>], side: usize, biomes: Option<&BiomeMap>, palette: &Palette) -> RgbImage {
+    let size = tiles.len();
+    let scale = size as f64 / side as f64;
+    let mut img: RgbImage = RgbImage::new(side as u32, side as u32);
 
-            for my in 0..tile_size {
-                for mx in 0..tile_size {
-                    img.put_pixel((x * tile_size + mx) as u32, (y * tile_size + my) as u32, pixels[my][mx]);
-                }
-            }
+    for y in 0..side {
+        // the center of the pixel in tile coordinates, between the centers of two rows of tiles
+        let row = ((y as f64 + 0.5) * scale - 0.5).clamp(0.0, (size - 1) as f64);
+        let (top, ty) = (row.floor() as usize, row.fract() as f32);
+        let bottom = (top + 1).min(size - 1);
+        for x in 0..side {
+            let col = ((x as f64 + 0.5) * scale - 0.5).clamp(0.0, (size - 1) as f64);
+            let (left, tx) = (col.floor() as usize, col.fract() as f32);
+            let right = (left + 1).min(size - 1);
+
+            let [a, b, c, d] = [(top, left), (top, right), (bottom, left), (bottom, right)].map(|(r, c)| mean_tile_color(&tiles[r][c], biomes.and_then(|biomes| biomes.colors_at(r, c)), palette));
+            let channel = |i: usize| {
+                let upper = a[i] + (b[i] - a[i]) * tx;
+                let lower = c[i] + (d[i] - c[i]) * tx;
+                (upper + (lower - upper) * ty).round() as u8
+            };
+            img.put_pixel(x as u32, y as u32, palette.shade(Rgb([channel(0), channel(1), channel(2)])));
         }
     }
     img
 }
 
 pub fn save_world_image(tiles: &[Vec<Tile>], bot_position: (usize, usize), file_name: &str, tile_size: usize) {
+    let options = RenderOptions {
+        tile_size,
+        max_side: None,
+        biomes: None,
+        decor: None,
+        palette: Palette::default(),
+    };
+    if let Err(e) = save_world_image_with_options(tiles, bot_position, file_name, &options) {
+        panic!("{}", e);
+    }
+}
+
+/// Saves the world as a PNG image like `save_world_image`, fitting it in the max side of the options and coloring the
+/// Grass and Hill tiles after their biome if the options have a biome map and drawing the decor under the content if
+/// they have the decor layer of the world; the terrain, the water and the vegetation take the colors of the palette of
+/// the options.
+///
+/// The memory the image takes is checked before drawing it, so a world too large for the tile size is reported
+/// instead of aborting the process.
+///
+/// # Errors
+///
+/// Returns an error if the image is too large, empty or cannot be saved.
+#[allow(dead_code)]
+pub fn save_world_image_with_options(tiles: &[Vec<Tile>], bot_position: (usize, usize), file_name: &str, options: &RenderOptions) -> Result<RenderedImage, RenderError> {
     debug_println!("Start: saving world as png");
     let start = Utc::now();
-    let img = create_image_from_tiles(tiles, bot_position, tile_size);
+    let (img, rendered) = render_world(tiles, bot_position, options)?;
 
-    if let Err(e) = img.save_with_format(file_name, ImageFormat::Png) {
-        panic!("Error saving the image, {}", e);
-    }
+    img.save_with_format(file_name, ImageFormat::Png).map_err(|e| RenderError::Save(e.to_string()))?;
     debug_println!("Done: saving world as png {}ms", (Utc::now() - start).num_milliseconds());
+    Ok(rendered)
+}
+
+// draws the world image, fitting it in the max side of the options
+fn render_world(tiles: &[Vec<Tile>], bot_position: (usize, usize), options: &RenderOptions) -> Result<(RgbImage, RenderedImage), RenderError> {
+    let size = tiles.len();
+    let full_side = size.saturating_mul(options.tile_size);
+    let biomes = options.biomes.as_ref();
+    let decor = options.decor.as_ref();
+    let palette = &options.palette;
+    let (img, rendered) = match options.max_side {
+        | Some(max_side) if full_side > max_side && max_side >= size => {
+            // the largest tiles fitting
+            let tile_size = max_side / size;
+            check_image_size(size * tile_size)?;
+            let rendered = RenderedImage {
+                side: size * tile_size,
+                tile_size: tile_size as f64,
+            };
+            (create_image_from_tiles(tiles, bot_position, tile_size, biomes, decor, palette), rendered)
+        }
+        | Some(max_side) if full_side > max_side => {
+            check_image_size(max_side)?;
+            let rendered = RenderedImage {
+                side: max_side,
+                tile_size: max_side as f64 / size as f64,
+            };
+            (create_downsampled_image(tiles, max_side, biomes, palette), rendered)
+        }
+        | _ => {
+            check_image_size(full_side)?;
+            let rendered = RenderedImage {
+                side: full_side,
+                tile_size: options.tile_size as f64,
+            };
+            (create_image_from_tiles(tiles, bot_position, options.tile_size, biomes, decor, palette), rendered)
+        }
+    };
+    Ok((img, rendered))
+}
+
+/// Saves the world of hexes generated by `gen_hex` as a PNG image, each hex `hex_size` pixels from its center to a
+/// corner, with the color of its tile type and a disc of the color of its content at its center.
+///
+/// # Errors
+///
+/// Returns an error if the image is too large, empty or cannot be saved.
+#[allow(dead_code)]
+pub fn save_hex_world_image(world: &HexWorld, file_name: &str, hex_size: usize) -> Result<(), RenderError> {
+    debug_println!("Start: saving hex world as png");
+    let start = Utc::now();
+    let size = world.size();
+    if size == 0 || hex_size == 0 {
+        return Err(RenderError::Empty);
+    }
+
+    // the rows of hexes overlap by a quarter of their height, the odd rows are shifted by half a hex
+    let radius = hex_size as f64;
+    let hex_width = 3f64.sqrt() * radius;
+    let width = (hex_width * (size as f64 + 0.5)).ceil() as usize;
+    let height = (radius * (1.5 * (size - 1) as f64 + 2.0)).ceil() as usize;
+    check_image_size(width.max(height))?;
+
+    let palette = Palette::default();
+    let mut img: RgbImage = RgbImage::new(width as u32, height as u32);
+    for y in 0..height {
+        for x in 0..width {
+            // the center of the pixel on the plane where the hex (0, 0) is centered on the origin
+            let (px, py) = (x as f64 + 0.5 - hex_width / 2.0, y as f64 + 0.5 - radius);
+            let hex = HexCoordinate::from_pixel(px, py, radius);
+            let Some(tile) = world.get(hex) else {
+                continue;
+            };
+            let (cx, cy) = hex.to_pixel(radius);
+            let in_content = tile.content != Content::None && (px - cx).powi(2) + (py - cy).powi(2) <= (radius / 2.0).powi(2);
+            let color = if in_content { content_color(&tile.content, &palette) } else { choose_tile_color(&tile.tile_type, &palette) };
+            img.put_pixel(x as u32, y as u32, color);
+        }
+    }
+
+    img.save_with_format(file_name, ImageFormat::Png).map_err(|e| RenderError::Save(e.to_string()))?;
+    debug_println!("Done: saving hex world as png {}ms", (Utc::now() - start).num_milliseconds());
+    Ok(())
+}
+
+// the image must have pixels and fit in the memory the encoder accepts
+#[inline(always)]
+fn check_image_size(side: usize) -> Result<(), RenderError> {
+    if side == 0 {
+        return Err(RenderError::Empty);
+    }
+    let bytes = (side as u64).saturating_mul(side as u64).saturating_mul(3);
+    if bytes > MAX_IMAGE_BYTES {
+        return Err(RenderError::TooLarge { side, bytes });
+    }
+    Ok(())
+}
+
+/// Redraws only the given tiles over an image saved by `save_world_image`, instead of rendering the whole world again,
+/// so that the world can be shown inside the tick loop of a robot, where only a few tiles change at each tick.
+///
+/// The tile size of the options must be the one the image was saved with.
+///
+/// # Errors
+///
+/// Returns an error if the image cannot be read or saved, or a tile is out of the image.
+#[allow(dead_code)]
+pub fn update_world_image(prev_png: &str, changed: &[(Coordinate, Tile)], options: &RenderOptions) -> Result<(), String> {
+    let start = Utc::now();
+    let mut img = image::open(prev_png).map_err(|e| format!("Unable to open the image {prev_png}: {e}"))?.into_rgb8();
+
+    let tile_size = options.tile_size;
+    for (coordinate, tile) in changed {
+        let (y, x) = (coordinate.get_row(), coordinate.get_col());
+        if (x + 1) * tile_size > img.width() as usize || (y + 1) * tile_size > img.height() as usize {
+            return Err(format!("The tile ({y}, {x}) is out of the image {prev_png}"));
+        }
+        draw_tile(&mut img, y, x, tile, tile_size, options.biomes.as_ref(), options.decor.as_ref(), &options.palette);
+    }
+
+    img.save_with_format(prev_png, ImageFormat::Png).map_err(|e| format!("Error saving the image, {e}"))?;
+    debug_println!("Done: updating {} tiles of the world png {}ms", changed.len(), (Utc::now() - start).num_milliseconds());
+    Ok(())
 }
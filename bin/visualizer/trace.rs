@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use debug_print::debug_println;
+use image::{ImageFormat, Rgb, RgbImage};
+
+use robotics_lib::world::coordinates::Coordinate;
+use robotics_lib::world::tile::*;
+
+use super::{blend, colors, render_world, RenderError, RenderOptions, RenderedImage};
+
+// the opacity of the oldest step of the path, the newest one is opaque
+const OLDEST_ALPHA: f32 = 0.2;
+
+/// Saves the world as a PNG image like `save_world_image_with_options`, with the path the robot walked drawn over it,
+/// for the reports of the runs of a robot.
+///
+/// The trace is the sequence of the tiles visited by the robot, like the coordinates read from the robot at each tick.
+/// The path joins the centers of the tiles in order and fades from the oldest steps to the newest ones, so that the
+/// sense of the walk is visible where the path crosses itself; a green disc marks the start and a red one the end.
+///
+/// # Errors
+///
+/// Returns an error if a tile of the trace is out of the world, or if the image is too large, empty or cannot be saved.
+///
+/// # Examples
+///
+/// ```ignore
+/// // the coordinates of the robot, collected at each tick
+/// let trace: Vec<Coordinate> = ticks.iter().map(|tick| tick.robot_coordinate.clone()).collect();
+/// render_with_trace(&world, &trace, "run.png", &RenderOptions::default())?;
+/// ```
+#[allow(dead_code)]
+pub fn render_with_trace(tiles: &[Vec<Tile>], trace: &[Coordinate], file_name: &str, options: &RenderOptions) -> Result<RenderedImage, RenderError> {
+    debug_println!("Start: saving world with trace as png");
+    let start = Utc::now();
+    let size = tiles.len();
+    if let Some(c) = trace.iter().find(|c| c.get_row() >= size || c.get_col() >= size) {
+        return Err(RenderError::OutOfWorld {
+            row: c.get_row(),
+            col: c.get_col(),
+        });
+    }
+
+    let bot_position = trace.first().map(|c| (c.get_row(), c.get_col())).unwrap_or_default();
+    let (mut img, rendered) = render_world(tiles, bot_position, options)?;
+    let tile_size = rendered.tile_size;
+    let center = |c: &Coordinate| ((c.get_col() as f64 + 0.5) * tile_size, (c.get_row() as f64 + 0.5) * tile_size);
+
+    // the opacity of each pixel of the path, the newest step wins where the path crosses itself
+    let width = (tile_size / 6.0).max(0.75);
+    let mut path: HashMap<(u32, u32), f32> = HashMap::new();
+    let steps = trace.len().saturating_sub(1);
+    for (i, step) in trace.windows(2).enumerate() {
+        let alpha = OLDEST_ALPHA + (1.0 - OLDEST_ALPHA) * (i + 1) as f32 / steps as f32;
+        for pixel in segment_pixels(center(&step[0]), center(&step[1]), width, rendered.side) {
+            let opacity = path.entry(pixel).or_default();
+            *opacity = opacity.max(alpha);
+        }
+    }
+    for ((x, y), alpha) in path {
+        let pixel = img.get_pixel_mut(x, y);
+        *pixel = blend(*pixel, colors::trace::PATH, alpha);
+    }
+
+    let radius = (tile_size * 0.4).max(2.0);
+    if let (Some(first), Some(last)) = (trace.first(), trace.last()) {
+        draw_disc(&mut img, center(first), radius, colors::trace::START);
+        draw_disc(&mut img, center(last), radius, colors::trace::END);
+    }
+
+    img.save_with_format(file_name, ImageFormat::Png).map_err(|e| RenderError::Save(e.to_string()))?;
+    debug_println!("Done: saving world with trace as png {}ms", (Utc::now() - start).num_milliseconds());
+    Ok(rendered)
+}
+
+// the pixels of the image within `width` of the segment between the two points
+fn segment_pixels(from: (f64, f64), to: (f64, f64), width: f64, side: usize) -> impl Iterator<Item = (u32, u32)> {
+    let bound = |a: f64, b: f64| ((a.min(b) - width).floor().max(0.0) as usize, ((a.max(b) + width).ceil() as usize).min(side));
+    let ((left, right), (top, bottom)) = (bound(from.0, to.0), bound(from.1, to.1));
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = dx * dx + dy * dy;
+
+    (top..bottom).flat_map(move |y| (left..right).map(move |x| (x, y))).filter_map(move |(x, y)| {
+        // the distance from the center of the pixel to the nearest point of the segment
+        let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+        let t = if length > 0.0 {
+            (((px - from.0) * dx + (py - from.1) * dy) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (nx, ny) = (from.0 + t * dx, from.1 + t * dy);
+        ((px - nx).hypot(py - ny) <= width).then_some((x as u32, y as u32))
+    })
+}
+
+// fills the disc with the color
+fn draw_disc(img: &mut RgbImage, center: (f64, f64), radius: f64, color: Rgb<u8>) {
+    for (x, y) in segment_pixels(center, center, radius, img.width() as usize) {
+        img.put_pixel(x, y, color);
+    }
+}
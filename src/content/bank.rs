@@ -1,16 +1,20 @@
-use rand::{thread_rng, Rng};
 use robotics_lib::world::tile::Content::Bank;
 use serde::{Deserialize, Serialize};
 
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::utils::{generation_rng, spawn_content_randomly};
 
 /// Settings defining the behavior of bank spawn,
 /// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct BankSettings {
     /// the number of banks to spawn
     pub number_of_spawn_points: usize,
+    /// the min distance in tiles between two banks, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
 }
 
 impl BankSettings {
@@ -19,6 +23,7 @@ impl BankSettings {
     pub fn default(size: usize) -> Self {
         BankSettings {
             number_of_spawn_points: usize::pow(size, 2) / 100,
+            min_spacing: None,
         }
     }
 
@@ -42,17 +47,52 @@ impl BankSettings {
     pub fn new(number_of_spawn_points: usize) -> Self {
         BankSettings {
             number_of_spawn_points,
+            min_spacing: None,
         }
     }
+
+    /// Sets the min distance in tiles between two banks, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer banks are spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::bank::BankSettings;
+    ///
+    /// // no two banks within 20 tiles of each other
+    /// let settings = BankSettings::new(10).with_min_spacing(20);
+    /// ```
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::bank::BankSettings;
+    ///
+    /// // more banks than tiles
+    /// assert!(!BankSettings::new(20000).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("BankSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_bank(world: &mut TileMatrix, bank_settings: BankSettings) {
-    thread_rng();
-    let max = Bank(0..0).properties().max();
-    let spawn_points = spawn_content_randomly(world, bank_settings.number_of_spawn_points, Bank(0..0));
+pub(crate) fn spawn_bank(world: &mut TileMatrix, occupancy: &mut Occupancy, bank_settings: BankSettings) {
+    generation_rng();
+    // a bank holds at least 1 coin
+    let capacity = QuantityRange::new(2, Bank(0..0).properties().max());
+    let spawn_points = spawn_content_randomly(occupancy, bank_settings.number_of_spawn_points, Bank(0..0), bank_settings.min_spacing);
 
     for c in spawn_points {
-        let upper_bound = thread_rng().gen_range(2..=max);
-        world[c.row][c.col].content = Bank(1..upper_bound);
+        let upper_bound = capacity.sample(&mut generation_rng());
+        occupancy.place(world, c, Bank(1..upper_bound));
     }
 }
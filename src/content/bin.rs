@@ -1,15 +1,19 @@
-use rand::{thread_rng, Rng};
 use robotics_lib::world::tile::Content::Bin;
 use serde::{Deserialize, Serialize};
 
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::utils::{generation_rng, spawn_content_randomly};
 
 /// Settings defining the behavior of bins spawn,
 /// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct BinSettings {
     pub number_of_spawn_points: usize,
+    /// the min distance in tiles between two bins, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
 }
 
 impl BinSettings {
@@ -18,6 +22,7 @@ impl BinSettings {
     pub fn default(size: usize) -> Self {
         BinSettings {
             number_of_spawn_points: usize::pow(size, 2) / 25,
+            min_spacing: None,
         }
     }
 
@@ -41,16 +46,42 @@ impl BinSettings {
     pub fn new(spawn_points: usize) -> Self {
         BinSettings {
             number_of_spawn_points: spawn_points,
+            min_spacing: None,
         }
     }
+
+    /// Sets the min distance in tiles between two bins, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer bins are spawned.
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::bin::BinSettings;
+    ///
+    /// // more bins than tiles
+    /// assert!(!BinSettings::new(20000).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("BinSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_bin(world: &mut TileMatrix, bin_settings: BinSettings) {
-    let max = Bin(0..0).properties().max();
-    let spawn_points = spawn_content_randomly(world, bin_settings.number_of_spawn_points, Bin(0..0));
+pub(crate) fn spawn_bin(world: &mut TileMatrix, occupancy: &mut Occupancy, bin_settings: BinSettings) {
+    // a bin holds at least 1 item
+    let capacity = QuantityRange::new(2, Bin(0..0).properties().max());
+    let spawn_points = spawn_content_randomly(occupancy, bin_settings.number_of_spawn_points, Bin(0..0), bin_settings.min_spacing);
 
     for c in spawn_points {
-        let upper_bound = thread_rng().gen_range(2..=max);
-        world[c.row][c.col].content = Bin(1..upper_bound);
+        let upper_bound = capacity.sample(&mut generation_rng());
+        occupancy.place(world, c, Bin(1..upper_bound));
     }
 }
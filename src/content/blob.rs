@@ -1,18 +1,71 @@
 use std::ops::Range;
 
-use crate::generator::TileMatrix;
-use nannou_core::math::{deg_to_rad, map_range};
-use noise::{NoiseFn, Perlin};
+use crate::placement::blob::{fill_outline, keep_connected_to_center, outline_bounds, retain_allowed, stretched_blob_outline, stretched_disc};
+use crate::generator::occupancy::Occupancy;
+use crate::generator::transaction::WorldTransaction;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::wind::WindSettings;
+use crate::generator::{QualityProfile, TileMatrix};
+use noise::Perlin;
+use rand::seq::SliceRandom;
 use rand::Rng;
-use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::tile::Content;
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{get_random_seeded_noise, Coordinate};
-#[derive(Serialize, Deserialize, Clone)]
+use crate::utils::{generation_rng, get_random_seeded_noise, Coordinate};
+/// Settings of the content spawned in blobs, like the trees and the fire
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct BlobSettings {
-    pub(crate) n_tiles: Range<usize>,
-    pub(crate) radius_range: Range<f32>,
-    pub(crate) n_blob: Range<usize>,
+    /// the range of the total number of tiles of the blobs, no more blobs are placed once the end is reached
+    pub n_tiles: Range<usize>,
+    /// the range of the radius of each blob, in tiles
+    pub radius_range: Range<f32>,
+    /// the range of the number of blobs, no more blobs are placed once the end is reached
+    pub n_blob: Range<usize>,
+}
+
+impl BlobSettings {
+    // the issues of the settings of the blob-shaped content, named after the settings holding them
+    pub(crate) fn validate(&self, owner: &str) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        let radius_range = &self.radius_range;
+        issues.check(radius_range.start >= 0.0 && radius_range.start < radius_range.end, &format!("{owner}::radius_range"), || {
+            format!("the range {radius_range:?} must be non-empty and not negative, the end is excluded")
+        });
+        issues.check(self.n_blob.start <= self.n_blob.end, &format!("{owner}::n_blob"), || {
+            format!("the start of {:?} is after its end", self.n_blob)
+        });
+        issues.check(self.n_tiles.start <= self.n_tiles.end, &format!("{owner}::n_tiles"), || {
+            format!("the start of {:?} is after its end", self.n_tiles)
+        });
+        // the minimum number of tiles that could be generated would be higher than the maximum number of tiles provided
+        issues.check(radius_range.start.floor() as usize * self.n_blob.start <= self.n_tiles.end, &format!("{owner}::n_tiles"), || {
+            format!(
+                "n_tiles.end: {} is too small for the given radius_range.start: {} and n_blob.start: {}, the minimum number of tiles that could be generated would be higher than the maximum number of tiles provided",
+                self.n_tiles.end, radius_range.start, self.n_blob.start
+            )
+        });
+        // the maximum number of tiles that could be generated would be lower than the minimum number of tiles provided
+        issues.check(radius_range.end.ceil() as usize * self.n_blob.end >= self.n_tiles.start, &format!("{owner}::n_tiles"), || {
+            format!(
+                "n_tiles.start: {} is too big for the given radius_range.end: {} and n_blob.end: {}, the maximum number of tiles that could be generated would be lower than the minimum number of tiles provided",
+                self.n_tiles.start, radius_range.end, self.n_blob.end
+            )
+        });
+        issues.0
+    }
+
+    // the same settings with the number of blobs and of their tiles scaled by the given factor, the lower bounds are
+    // rounded down and the upper ones up so that the settings stay valid
+    pub(crate) fn scaled(&self, factor: f64) -> Self {
+        let factor = factor.max(0.0);
+        let scale = |range: &Range<usize>| (range.start as f64 * factor).floor() as usize..(range.end as f64 * factor).ceil() as usize;
+        BlobSettings {
+            n_tiles: scale(&self.n_tiles),
+            radius_range: self.radius_range.clone(),
+            n_blob: scale(&self.n_blob),
+        }
+    }
 }
 
 pub(crate) struct Blob {
@@ -25,40 +78,44 @@ pub(crate) struct Blob {
 }
 
 pub(crate) trait BlobTrait {
-    fn limit_on_proper_tile(&mut self, world: &[Vec<Tile>], content: &Content);
+    fn limit_on_proper_tile(&mut self, occupancy: &Occupancy, content: &Content);
     fn get_extreme_points(&self) -> (usize, usize, usize, usize);
-    fn default(world: &[Vec<Tile>], size: usize, radius: f32, variation: f32, content: &Content) -> Self;
 
     fn new() -> Self;
     fn spread_blob(&mut self, upper_border: usize, left_border: usize, lower_border: usize, righter_border: usize);
 }
 
 impl BlobTrait for Blob {
-    fn limit_on_proper_tile(&mut self, world: &[Vec<Tile>], content: &Content) {
-        let mut i = 0;
-        while i < self.points.len() {
-            let point = self.points[i];
-
-            if !world[point.row][point.col].tile_type.properties().can_hold(&content) { // || world[point.row][point.col].content != Content::None  
-                // Remove the point from the blob
-                self.points.swap_remove(i);
-            } else {
-                // Move to the next index
-                i += 1;
-            }
-        }
+    fn limit_on_proper_tile(&mut self, occupancy: &Occupancy, content: &Content) {
+        retain_allowed(&mut self.points, |point| occupancy.can_hold(point, content));
     }
 
     fn get_extreme_points(&self) -> (usize, usize, usize, usize) {
-        let min_col = self.border_points.iter().map(|f| f.col).min().unwrap();
-        let max_col = self.border_points.iter().map(|f| f.col).max().unwrap();
-        let min_row = self.border_points.iter().map(|f| f.row).min().unwrap();
-        let max_row = self.border_points.iter().map(|f| f.row).max().unwrap();
+        outline_bounds(&self.border_points).unwrap()
+    }
+
+    fn new() -> Self {
+        Blob {
+            points: vec![],
+            noise: Perlin::new(0),
+            border_points: vec![],
+            radius: 0.0,
+            variation: 0.0,
+            center: Coordinate { row: 0, col: 0 },
+        }
+    }
 
-        (min_row, min_col, max_row, max_col)
+    // a function to spread from the center to the border points of the blob
+    fn spread_blob(&mut self, upper_border: usize, left_border: usize, lower_border: usize, righter_border: usize) {
+        let points = fill_outline(&self.border_points, self.center, (upper_border, left_border, lower_border, righter_border));
+        self.points.extend(points);
     }
+}
 
-    fn default(world: &[Vec<Tile>], size: usize, radius: f32, variation: f32, content: &Content) -> Self {
+impl Blob {
+    // generate a blob around the given center, the quality defines how finely its border is sampled, see `outline_step`,
+    // and the wind elongates it downwind, up to the border of the world
+    fn generate(occupancy: &Occupancy, center: Coordinate, radius: f32, variation: f32, content: &Content, quality: QualityProfile, wind: Option<&WindSettings>) -> Self {
         let mut blob = Blob::new();
 
         // set the radius
@@ -70,199 +127,130 @@ impl BlobTrait for Blob {
         // set the noise function
         blob.noise = get_random_seeded_noise();
 
-        // get the center of the blob
-        let mut rng = rand::thread_rng();
-        let max_radius = (radius.ceil() + variation.ceil()) as usize;
-        let x = rng.gen_range(max_radius..size - max_radius);
-        let y = rng.gen_range(max_radius..size - max_radius);
-        blob.center = Coordinate { row: y, col: x };
-
-        // set boarder points
-        blob.border_points = (0..=360)
-            .map(|i| {
-                // Map over an array of integers from 0 to 360 to represent the degrees in a circle.
-                // Convert each degree to radians.
-                let radian = deg_to_rad(i as f32);
-                // Get the sine of the radian to find the x co-ordinate of this point of the circle
-                // and multiply it by the radius.
-                let xoff = (radian.cos() + 1.0) as f64;
-                let yoff = (radian.sin() + 1.0) as f64;
-
-                let r = map_range(blob.noise.get([xoff, yoff]), 0.0, 1.0, radius * (1. - variation), radius * (1. + variation));
-                let relative_x = radian.cos() * r;
-                let relative_y = radian.sin() * r;
-
-                let border_x = (blob.center.col as f32 + relative_x) as usize;
-                let border_y = (blob.center.row as f32 + relative_y) as usize;
-
-                Coordinate {
-                    row: border_y,
-                    col: border_x,
-                }
-            })
-            .collect();
-
-        let (min_row, min_col, max_row, max_col) = blob.get_extreme_points();
-
-        blob.spread_blob(min_row, min_col, max_row, max_col);
+        // set the center, on a tile that can hold the content and far enough from the border of the world
+        blob.center = center;
 
-        blob.limit_on_proper_tile(world, content);
+        // without wind the blob is as long in every direction
+        let stretch = |d_row, d_col| wind.map_or(1.0, |wind| wind.stretch(d_row, d_col));
+        let reach = radius * wind.map_or(1.0, WindSettings::max_stretch);
+        let size = occupancy.size();
 
-        blob
-    }
+        // the fast profile skips the noisy border, the blob is a plain disc
+        if quality == QualityProfile::Fast {
+            blob.points = stretched_disc(center, radius, reach, size, stretch);
+        } else {
+            // set boarder points, as close as the farthest ones need to close the outline
+            blob.border_points = stretched_blob_outline(&blob.noise, center, radius, variation, outline_step(reach, quality), size, stretch);
 
-    fn new() -> Self {
-        Blob {
-            points: vec![],
-            noise: Perlin::new(0),
-            border_points: vec![],
-            radius: 0.0,
-            variation: 0.0,
-            center: Coordinate { row: 0, col: 0 },
-        }
-    }
+            let (min_row, min_col, max_row, max_col) = blob.get_extreme_points();
 
-    // a function to spread from the center to the border points of the blob
-    fn spread_blob(&mut self, upper_border: usize, left_border: usize, lower_border: usize, righter_border: usize) {
-        let rect_width = righter_border - left_border + 1;
-        let rect_height = lower_border - upper_border + 1;
-        //marking `border_points` as already visited
-        let mut visited: Vec<Vec<bool>> = vec![vec![false; rect_width]; rect_height];
-
-        // set as contained and visited all the border points
-        for point in self.border_points.clone() {
-            let x = point.col - left_border;
-            let y = point.row - upper_border;
-            visited[y][x] = true;
+            blob.spread_blob(min_row, min_col, max_row, max_col);
         }
 
-        let mut stack: Vec<Coordinate> = Vec::new();
-        stack.push(Coordinate {
-            row: self.center.row - upper_border,
-            col: self.center.col - left_border,
-        });
-        // mark center as visited
-        visited[self.center.row - upper_border][self.center.col - left_border] = true;
-        while !stack.is_empty() {
-            if let Some(current) = stack.pop() {
-                let x = current.col;
-                let y = current.row;
-
-                // upper left
-                if x > 0 && y > 0 && !visited[y - 1][x - 1] && !visited[y - 1][x] && !visited[y][x - 1] {
-                    visited[y - 1][x - 1] = true;
-                    stack.push(Coordinate {
-                        row: y - 1,
-                        col: x - 1,
-                    });
-                }
-                // upper center
-                if y > 0 && !visited[y - 1][x] {
-                    visited[y - 1][x] = true;
-                    stack.push(Coordinate { row: y - 1, col: x });
-                }
-                // upper right
-                if x < rect_width - 1 && y > 0 && !visited[y - 1][x + 1] && !visited[y - 1][x] && !visited[y][x + 1] {
-                    visited[y - 1][x + 1] = true;
-                    stack.push(Coordinate {
-                        row: y - 1,
-                        col: x + 1,
-                    });
-                }
-                // right center
-                if x < rect_width - 1 && !visited[y][x + 1] {
-                    visited[y][x + 1] = true;
-                    stack.push(Coordinate { row: y, col: x + 1 });
-                }
-                // lower right
-                if x < rect_width - 1 && y < rect_height - 1 && !visited[y + 1][x + 1] && !visited[y + 1][x] && !visited[y][x + 1] {
-                    visited[y + 1][x + 1] = true;
-                    stack.push(Coordinate {
-                        row: y + 1,
-                        col: x + 1,
-                    });
-                }
-                // lower center
-                if y < rect_height - 1 && !visited[y + 1][x] {
-                    visited[y + 1][x] = true;
-                    stack.push(Coordinate { row: y + 1, col: x });
-                }
-                // lower left
-                if x > 0 && y < rect_height - 1 && !visited[y + 1][x - 1] && !visited[y + 1][x] && !visited[y][x - 1] {
-                    visited[y + 1][x - 1] = true;
-                    stack.push(Coordinate {
-                        row: y + 1,
-                        col: x - 1,
-                    });
-                }
-                // left center
-                if x > 0 && !visited[y][x - 1] {
-                    visited[y][x - 1] = true;
-                    stack.push(Coordinate { row: y, col: x - 1 });
-                }
-            }
-        }
+        blob.limit_on_proper_tile(occupancy, content);
+        keep_connected_to_center(&mut blob.points, blob.center);
 
-        for (y, row) in visited.iter().enumerate() {
-            for (x, visited) in row.iter().enumerate() {
-                if *visited {
-                    self.points.push(Coordinate {
-                        row: y + upper_border,
-                        col: x + left_border,
-                    });
-                }
-            }
-        }
+        blob
     }
 }
 
-pub(crate) fn spawn_blob(world: &mut TileMatrix, settings: &mut BlobSettings, content: Content) {
+// the degrees between two points of the outline of a blob, the angle covering one tile at most, or the outline would be
+// open, up to 3 degrees with the balanced quality profile
+#[inline(always)]
+fn outline_step(radius: f32, quality: QualityProfile) -> usize {
+    let max_step = if quality == QualityProfile::Balanced { 3 } else { 1 };
+    ((57.0 / radius.max(1.0)) as usize).clamp(1, max_step)
+}
+
+pub(crate) fn spawn_blob(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &mut BlobSettings, content: Content, quality: QualityProfile, wind: Option<&WindSettings>) {
     // checks if settings are valid
-    if let Err(msg) = errors(settings) {
-        panic!("{}", msg);
+    let issues = settings.validate("BlobSettings");
+    if !issues.is_empty() {
+        panic!("{}", issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>().join("\n"));
     };
 
+    // the variation is below 1, so the blobs never cross the border of the world if their center is this far from it,
+    // the wind does not move it: the blobs it elongates past the border are cut by it
+    let margin = settings.radius_range.end.ceil() as usize + 1;
+    let mut centers = BlobCenters::new(world.len(), margin, &content);
+
     // generate blobs and place them in the world
     loop {
         // Generate random for variation
-        let mut rng = rand::thread_rng();
+        let mut rng = generation_rng();
         let variation = rng.gen_range(0.075..0.125);
         let radius = rng.gen_range(settings.radius_range.start..settings.radius_range.end);
-        let blob = Blob::default(world.as_slice(), world.len(), radius, variation, &content);
+        let Some(center) = centers.pick(occupancy) else {
+            // no tile of the world can hold the content
+            break;
+        };
+        let blob = Blob::generate(occupancy, center, radius, variation, &content, quality, wind);
 
         // checks before placing the blob
         if blob.points.len() > settings.n_tiles.end || settings.n_blob.end < 1 {
             break;
         }
 
-        // Decrease the counter of total tiles
+        // Decrease the counter of total tiles, a blob dropped below counts too so that the loop ends
         settings.n_tiles.end -= blob.points.len();
         // Decrease the blob counter
         settings.n_blob.end -= 1;
 
-        // Place tiles of the blob
+        // Place tiles of the blob, whole or not at all
+        let mut transaction = WorldTransaction::default();
         for point in blob.points {
-            world[point.row][point.col].content = content.clone();
+            transaction.stage(world, occupancy, point, content.clone());
         }
+        transaction.commit(world, occupancy);
     }
 }
 
-fn errors(settings: &BlobSettings) -> Result<(), String> {
-    if settings.radius_range.start.floor() as usize * settings.n_blob.start > settings.n_tiles.end {
-        // the minimum number of tiles that could be generated would be higher than the maximum number of tiles provided
-        Err(format!(
-            r#"n_tiles.end: {} is too small for the given radius_range.start: {} and n_blob.start: {}.
-The minimum number of tiles, that could be generated, would be higher than the maximum number of tiles provided."#,
-            settings.n_tiles.end, settings.radius_range.start, settings.n_blob.start
-        ))
-    } else if settings.radius_range.end.ceil() as usize * settings.n_blob.end < settings.n_tiles.start {
-        // the maximum number of tiles that could be generated would be lower than the minimum number of tiles provided
-        Err(format!(
-            r#"n_tiles.start: {} is too small for the given radius_range.end: {} and n_blob.end: {}.
-The maximum number of tiles that could be generated would be lower than the minimum number of tiles provided"#,
-            settings.n_tiles.start, settings.radius_range.end, settings.n_blob.end
-        ))
-    } else {
-        Ok(())
+// random draws of a center before listing all the tiles that can hold the content
+const CENTER_ATTEMPTS: usize = 64;
+
+// picks the centers of the blobs among the tiles that can hold the content, away from the border of the world
+struct BlobCenters<'a> {
+    content: &'a Content,
+    range: Range<usize>,
+    // listed only when the random draws keep missing, in worlds where the content fits on few tiles
+    candidates: Option<Vec<Coordinate>>,
+}
+
+impl<'a> BlobCenters<'a> {
+    fn new(size: usize, margin: usize, content: &'a Content) -> Self {
+        BlobCenters {
+            content,
+            range: margin..size.saturating_sub(margin),
+            candidates: None,
+        }
+    }
+
+    fn pick(&mut self, occupancy: &Occupancy) -> Option<Coordinate> {
+        if self.range.is_empty() {
+            return None;
+        }
+
+        let mut rng = generation_rng();
+        if self.candidates.is_none() {
+            for _ in 0..CENTER_ATTEMPTS {
+                let c = Coordinate {
+                    row: rng.gen_range(self.range.clone()),
+                    col: rng.gen_range(self.range.clone()),
+                };
+                if occupancy.can_hold(c, self.content) {
+                    return Some(c);
+                }
+            }
+
+            let range = self.range.clone();
+            self.candidates = Some(
+                range
+                    .clone()
+                    .flat_map(|row| range.clone().map(move |col| Coordinate { row, col }))
+                    .filter(|c| occupancy.can_hold(*c, self.content))
+                    .collect(),
+            );
+        }
+        self.candidates.as_ref().and_then(|candidates| candidates.choose(&mut rng).copied())
     }
 }
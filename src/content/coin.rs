@@ -1,15 +1,40 @@
-use rand::{thread_rng, Rng};
-use robotics_lib::world::tile::Content::Coin;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use robotics_lib::world::tile::Content::{Bank, Coin, Market};
 use serde::{Deserialize, Serialize};
 
-use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::reachability::neighbours;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{robot_spawn, TileMatrix};
+use crate::utils::{generation_rng, spawn_content_randomly, Coordinate};
+
+/// Defines whether coins are spawned along trails between the points of interest: the banks, the markets and the robot spawn.
+///
+/// Each point of interest is linked to the nearest other one by the shortest walkable path, and a coin is placed every
+/// `spacing` tiles of the path. Only the banks and markets already spawned are linked, so `Coin` should come after
+/// `Bank` and `Market` in the spawn order. Points of interest farther than the side of the world are not linked.
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum CoinTrailMode {
+    /// coins are spawned only in random spawn points
+    #[default]
+    Disabled,
+    /// coins are spawned only along the trails, the number of spawn points is ignored
+    Only { spacing: usize },
+    /// coins are spawned along the trails in addition to the random spawn points
+    Additional { spacing: usize },
+}
 
 /// Settings defining the behavior of coins spawn,
 /// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct CoinSettings {
     pub number_of_spawn_points: usize,
+    /// whether coins are spawned along trails between the points of interest, `Disabled` by default
+    pub trail_mode: CoinTrailMode,
+    /// the min distance in tiles between two coins, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
 }
 
 impl CoinSettings {
@@ -18,6 +43,8 @@ impl CoinSettings {
     pub fn default(size: usize) -> Self {
         CoinSettings {
             number_of_spawn_points: usize::pow(size, 2) / 25,
+            trail_mode: CoinTrailMode::Disabled,
+            min_spacing: None,
         }
     }
 
@@ -41,16 +68,147 @@ impl CoinSettings {
     pub fn new(spawn_points: usize) -> Self {
         CoinSettings {
             number_of_spawn_points: spawn_points,
+            trail_mode: CoinTrailMode::Disabled,
+            min_spacing: None,
+        }
+    }
+
+    /// Creates a new instance of `CoinSettings` with the given number of spawn points and trail mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `spawn_points` - The number of random spawn points for coins within the world.
+    /// * `trail_mode` - Whether coins are also spawned along trails between banks, markets and the robot spawn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::coin::{CoinSettings, CoinTrailMode};
+    ///
+    /// // a coin every 5 tiles on the way between banks, markets and the robot spawn
+    /// let settings = CoinSettings::with_trails(0, CoinTrailMode::Only { spacing: 5 });
+    /// ```
+    pub fn with_trails(spawn_points: usize, trail_mode: CoinTrailMode) -> Self {
+        CoinSettings {
+            number_of_spawn_points: spawn_points,
+            trail_mode,
+            min_spacing: None,
+        }
+    }
+
+    /// Sets the min distance in tiles between two coins, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer coins are spawned.
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::coin::CoinSettings;
+    ///
+    /// // more coins than tiles
+    /// assert!(!CoinSettings::new(20000).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("CoinSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        issues.0
+    }
+}
+
+pub(crate) fn spawn_coin(world: &mut TileMatrix, occupancy: &mut Occupancy, coin_settings: CoinSettings) {
+    let quantity = QuantityRange::full(&Coin(0));
+
+    if !matches!(coin_settings.trail_mode, CoinTrailMode::Only { .. }) {
+        let spawn_points = spawn_content_randomly(occupancy, coin_settings.number_of_spawn_points, Coin(0), coin_settings.min_spacing);
+        for c in spawn_points {
+            occupancy.place(world, c, Coin(quantity.sample(&mut generation_rng())));
+        }
+    }
+
+    let spacing = match coin_settings.trail_mode {
+        | CoinTrailMode::Disabled => return,
+        | CoinTrailMode::Only { spacing } | CoinTrailMode::Additional { spacing } => spacing.max(1),
+    };
+    for trail in find_trails(world) {
+        for c in trail.into_iter().skip(spacing - 1).step_by(spacing) {
+            if occupancy.accepts(world, c, &Coin(0)) {
+                occupancy.place(world, c, Coin(quantity.sample(&mut generation_rng())));
+            }
         }
     }
 }
 
-pub(crate) fn spawn_coin(world: &mut TileMatrix, coin_settings: CoinSettings) {
-    let max = Coin(0).properties().max();
-    let spawn_points = spawn_content_randomly(world, coin_settings.number_of_spawn_points, Coin(0));
+// the shortest walkable paths linking each point of interest to the nearest other one, without their extremes
+fn find_trails(world: &TileMatrix) -> Vec<Vec<Coordinate>> {
+    let size = world.len();
+    let mut points = vec![robot_spawn(world).coordinate()];
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if matches!(tile.content, Bank(_) | Market(_)) {
+                points.push(Coordinate { row, col });
+            }
+        }
+    }
+
+    let mut is_point = vec![vec![false; size]; size];
+    for c in points.iter() {
+        is_point[c.row][c.col] = true;
+    }
+
+    let mut linked = HashSet::new();
+    let mut trails = Vec::new();
+    for &start in points.iter() {
+        if let Some(trail) = path_to_nearest_point(world, &is_point, start) {
+            // the trail from a to b is the same as the one from b to a
+            let end = trail[trail.len() - 1];
+            if linked.insert((start.min(end), start.max(end))) {
+                trails.push(trail[..trail.len() - 1].to_vec());
+            }
+        }
+    }
+    trails
+}
+
+// breadth first search over the walkable tiles, returns the path from the start, excluded, to the nearest other point of interest.
+// The search is local, so the visited tiles are kept in a map rather than in matrices as big as the world
+fn path_to_nearest_point(world: &TileMatrix, is_point: &[Vec<bool>], start: Coordinate) -> Option<Vec<Coordinate>> {
+    let size = world.len();
+    // the parent and the distance from the start of each visited tile
+    let mut visited: HashMap<Coordinate, (Coordinate, usize)> = HashMap::from([(start, (start, 0))]);
+    let mut queue = VecDeque::from([start]);
 
-    for c in spawn_points {
-        let random = thread_rng().gen_range(1..=max);
-        world[c.row][c.col].content = Coin(random);
+    while let Some(current) = queue.pop_front() {
+        let distance = visited[&current].1;
+        if distance >= size {
+            break;
+        }
+        for next in neighbours(current, size) {
+            if visited.contains_key(&next) {
+                continue;
+            }
+            visited.insert(next, (current, distance + 1));
+
+            // banks and markets are reached from their neighbours, their own tile does not need to be walkable
+            if is_point[next.row][next.col] {
+                let mut path = vec![next];
+                let mut step = current;
+                while step != start {
+                    path.push(step);
+                    step = visited[&step].0;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if world[next.row][next.col].tile_type.properties().walk() {
+                queue.push_back(next);
+            }
+        }
     }
+    None
 }
@@ -0,0 +1,167 @@
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, spawn_content_randomly};
+
+/// A content spawned on random tiles which is not one of the `Spawnables`, registered in
+/// `WorldGenerator::extra_content`, usually built from the settings defined with `define_content_settings!`.
+///
+/// The extra content is spawned after the spawn order, in the order it was registered, on the tiles left free, each
+/// one with its own seed derived from the master seed, so adding an extra content leaves the content of the spawn
+/// order untouched.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExtraContent {
+    /// the name of the settings, used in the issues
+    pub name: String,
+    /// the kind of content placed, its quantity is drawn from `quantity`
+    pub content: Content,
+    /// the number of tiles holding the content
+    pub number_of_spawn_points: usize,
+    /// the quantity on each tile, the capacity for the Bank, the Bin and the Crate, clamped to what a tile can hold
+    pub quantity: QuantityRange,
+    /// the min distance in tiles between two tiles holding the content, `None` to place them anywhere
+    pub min_spacing: Option<usize>,
+}
+
+impl ExtraContent {
+    /// Checks the settings for the given world size, returning the issues found.
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits(&format!("{}::number_of_spawn_points", self.name), self.number_of_spawn_points, size);
+        issues.quantity(&format!("{}::quantity", self.name), &self.quantity);
+        issues.check(self.content != Content::None, &format!("{}::content", self.name), || {
+            "Content::None places nothing, use a content kind".to_string()
+        });
+        issues.0
+    }
+}
+
+/// Defines the settings of a content spawned on random tiles, with the fields and the methods shared by the
+/// settings of the crate: `number_of_spawn_points`, `quantity` and `min_spacing`, a `default(size)` placing a
+/// content every given number of tiles, `new`, `with_quantity`, `with_min_spacing` and `validate`.
+///
+/// The settings convert into an `ExtraContent`, which registers them in `WorldGenerator::extra_content`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use robotics_lib::world::tile::Content;
+/// use exclusion_zone::define_content_settings;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// define_content_settings! {
+///     /// Settings defining the spawn of the bushes
+///     pub struct BushSettings {
+///         content: Content::Bush(0),
+///         // a bush every 200 tiles by default
+///         tiles_per_spawn_point: 200,
+///     }
+/// }
+///
+/// let mut generator = WorldGenerator::default(500);
+/// generator.extra_content.push(BushSettings::default(500).with_min_spacing(3).into());
+/// let (world, ..) = generator.gen_with_artifacts().0;
+/// ```
+#[macro_export]
+macro_rules! define_content_settings {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            content: $content:expr,
+            tiles_per_spawn_point: $tiles:expr $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        $vis struct $name {
+            /// the number of tiles holding the content
+            pub number_of_spawn_points: usize,
+            /// the quantity on each tile, clamped to what a tile can hold
+            pub quantity: $crate::content::quantity::QuantityRange,
+            /// the min distance in tiles between two tiles holding the content, `None` by default to place them anywhere
+            pub min_spacing: Option<usize>,
+        }
+
+        impl $name {
+            /// Custom version of default that provides an instance of the settings with a content every
+            #[doc = concat!("`", stringify!($tiles), "` tiles of the world of the given size")]
+            pub fn default(size: usize) -> Self {
+                Self::new(usize::pow(size, 2) / $tiles)
+            }
+
+            /// Creates a new instance of the settings with the given number of spawn points, any quantity a tile
+            /// can hold and no min spacing.
+            pub fn new(spawn_points: usize) -> Self {
+                $name {
+                    number_of_spawn_points: spawn_points,
+                    quantity: $crate::content::quantity::QuantityRange::full(&$content),
+                    min_spacing: None,
+                }
+            }
+
+            /// Sets the quantity on each tile.
+            pub fn with_quantity(mut self, quantity: $crate::content::quantity::QuantityRange) -> Self {
+                self.quantity = quantity;
+                self
+            }
+
+            /// Sets the min distance in tiles between two tiles holding the content, measured along rows, columns
+            /// and diagonals.
+            pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+                self.min_spacing = Some(min_spacing);
+                self
+            }
+
+            /// Checks the settings for the given world size, returning the issues found.
+            pub fn validate(&self, size: usize) -> Vec<$crate::generator::validation::SettingsIssue> {
+                $crate::content::custom::ExtraContent::from(*self).validate(size)
+            }
+        }
+
+        impl From<$name> for $crate::content::custom::ExtraContent {
+            fn from(settings: $name) -> Self {
+                $crate::content::custom::ExtraContent {
+                    name: stringify!($name).to_string(),
+                    content: $content,
+                    number_of_spawn_points: settings.number_of_spawn_points,
+                    quantity: settings.quantity,
+                    min_spacing: settings.min_spacing,
+                }
+            }
+        }
+    };
+}
+
+pub(crate) fn spawn_extra_content(world: &mut TileMatrix, occupancy: &mut Occupancy, extra: &ExtraContent) {
+    let quantity = extra.quantity.clamped(&extra.content);
+    let spawn_points = spawn_content_randomly(occupancy, extra.number_of_spawn_points, extra.content.to_default(), extra.min_spacing);
+
+    for c in spawn_points {
+        occupancy.place(world, c, with_quantity(&extra.content, quantity.sample(&mut generation_rng())));
+    }
+}
+
+// the content with the given quantity, the capacity for the content holding a range
+#[inline(always)]
+fn with_quantity(kind: &Content, quantity: usize) -> Content {
+    match kind {
+        | Content::Rock(_) => Content::Rock(quantity),
+        | Content::Tree(_) => Content::Tree(quantity),
+        | Content::Garbage(_) => Content::Garbage(quantity),
+        | Content::Coin(_) => Content::Coin(quantity),
+        | Content::Water(_) => Content::Water(quantity),
+        | Content::Market(_) => Content::Market(quantity),
+        | Content::Fish(_) => Content::Fish(quantity),
+        | Content::Bush(_) => Content::Bush(quantity),
+        | Content::JollyBlock(_) => Content::JollyBlock(quantity),
+        // the content holding a range holds at least 1 item
+        | Content::Bin(_) => Content::Bin(1..quantity.max(2)),
+        | Content::Crate(_) => Content::Crate(1..quantity.max(2)),
+        | Content::Bank(_) => Content::Bank(1..quantity.max(2)),
+        | _ => kind.clone(),
+    }
+}
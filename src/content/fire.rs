@@ -1,15 +1,25 @@
+use std::collections::VecDeque;
 use std::ops::Mul;
 
 use nannou_core::prelude::Pow;
-use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::tile::{Content, TileType};
 use serde::{Deserialize, Serialize};
 
 use crate::content::blob::{spawn_blob, Blob, BlobSettings, BlobTrait};
-use crate::generator::TileMatrix;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::SettingsIssue;
+use crate::generator::wind::WindSettings;
+use crate::generator::{QualityProfile, TileMatrix};
+use crate::utils::Coordinate;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct FireSettings {
-    settings: BlobSettings,
+    /// the blobs of fire: how many, how large and how many tiles in total
+    pub settings: BlobSettings,
+    /// converts the Grass and Hill tiles bordering the fires to Sand, the scorched earth, `false` by default
+    pub scorched_earth: bool,
+    /// the distance in tiles from the fires within which trees burn and cannot spawn, 0 by default
+    pub tree_clearance: usize,
 }
 
 pub struct Fire {
@@ -17,20 +27,14 @@ pub struct Fire {
 }
 
 impl BlobTrait for Fire {
-    fn limit_on_proper_tile(&mut self, world: &[Vec<Tile>], content: &Content) {
-        self.inner.limit_on_proper_tile(world, content);
+    fn limit_on_proper_tile(&mut self, occupancy: &Occupancy, content: &Content) {
+        self.inner.limit_on_proper_tile(occupancy, content);
     }
 
     fn get_extreme_points(&self) -> (usize, usize, usize, usize) {
         self.inner.get_extreme_points()
     }
 
-    fn default(world: &[Vec<Tile>], size: usize, radius: f32, variation: f32, content: &Content) -> Self {
-        Fire {
-            inner: Blob::default(world, size, radius, variation, content),
-        }
-    }
-
     fn new() -> Self {
         Fire { inner: Blob::new() }
     }
@@ -64,8 +68,7 @@ impl FireSettings {
     /// let default_fire = FireSettings::default(size);
     /// ```
     pub fn default(size: usize) -> Self {
-
-        let radius_range = 1.0..(size as f32 / 50.0).min(4.0);
+        let radius_range = 1.0..(size as f32 / 50.0).clamp(2.0, 4.0);
         let n_blob = (size as f32 * 0.1) as usize..(size as f32 * 0.15) as usize;
         let n_tiles = 1..(radius_range.end.ceil().mul(2.0).pow(2) as usize) * n_blob.end;
         FireSettings {
@@ -74,10 +77,123 @@ impl FireSettings {
                 n_blob,
                 n_tiles,
             },
+            scorched_earth: false,
+            tree_clearance: 0,
         }
     }
+
+    /// Sets the fire breaks placed around the fires, so that fire zones stand out both for the robots and in the images.
+    ///
+    /// # Arguments
+    ///
+    /// * `scorched_earth` - Whether the Grass and Hill tiles bordering the fires are converted to Sand.
+    /// * `tree_clearance` - The distance in tiles from the fires within which trees burn and cannot spawn,
+    ///   whichever is spawned first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::fire::FireSettings;
+    ///
+    /// let settings = FireSettings::default(1000).with_fire_breaks(true, 3);
+    /// ```
+    pub fn with_fire_breaks(mut self, scorched_earth: bool, tree_clearance: usize) -> Self {
+        self.scorched_earth = scorched_earth;
+        self.tree_clearance = tree_clearance;
+        self
+    }
+
+    // the same settings with the number of fires and of their tiles scaled by the given factor,
+    // the lower bounds are rounded down and the upper ones up so that the settings stay valid
+    pub(crate) fn scaled(&self, factor: f64) -> Self {
+        FireSettings {
+            settings: self.settings.scaled(factor),
+            ..self.clone()
+        }
+    }
+
+    /// Checks the settings, returning the issues that would make the generation panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::fire::FireSettings;
+    ///
+    /// assert!(FireSettings::default(1000).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        self.settings.validate("FireSettings")
+    }
+
+    // the max number of tiles the fires can cover
+    pub(crate) fn max_tiles(&self) -> usize {
+        self.settings.n_tiles.end
+    }
 }
 
 pub fn spawn_fire(world: &mut TileMatrix, settings: &mut FireSettings) {
-    spawn_blob(world, &mut settings.settings, Content::Fire)
+    spawn_fire_with_quality(world, settings, QualityProfile::Detailed)
+}
+
+/// Spawns the fires like `spawn_fire`, sampling the border of the blobs as finely as the quality profile requires
+pub fn spawn_fire_with_quality(world: &mut TileMatrix, settings: &mut FireSettings, quality: QualityProfile) {
+    let mut occupancy = Occupancy::build(world);
+    spawn_fire_with_occupancy(world, &mut occupancy, settings, quality, None)
+}
+
+// used by the generator, which shares the occupancy of the tiles between the spawn stages and elongates the fires
+// downwind
+pub(crate) fn spawn_fire_with_occupancy(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &mut FireSettings, quality: QualityProfile, wind: Option<&WindSettings>) {
+    spawn_blob(world, occupancy, &mut settings.settings, Content::Fire, quality, wind);
+    place_fire_breaks(world, occupancy, settings);
+}
+
+// the offsets of the 8 neighbours of a tile
+const NEIGHBOURS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// breadth first search from the fires over the 8 neighbours, so that the distance is the Chebyshev one:
+// the tiles at distance 1 are scorched, the trees within the clearance burn and the tiles are forbidden to new trees
+fn place_fire_breaks(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &FireSettings) {
+    if !settings.scorched_earth && settings.tree_clearance == 0 {
+        return;
+    }
+
+    let size = world.len();
+    let max_distance = settings.tree_clearance.max(settings.scorched_earth as usize);
+    let mut distance = vec![vec![usize::MAX; size]; size];
+    let mut queue = VecDeque::new();
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if tile.content == Content::Fire {
+                distance[row][col] = 0;
+                queue.push_back(Coordinate { row, col });
+            }
+        }
+    }
+
+    while let Some(c) = queue.pop_front() {
+        let d = distance[c.row][c.col];
+        if settings.scorched_earth && d == 1 && matches!(world[c.row][c.col].tile_type, TileType::Grass | TileType::Hill) {
+            occupancy.set_tile_type(world, c, TileType::Sand);
+        }
+        if d <= settings.tree_clearance {
+            if matches!(world[c.row][c.col].content, Content::Tree(_)) {
+                occupancy.place(world, c, Content::None);
+            }
+            occupancy.forbid(c, &Content::Tree(0));
+        }
+        if d == max_distance {
+            continue;
+        }
+
+        for (d_row, d_col) in NEIGHBOURS {
+            let (Some(row), Some(col)) = (c.row.checked_add_signed(d_row), c.col.checked_add_signed(d_col)) else {
+                continue;
+            };
+            if row < size && col < size && distance[row][col] == usize::MAX {
+                distance[row][col] = d + 1;
+                queue.push_back(Coordinate { row, col });
+            }
+        }
+    }
 }
@@ -1,15 +1,36 @@
-use rand::{Rng, thread_rng};
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
 use robotics_lib::world::tile::Content::Fish;
+use robotics_lib::world::tile::TileType;
 use serde::{Deserialize, Serialize};
 
+use crate::placement::sampling::SpacingGrid;
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::spawn_report::SkipReason;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::utils::{generation_rng, spawn_content_randomly, Coordinate, GenerationRng};
+
+// attempts to find a free ShallowWater tile for a fish of a school before giving up on it
+const SCHOOL_PLACEMENT_ATTEMPTS: usize = 10;
 
 /// Settings defining the behavior of fish spawn,
-/// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+/// such as the number of spawn points and how they are grouped in schools near the shore
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct FishSettings {
     pub number_of_spawn_points: usize,
+    /// the average number of fish of each school, 0 spreads the fish uniformly over the water
+    pub school_size: usize,
+    /// the standard deviation, in tiles, of the distance between a fish and the center of its school
+    pub school_radius: f64,
+    /// the fraction of the fish spread uniformly over DeepWater instead of being grouped in schools
+    pub deep_water_ratio: f64,
+    /// the min distance in tiles between two fish, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
 }
 
 impl FishSettings {
@@ -18,10 +39,12 @@ impl FishSettings {
     pub fn default(size: usize) -> Self {
         FishSettings {
             number_of_spawn_points: usize::pow(size, 2) / 25,
+            ..FishSettings::new(0)
         }
     }
 
-    /// Creates a new instance of `FishSettings` with the given number of spawn points.
+    /// Creates a new instance of `FishSettings` with the given number of spawn points,
+    /// grouped in schools of 30 fish with a radius of 4 tiles, 10% of them in DeepWater.
     ///
     /// # Arguments
     ///
@@ -39,19 +62,205 @@ impl FishSettings {
     /// let settings = FishSettings::new(5);
     /// ```
     pub fn new(spawn_points: usize) -> Self {
+        FishSettings::with_schools(spawn_points, 30, 4.0, 0.1)
+    }
+
+    /// Creates a new instance of `FishSettings` defining how the fish are grouped in schools.
+    ///
+    /// Schools are centered on ShallowWater tiles bordering Sand, each fish is placed at a gaussian distance
+    /// from the center of its school, and the quantity of a fish decreases with its distance from the shore.
+    ///
+    /// # Arguments
+    ///
+    /// * `spawn_points` - The number of spawn points for fish within the world.
+    /// * `school_size` - The average number of fish of each school, 0 spreads the fish uniformly over the water.
+    /// * `school_radius` - The standard deviation, in tiles, of the distance between a fish and the center of its school.
+    /// * `deep_water_ratio` - The fraction of the fish spread uniformly over DeepWater, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::fish::FishSettings;
+    ///
+    /// // big and tight schools, no fish in the open sea
+    /// let settings = FishSettings::with_schools(40000, 100, 2.5, 0.0);
+    /// ```
+    pub fn with_schools(spawn_points: usize, school_size: usize, school_radius: f64, deep_water_ratio: f64) -> Self {
         FishSettings {
             number_of_spawn_points: spawn_points,
+            school_size,
+            school_radius,
+            deep_water_ratio: deep_water_ratio.clamp(0.0, 1.0),
+            min_spacing: None,
         }
     }
+
+    /// Sets the min distance in tiles between two fish, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer fish are spawned.
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::fish::FishSettings;
+    ///
+    /// let mut settings = FishSettings::new(500);
+    /// settings.school_radius = -1.0;
+    /// assert_eq!(settings.validate(100).len(), 1);
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("FishSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        issues.check(self.school_radius.is_finite() && self.school_radius >= 0.0, "FishSettings::school_radius", || {
+            format!("{} is not a distance, use a number of tiles not lower than 0", self.school_radius)
+        });
+        issues.probability("FishSettings::deep_water_ratio", self.deep_water_ratio);
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_fish(world: &mut TileMatrix, fish: FishSettings) {
+pub(crate) fn spawn_fish(world: &mut TileMatrix, occupancy: &mut Occupancy, fish: FishSettings) {
     let max = Fish(0).properties().max();
-    let spawn_points = spawn_content_randomly(world, fish.number_of_spawn_points, Fish(0).to_default());
 
-    for c in spawn_points {
-        let random = thread_rng().gen_range(1..=max);
-        world[c.row][c.col].content = Fish(random);
+    if fish.school_size == 0 {
+        let spawn_points = spawn_content_randomly(occupancy, fish.number_of_spawn_points, Fish(0).to_default(), fish.min_spacing);
+        for c in spawn_points {
+            occupancy.place(world, c, Fish(QuantityRange::new(1, max).sample(&mut generation_rng())));
+        }
+        return;
     }
+
+    let mut rng = generation_rng();
+    let shore_distance = distance_from_shore(world);
+    let deep_fish = (fish.number_of_spawn_points as f64 * fish.deep_water_ratio).round() as usize;
+    let schooled_fish = fish.number_of_spawn_points - deep_fish;
+
+    let mut grid = fish.min_spacing.map(|spacing| SpacingGrid::new(world.len(), spacing));
+
+    // the open sea, uniformly
+    let mut deep_water: Vec<Coordinate> = free_tiles(world, occupancy, TileType::DeepWater).collect();
+    deep_water.shuffle(&mut rng);
+    let mut placed = 0;
+    for c in deep_water.into_iter() {
+        if placed == deep_fish {
+            break;
+        }
+        if is_spaced(&mut grid, c) {
+            place_fish(world, occupancy, c, &shore_distance, max, &mut rng);
+            placed += 1;
+        }
+    }
+    // the deep water ran out before all the fish were placed
+    let shortfall_reason = if grid.is_some() { SkipReason::MinSpacing } else { SkipReason::NoRoom };
+    occupancy.skip(shortfall_reason, deep_fish - placed);
+
+    // the schools, around ShallowWater tiles bordering Sand
+    let hotspots: Vec<Coordinate> = free_tiles(world, occupancy, TileType::ShallowWater).filter(|c| shore_distance[c.row][c.col] == 1).collect();
+    if hotspots.is_empty() {
+        occupancy.skip(SkipReason::NoRoom, schooled_fish);
+        return;
+    }
+    let size = world.len() as f64;
+    let number_of_schools = schooled_fish.div_ceil(fish.school_size);
+    let centers: Vec<Coordinate> = (0..number_of_schools).filter_map(|_| hotspots.choose(&mut rng).copied()).collect();
+    for i in 0..schooled_fish {
+        let center = centers[i % centers.len()];
+        let mut found = false;
+        for _ in 0..SCHOOL_PLACEMENT_ATTEMPTS {
+            let (d_row, d_col) = gaussian_offset(&mut rng, fish.school_radius);
+            let row = center.row as f64 + d_row;
+            let col = center.col as f64 + d_col;
+            if row < 0.0 || col < 0.0 || row >= size || col >= size {
+                continue;
+            }
+
+            let c = Coordinate {
+                row: row as usize,
+                col: col as usize,
+            };
+            if world[c.row][c.col].tile_type == TileType::ShallowWater && occupancy.accepts(world, c, &Fish(0)) && is_spaced(&mut grid, c) {
+                place_fish(world, occupancy, c, &shore_distance, max, &mut rng);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            occupancy.skip(shortfall_reason, 1);
+        }
+    }
+}
+
+// true if the tile respects the min spacing, in which case it is taken
+#[inline(always)]
+fn is_spaced(grid: &mut Option<SpacingGrid>, c: Coordinate) -> bool {
+    match grid {
+        | Some(grid) if !grid.accepts(c) => false,
+        | Some(grid) => {
+            grid.insert(c);
+            true
+        }
+        | None => true,
+    }
+}
+
+// the quantity decreases with the distance from the shore, the nearest fish are the richest
+#[inline(always)]
+fn place_fish(world: &mut TileMatrix, occupancy: &mut Occupancy, c: Coordinate, shore_distance: &[Vec<usize>], max: usize, rng: &mut GenerationRng) {
+    let upper_bound = ((max as f64) / shore_distance[c.row][c.col].max(1) as f64).ceil().max(1.0) as usize;
+    occupancy.place(world, c, Fish(QuantityRange::new(1, upper_bound).sample(rng)));
+}
+
+// the tiles of the given type which can hold a fish and are still empty
+#[inline(always)]
+fn free_tiles<'a>(world: &'a TileMatrix, occupancy: &'a Occupancy, tile_type: TileType) -> impl Iterator<Item = Coordinate> + 'a {
+    world.iter().enumerate().flat_map(move |(row, tiles)| {
+        tiles
+            .iter()
+            .enumerate()
+            .map(move |(col, tile)| (Coordinate { row, col }, tile))
+            .filter(move |(c, tile)| tile.tile_type == tile_type && occupancy.accepts(world, *c, &Fish(0)))
+            .map(|(c, _)| c)
+    })
+}
+
+// breadth first search from every Sand tile, the distance of each tile from the nearest Sand tile, usize::MAX without Sand
+#[inline(always)]
+fn distance_from_shore(world: &TileMatrix) -> Vec<Vec<usize>> {
+    let size = world.len();
+    let mut distance = vec![vec![usize::MAX; size]; size];
+    let mut queue = VecDeque::new();
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if tile.tile_type == TileType::Sand {
+                distance[row][col] = 0;
+                queue.push_back((row, col));
+            }
+        }
+    }
+
+    while let Some((row, col)) = queue.pop_front() {
+        let next = [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)];
+        for (r, c) in next {
+            if r < size && c < size && distance[r][c] == usize::MAX {
+                distance[r][c] = distance[row][col] + 1;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    distance
 }
 
+// Box-Muller transform, two independent normal samples with the given standard deviation
+#[inline(always)]
+fn gaussian_offset(rng: &mut GenerationRng, std_dev: f64) -> (f64, f64) {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    let radius = std_dev * (-2.0 * u1.ln()).sqrt();
+    (radius * (2.0 * PI * u2).cos(), radius * (2.0 * PI * u2).sin())
+}
@@ -1,25 +1,39 @@
 use std::cmp::min;
+use std::collections::{HashSet, VecDeque};
 use std::ops::Range;
 
-use rand::prelude::ThreadRng;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use robotics_lib::world::tile::Content::Garbage;
-use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::tile::{Content, TileType};
 use serde::{Deserialize, Serialize};
 
-use crate::generator::TileMatrix;
+use crate::content::quantity::QuantityRange;
+use crate::placement::probability::probability_matrix;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::transaction::WorldTransaction;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::wind::WindSettings;
+use crate::generator::{QualityProfile, TileMatrix};
+use crate::utils::{generation_rng, Coordinate, GenerationRng};
 
 /// Settings defining the behavior of garbage spawn.
 ///
 /// This struct represents the configuration for garbage spawn, including the total quantity
-/// of garbage, pile sizes, quantity per tile and the likelihood that it will spawn a pile.
-#[derive(Serialize, Deserialize, Clone)]
+/// of garbage, pile sizes, quantity per tile, the likelihood that it will spawn a pile and
+/// how much the piles are concentrated around the urban areas.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GarbageSettings {
     pub total_garbage_quantity: usize,
     pub garbage_pile_size: Range<usize>,
-    pub garbage_per_tile_quantity: Range<usize>,
+    /// the quantity of garbage on each tile of a pile, clamped to the max a tile can hold
+    pub garbage_per_tile_quantity: QuantityRange,
     pub spawn_in_near_tiles_probability: f64,
     pub probability_step_by: f64,
+    /// the probability, between 0 and 1, that a pile is centered near a Street or a Building instead of anywhere in the world,
+    /// 0 by default
+    pub urban_bias: f64,
+    /// the max distance, in tiles, from a Street or a Building at which an urban pile can be centered
+    pub urban_distance: usize,
 }
 
 impl GarbageSettings {
@@ -29,20 +43,23 @@ impl GarbageSettings {
     pub fn default(size: usize) -> Self {
         GarbageSettings {
             total_garbage_quantity: usize::pow(size, 2) / 100,
-            garbage_pile_size: 1..size / 10,
-            garbage_per_tile_quantity: 1..Garbage(0).properties().max(),
+            garbage_pile_size: 1..(size / 10).max(3),
+            garbage_per_tile_quantity: QuantityRange::full(&Garbage(0)),
             spawn_in_near_tiles_probability: 1.0,
             probability_step_by: 0.2,
+            urban_bias: 0.0,
+            urban_distance: (size / 50).max(1),
         }
     }
 
-    /// Creates a new instance of `GarbageSettings` with the provided parameters.
+    /// Creates a new instance of `GarbageSettings` with the provided parameters,
+    /// the piles are spread over the whole world regardless of the urban areas.
     ///
     /// # Arguments
     ///
     /// * `total_garbage_quantity` - The total quantity of garbage.
     /// * `garbage_pile_size` - The range representing pile sizes.
-    /// * `garbage_per_tile_quantity` - The range representing quantity per tile.
+    /// * `garbage_per_tile_quantity` - The range of the quantity per tile, both bounds included.
     /// * `spawn_in_near_tiles_probability` - Likelihood that garbage will spawn in near tiles.
     /// * `probability_step_by` - Step by which probability increases/decreases.
     ///
@@ -53,122 +70,241 @@ impl GarbageSettings {
     /// # Example
     ///
     /// ```
-    /// use std::ops::Range;
     /// use exclusion_zone::content::garbage::GarbageSettings;
+    /// use exclusion_zone::content::quantity::QuantityRange;
     ///
-    /// let settings = GarbageSettings::with_parameters(
+    /// let settings = GarbageSettings::new(
     ///     1000,
-    ///     5..=10,
-    ///     1..=3,
+    ///     5..10,
+    ///     QuantityRange::new(1, 3),
     ///     0.7,
     ///     0.1,
     /// );
     /// ```
-    pub fn new(total_garbage_quantity: usize, garbage_pile_size: Range<usize>, garbage_per_tile_quantity: Range<usize>, spawn_in_near_tiles_probability: f64, probability_step_by: f64) -> Self {
+    pub fn new(total_garbage_quantity: usize, garbage_pile_size: Range<usize>, garbage_per_tile_quantity: QuantityRange, spawn_in_near_tiles_probability: f64, probability_step_by: f64) -> Self {
         GarbageSettings {
             total_garbage_quantity,
             garbage_pile_size,
             garbage_per_tile_quantity,
             spawn_in_near_tiles_probability,
             probability_step_by,
+            urban_bias: 0.0,
+            urban_distance: 10,
         }
     }
+
+    /// Checks the settings for the given world size, returning the issues that would make the generation
+    /// panic or never place all the garbage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::garbage::GarbageSettings;
+    /// use exclusion_zone::content::quantity::QuantityRange;
+    ///
+    /// // piles wider than the world
+    /// let settings = GarbageSettings::new(1000, 5..500, QuantityRange::new(1, 10), 0.7, 0.1);
+    /// for issue in settings.validate(100) {
+    ///     println!("{issue}");
+    /// }
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits(
+            "GarbageSettings::total_garbage_quantity",
+            self.total_garbage_quantity,
+            usize::pow(size, 2) * Garbage(0).properties().max(),
+        );
+        if self.total_garbage_quantity == 0 {
+            return issues.0;
+        }
+
+        let pile_size = &self.garbage_pile_size;
+        issues.not_empty("GarbageSettings::garbage_pile_size", pile_size);
+        // the piles of a single tile hold no garbage, the generation would never end if all of them were that small
+        issues.check(pile_size.end > 2, "GarbageSettings::garbage_pile_size", || {
+            format!("the piles of {pile_size:?} are all smaller than 2 tiles, which hold no garbage, use an end of at least 3")
+        });
+        issues.check(pile_size.end <= size, "GarbageSettings::garbage_pile_size", || {
+            format!("the piles of {pile_size:?} can be bigger than the {size} tiles of the world side, use an end of at most {size}")
+        });
+        issues.quantity("GarbageSettings::garbage_per_tile_quantity", &self.garbage_per_tile_quantity);
+        issues.probability("GarbageSettings::spawn_in_near_tiles_probability", self.spawn_in_near_tiles_probability);
+        // the inner ring of a pile has a probability of 1 - probability_step_by, so no garbage is placed from 1 up
+        issues.check(self.probability_step_by > 0.0 && self.probability_step_by < 1.0, "GarbageSettings::probability_step_by", || {
+            format!("{} must be greater than 0 and lower than 1", self.probability_step_by)
+        });
+        issues.probability("GarbageSettings::urban_bias", self.urban_bias);
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_garbage(world: &mut TileMatrix, settings: &GarbageSettings) {
+pub(crate) fn spawn_garbage(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &GarbageSettings, quality: QualityProfile, wind: Option<&WindSettings>) {
     let mut i = 0;
-    let mut rng = thread_rng();
-    let max_amount = min(settings.garbage_per_tile_quantity.clone().max().unwrap_or(1), Garbage(0).properties().max());
+    let mut rng = generation_rng();
     let spawn_prob = f64::max(0.2, settings.spawn_in_near_tiles_probability);
+    let urban_tiles = if settings.urban_bias > 0.0 { urban_tiles(world, settings.urban_distance) } else { Vec::new() };
     while i < settings.total_garbage_quantity {
-        spawn_garbage_build_up(world, settings.garbage_pile_size.clone(), settings.probability_step_by, spawn_prob, &mut i, &mut rng, max_amount);
+        // Get size of garbage pile
+        let pile_range = rng.gen_range(settings.garbage_pile_size.clone());
+
+        // urban piles are centered on a tile near a street, the others start anywhere
+        let base = if !urban_tiles.is_empty() && rng.gen_bool(settings.urban_bias.clamp(0.0, 1.0)) {
+            let center = urban_tiles[rng.gen_range(0..urban_tiles.len())];
+            Coordinate {
+                row: center.row.saturating_sub(pile_range / 2),
+                col: center.col.saturating_sub(pile_range / 2),
+            }
+        } else {
+            Coordinate {
+                row: rng.gen_range(0..world.len()),
+                col: rng.gen_range(0..world.len()),
+            }
+        };
+
+        spawn_garbage_build_up(world, occupancy, pile_range, base, settings.probability_step_by, spawn_prob, &mut i, &mut rng, settings.garbage_per_tile_quantity.clamped(&Garbage(0)), quality, wind);
     }
 }
 
 #[inline(always)]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn_garbage_build_up(
     world: &mut TileMatrix,
-    garbage_pile_size: Range<usize>,
+    occupancy: &mut Occupancy,
+    pile_range: usize,
+    base: Coordinate,
     probability_step_by: f64,
     spawn_prob: f64,
     placed: &mut usize,
-    rng: &mut ThreadRng,
-    max_garbage_per_tile: usize,
+    rng: &mut GenerationRng,
+    per_tile_quantity: QuantityRange,
+    quality: QualityProfile,
+    wind: Option<&WindSettings>,
 ) {
-    // Get size of garbage pile
-    let pile_range = rng.gen_range(garbage_pile_size);
-
     // Note that the matrix size will be rounded to greater odd number
-    let probability_matrix = generate_prob_matrix(pile_range, probability_step_by);
+    let probability_matrix = probability_matrix(pile_range, probability_step_by);
 
-    // get random x and y coordinates, the base indexes where matrix garbage will starts
-    let map_range = 0..world.len();
+    // the base indexes where matrix garbage will starts
+    let base_y = base.row;
+    let base_x = base.col;
 
-    let base_y = rng.gen_range(map_range.clone());
-    let base_x = rng.gen_range(map_range.clone());
+    // roll the random values once per sampling cell, a single tile when detailed, a whole ring when fast
+    let size = probability_matrix.len();
+    let samples: Vec<f64> = (0..sampling_cells(quality, size)).map(|_| rng.gen_range(0.1..=spawn_prob)).collect();
+
+    // the pile is placed whole or not at all
+    let mut pile = WorldTransaction::default();
+    let mut amount = 0;
+    // the tiles of the pile, the wind can drift two of them on the same tile
+    let mut covered = HashSet::new();
 
     //(x,y) will be the (0,0) of the probability matrix (not the center cause im lazy)
     for (row_index, row) in probability_matrix.iter().enumerate() {
         for col_index in 0..row.len() {
             // get the random value for the spawn
-            let value: f64 = thread_rng().gen_range(0.1..=spawn_prob);
+            let value: f64 = samples[sampling_cell(quality, size, row_index, col_index)];
 
             // assign if the probability is satisfied
             if value > (1. - probability_matrix[row_index][col_index]) {
                 // get random amount of garbage fot the tile content
-                let random_amount = rng.gen_range(1..max_garbage_per_tile);
-                if set_content(world, base_y + col_index, base_x + row_index, random_amount, probability_matrix.len()) {
-                    *placed += random_amount;
+                let random_amount = per_tile_quantity.sample(rng);
+                let c = Coordinate {
+                    row: base_y + col_index,
+                    col: base_x + row_index,
+                };
+                // the outer rings of the pile drift farther downwind than the inner ones
+                let Some(c) = wind.map_or(Some(c), |wind| drift(c, wind, ring(size, row_index, col_index))) else {
+                    continue;
+                };
+                if in_pile(world, occupancy, c, probability_matrix.len()) && covered.insert(c) {
+                    pile.stage(world, occupancy, c, Garbage(random_amount));
+                    amount += random_amount;
                 }
             }
         }
     }
-}
 
-#[inline(always)]
-fn set_content(world: &mut [Vec<Tile>], y: usize, x: usize, amount: usize, mat_size: usize) -> bool {
-    if y == 0 || y >= world.len() - mat_size || x == 0 || x >= world.len() - mat_size {
-        return false;
+    if pile.commit(world, occupancy) {
+        *placed += amount;
     }
+}
 
-    if world[y][x].tile_type.properties().can_hold(&Garbage(0)) && !(world[y][x].content != Content::None) {
-        world[y][x].content = Garbage(amount);
-        true
-    } else {
-        false
+// number of independent samples rolled for a pile of the given size
+#[inline(always)]
+fn sampling_cells(quality: QualityProfile, size: usize) -> usize {
+    match quality {
+        | QualityProfile::Detailed => size * size,
+        | QualityProfile::Balanced => size.div_ceil(2) * size.div_ceil(2),
+        | QualityProfile::Fast => size / 2 + 1,
     }
 }
 
-// probability matrix
+// index of the sample shared by the tile in the given position of the probability matrix
 #[inline(always)]
-fn generate_prob_matrix(mut size: usize, probability_step: f64) -> Vec<Vec<f64>> {
-    // some edgy checks
-    if size == 0 {
-        return vec![vec![]];
-    } else if size / 2 == 1 {
-        size += 1; //we want the size to be odd
+fn sampling_cell(quality: QualityProfile, size: usize, row: usize, col: usize) -> usize {
+    match quality {
+        | QualityProfile::Detailed => row * size + col,
+        | QualityProfile::Balanced => (row / 2) * size.div_ceil(2) + col / 2,
+        // the ring the tile belongs to
+        | QualityProfile::Fast => min(min(row, col), min(size - 1 - row, size - 1 - col)),
     }
+}
 
-    // initialize the matrix and calculate the total number of rings
-    let mut matrix = vec![vec![0.0; size]; size];
-    let total_rings = size / 2; // total number of ring
+// the ring of the tile in the given position of the probability matrix, 0 for the center
+#[inline(always)]
+fn ring(size: usize, row: usize, col: usize) -> usize {
+    row.abs_diff(size / 2).max(col.abs_diff(size / 2))
+}
 
-    // iterate over the ring
-    for ring in 0..total_rings {
-        // assign the probability for the ring
-        let prob = 1. - probability_step * ((total_rings - ring) as f64);
+// the tile the garbage drifts to, `None` if the wind blows it out of the world
+#[inline(always)]
+fn drift(c: Coordinate, wind: &WindSettings, ring: usize) -> Option<Coordinate> {
+    let (d_row, d_col) = wind.drift(ring);
+    Some(Coordinate {
+        row: c.row.checked_add_signed(d_row)?,
+        col: c.col.checked_add_signed(d_col)?,
+    })
+}
 
-        // iterate over the first row of the ring
-        for col_index in ring..(size - ring) {
-            matrix[ring][col_index] = prob;
-            matrix[size - 1 - ring][col_index] = prob;
+// the tiles within the given distance from a Street or a Building, found with a breadth first search from them
+#[inline(always)]
+fn urban_tiles(world: &TileMatrix, max_distance: usize) -> Vec<Coordinate> {
+    let size = world.len();
+    let mut distance = vec![vec![usize::MAX; size]; size];
+    let mut queue = VecDeque::new();
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if tile.tile_type == TileType::Street || tile.content == Content::Building {
+                distance[row][col] = 0;
+                queue.push_back(Coordinate { row, col });
+            }
         }
+    }
 
-        // iterate over the first column of the ring
-        for row_index in ring..(size - ring) {
-            matrix[row_index][ring] = prob;
-            matrix[row_index][size - 1 - ring] = prob;
+    let mut urban = Vec::new();
+    while let Some(c) = queue.pop_front() {
+        urban.push(c);
+        if distance[c.row][c.col] == max_distance {
+            continue;
+        }
+        let next = [(c.row.wrapping_sub(1), c.col), (c.row + 1, c.col), (c.row, c.col.wrapping_sub(1)), (c.row, c.col + 1)];
+        for (row, col) in next {
+            if row < size && col < size && distance[row][col] == usize::MAX {
+                distance[row][col] = distance[c.row][c.col] + 1;
+                queue.push_back(Coordinate { row, col });
+            }
         }
     }
-    matrix
+    urban
+}
+
+// whether the tile is part of the pile, away from the border of the world and able to hold garbage; the pile follows
+// the terrain, the tiles which cannot hold garbage are left out of it rather than dropping it
+#[inline(always)]
+fn in_pile(world: &TileMatrix, occupancy: &Occupancy, c: Coordinate, mat_size: usize) -> bool {
+    if c.row == 0 || c.row >= world.len() - mat_size || c.col == 0 || c.col >= world.len() - mat_size {
+        return false;
+    }
+
+    occupancy.can_hold(c, &Garbage(0))
 }
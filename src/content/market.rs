@@ -1,16 +1,20 @@
-use rand::{thread_rng, Rng};
 use robotics_lib::world::tile::Content::Market;
 use serde::{Deserialize, Serialize};
 
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::utils::{generation_rng, spawn_content_randomly};
 
 /// Settings defining the behavior of market spawn,
 /// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct MarketSettings {
     /// the number of markets to spawn
     pub number_of_spawn_points: usize,
+    /// the min distance in tiles between two markets, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
 }
 
 impl MarketSettings {
@@ -19,6 +23,7 @@ impl MarketSettings {
     pub fn default(size: usize) -> Self {
         MarketSettings {
             number_of_spawn_points: usize::pow(size, 2) / 100,
+            min_spacing: None,
         }
     }
 
@@ -42,16 +47,41 @@ impl MarketSettings {
     pub fn new(number_of_spawn_points: usize) -> Self {
         MarketSettings {
             number_of_spawn_points,
+            min_spacing: None,
         }
     }
+
+    /// Sets the min distance in tiles between two markets, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer markets are spawned.
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::market::MarketSettings;
+    ///
+    /// // more markets than tiles
+    /// assert!(!MarketSettings::new(20000).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("MarketSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_market(world: &mut TileMatrix, market_settings: MarketSettings) {
-    thread_rng();
-    let max = Market(0).properties().max();
-    let spawn_points = spawn_content_randomly(world, market_settings.number_of_spawn_points, Market(0));
+pub(crate) fn spawn_market(world: &mut TileMatrix, occupancy: &mut Occupancy, market_settings: MarketSettings) {
+    generation_rng();
+    let quantity = QuantityRange::full(&Market(0));
+    let spawn_points = spawn_content_randomly(occupancy, market_settings.number_of_spawn_points, Market(0), market_settings.min_spacing);
 
     for c in spawn_points {
-        world[c.row][c.col].content = Market(thread_rng().gen_range(1..=max));
+        occupancy.place(world, c, Market(quantity.sample(&mut generation_rng())));
     }
 }
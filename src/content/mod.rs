@@ -5,6 +5,8 @@ pub mod bin;
 pub(crate) mod blob;
 /// Contains structures and functions related to the spawn of coins
 pub mod coin;
+/// Contains the content defined outside the crate with `define_content_settings!` and spawned as extra content
+pub mod custom;
 /// Contains structures and functions related to the spawn of fire
 pub mod fire;
 /// Contains structures and functions related to the spawn of fish
@@ -17,5 +19,7 @@ pub mod tree;
 pub mod wood_crate;
 /// Contains structures and functions related to the spawn of market
 pub mod market;
+/// Contains the range of the quantity of content placed on a single tile
+pub mod quantity;
 /// Contains structures and functions related to the spawn of rocks
 pub mod rock;
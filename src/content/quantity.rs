@@ -0,0 +1,77 @@
+use rand::Rng;
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+/// The range of the quantity of content placed on a single tile, both bounds included.
+///
+/// The ranges read from the settings are clamped with `clamped` between 1 and the max the content can hold, as given
+/// by its properties, before drawing from them, so that a range reaching past the max never places a quantity the
+/// content cannot hold.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct QuantityRange {
+    /// the lowest quantity, included
+    pub min: usize,
+    /// the highest quantity, included
+    pub max: usize,
+}
+
+impl QuantityRange {
+    /// Creates a new instance of `QuantityRange` from `min` to `max`, both included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::Content;
+    /// use exclusion_zone::content::quantity::QuantityRange;
+    ///
+    /// // a single garbage per tile
+    /// let range = QuantityRange::new(1, 1);
+    /// assert_eq!(range.clamped(&Content::Garbage(0)).sample(&mut rand::thread_rng()), 1);
+    /// // more than a tile can hold
+    /// let range = QuantityRange::new(1, usize::MAX).clamped(&Content::Garbage(0));
+    /// assert_eq!(range.max, Content::Garbage(0).properties().max());
+    /// ```
+    pub fn new(min: usize, max: usize) -> Self {
+        QuantityRange { min, max }
+    }
+
+    /// Returns the range from 1 to the max the content can hold
+    pub fn full(content: &Content) -> Self {
+        QuantityRange {
+            min: 1,
+            max: content.properties().max().max(1),
+        }
+    }
+
+    /// Returns true if the range holds no quantity, the min is above the max
+    pub fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+
+    /// Returns the range clamped between 1 and the max the content can hold, a single quantity if it is empty
+    pub fn clamped(&self, content: &Content) -> Self {
+        let cap = content.properties().max().max(1);
+        let max = self.max.clamp(1, cap);
+        QuantityRange {
+            min: self.min.clamp(1, max),
+            max,
+        }
+    }
+
+    /// Returns the mean quantity drawn from the range
+    pub fn mean(&self) -> f64 {
+        (self.min + self.max.max(self.min)) as f64 / 2.0
+    }
+
+    /// Draws a quantity uniformly in the range, the min if it is empty
+    #[inline(always)]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        rng.gen_range(self.min..=self.max.max(self.min))
+    }
+}
+
+impl std::fmt::Display for QuantityRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
@@ -1,21 +1,104 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use robotics_lib::world::tile::Content::Rock;
 use robotics_lib::world::tile::{ TileType};
 use serde::{Deserialize, Serialize};
 
 use rand::seq::SliceRandom;
 
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::{ TileMatrix};
+use crate::utils::{generation_rng, Coordinate};
 
 /// Settings defining the behavior of rock spawn,
 /// such as the total number of rocks in the world
 /// and the probability to spawn in each environment
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RockSettings {
     /// The spawn probability sta for each environment (deep water, sand, mountains...).
     pub probability_vector: [f64; 7],
     /// The total number of rocks available in the world.
-    pub max_num_rocks: usize
+    pub max_num_rocks: usize,
+    /// The spawn probability as a function of the elevation, it replaces the `probability_vector` when set.
+    /// Tiles that cannot hold rocks, like streets and lava, never get one. `None` by default.
+    pub elevation_curve: Option<ProbabilityCurve>,
+}
+
+/// Curve mapping the normalized elevation of a tile, from 0 at the lowest tile of the world
+/// to 1 at the highest one, to the probability that the tile holds a rock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ProbabilityCurve {
+    /// the probability grows linearly from `low` at the lowest tile to `high` at the highest one
+    Linear { low: f64, high: f64 },
+    /// the probability is `low + (high - low) * elevation^exponent`,
+    /// an exponent above 1 keeps the rocks on the high ground, below 1 spreads them on the low one
+    Exponential { low: f64, high: f64, exponent: f64 },
+    /// piecewise linear between `(elevation, probability)` breakpoints, constant before the first and after the last one.
+    /// The breakpoints are sorted by elevation before use, a curve without breakpoints never spawns rocks
+    Breakpoints(Vec<(f64, f64)>),
+}
+
+impl ProbabilityCurve {
+    /// Returns the probability, clamped between 0 and 1, of the given normalized elevation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::rock::ProbabilityCurve;
+    ///
+    /// // rocky beaches and rocky peaks, few rocks in between
+    /// let curve = ProbabilityCurve::Breakpoints(vec![(0.3, 0.0), (0.4, 0.5), (0.5, 0.05), (0.8, 0.05), (1.0, 0.9)]);
+    /// assert_eq!(curve.probability(0.4), 0.5);
+    /// ```
+    pub fn probability(&self, elevation: f64) -> f64 {
+        self.sorted().sorted_probability(elevation)
+    }
+
+    // the same curve with the breakpoints sorted by elevation
+    #[inline(always)]
+    fn sorted(&self) -> Self {
+        match self {
+            | ProbabilityCurve::Breakpoints(breakpoints) => {
+                let mut sorted = breakpoints.clone();
+                sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+                ProbabilityCurve::Breakpoints(sorted)
+            }
+            | curve => curve.clone(),
+        }
+    }
+
+    // the probability of a curve whose breakpoints are already sorted
+    #[inline(always)]
+    fn sorted_probability(&self, elevation: f64) -> f64 {
+        let probability = match self {
+            | ProbabilityCurve::Linear { low, high } => low + (high - low) * elevation,
+            | ProbabilityCurve::Exponential { low, high, exponent } => low + (high - low) * elevation.max(0.0).powf(*exponent),
+            | ProbabilityCurve::Breakpoints(breakpoints) => interpolate(breakpoints, elevation),
+        };
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+// linear interpolation between the breakpoints surrounding the elevation
+#[inline(always)]
+fn interpolate(breakpoints: &[(f64, f64)], elevation: f64) -> f64 {
+    let (Some(first), Some(last)) = (breakpoints.first(), breakpoints.last()) else {
+        return 0.0;
+    };
+    if elevation <= first.0 {
+        return first.1;
+    }
+    if elevation >= last.0 {
+        return last.1;
+    }
+    breakpoints
+        .windows(2)
+        .find(|pair| elevation <= pair[1].0)
+        .map_or(last.1, |pair| {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if x1 == x0 { y1 } else { y0 + (y1 - y0) * (elevation - x0) / (x1 - x0) }
+        })
 }
 
 impl RockSettings {
@@ -28,6 +111,7 @@ impl RockSettings {
         RockSettings{
             max_num_rocks,
             probability_vector,
+            elevation_curve: None,
         }
     }
     /// Creates a new instance of `RockSettings` with the given number of spawn points
@@ -52,11 +136,55 @@ impl RockSettings {
         RockSettings {
             probability_vector,
             max_num_rocks,
+            elevation_curve: None,
         }
     }
+
+    /// Creates a new instance of `RockSettings` whose spawn probability depends on the elevation rather than on the tile type
+    ///
+    /// # Arguments
+    ///
+    /// * `max_num_rocks` - The total number of rocks available in the world.
+    /// * `elevation_curve` - The spawn probability as a function of the normalized elevation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::rock::{ProbabilityCurve, RockSettings};
+    ///
+    /// // the higher, the rockier
+    /// let settings = RockSettings::with_curve(50000, ProbabilityCurve::Exponential { low: 0.0, high: 0.9, exponent: 3.0 });
+    /// ```
+    pub fn with_curve(max_num_rocks: usize, elevation_curve: ProbabilityCurve) -> Self {
+        RockSettings {
+            probability_vector: [0.0; 7],
+            max_num_rocks,
+            elevation_curve: Some(elevation_curve),
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::rock::RockSettings;
+    ///
+    /// // 1.5 is not a probability
+    /// let settings = RockSettings::new(500, [0.0, 0.0, 0.1, 0.25, 0.45, 0.5, 1.5]);
+    /// assert_eq!(settings.validate(100).len(), 1);
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("RockSettings::max_num_rocks", self.max_num_rocks, size);
+        for probability in self.probability_vector {
+            issues.probability("RockSettings::probability_vector", probability);
+        }
+        issues.0
+    }
 }
 
-fn match_probabilities(rock_settings: RockSettings, tile_type: TileType ) -> f64 {
+fn match_probabilities(rock_settings: &RockSettings, tile_type: TileType ) -> f64 {
     match tile_type {
         TileType::DeepWater => { rock_settings.probability_vector[0] }
         TileType::ShallowWater => { rock_settings.probability_vector[1] }
@@ -73,9 +201,13 @@ fn match_probabilities(rock_settings: RockSettings, tile_type: TileType ) -> f64
 }
 
 #[inline(always)]
-pub(crate)  fn spawn_rock(world: &mut TileMatrix, rock_settings: RockSettings) {
+pub(crate)  fn spawn_rock(world: &mut TileMatrix, occupancy: &mut Occupancy, rock_settings: &RockSettings, elevation_map: &[Vec<f64>]) {
     let mut cnt = rock_settings.max_num_rocks;
 
+    // the breakpoints are sorted once, not for every tile
+    let curve = rock_settings.elevation_curve.as_ref().map(ProbabilityCurve::sorted);
+    let (min, max) = elevation_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), &value| (min.min(value), max.max(value)));
+
     let mut possible_rock_tile : Vec<(usize,usize)> = vec![];
 
 
@@ -85,10 +217,13 @@ pub(crate)  fn spawn_rock(world: &mut TileMatrix, rock_settings: RockSettings) {
         }
         for (x,tile) in row.iter().enumerate() {
             let tile_type = tile.tile_type;
-            let prob = match_probabilities(rock_settings, tile_type);
+            let prob = match &curve {
+                | Some(curve) => curve.sorted_probability(normalize(elevation_map[y][x], min, max)),
+                | None => match_probabilities(rock_settings, tile_type),
+            };
 
-            let rock = thread_rng().gen_bool(prob);
-            let can_hold = tile.tile_type.properties().can_hold(&Rock(0).to_default());
+            let rock = generation_rng().gen_bool(prob);
+            let can_hold = occupancy.can_hold(Coordinate { row: y, col: x }, &Rock(0));
 
             if rock && can_hold && cnt > 0{
                 possible_rock_tile.push((y,x));
@@ -101,16 +236,19 @@ pub(crate)  fn spawn_rock(world: &mut TileMatrix, rock_settings: RockSettings) {
         }
     }
 
-    possible_rock_tile.shuffle(&mut thread_rng());
+    possible_rock_tile.shuffle(&mut generation_rng());
 
     for c in possible_rock_tile.iter(){
         // random quantity of rock
-        let qt = thread_rng().gen_range(1..=Rock(0).properties().max());
-        world[c.0][c.1].content = Rock(qt);
+        let qt = QuantityRange::full(&Rock(0)).sample(&mut generation_rng());
+        occupancy.place(world, Coordinate { row: c.0, col: c.1 }, Rock(qt));
     }
 
 
 
 }
 
-
+#[inline(always)]
+fn normalize(value: f64, min: f64, max: f64) -> f64 {
+    if max > min { (value - min) / (max - min) } else { 0.0 }
+}
@@ -1,15 +1,20 @@
 use std::ops::Mul;
 
 use nannou_core::prelude::Pow;
-use robotics_lib::world::tile::{Content, Tile};
+use rand::Rng;
+use robotics_lib::world::tile::Content;
 use serde::{Deserialize, Serialize};
 
 use crate::content::blob::{spawn_blob, Blob, BlobSettings, BlobTrait};
-use crate::generator::TileMatrix;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::SettingsIssue;
+use crate::generator::{QualityProfile, TileMatrix};
+use crate::utils::generation_rng;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TreeSettings {
-    settings: BlobSettings,
+    /// the blobs of trees: how many, how large and how many tiles in total
+    pub settings: BlobSettings,
 }
 
 pub struct Tree {
@@ -17,20 +22,14 @@ pub struct Tree {
 }
 
 impl BlobTrait for Tree {
-    fn limit_on_proper_tile(&mut self, world: &[Vec<Tile>], content: &Content) {
-        self.inner.limit_on_proper_tile(world, content);
+    fn limit_on_proper_tile(&mut self, occupancy: &Occupancy, content: &Content) {
+        self.inner.limit_on_proper_tile(occupancy, content);
     }
 
     fn get_extreme_points(&self) -> (usize, usize, usize, usize) {
         self.inner.get_extreme_points()
     }
 
-    fn default(world: &[Vec<Tile>], size: usize, radius: f32, variation: f32, content: &Content) -> Self {
-        Tree {
-            inner: Blob::default(world, size, radius, variation, content),
-        }
-    }
-
     fn new() -> Self {
         Tree { inner: Blob::new() }
     }
@@ -39,13 +38,13 @@ impl BlobTrait for Tree {
         self.inner.spread_blob(upper_border, left_border, lower_border, righter_border);
 
         // remove with a certain probability
-        self.inner.points.retain(|_| rand::random::<f32>() > 0.1);
+        self.inner.points.retain(|_| generation_rng().gen::<f32>() > 0.1);
     }
 }
 
 impl TreeSettings {
     pub fn default(size: usize) -> Self {
-        let radius_range = 1.0..(size as f32 / 50.0).min(4.0);
+        let radius_range = 1.0..(size as f32 / 50.0).clamp(2.0, 4.0);
         let n_blob = (size as f32 * 0.1) as usize..(size as f32 * 0.15) as usize;
         let n_tiles = 1..(radius_range.end.ceil().mul(2.0).pow(2) as usize) * n_blob.end;
         TreeSettings {
@@ -56,8 +55,44 @@ impl TreeSettings {
             },
         }
     }
+
+    /// Checks the settings, returning the issues that would make the generation panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::tree::TreeSettings;
+    ///
+    /// assert!(TreeSettings::default(1000).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        self.settings.validate("TreeSettings")
+    }
+
+    // the same settings with the number of trees and of their tiles scaled by the given factor
+    pub(crate) fn scaled(&self, factor: f64) -> Self {
+        TreeSettings {
+            settings: self.settings.scaled(factor),
+        }
+    }
+
+    // the max number of tiles the trees can cover
+    pub(crate) fn max_tiles(&self) -> usize {
+        self.settings.n_tiles.end
+    }
 }
 
 pub fn spawn_tree(world: &mut TileMatrix, settings: &mut TreeSettings) {
-    spawn_blob(world, &mut settings.settings, Content::Tree(0))
+    spawn_tree_with_quality(world, settings, QualityProfile::Detailed)
+}
+
+/// Spawns the trees like `spawn_tree`, sampling the border of the blobs as finely as the quality profile requires
+pub fn spawn_tree_with_quality(world: &mut TileMatrix, settings: &mut TreeSettings, quality: QualityProfile) {
+    let mut occupancy = Occupancy::build(world);
+    spawn_tree_with_occupancy(world, &mut occupancy, settings, quality)
+}
+
+// used by the generator, which shares the occupancy of the tiles between the spawn stages
+pub(crate) fn spawn_tree_with_occupancy(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &mut TreeSettings, quality: QualityProfile) {
+    spawn_blob(world, occupancy, &mut settings.settings, Content::Tree(0), quality, None)
 }
@@ -1,15 +1,93 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use robotics_lib::world::tile::Content::Crate;
+use robotics_lib::world::tile::{Content, TileType};
 use serde::{Deserialize, Serialize};
 
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::spawn_content_randomly;
+use crate::utils::{generation_rng, spawn_content_randomly, Coordinate};
 
 /// Settings defining the behavior of wood crate spawn,
 /// such as the number of spawn points
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct CrateSettings {
     pub number_of_spawn_points: usize,
+    /// the min distance in tiles between two wood crates, `None` by default to place them anywhere
+    pub min_spacing: Option<usize>,
+    /// the loot implied by the surroundings of each wood crate, `None` by default to leave the crates without loot
+    pub loot: Option<LootSettings>,
+}
+
+/// Settings of the loot implied by the surroundings of the wood crates: wood in the crates near the forests,
+/// garbage in the crates in the cities.
+///
+/// The loot is listed in the crate manifest of the generation artifacts, the tile keeps a crate whose capacity fits it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LootSettings {
+    /// the distance, in tiles along any direction, of the surroundings of a crate
+    pub radius: usize,
+    /// the min quantity of loot in a crate, before the bias of the surroundings
+    pub min_quantity: usize,
+    /// the max quantity of loot in a crate, before the bias of the surroundings
+    pub max_quantity: usize,
+    /// how much the surroundings raise the loot: the quantity is multiplied by `1 + bias * share`, where `share` is
+    /// the share of the surrounding tiles which are trees or city
+    pub bias: f64,
+}
+
+impl Default for LootSettings {
+    fn default() -> Self {
+        LootSettings {
+            radius: 5,
+            min_quantity: 1,
+            max_quantity: 5,
+            bias: 3.0,
+        }
+    }
+}
+
+impl LootSettings {
+    /// Creates a new instance of `LootSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius` - The distance, in tiles along any direction, of the surroundings of a crate, by default 5.
+    /// * `min_quantity` - The min quantity of loot in a crate, before the bias of the surroundings, by default 1.
+    /// * `max_quantity` - The max quantity of loot in a crate, before the bias of the surroundings, by default 5.
+    /// * `bias` - How much the surroundings raise the loot, by default 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::wood_crate::{CrateSettings, LootSettings};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(300);
+    /// generator.crate_settings = CrateSettings::default(300).with_loot(LootSettings::new(3, 1, 4, 5.0));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// for entry in artifacts.crate_manifest {
+    ///     println!("{:?} holds {:?}", entry.position, entry.loot);
+    /// }
+    /// ```
+    pub fn new(radius: usize, min_quantity: usize, max_quantity: usize, bias: f64) -> Self {
+        LootSettings {
+            radius,
+            min_quantity,
+            max_quantity,
+            bias,
+        }
+    }
+}
+
+/// The loot of a wood crate of the generated world
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CrateLoot {
+    /// the tile of the crate
+    pub position: Coordinate,
+    /// the loot, `Tree` for wood or `Garbage`, with its quantity
+    pub loot: Content,
 }
 
 impl CrateSettings {
@@ -18,6 +96,8 @@ impl CrateSettings {
     pub fn default(size: usize) -> Self {
         CrateSettings {
             number_of_spawn_points: usize::pow(size, 2) / 40,
+            min_spacing: None,
+            loot: None,
         }
     }
 
@@ -41,16 +121,104 @@ impl CrateSettings {
     pub fn new(number_of_spawn_points: usize) -> Self {
         Self {
             number_of_spawn_points,
+            min_spacing: None,
+            loot: None,
+        }
+    }
+
+    /// Sets the min distance in tiles between two wood crates, measured along rows, columns and diagonals.
+    ///
+    /// When the world has no room for all of them at that distance, fewer wood crates are spawned.
+    pub fn with_min_spacing(mut self, min_spacing: usize) -> Self {
+        self.min_spacing = Some(min_spacing);
+        self
+    }
+
+    /// Assigns to each wood crate the loot implied by its surroundings, listed in the crate manifest of the generation artifacts
+    pub fn with_loot(mut self, loot: LootSettings) -> Self {
+        self.loot = Some(loot);
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::wood_crate::CrateSettings;
+    ///
+    /// // more crates than tiles
+    /// assert!(!CrateSettings::new(20000).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("CrateSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        if let Some(loot) = &self.loot {
+            issues.check(loot.min_quantity <= loot.max_quantity, "LootSettings::min_quantity", || {
+                format!("{} is greater than the max quantity {}", loot.min_quantity, loot.max_quantity)
+            });
+            issues.check(loot.bias.is_finite() && loot.bias >= 0.0, "LootSettings::bias", || {
+                format!("{} is not a finite value of at least 0", loot.bias)
+            });
         }
+        issues.0
     }
 }
 
-pub(crate) fn spawn_crate(world: &mut TileMatrix, crate_settings: CrateSettings) {
-    let max = Crate(0..0).properties().max();
-    let spawn_points = spawn_content_randomly(world, crate_settings.number_of_spawn_points, Crate(0..0).to_default());
+pub(crate) fn spawn_crate(world: &mut TileMatrix, occupancy: &mut Occupancy, crate_settings: CrateSettings) {
+    let capacity = QuantityRange::full(&Crate(0..0));
+    let spawn_points = spawn_content_randomly(occupancy, crate_settings.number_of_spawn_points, Crate(0..0).to_default(), crate_settings.min_spacing);
 
     for c in spawn_points {
-        let upper_bound = thread_rng().gen_range(1..=max);
-        world[c.row][c.col].content = Crate(1..upper_bound);
+        let upper_bound = capacity.sample(&mut generation_rng());
+        occupancy.place(world, c, Crate(1..upper_bound));
+    }
+}
+
+/// Assigns the loot to each wood crate of the generated world, row by row, returning the crate manifest.
+///
+/// Runs once the world is final, so that the surroundings are the ones the robot finds; the capacity of each crate is
+/// set to fit its loot, overriding the quantity fields.
+pub(crate) fn assign_loot(world: &mut TileMatrix, settings: &LootSettings) -> Vec<CrateLoot> {
+    let size = world.len();
+    let mut rng = generation_rng();
+    let max = Crate(0..0).properties().max();
+    let mut manifest = Vec::new();
+
+    for row in 0..size {
+        for col in 0..size {
+            if !matches!(world[row][col].content, Content::Crate(_)) {
+                continue;
+            }
+
+            let (mut trees, mut city, mut tiles) = (0, 0, 0);
+            for surrounding in &world[row.saturating_sub(settings.radius)..(row + settings.radius + 1).min(size)] {
+                for tile in &surrounding[col.saturating_sub(settings.radius)..(col + settings.radius + 1).min(size)] {
+                    tiles += 1;
+                    if matches!(tile.content, Content::Tree(_)) {
+                        trees += 1;
+                    } else if tile.tile_type == TileType::Street || tile.content == Content::Building {
+                        city += 1;
+                    }
+                }
+            }
+
+            // the crates far from both hold wood
+            let (kind, share) = if city > trees { (Content::Garbage(0), city) } else { (Content::Tree(0), trees) };
+            let base = rng.gen_range(settings.min_quantity..=settings.max_quantity.max(settings.min_quantity));
+            let quantity = (base as f64 * (1.0 + settings.bias * share as f64 / tiles as f64)).round() as usize;
+            let quantity = quantity.clamp(1, kind.properties().max().max(1));
+
+            let loot = match kind {
+                | Content::Garbage(_) => Content::Garbage(quantity),
+                | _ => Content::Tree(quantity),
+            };
+            world[row][col].content = Crate(1..(quantity + 1).clamp(2, max.max(2)));
+            manifest.push(CrateLoot {
+                position: Coordinate { row, col },
+                loot,
+            });
+        }
     }
+    manifest
 }
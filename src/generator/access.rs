@@ -0,0 +1,177 @@
+use std::collections::{HashSet, VecDeque};
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::reachability::neighbours;
+use crate::generator::reconcile::ContentRelocation;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Settings of the access check of the Banks, the Markets and the Bins, the content the robots interact with from a
+/// tile next to it: each of them is checked to have a walkable tile sharing a side, the ones enclosed by the mountains,
+/// the water or the lava are moved to the nearest free tile which has one.
+///
+/// Unlike the reachability check, which carves the terrain from the robot spawn, the terrain is left untouched and
+/// nothing is carved; the two can be enabled together, the access check runs first.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AccessSettings {
+    /// the max number of steps, between tiles sharing a side, from the tile of an enclosed content to its new tile
+    pub radius: usize,
+    /// move the enclosed content next to a Street if there is one within the radius, like in a city block, rather
+    /// than next to the nearest walkable tile
+    pub prefer_street: bool,
+}
+
+impl Default for AccessSettings {
+    /// Provides an instance of `AccessSettings` moving the enclosed content up to 10 tiles away, next to a street if
+    /// possible
+    fn default() -> Self {
+        AccessSettings {
+            radius: 10,
+            prefer_street: true,
+        }
+    }
+}
+
+impl AccessSettings {
+    /// Creates a new instance of `AccessSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius` - The max number of steps from the tile of an enclosed content to its new tile.
+    /// * `prefer_street` - Move the enclosed content next to a Street if there is one within the radius.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use exclusion_zone::generator::access::AccessSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.access_settings = Some(AccessSettings::new(20, true));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let access = artifacts.access.unwrap();
+    /// println!("{} of {} moved, {} next to a street", access.relocated.len(), access.checked, access.street_access);
+    /// ```
+    pub fn new(radius: usize, prefer_street: bool) -> Self {
+        AccessSettings {
+            radius,
+            prefer_street,
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::access::AccessSettings;
+    ///
+    /// assert!(AccessSettings::default().validate().is_empty());
+    /// assert!(!AccessSettings::new(0, true).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.radius > 0, "AccessSettings::radius", || {
+            "a radius of 0 moves no content, disable the access check instead".to_string()
+        });
+        issues.0
+    }
+}
+
+/// Result of the access check
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct AccessReport {
+    /// the number of Banks, Markets and Bins checked
+    pub checked: usize,
+    /// the number of them next to a Street once the enclosed ones are moved
+    pub street_access: usize,
+    /// the enclosed content moved next to a walkable tile, the new tile is always set
+    pub relocated: Vec<ContentRelocation>,
+    /// the enclosed content left in place because no free tile within the radius can hold it
+    pub enclosed: Vec<Coordinate>,
+}
+
+// the content the robot must be able to interact with
+#[inline(always)]
+fn is_interactive(content: &Content) -> bool {
+    matches!(content, Content::Bank(_) | Content::Market(_) | Content::Bin(_))
+}
+
+// whether any tile sharing a side with the given one satisfies the predicate
+#[inline(always)]
+fn has_neighbour(world: &TileMatrix, c: Coordinate, predicate: impl Fn(TileType) -> bool) -> bool {
+    neighbours(c, world.len()).any(|n| predicate(world[n.row][n.col].tile_type))
+}
+
+/// Moves each Bank, Market and Bin with no walkable tile sharing a side to the nearest free tile which can hold it and
+/// has one, next to a Street if `prefer_street` is set and one is within the radius; returns what was checked and moved.
+pub(crate) fn check_access(world: &mut TileMatrix, settings: &AccessSettings) -> AccessReport {
+    let mut report = AccessReport::default();
+    let mut interactive = Vec::new();
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if is_interactive(&tile.content) {
+                interactive.push(Coordinate { row, col });
+            }
+        }
+    }
+    report.checked = interactive.len();
+
+    for from in interactive {
+        let content = world[from.row][from.col].content.clone();
+        if !has_neighbour(world, from, |tile_type| tile_type.properties().walk()) {
+            match accessible_tile(world, from, &content, settings) {
+                | Some(to) => {
+                    world[from.row][from.col].content = Content::None;
+                    world[to.row][to.col].content = content.clone();
+                    report.relocated.push(ContentRelocation {
+                        content,
+                        from,
+                        to: Some(to),
+                    });
+                    if has_neighbour(world, to, |tile_type| tile_type == TileType::Street) {
+                        report.street_access += 1;
+                    }
+                }
+                | None => report.enclosed.push(from),
+            }
+        } else if has_neighbour(world, from, |tile_type| tile_type == TileType::Street) {
+            report.street_access += 1;
+        }
+    }
+    report
+}
+
+// breadth first search of the nearest empty tile which can hold the content and has a walkable tile sharing a side,
+// other than the tile of the content, the nearest one next to a Street first if preferred
+#[inline(always)]
+fn accessible_tile(world: &TileMatrix, start: Coordinate, content: &Content, settings: &AccessSettings) -> Option<Coordinate> {
+    let size = world.len();
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut walkable = None;
+
+    while let Some((current, distance)) = queue.pop_front() {
+        let tile = &world[current.row][current.col];
+        // the tile of the content is left out, it is enclosed
+        let walkable_neighbour = neighbours(current, size).any(|n| n != start && world[n.row][n.col].tile_type.properties().walk());
+        if current != start && tile.content == Content::None && tile.tile_type.properties().can_hold(&content.to_default()) && walkable_neighbour {
+            if !settings.prefer_street || has_neighbour(world, current, |tile_type| tile_type == TileType::Street) {
+                return Some(current);
+            }
+            walkable.get_or_insert(current);
+        }
+        if distance == settings.radius {
+            continue;
+        }
+        for next in neighbours(current, size) {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    walkable
+}
@@ -0,0 +1,200 @@
+use std::thread::available_parallelism;
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::batch::generate_batch;
+use crate::generator::{TileMatrix, WorldGenerator};
+
+/// The tile types in the order of the report
+const TILE_TYPES: [TileType; 11] = [
+    TileType::DeepWater,
+    TileType::ShallowWater,
+    TileType::Sand,
+    TileType::Grass,
+    TileType::Street,
+    TileType::Hill,
+    TileType::Mountain,
+    TileType::Snow,
+    TileType::Lava,
+    TileType::Teleport(false),
+    TileType::Wall,
+];
+
+/// The spread of a measure across the worlds of an ensemble
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// the mean of the measure
+    pub mean: f64,
+    /// the sample variance of the measure, 0 for a single world
+    pub variance: f64,
+    /// the lowest value of the measure
+    pub min: f64,
+    /// the highest value of the measure
+    pub max: f64,
+}
+
+impl Stats {
+    /// Returns the statistics of the given samples, all 0 if there are none
+    pub fn of(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Stats {
+            mean,
+            variance,
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    /// Returns the standard deviation of the measure
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+/// The distribution of the tile types and of the content across the worlds generated from the same settings with
+/// different seeds, to check that the settings give the intended worlds on average and how much a world can stray.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EnsembleReport {
+    /// the number of worlds generated
+    pub worlds: usize,
+    /// the percentage of the tiles of each tile type, every tile type is listed, the teleports whatever their state
+    pub tile_percentages: Vec<(TileType, Stats)>,
+    /// the number of tiles holding each kind of content found in at least one world, the kind is given by the default
+    /// value of the content; the worlds without that kind count as 0
+    pub content_counts: Vec<(Content, Stats)>,
+}
+
+impl EnsembleReport {
+    /// Returns the statistics of the percentage of the tiles of the given tile type
+    pub fn tile_percentage(&self, tile_type: TileType) -> Stats {
+        let index = tile_type_index(&tile_type);
+        self.tile_percentages.get(index).map_or_else(Stats::default, |(_, stats)| *stats)
+    }
+
+    /// Returns the statistics of the number of tiles holding the given kind of content, whatever its quantity
+    pub fn content_count(&self, content: &Content) -> Stats {
+        let kind = content.to_default();
+        self.content_counts.iter().find(|(other, _)| *other == kind).map_or_else(Stats::default, |(_, stats)| *stats)
+    }
+}
+
+/// Generates a world for each seed with the given settings, on as many threads as the machine has, and reports the
+/// mean and the variance of the share of each tile type and of the amount of each kind of content.
+///
+/// The worlds are generated with `generate_batch` and dropped as soon as they are counted, so thousands of seeds
+/// take the memory of a few worlds.
+///
+/// # Panics
+///
+/// Panics if the settings are invalid, like `gen`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use exclusion_zone::generator::analysis::ensemble_stats;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let seeds: Vec<u32> = (0..50).collect();
+/// let report = ensemble_stats(&WorldGenerator::default(300), &seeds);
+/// let grass = report.tile_percentage(TileType::Grass);
+/// println!("grass {:.1}% ± {:.1}", grass.mean, grass.std_dev());
+/// let banks = report.content_count(&Content::Bank(0..0));
+/// println!("banks between {} and {}", banks.min, banks.max);
+/// ```
+pub fn ensemble_stats(settings: &WorldGenerator, seeds: &[u32]) -> EnsembleReport {
+    let parallelism = available_parallelism().map_or(1, |n| n.get());
+    let counts: Vec<WorldCounts> = generate_batch(settings, seeds, parallelism).map(|(_, (world, ..))| WorldCounts::of(&world)).collect();
+
+    let tile_percentages = TILE_TYPES
+        .iter()
+        .enumerate()
+        .map(|(index, tile_type)| {
+            let samples: Vec<f64> = counts.iter().map(|world| world.tiles[index] as f64 / world.area as f64 * 100.0).collect();
+            (*tile_type, Stats::of(&samples))
+        })
+        .collect();
+
+    // every kind found in any world, in the order they were first found
+    let mut kinds: Vec<Content> = Vec::new();
+    for (kind, _) in counts.iter().flat_map(|world| world.contents.iter()) {
+        if !kinds.contains(kind) {
+            kinds.push(kind.clone());
+        }
+    }
+    let content_counts = kinds
+        .into_iter()
+        .map(|kind| {
+            let samples: Vec<f64> = counts
+                .iter()
+                .map(|world| world.contents.iter().find(|(other, _)| *other == kind).map_or(0.0, |(_, count)| *count as f64))
+                .collect();
+            (kind, Stats::of(&samples))
+        })
+        .collect();
+
+    EnsembleReport {
+        worlds: counts.len(),
+        tile_percentages,
+        content_counts,
+    }
+}
+
+// the tiles of each tile type and of each kind of content of a world
+struct WorldCounts {
+    area: usize,
+    // [tile_type_index] -> the number of tiles
+    tiles: [usize; TILE_TYPES.len()],
+    contents: Vec<(Content, usize)>,
+}
+
+impl WorldCounts {
+    fn of(world: &TileMatrix) -> Self {
+        let mut tiles = [0; TILE_TYPES.len()];
+        let mut contents: Vec<(Content, usize)> = Vec::new();
+        for tile in world.iter().flatten() {
+            tiles[tile_type_index(&tile.tile_type)] += 1;
+            if tile.content == Content::None {
+                continue;
+            }
+            let kind = tile.content.to_default();
+            match contents.iter_mut().find(|(content, _)| *content == kind) {
+                | Some((_, count)) => *count += 1,
+                | None => contents.push((kind, 1)),
+            }
+        }
+        WorldCounts {
+            area: (world.len() * world.len()).max(1),
+            tiles,
+            contents,
+        }
+    }
+}
+
+// the index of the tile type in `TILE_TYPES`
+#[inline(always)]
+fn tile_type_index(tile_type: &TileType) -> usize {
+    match tile_type {
+        | TileType::DeepWater => 0,
+        | TileType::ShallowWater => 1,
+        | TileType::Sand => 2,
+        | TileType::Grass => 3,
+        | TileType::Street => 4,
+        | TileType::Hill => 5,
+        | TileType::Mountain => 6,
+        | TileType::Snow => 7,
+        | TileType::Lava => 8,
+        | TileType::Teleport(_) => 9,
+        | TileType::Wall => 10,
+    }
+}
@@ -0,0 +1,89 @@
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::content::wood_crate::CrateLoot;
+use crate::generator::access::AccessReport;
+use crate::generator::balance::BalanceReport;
+use crate::generator::constraints::ConstraintReport;
+use crate::generator::content_index::ContentIndex;
+use crate::generator::decor::DecorLayer;
+use crate::generator::flow::FlowField;
+use crate::generator::hazards::HazardMap;
+use crate::generator::landmarks::Landmark;
+use crate::generator::options::Degradation;
+use crate::generator::pyramid::ElevationPyramid;
+use crate::generator::reachability::ReachabilityReport;
+use crate::generator::reconcile::ContentRelocation;
+use crate::generator::regions::RegionMap;
+use crate::generator::seeds::StageSeeds;
+use crate::generator::slope::SlopeMap;
+use crate::generator::spawn_heatmap::SpawnHeatmap;
+use crate::generator::spawn_report::SpawnReport;
+use crate::generator::spawn_view::SpawnView;
+use crate::generator::tags::TagMap;
+use crate::generator::vaults::VaultPair;
+use crate::generator::water_source::WaterSource;
+use crate::generator::Spawnables;
+use crate::utils::Coordinate;
+
+/// Extra data produced during generation, which does not fit in the `GenResult` required by
+/// the `Generator` trait but is useful to robots, visualizers and scenario scripts
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct GenerationArtifacts {
+    /// the checkpoints of the benchmark course, in the order the robot is expected to reach them
+    pub checkpoints: Vec<Coordinate>,
+    /// the continuous heightfield used to classify the terrain, indexed as `[row][col]`
+    pub elevation_map: Vec<Vec<f32>>,
+    /// the pyramid of the elevation map at decreasing resolutions, if enabled in the generation options
+    pub elevation_pyramid: Option<ElevationPyramid>,
+    /// the result of the reachability check of Banks, Markets and Bins
+    pub reachability: ReachabilityReport,
+    /// the result of the access check of Banks, Markets and Bins, `None` if the check is disabled
+    pub access: Option<AccessReport>,
+    /// the content moved or removed because the tile type of its tile changed after it was spawned,
+    /// by the cooled border of the lava and by the streets carved by the reachability repair
+    pub relocated_content: Vec<ContentRelocation>,
+    /// the totals computed by the score balancing pass, `None` if balancing is disabled
+    pub balance: Option<BalanceReport>,
+    /// the index of the content of the generated world, to query it without scanning the tile matrix
+    pub content_index: ContentIndex,
+    /// the distance of each walkable tile from the nearest Fire or Lava
+    pub hazard_map: HazardMap,
+    /// the tags of each tile, like being part of the city or a bridge, if the tags are enabled
+    pub tags: Option<TagMap>,
+    /// the content requested and actually placed by each spawn stage, in the spawn order
+    pub spawn_reports: Vec<(Spawnables, SpawnReport)>,
+    /// where each spawn stage considered, accepted and rejected its content, in the spawn order, only if the crate is
+    /// built with the `spawn_telemetry` feature
+    pub spawn_heatmaps: Vec<(Spawnables, SpawnHeatmap)>,
+    /// how the constraints on the terrain were met, if any
+    pub constraints: Option<ConstraintReport>,
+    /// the content spawned while the simulation runs, as `(tick, tile, content)` sorted by tick, if scheduled
+    pub timed_spawns: Vec<(u64, Coordinate, Content)>,
+    /// the seeds used by the spawn stages, to re-roll a single stage in a later generation
+    pub stage_seeds: Option<StageSeeds>,
+    /// the reactor and the radio masts placed in the world, for scenario scripting
+    pub landmarks: Vec<Landmark>,
+    /// the epicenter the contamination spreads from, if enabled
+    pub epicenter: Option<Coordinate>,
+    /// the loot of each wood crate, row by row, if the crates have loot
+    pub crate_manifest: Vec<CrateLoot>,
+    /// the robot spawn with the notable tile in its view and the direction to face it, if required by the settings
+    pub spawn_view: Option<SpawnView>,
+    /// the ShallowWater tile near the robot spawn and the Water content next to it, if required by the settings
+    pub water_source: Option<WaterSource>,
+    /// the vault and key pairs of the bank robbery scenario, if enabled
+    pub vault_pairs: Vec<VaultPair>,
+    /// the slope of each tile, with the steep tiles and the cliffs, if the slope analysis is enabled
+    pub slope: Option<SlopeMap>,
+    /// the direction and the speed of the current on each water tile, if the flow field is enabled
+    pub flow_field: Option<FlowField>,
+    /// the Street tiles of the gates of the fence, clockwise along it, if the fence is enabled
+    pub fence_gates: Vec<Coordinate>,
+    /// the world segmented in named regions, if the segmentation is enabled
+    pub regions: Option<RegionMap>,
+    /// the visual markers drawn by the visualizer under the content of each tile, if the decor is enabled
+    pub decor: Option<DecorLayer>,
+    /// the stages simplified or skipped because the time budget was exceeded
+    pub degraded: Vec<Degradation>,
+}
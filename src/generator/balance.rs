@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+
+// scaling the quantities is repeated because rounding and per tile limits move the score away from the target
+const MAX_BALANCE_ITERATIONS: usize = 10;
+
+/// Settings of the balancing pass, which scales the quantity of coins, the capacity of banks and
+/// the quantity of trees so that the score achievable on the map matches a target max score.
+///
+/// The achievable score is the sum, over every tile, of the quantity of its content multiplied by the weight of the content.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBalance {
+    /// the max score the map should allow
+    pub target_max_score: f32,
+    /// accepted relative distance between the achievable score and the target, 0.05 means ±5%
+    pub tolerance: f32,
+    /// points given by each coin
+    pub coin_weight: f32,
+    /// points given by each unit of bank capacity
+    pub bank_weight: f32,
+    /// points given by each unit of wood of the trees
+    pub tree_weight: f32,
+}
+
+impl Default for ScoreBalance {
+    fn default() -> Self {
+        ScoreBalance {
+            target_max_score: 100.0,
+            tolerance: 0.05,
+            coin_weight: 1.0,
+            bank_weight: 1.0,
+            tree_weight: 1.0,
+        }
+    }
+}
+
+impl ScoreBalance {
+    /// Creates a new instance of `ScoreBalance` with the given target and tolerance, every content weighting 1 point per unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_max_score` - The max score the map should allow.
+    /// * `tolerance` - The accepted relative distance between the achievable score and the target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::balance::ScoreBalance;
+    ///
+    /// let balance = ScoreBalance::new(5000.0, 0.01);
+    /// ```
+    pub fn new(target_max_score: f32, tolerance: f32) -> Self {
+        ScoreBalance {
+            target_max_score,
+            tolerance,
+            ..Default::default()
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::balance::ScoreBalance;
+    ///
+    /// assert!(!ScoreBalance::new(0.0, 0.01).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.target_max_score.is_finite() && self.target_max_score > 0.0, "ScoreBalance::target_max_score", || {
+            format!("{} cannot be reached by scaling the content, use a score greater than 0", self.target_max_score)
+        });
+        issues.check(self.tolerance.is_finite() && self.tolerance >= 0.0, "ScoreBalance::tolerance", || {
+            format!("{} is not a relative distance, use a value not lower than 0", self.tolerance)
+        });
+        for (setting, weight) in [
+            ("ScoreBalance::coin_weight", self.coin_weight),
+            ("ScoreBalance::bank_weight", self.bank_weight),
+            ("ScoreBalance::tree_weight", self.tree_weight),
+        ] {
+            issues.check(weight.is_finite() && weight >= 0.0, setting, || format!("{weight} is not a valid weight, use a value not lower than 0"));
+        }
+        issues.0
+    }
+
+    /// The score table matching the weights, as returned in the `GenResult`
+    pub fn score_table(&self) -> HashMap<Content, f32> {
+        HashMap::from([(Content::Coin(0), self.coin_weight), (Content::Bank(0..0), self.bank_weight), (Content::Tree(0), self.tree_weight)])
+    }
+
+    #[inline(always)]
+    fn weight(&self, content: &Content) -> Option<f32> {
+        match content {
+            | Content::Coin(_) => Some(self.coin_weight),
+            | Content::Bank(_) => Some(self.bank_weight),
+            | Content::Tree(_) => Some(self.tree_weight),
+            | _ => None,
+        }
+    }
+}
+
+/// Totals computed by the balancing pass
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct BalanceReport {
+    /// the total number of coins on the map
+    pub coins: usize,
+    /// the total capacity of the banks on the map
+    pub bank_capacity: usize,
+    /// the total quantity of wood of the trees on the map
+    pub trees: usize,
+    /// the score achievable on the map after balancing
+    pub achieved_score: f32,
+    /// whether the achieved score is within the tolerance of the target
+    pub within_tolerance: bool,
+}
+
+// the quantity counting towards the score, for banks the capacity still available
+#[inline(always)]
+fn quantity(content: &Content) -> usize {
+    match content {
+        | Content::Coin(q) | Content::Tree(q) => *q,
+        | Content::Bank(r) => r.end.saturating_sub(r.start),
+        | _ => 0,
+    }
+}
+
+// the same content with a new quantity, coins and trees scaled down to 0 are removed, banks are always kept
+#[inline(always)]
+fn with_quantity(content: &Content, q: usize) -> Content {
+    match content {
+        | Content::Bank(r) => Content::Bank(r.start..r.start + q.clamp(1, Content::Bank(0..0).properties().max())),
+        | _ if q == 0 => Content::None,
+        | Content::Coin(_) => Content::Coin(q.min(Content::Coin(0).properties().max())),
+        | Content::Tree(_) => Content::Tree(q.min(Content::Tree(0).properties().max())),
+        | other => other.clone(),
+    }
+}
+
+#[inline(always)]
+fn achievable_score(world: &TileMatrix, balance: &ScoreBalance) -> f32 {
+    world
+        .iter()
+        .flatten()
+        .filter_map(|tile| balance.weight(&tile.content).map(|w| w * quantity(&tile.content) as f32))
+        .sum()
+}
+
+/// Scales the balanced content until the achievable score is within the tolerance of the target.
+///
+/// Quantities are multiplied by the ratio between the target and the current score, rounded and limited to the
+/// max quantity a tile can hold; coins and trees scaled down to 0 are removed, banks keep a capacity of at least 1.
+/// If the limits prevent reaching the target a warning is logged and the report contains the closest score reached.
+pub(crate) fn balance_score(world: &mut TileMatrix, balance: &ScoreBalance) -> BalanceReport {
+    let target = balance.target_max_score;
+    let mut score = achievable_score(world, balance);
+
+    for _ in 0..MAX_BALANCE_ITERATIONS {
+        if score <= 0.0 || (score - target).abs() <= balance.tolerance * target {
+            break;
+        }
+
+        let factor = target / score;
+        for tile in world.iter_mut().flatten() {
+            if balance.weight(&tile.content).is_some() {
+                let q = quantity(&tile.content);
+                tile.content = with_quantity(&tile.content, (q as f32 * factor).round() as usize);
+            }
+        }
+
+        let previous = score;
+        score = achievable_score(world, balance);
+        // the per tile limits stop any further progress
+        if score == previous {
+            break;
+        }
+    }
+
+    let mut report = BalanceReport {
+        achieved_score: score,
+        within_tolerance: (score - target).abs() <= balance.tolerance * target,
+        ..Default::default()
+    };
+    for tile in world.iter().flatten() {
+        match &tile.content {
+            | Content::Coin(q) => report.coins += q,
+            | Content::Bank(_) => report.bank_capacity += quantity(&tile.content),
+            | Content::Tree(q) => report.trees += q,
+            | _ => {}
+        }
+    }
+
+    if !report.within_tolerance {
+        warn!(score, target, "ScoreNotBalanced: the limits of the content prevent reaching the target score");
+    }
+    report
+}
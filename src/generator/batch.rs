@@ -0,0 +1,126 @@
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::generator::{GenResult, WorldGenerator};
+
+/// Generates a world for each seed with the same settings, on `parallelism` threads, to sweep many seeds and pick
+/// the worlds meeting some criteria.
+///
+/// Only the seed of the noise settings changes between the worlds; the seeds of the spawn stages, if set in the
+/// settings, are kept, so each world is the one `gen` returns for the settings with that seed.
+/// The worlds are yielded as soon as they are generated, so not in the order of the seeds, paired with their seed;
+/// at most `parallelism` generated worlds wait to be consumed, so the memory stays bounded even for thousands of seeds.
+/// A `parallelism` of 0 is treated as 1.
+///
+/// # Panics
+///
+/// Panics if the settings are invalid, like `gen`.
+///
+/// # Examples
+///
+/// Keeping the worlds with at least 30% of land and 2 banks:
+///
+/// ```no_run
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use exclusion_zone::generator::batch::generate_batch;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let seeds: Vec<u32> = (0..200).collect();
+/// let picked: Vec<u32> = generate_batch(&WorldGenerator::default(300), &seeds, 4)
+///     .filter(|(_, (world, ..))| {
+///         let tiles = world.iter().flatten();
+///         let land = tiles.clone().filter(|tile| !matches!(tile.tile_type, TileType::DeepWater | TileType::ShallowWater)).count();
+///         let banks = tiles.filter(|tile| matches!(tile.content, Content::Bank(_))).count();
+///         land * 10 >= world.len() * world.len() * 3 && banks >= 2
+///     })
+///     .map(|(seed, _)| seed)
+///     .collect();
+/// ```
+pub fn generate_batch(settings: &WorldGenerator, seeds: &[u32], parallelism: usize) -> impl Iterator<Item = (u32, GenResult)> {
+    generate_batch_with_thumbnails(settings, seeds, parallelism, |_, _| ()).map(|(seed, world, _)| (seed, world))
+}
+
+/// Like `generate_batch`, also rendering a thumbnail of each world with the given renderer, on the thread which
+/// generated it, so that the rendering runs in parallel too.
+///
+/// The thumbnail can be anything the renderer returns, like an image or a score; a renderer returning the thumbnail
+/// alone allows dropping the worlds that are not picked, keeping only their thumbnails.
+///
+/// # Panics
+///
+/// Panics if the settings are invalid, like `gen`, or if the renderer panics.
+///
+/// # Examples
+///
+/// ```no_run
+/// use exclusion_zone::generator::batch::generate_batch_with_thumbnails;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let settings = WorldGenerator::default(300);
+/// for (seed, _, thumbnail) in generate_batch_with_thumbnails(&settings, &[1, 2, 3], 3, |_, (world, ..)| world.len()) {
+///     println!("{seed}: {thumbnail}");
+/// }
+/// ```
+pub fn generate_batch_with_thumbnails<T, R>(settings: &WorldGenerator, seeds: &[u32], parallelism: usize, renderer: R) -> impl Iterator<Item = (u32, GenResult, T)>
+where
+    T: Send + 'static,
+    R: Fn(u32, &GenResult) -> T + Send + Sync + 'static,
+{
+    // checked once here, a panic in the workers would only end the batch early
+    if let Err(issues) = settings.validate_all() {
+        panic!("Invalid settings:\n{}", issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>().join("\n"));
+    }
+
+    let parallelism = parallelism.max(1);
+    let seeds: Arc<Vec<u32>> = Arc::new(seeds.to_vec());
+    let next = Arc::new(AtomicUsize::new(0));
+    let renderer = Arc::new(renderer);
+    let (sender, receiver) = sync_channel(parallelism);
+
+    let workers = (0..parallelism.min(seeds.len()))
+        .map(|_| {
+            let (settings, seeds, next, renderer, sender) = (settings.clone(), seeds.clone(), next.clone(), renderer.clone(), sender.clone());
+            std::thread::spawn(move || {
+                while let Some(&seed) = seeds.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let mut generator = settings.clone();
+                    generator.noise_settings.seed = seed;
+                    let (world, _) = generator.gen_with_artifacts();
+                    let thumbnail = renderer(seed, &world);
+                    if sender.send((seed, world, thumbnail)).is_err() {
+                        // the batch has been dropped
+                        return;
+                    }
+                }
+            })
+        })
+        .collect();
+
+    Batch { receiver, workers }
+}
+
+// the worlds sent by the workers, rethrowing the panic of a worker once they are all done
+struct Batch<T> {
+    receiver: Receiver<(u32, GenResult, T)>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<T> Iterator for Batch<T> {
+    type Item = (u32, GenResult, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.receiver.recv() {
+            | Ok(item) => Some(item),
+            | Err(_) => {
+                // every worker has ended
+                let panics: Vec<Box<dyn Any + Send>> = self.workers.drain(..).filter_map(|worker| worker.join().err()).collect();
+                if let Some(panic) = panics.into_iter().next() {
+                    std::panic::resume_unwind(panic);
+                }
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs::{create_dir_all, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use zstd::stream::copy_encode;
+use zstd::stream::read::Decoder;
+
+use crate::generator::{NoiseSettings, Thresholds, TileMatrix};
+
+/// Compression level of the cache files, low because the cache favours speed over size
+const CACHE_COMPRESSION_LEVEL: i32 = 3;
+
+// the settings which fully define the noise map and the terrain classification
+#[derive(Serialize)]
+struct TerrainKey<'a> {
+    size: usize,
+    noise_settings: &'a NoiseSettings,
+    thresholds: &'a Thresholds,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedTerrain {
+    // the serialized key, compared on load to rule out hash collisions
+    key: Vec<u8>,
+    noise_map: Vec<Vec<f64>>,
+    terrain: TileMatrix,
+}
+
+// FNV-1a, stable across compiler versions unlike the std hasher, so cache file names stay valid
+#[inline(always)]
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Returns the noise map and the terrain stored in the cache directory for the given settings,
+/// generating and storing them when missing.
+///
+/// Cache failures never stop the generation, the terrain is generated again instead.
+pub(crate) fn cached_terrain(
+    cache_dir: &str,
+    size: usize,
+    noise_settings: &NoiseSettings,
+    thresholds: &Thresholds,
+    generate: impl FnOnce() -> (Vec<Vec<f64>>, TileMatrix),
+) -> (Vec<Vec<f64>>, TileMatrix) {
+    let key = match bincode::serialize(&TerrainKey {
+        size,
+        noise_settings,
+        thresholds,
+    }) {
+        | Ok(k) => k,
+        | Err(_) => return generate(),
+    };
+    let file_path = Path::new(cache_dir).join(format!("terrain-{:016x}.zst", fnv1a(&key)));
+
+    match load(&file_path) {
+        | Ok(cached) if cached.key == key => {
+            debug!(path = %file_path.display(), "terrain loaded from cache");
+            return (cached.noise_map, cached.terrain);
+        }
+        | _ => {}
+    }
+
+    let (noise_map, terrain) = generate();
+    let cached = CachedTerrain {
+        key,
+        noise_map,
+        terrain,
+    };
+    if let Err(e) = store(cache_dir, &file_path, &cached) {
+        warn!(path = %file_path.display(), error = %e, "unable to store the terrain in cache");
+    }
+    (cached.noise_map, cached.terrain)
+}
+
+#[inline(always)]
+fn load(file_path: &Path) -> io::Result<CachedTerrain> {
+    let mut buffer = Vec::new();
+    Decoder::new(File::open(file_path)?)?.read_to_end(&mut buffer)?;
+    bincode::deserialize(&buffer).map_err(|e| io::Error::other(format!("Deserialization failed: {}", e)))
+}
+
+#[inline(always)]
+fn store(cache_dir: &str, file_path: &Path, cached: &CachedTerrain) -> io::Result<()> {
+    create_dir_all(cache_dir)?;
+    let serialized = bincode::serialize(cached).map_err(|e| io::Error::other(format!("Serialization failed: {}", e)))?;
+    copy_encode(&*serialized, File::create(file_path)?, CACHE_COMPRESSION_LEVEL)
+}
@@ -0,0 +1,274 @@
+use std::cmp::max;
+
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
+use robotics_lib::world::tile::Tile;
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{robot_spawn, GenResult, TileMatrix};
+use crate::utils::{into_maybe_par_iter, Position};
+
+/// Default number of tiles, on each side of a seam, over which the elevation is blended
+pub const DEFAULT_SEAM_WIDTH: usize = 4;
+
+/// Defines where a generated region is placed inside the composed world and how much of it is used.
+///
+/// The region tile matrix is cropped to `width` x `height` starting from its top left corner,
+/// then copied into the composed world starting at (`row`, `col`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OffsetRect {
+    /// the row of the composed world where the region starts
+    pub row: usize,
+    /// the column of the composed world where the region starts
+    pub col: usize,
+    /// the number of columns of the region to use
+    pub width: usize,
+    /// the number of rows of the region to use
+    pub height: usize,
+}
+
+impl OffsetRect {
+    /// Creates a new instance of `OffsetRect` with the given offset and dimensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row of the composed world where the region starts.
+    /// * `col` - The column of the composed world where the region starts.
+    /// * `width` - The number of columns of the region to use.
+    /// * `height` - The number of rows of the region to use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::compose::OffsetRect;
+    ///
+    /// // the bottom right quadrant of a 2000x2000 world
+    /// let rect = OffsetRect::new(1000, 1000, 1000, 1000);
+    /// ```
+    pub fn new(row: usize, col: usize, width: usize, height: usize) -> Self {
+        OffsetRect {
+            row,
+            col,
+            width,
+            height,
+        }
+    }
+}
+
+/// Stitches multiple generated regions into one world, blending the elevation at the seams.
+///
+/// Same as [`compose_with_blending`] using [`DEFAULT_SEAM_WIDTH`] as seam width.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::compose::{compose, OffsetRect};
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let volcanic = WorldGenerator::default(100).gen();
+/// let city = WorldGenerator::default(100).gen();
+/// let volcanic_spawn = volcanic.1;
+///
+/// let (tiles, spawn, ..) = compose(vec![
+///     (volcanic, OffsetRect::new(0, 0, 50, 100)),
+///     (city, OffsetRect::new(0, 50, 50, 100)),
+/// ])
+/// .unwrap();
+/// assert_eq!(tiles.len(), 100);
+/// assert!(tiles.iter().all(|row| row.len() == 100));
+/// // the robot spawns where the volcanic region spawned it, or on the first walkable tile if it was cropped out
+/// if volcanic_spawn.1 < 50 {
+///     assert_eq!(spawn, volcanic_spawn);
+/// }
+/// assert!(tiles[spawn.0][spawn.1].tile_type.properties().walk());
+/// ```
+///
+/// # Errors
+///
+/// Returns the same issues as [`compose_with_blending`].
+pub fn compose(regions: Vec<(GenResult, OffsetRect)>) -> Result<GenResult, Vec<SettingsIssue>> {
+    compose_with_blending(regions, DEFAULT_SEAM_WIDTH)
+}
+
+/// Stitches multiple generated regions into one world, blending the elevation at the seams.
+///
+/// Regions are copied in the given order, if two regions overlap the latter overwrites the former.
+/// The composed world must be a square completely covered by the regions.
+///
+/// # Arguments
+///
+/// * `regions` - The generated regions and where to place them.
+/// * `seam_width` - The number of tiles, on each side of a seam, over which the elevation is blended, 0 disables blending.
+///
+/// # Returns
+///
+/// The composed world, having:
+/// - the robot spawn point of the first region, moved by its offset;
+/// - the environmental conditions of the first region;
+/// - the sum of the max score of each region;
+/// - the first score table provided by the regions.
+///
+/// # Errors
+///
+/// Returns the issues found if no region is provided, if a region is smaller than its `OffsetRect`
+/// or if the composed world is not completely covered.
+pub fn compose_with_blending(regions: Vec<(GenResult, OffsetRect)>, seam_width: usize) -> Result<GenResult, Vec<SettingsIssue>> {
+    let mut issues = Issues::default();
+    issues.check(!regions.is_empty(), "compose::regions", || "at least one region is required to compose a world".to_string());
+
+    // the composed world is a square big enough to contain every region
+    let size = regions.iter().map(|(_, rect)| max(rect.row + rect.height, rect.col + rect.width)).max().unwrap_or(0);
+
+    let mut tiles: Vec<Vec<Option<Tile>>> = vec![vec![None; size]; size];
+    let mut owners: Vec<Vec<usize>> = vec![vec![usize::MAX; size]; size];
+
+    for (index, (region, rect)) in regions.iter().enumerate() {
+        let region_tiles = &region.0;
+        let fits = region_tiles.len() >= rect.height && region_tiles.iter().all(|row| row.len() >= rect.width);
+        issues.check(fits, &format!("compose::regions[{index}]"), || {
+            format!(
+                "the region is {}x{} but its OffsetRect requires at least {}x{}",
+                region_tiles.len(),
+                region_tiles.first().map_or(0, |row| row.len()),
+                rect.height,
+                rect.width
+            )
+        });
+        if !fits {
+            continue;
+        }
+
+        for (row, region_row) in region_tiles.iter().take(rect.height).enumerate() {
+            for (col, tile) in region_row.iter().take(rect.width).enumerate() {
+                tiles[rect.row + row][rect.col + col] = Some(tile.clone());
+                owners[rect.row + row][rect.col + col] = index;
+            }
+        }
+    }
+
+    // the regions too small leave holes, already reported
+    if issues.0.is_empty() {
+        let missing = tiles.iter().enumerate().find_map(|(row, tiles_row)| tiles_row.iter().position(Option::is_none).map(|col| (row, col)));
+        issues.check(missing.is_none(), "compose::regions", || {
+            let (row, col) = missing.unwrap_or_default();
+            format!("the composed world is not completely covered, tile ({row}, {col}) is missing")
+        });
+    }
+    if !issues.0.is_empty() {
+        return Err(issues.0);
+    }
+
+    let mut world: TileMatrix = tiles.into_iter().map(|tiles_row| tiles_row.into_iter().flatten().collect()).collect();
+
+    if seam_width > 0 {
+        blend_seams(&mut world, &owners, seam_width);
+    }
+
+    // spawn the robot where the first region would have spawned it
+    let (first, first_rect) = &regions[0];
+    let spawn = Position::from_spawn(first.1);
+    let spawn = if spawn.row() < first_rect.height && spawn.col() < first_rect.width {
+        Position::from_row_col(spawn.row() + first_rect.row, spawn.col() + first_rect.col)
+    } else {
+        robot_spawn(&world)
+    };
+
+    let max_score = regions.iter().map(|(region, _)| region.3).sum();
+    let score_table = regions.iter().find_map(|(region, _)| region.4.clone());
+
+    Ok((world, spawn.spawn(), first.2.clone(), max_score, score_table))
+}
+
+/// Cuts a generated world into `n` x `n` square sub-worlds, so that a large world can be distributed across multiple
+/// simulation workers, the inverse of [`compose`].
+///
+/// The parts are returned row by row, the part at row `i` and column `j` of the grid is at index `i * n + j`. Each part has:
+/// - the robot spawn point of the world, moved into the part, if it falls inside it, otherwise the first walkable tile of the part;
+/// - a copy of the environmental conditions of the world;
+/// - the max score of the world divided evenly among the parts;
+/// - the score table of the world.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::compose::split;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let world = WorldGenerator::default(1000).gen();
+/// let parts = split(&world, 4).unwrap();
+/// assert_eq!(parts.len(), 16);
+/// assert_eq!(parts[0].0.len(), 250);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `n` is 0 or the side of the world is not a multiple of `n`.
+pub fn split(world: &GenResult, n: usize) -> Result<Vec<GenResult>, String> {
+    let (tiles, spawn, environmental_conditions, max_score, score_table) = world;
+    let spawn = Position::from_spawn(*spawn);
+    let size = tiles.len();
+    if n == 0 || size % n != 0 {
+        return Err(format!("A {size}x{size} world cannot be split in {n}x{n} square parts, the side must be a multiple of {n}"));
+    }
+
+    let side = size / n;
+    let mut parts = Vec::with_capacity(n * n);
+    for part_row in 0..n {
+        for part_col in 0..n {
+            let (row, col) = (part_row * side, part_col * side);
+            let part: TileMatrix = tiles[row..row + side].iter().map(|tiles_row| tiles_row[col..col + side].to_vec()).collect();
+
+            let part_spawn = if (row..row + side).contains(&spawn.row()) && (col..col + side).contains(&spawn.col()) {
+                Position::from_row_col(spawn.row() - row, spawn.col() - col)
+            } else {
+                robot_spawn(&part)
+            };
+            parts.push((part, part_spawn.spawn(), environmental_conditions.clone(), max_score / (n * n) as f32, score_table.clone()));
+        }
+    }
+    Ok(parts)
+}
+
+// average the elevation of the tiles near a seam with their neighbours belonging to other regions
+#[inline(always)]
+fn blend_seams(world: &mut TileMatrix, owners: &[Vec<usize>], seam_width: usize) {
+    let size = world.len();
+    let elevations: Vec<Vec<usize>> = world.iter().map(|row| row.iter().map(|tile| tile.elevation).collect()).collect();
+
+    let blended: Vec<Vec<Option<usize>>> = into_maybe_par_iter(0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    let row_range = row.saturating_sub(seam_width)..(row + seam_width + 1).min(size);
+                    let col_range = col.saturating_sub(seam_width)..(col + seam_width + 1).min(size);
+                    let owner = owners[row][col];
+
+                    let near_seam = row_range.clone().any(|r| col_range.clone().any(|c| owners[r][c] != owner));
+                    if !near_seam {
+                        return None;
+                    }
+
+                    let mut sum = 0;
+                    let mut count = 0;
+                    for r in row_range {
+                        for c in col_range.clone() {
+                            sum += elevations[r][c];
+                            count += 1;
+                        }
+                    }
+                    Some(sum / count)
+                })
+                .collect()
+        })
+        .collect();
+
+    for (row, blended_row) in blended.into_iter().enumerate() {
+        for (col, elevation) in blended_row.into_iter().enumerate() {
+            if let Some(e) = elevation {
+                world[row][col].elevation = e;
+            }
+        }
+    }
+}
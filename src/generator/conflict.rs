@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::mem::discriminant;
+
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::Spawnables;
+
+/// Defines what happens when a spawnable is placed on a tile already holding content
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum ConflictPolicy {
+    /// the new content is not placed, the tile keeps the content spawned first
+    Skip,
+    /// the new content replaces the one spawned first
+    #[default]
+    Replace,
+    /// if the tile holds the same kind of content the quantities are summed, up to the max of the content,
+    /// otherwise the new content is not placed
+    Stack,
+}
+
+/// The conflict policy of each spawnable.
+///
+/// The policy applies to the content spawned following the spawn order, the terrain stages,
+/// like streets and lava, run before any content is spawned.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConflictSettings {
+    /// the policy of the spawnables without an override
+    pub default_policy: ConflictPolicy,
+    /// the policy of specific spawnables
+    pub overrides: HashMap<Spawnables, ConflictPolicy>,
+}
+
+impl Default for ConflictSettings {
+    /// Every spawnable replaces the content spawned before it, except garbage and fish
+    /// which are only placed on empty tiles
+    fn default() -> Self {
+        ConflictSettings {
+            default_policy: ConflictPolicy::Replace,
+            overrides: HashMap::from([(Spawnables::Garbage, ConflictPolicy::Skip), (Spawnables::Fish, ConflictPolicy::Skip)]),
+        }
+    }
+}
+
+impl ConflictSettings {
+    /// Creates a new instance of `ConflictSettings` with the same policy for every spawnable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::conflict::{ConflictPolicy, ConflictSettings};
+    /// use exclusion_zone::generator::{Spawnables, WorldGenerator};
+    ///
+    /// // the content spawned first always wins, but coins pile up
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.conflict_settings = ConflictSettings::new(ConflictPolicy::Skip).with_override(Spawnables::Coin, ConflictPolicy::Stack);
+    /// ```
+    pub fn new(default_policy: ConflictPolicy) -> Self {
+        ConflictSettings {
+            default_policy,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Overrides the policy of the given spawnable
+    pub fn with_override(mut self, spawnable: Spawnables, policy: ConflictPolicy) -> Self {
+        self.overrides.insert(spawnable, policy);
+        self
+    }
+
+    /// Returns the policy of the given spawnable
+    pub fn policy(&self, spawnable: Spawnables) -> ConflictPolicy {
+        self.overrides.get(&spawnable).copied().unwrap_or(self.default_policy)
+    }
+}
+
+/// Returns the content resulting from stacking `added` on `existing`, `None` if they are not the same kind of content
+pub(crate) fn stack(existing: &Content, added: &Content) -> Option<Content> {
+    if discriminant(existing) != discriminant(added) {
+        return None;
+    }
+
+    let max = existing.properties().max();
+    let stacked = match (existing, added) {
+        | (Content::Rock(a), Content::Rock(b)) => Content::Rock((a + b).min(max)),
+        | (Content::Tree(a), Content::Tree(b)) => Content::Tree((a + b).min(max)),
+        | (Content::Garbage(a), Content::Garbage(b)) => Content::Garbage((a + b).min(max)),
+        | (Content::Coin(a), Content::Coin(b)) => Content::Coin((a + b).min(max)),
+        | (Content::Water(a), Content::Water(b)) => Content::Water((a + b).min(max)),
+        | (Content::Market(a), Content::Market(b)) => Content::Market((a + b).min(max)),
+        | (Content::Fish(a), Content::Fish(b)) => Content::Fish((a + b).min(max)),
+        | (Content::Bush(a), Content::Bush(b)) => Content::Bush((a + b).min(max)),
+        | (Content::JollyBlock(a), Content::JollyBlock(b)) => Content::JollyBlock((a + b).min(max)),
+        | (Content::Bin(a), Content::Bin(b)) => Content::Bin(a.start..(a.end + b.end).min(max)),
+        | (Content::Crate(a), Content::Crate(b)) => Content::Crate(a.start..(a.end + b.end).min(max)),
+        | (Content::Bank(a), Content::Bank(b)) => Content::Bank(a.start..(a.end + b.end).min(max)),
+        // fire, buildings and scarecrows have no quantity
+        | _ => existing.clone(),
+    };
+    Some(stacked)
+}
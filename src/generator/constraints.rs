@@ -0,0 +1,208 @@
+use robotics_lib::world::tile::TileType;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{Thresholds, TileMatrix, WorldGenerator};
+use crate::utils::{find_max_value, find_min_value};
+
+/// An archetype of world the terrain is required to match
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum WorldConstraint {
+    /// the land never touches the border of the world, every border tile is DeepWater or ShallowWater
+    IslandOnly,
+    /// the world has no DeepWater, its water is only lakes and marshes of ShallowWater
+    NoOcean,
+    /// at least the given share of the tiles, between 0 and 1, is land, neither DeepWater nor ShallowWater
+    MinLandFraction(f64),
+}
+
+impl WorldConstraint {
+    /// Returns true if the terrain matches the constraint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::constraints::WorldConstraint;
+    /// use exclusion_zone::generator::mini::MiniWorldGenerator;
+    ///
+    /// let (world, ..) = MiniWorldGenerator::new(50).gen();
+    /// assert!(WorldConstraint::MinLandFraction(0.0).is_satisfied(&world));
+    /// ```
+    pub fn is_satisfied(&self, world: &TileMatrix) -> bool {
+        match self {
+            | WorldConstraint::IslandOnly => {
+                let last = world.len().saturating_sub(1);
+                world
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(row, tiles)| tiles.iter().enumerate().filter(move |(col, _)| row == 0 || row == last || *col == 0 || *col == last))
+                    .all(|(_, tile)| is_water(tile.tile_type))
+            }
+            | WorldConstraint::NoOcean => world.iter().flatten().all(|tile| tile.tile_type != TileType::DeepWater),
+            | WorldConstraint::MinLandFraction(fraction) => {
+                let area = world.iter().map(|row| row.len()).sum::<usize>().max(1);
+                let land = world.iter().flatten().filter(|tile| !is_water(tile.tile_type)).count();
+                land as f64 / area as f64 >= *fraction
+            }
+        }
+    }
+}
+
+/// Settings of the constraints on the terrain, checked on the terrain classified from the elevation map, before the
+/// weather, the streets and the lava change it.
+///
+/// When the terrain does not match a constraint, the generator first adjusts the thresholds, which makes the world
+/// match `NoOcean` and `MinLandFraction` on most elevation maps, then retries with the next seed, up to `max_attempts`
+/// elevation maps. If no attempt matches, the world of the last attempt is generated and a warning is logged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConstraintSettings {
+    /// the constraints the terrain is required to match
+    pub constraints: Vec<WorldConstraint>,
+    /// the max number of elevation maps generated, the first one included
+    pub max_attempts: usize,
+}
+
+impl ConstraintSettings {
+    /// Creates a new instance of `ConstraintSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `constraints` - The constraints the terrain is required to match.
+    /// * `max_attempts` - The max number of elevation maps generated, each attempt after the first uses the next seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::constraints::{ConstraintSettings, WorldConstraint};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.constraint_settings = Some(ConstraintSettings::new(vec![WorldConstraint::IslandOnly, WorldConstraint::MinLandFraction(0.3)], 20));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let report = artifacts.constraints.unwrap();
+    /// // the generator keeps the seed and the thresholds of the generated world
+    /// println!("seed {} found after {} attempts", report.seed, report.attempts);
+    /// ```
+    pub fn new(constraints: Vec<WorldConstraint>, max_attempts: usize) -> Self {
+        ConstraintSettings {
+            constraints,
+            max_attempts,
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::constraints::{ConstraintSettings, WorldConstraint};
+    ///
+    /// assert!(!ConstraintSettings::new(vec![WorldConstraint::MinLandFraction(1.5)], 10).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.max_attempts > 0, "ConstraintSettings::max_attempts", || "the terrain needs at least an attempt".to_string());
+        for constraint in &self.constraints {
+            if let WorldConstraint::MinLandFraction(fraction) = constraint {
+                issues.probability("ConstraintSettings::constraints", *fraction);
+            }
+        }
+        issues.0
+    }
+}
+
+/// How the constraints on the terrain were met
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConstraintReport {
+    /// the number of elevation maps generated
+    pub attempts: usize,
+    /// the seed of the generated world
+    pub seed: u32,
+    /// true if the thresholds have been adjusted
+    pub adjusted_thresholds: bool,
+    /// the constraints the generated world does not match, empty if it matches all of them
+    pub unsatisfied: Vec<WorldConstraint>,
+}
+
+/// Generates the base terrain until it matches the constraints, adjusting the thresholds and trying the next seeds.
+///
+/// The seed and the thresholds of the generator are left to the ones of the generated terrain, so that the
+/// settings reproduce the world.
+pub(crate) fn constrained_base_terrain(generator: &mut WorldGenerator, settings: &ConstraintSettings) -> ((Vec<Vec<f64>>, TileMatrix), ConstraintReport) {
+    let thresholds = generator.thresholds;
+    let mut report = ConstraintReport::default();
+
+    loop {
+        report.attempts += 1;
+        generator.thresholds = thresholds;
+        let (noise_map, mut world) = generator.cached_base_terrain(Vec::new());
+        report.unsatisfied = unsatisfied(&settings.constraints, &world);
+
+        if !report.unsatisfied.is_empty() {
+            let (min, max) = (find_min_value(&noise_map).unwrap_or(f64::MAX), find_max_value(&noise_map).unwrap_or(f64::MIN));
+            if let Some(adjusted) = adjust_thresholds(&thresholds, &settings.constraints, &noise_map, min, max) {
+                generator.thresholds = adjusted;
+                world = generator.generate_terrain(&noise_map, min, max);
+                report.unsatisfied = unsatisfied(&settings.constraints, &world);
+            }
+        }
+
+        report.adjusted_thresholds = generator.thresholds.threshold_shallow_water != thresholds.threshold_shallow_water || generator.thresholds.threshold_deep_water != thresholds.threshold_deep_water;
+        report.seed = generator.noise_settings.seed;
+        if report.unsatisfied.is_empty() || report.attempts >= settings.max_attempts {
+            if !report.unsatisfied.is_empty() {
+                warn!(
+                    attempts = report.attempts,
+                    unsatisfied = ?report.unsatisfied,
+                    "ConstraintsNotSatisfied: no attempt matches the constraints, the last one is generated"
+                );
+            }
+            return ((noise_map, world), report);
+        }
+
+        debug!("Seed {} does not match {:?}", report.seed, report.unsatisfied);
+        generator.noise_settings.seed = generator.noise_settings.seed.wrapping_add(1);
+    }
+}
+
+// the constraints the terrain does not match
+#[inline(always)]
+fn unsatisfied(constraints: &[WorldConstraint], world: &TileMatrix) -> Vec<WorldConstraint> {
+    constraints.iter().filter(|constraint| !constraint.is_satisfied(world)).copied().collect()
+}
+
+// lowers the water thresholds so that the elevation map has no deep water and enough land,
+// None if the thresholds already allow it
+#[inline(always)]
+fn adjust_thresholds(thresholds: &Thresholds, constraints: &[WorldConstraint], noise_map: &[Vec<f64>], min: f64, max: f64) -> Option<Thresholds> {
+    let mut adjusted = *thresholds;
+    for constraint in constraints {
+        match constraint {
+            | WorldConstraint::NoOcean => adjusted.threshold_deep_water = 0.0,
+            | WorldConstraint::MinLandFraction(fraction) if max > min => {
+                // the elevation, as a percentage of the range, below which the allowed share of water lies
+                let mut values: Vec<f64> = noise_map.iter().flatten().copied().collect();
+                values.sort_by(f64::total_cmp);
+                let water = ((1.0 - fraction) * values.len() as f64) as usize;
+                let limit = (values[water.min(values.len() - 1)] - min) / (max - min) * 100.0;
+                if adjusted.threshold_shallow_water > limit {
+                    // the deep water keeps its share of the water
+                    adjusted.threshold_deep_water *= limit / adjusted.threshold_shallow_water;
+                    adjusted.threshold_shallow_water = limit;
+                }
+            }
+            | _ => {}
+        }
+    }
+
+    let changed = adjusted.threshold_deep_water != thresholds.threshold_deep_water || adjusted.threshold_shallow_water != thresholds.threshold_shallow_water;
+    changed.then_some(adjusted)
+}
+
+// the tile types counted as water by the constraints
+#[inline(always)]
+fn is_water(tile_type: TileType) -> bool {
+    matches!(tile_type, TileType::DeepWater | TileType::ShallowWater)
+}
@@ -0,0 +1,166 @@
+use noise::{NoiseFn, Perlin};
+use rand::Rng;
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::landmarks::{Landmark, LandmarkKind};
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, Coordinate};
+
+/// Settings of the contamination spreading from the epicenter of the disaster: the garbage, the fires and the dead
+/// trees are densest at the epicenter and fade with the distance from it, down to an untouched periphery.
+///
+/// The intensity of the contamination falls linearly from 1 at the epicenter to 0 at `radius` tiles from it, roughened
+/// by Perlin noise so that the contaminated area is not a perfect disk. At each tile inside the radius a tree dies with
+/// probability `vegetation_loss` times the intensity; each empty tile then catches fire with probability
+/// `fire_probability` times the intensity or, if it does not, holds garbage with probability `garbage_density` times
+/// the intensity, more garbage the more intense the contamination.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct ContaminationSettings {
+    /// the epicenter, `None` places it on the reactor if one is built, or on a random walkable tile otherwise
+    pub epicenter: Option<Coordinate>,
+    /// the distance, in tiles, at which the contamination ends
+    pub radius: usize,
+    /// the probability, between 0 and 1, that an empty tile at the epicenter holds garbage
+    pub garbage_density: f64,
+    /// the probability, between 0 and 1, that an empty tile at the epicenter is on fire
+    pub fire_probability: f64,
+    /// the probability, between 0 and 1, that a tree at the epicenter is dead and removed
+    pub vegetation_loss: f64,
+    /// how much the noise roughens the falloff, between 0 and 1, 0 gives a perfect disk
+    pub roughness: f64,
+}
+
+impl ContaminationSettings {
+    /// Custom version of default that provides an instance of `ContaminationSettings` with
+    /// reasonable parameters for the given world size, with the epicenter chosen by the generator
+    pub fn default(size: usize) -> Self {
+        ContaminationSettings {
+            epicenter: None,
+            radius: size / 4,
+            garbage_density: 0.3,
+            fire_probability: 0.05,
+            vegetation_loss: 0.9,
+            roughness: 0.4,
+        }
+    }
+
+    /// Creates a new instance of `ContaminationSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `epicenter` - The epicenter, `None` places it on the reactor if one is built, or on a random walkable tile otherwise.
+    /// * `radius` - The distance, in tiles, at which the contamination ends.
+    /// * `garbage_density` - The probability that an empty tile at the epicenter holds garbage.
+    /// * `fire_probability` - The probability that an empty tile at the epicenter is on fire.
+    /// * `vegetation_loss` - The probability that a tree at the epicenter is dead and removed.
+    /// * `roughness` - How much the noise roughens the falloff, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::contamination::ContaminationSettings;
+    /// use exclusion_zone::generator::landmarks::LandmarkSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // the contamination spreads from the reactor
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.landmark_settings = Some(LandmarkSettings::default(500));
+    /// generator.contamination_settings = Some(ContaminationSettings::new(None, 150, 0.4, 0.1, 1.0, 0.3));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let epicenter = artifacts.epicenter.unwrap();
+    /// ```
+    pub fn new(epicenter: Option<Coordinate>, radius: usize, garbage_density: f64, fire_probability: f64, vegetation_loss: f64, roughness: f64) -> Self {
+        ContaminationSettings {
+            epicenter,
+            radius,
+            garbage_density,
+            fire_probability,
+            vegetation_loss,
+            roughness,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::contamination::ContaminationSettings;
+    /// use exclusion_zone::generator::Coordinate;
+    ///
+    /// assert!(ContaminationSettings::default(100).validate(100).is_empty());
+    /// // an epicenter out of the world
+    /// assert!(!ContaminationSettings::new(Some(Coordinate::new(100, 0)), 25, 0.3, 0.05, 0.9, 0.4).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        if let Some(epicenter) = self.epicenter {
+            issues.check(epicenter.row < size && epicenter.col < size, "ContaminationSettings::epicenter", || {
+                format!("{epicenter:?} is out of the world of {size} tiles")
+            });
+        }
+        issues.check(self.radius > 0, "ContaminationSettings::radius", || "the contamination needs a radius of at least 1 tile".to_string());
+        issues.probability("ContaminationSettings::garbage_density", self.garbage_density);
+        issues.probability("ContaminationSettings::fire_probability", self.fire_probability);
+        issues.probability("ContaminationSettings::vegetation_loss", self.vegetation_loss);
+        issues.probability("ContaminationSettings::roughness", self.roughness);
+        issues.0
+    }
+}
+
+/// Spreads the contamination from the epicenter, returning the epicenter; `None` if the world has no walkable tile
+/// to place it on.
+pub(crate) fn spread_contamination(world: &mut TileMatrix, landmarks: &[Landmark], seed: u32, settings: &ContaminationSettings) -> Option<Coordinate> {
+    let size = world.len();
+    let mut rng = generation_rng();
+
+    let epicenter = match settings.epicenter {
+        | Some(epicenter) => epicenter,
+        | None => match landmarks.iter().find(|landmark| landmark.kind == LandmarkKind::Reactor) {
+            | Some(reactor) => reactor.position,
+            | None => {
+                let walkable: Vec<Coordinate> = (0..size)
+                    .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+                    .filter(|c| world[c.row][c.col].tile_type.properties().walk())
+                    .collect();
+                if walkable.is_empty() {
+                    return None;
+                }
+                walkable[rng.gen_range(0..walkable.len())]
+            }
+        },
+    };
+
+    let radius = settings.radius.max(1);
+    let noise = Perlin::new(seed.wrapping_add(1));
+    let max_garbage = Content::Garbage(0).properties().max().max(1);
+
+    for (row, tiles) in world.iter_mut().enumerate().take(epicenter.row + radius + 1).skip(epicenter.row.saturating_sub(radius)) {
+        for (col, tile) in tiles.iter_mut().enumerate().take(epicenter.col + radius + 1).skip(epicenter.col.saturating_sub(radius)) {
+            let distance = ((row.abs_diff(epicenter.row).pow(2) + col.abs_diff(epicenter.col).pow(2)) as f64).sqrt() / radius as f64;
+            // about 4 bumps along the radius
+            let bump = noise.get([row as f64 / radius as f64 * 4.0, col as f64 / radius as f64 * 4.0]);
+            let intensity = ((1.0 - distance) * (1.0 + settings.roughness * bump)).clamp(0.0, 1.0);
+            if intensity == 0.0 {
+                continue;
+            }
+
+            if matches!(tile.content, Content::Tree(_)) && rng.gen_bool(settings.vegetation_loss * intensity) {
+                tile.content = Content::None;
+            }
+            if tile.content != Content::None {
+                continue;
+            }
+            if tile.tile_type.properties().can_hold(&Content::Fire) && rng.gen_bool(settings.fire_probability * intensity) {
+                tile.content = Content::Fire;
+            } else if tile.tile_type.properties().can_hold(&Content::Garbage(0)) && rng.gen_bool(settings.garbage_density * intensity) {
+                let quantity = (1.0 + (max_garbage - 1) as f64 * intensity).round() as usize;
+                tile.content = Content::Garbage(quantity.clamp(1, max_garbage));
+            }
+        }
+    }
+
+    Some(epicenter)
+}
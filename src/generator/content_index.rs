@@ -0,0 +1,129 @@
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::TileMatrix;
+use crate::utils::{content_quantity, Coordinate};
+
+/// Side, in tiles, of the cells of the grid used to find the nearest content
+const CELL_SIZE: usize = 32;
+
+/// Sparse index of the content of a world, to query it without scanning the whole tile matrix.
+///
+/// The content is grouped by kind, ignoring its quantity, using `Content::index`; for each kind the
+/// positions are sorted by row then column and bucketed in a grid of square cells for the nearest queries.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ContentIndex {
+    size: usize,
+    // [content index] -> positions and quantities, sorted by coordinate
+    entries: Vec<Vec<(Coordinate, usize)>>,
+    // [content index][cell] -> indexes in entries of the content inside the cell
+    grid: Vec<Vec<Vec<usize>>>,
+}
+
+impl ContentIndex {
+    /// Builds the index of the content of the given world.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::Content;
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::content_index::ContentIndex;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let (world, ..) = WorldGenerator::default(200).gen();
+    /// let index = ContentIndex::build(&world);
+    /// let coins: usize = index.iter_content(&Content::Coin(0)).map(|(_, quantity)| quantity).sum();
+    /// ```
+    pub fn build(world: &TileMatrix) -> Self {
+        let size = world.len();
+        let cells_per_side = size.div_ceil(CELL_SIZE);
+        let mut index = ContentIndex {
+            size,
+            ..Default::default()
+        };
+
+        for (row, tiles) in world.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if tile.content == Content::None {
+                    continue;
+                }
+
+                let kind = tile.content.index();
+                if index.entries.len() <= kind {
+                    index.entries.resize(kind + 1, Vec::new());
+                    index.grid.resize(kind + 1, Vec::new());
+                }
+                if index.grid[kind].is_empty() {
+                    index.grid[kind] = vec![Vec::new(); cells_per_side * cells_per_side];
+                }
+
+                index.grid[kind][(row / CELL_SIZE) * cells_per_side + col / CELL_SIZE].push(index.entries[kind].len());
+                index.entries[kind].push((Coordinate { row, col }, content_quantity(&tile.content)));
+            }
+        }
+        index
+    }
+
+    /// Iterates over the positions of the given kind of content, with their quantity, sorted by row then column.
+    /// The quantity of `content` is ignored, `Content::Coin(0)` iterates over every coin.
+    pub fn iter_content(&self, content: &Content) -> impl Iterator<Item = (Coordinate, usize)> + '_ {
+        self.entries.get(content.index()).into_iter().flatten().copied()
+    }
+
+    /// Returns the number of tiles holding the given kind of content
+    pub fn count(&self, content: &Content) -> usize {
+        self.entries.get(content.index()).map_or(0, Vec::len)
+    }
+
+    /// Returns the `k` tiles holding the given kind of content nearest to `from`, with their quantity,
+    /// sorted by Manhattan distance. Fewer than `k` tiles are returned if the world does not contain enough.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::Content;
+    /// use exclusion_zone::generator::content_index::ContentIndex;
+    /// use exclusion_zone::generator::{Coordinate, WorldGenerator};
+    ///
+    /// let (_, artifacts) = WorldGenerator::default(200).gen_with_artifacts();
+    /// let banks = artifacts.content_index.nearest(&Content::Bank(0..0), Coordinate::new(100, 100), 3);
+    /// ```
+    pub fn nearest(&self, content: &Content, from: Coordinate, k: usize) -> Vec<(Coordinate, usize)> {
+        let kind = content.index();
+        let (Some(entries), Some(grid)) = (self.entries.get(kind), self.grid.get(kind)) else {
+            return Vec::new();
+        };
+        if k == 0 || entries.is_empty() {
+            return Vec::new();
+        }
+
+        let cells_per_side = self.size.div_ceil(CELL_SIZE) as isize;
+        let from_cell = ((from.row / CELL_SIZE) as isize, (from.col / CELL_SIZE) as isize);
+        let mut found: Vec<(usize, usize)> = Vec::new();
+
+        // visit the rings of cells around the starting one, until no unvisited cell can contain a nearer tile
+        for ring in 0..cells_per_side {
+            for cell_row in from_cell.0 - ring..=from_cell.0 + ring {
+                for cell_col in from_cell.1 - ring..=from_cell.1 + ring {
+                    let on_ring = (cell_row - from_cell.0).abs() == ring || (cell_col - from_cell.1).abs() == ring;
+                    if !on_ring || cell_row < 0 || cell_col < 0 || cell_row >= cells_per_side || cell_col >= cells_per_side {
+                        continue;
+                    }
+                    for &i in grid[(cell_row * cells_per_side + cell_col) as usize].iter() {
+                        let c = entries[i].0;
+                        found.push((c.row.abs_diff(from.row) + c.col.abs_diff(from.col), i));
+                    }
+                }
+            }
+
+            found.sort_unstable();
+            // every tile in the next rings is farther than ring * CELL_SIZE
+            if found.len() >= k && found[k - 1].0 <= ring as usize * CELL_SIZE {
+                break;
+            }
+        }
+
+        found.into_iter().take(k).map(|(_, i)| entries[i]).collect()
+    }
+}
@@ -0,0 +1,190 @@
+use robotics_lib::world::tile::Content::{Bank, Coin};
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::artifacts::GenerationArtifacts;
+use crate::generator::{default_environmental_conditions, GenResult, TileMatrix, WorldGenerator};
+use crate::utils::{Coordinate, Position};
+
+/// The tile type used to build the walls between the corridors of the course
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CourseObstacle {
+    /// impassable walls
+    Wall,
+    /// lava, walkable but deadly for the energy of the robot
+    Lava,
+}
+
+impl CourseObstacle {
+    #[inline(always)]
+    fn tile_type(&self) -> TileType {
+        match self {
+            | CourseObstacle::Wall => TileType::Wall,
+            | CourseObstacle::Lava => TileType::Lava,
+        }
+    }
+}
+
+/// Settings defining the layout of the benchmark course.
+///
+/// The course is a serpentine of horizontal Street corridors, separated by obstacles,
+/// the robot spawns at the beginning of the first corridor and has to reach the end of the last one.
+/// The layout is deterministic, the same settings always produce the same course.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct CourseSettings {
+    /// the number of Street tiles between two obstacle bands
+    pub corridor_width: usize,
+    /// the number of obstacle tiles between two corridors
+    pub wall_thickness: usize,
+    /// the tile type used for the obstacle bands and the border of the course
+    pub obstacle: CourseObstacle,
+    /// the distance, along the path, between two consecutive Coin checkpoints
+    pub checkpoint_spacing: usize,
+}
+
+impl CourseSettings {
+    /// Custom version of default that provides an instance of `CourseSettings` with
+    /// reasonable parameters for the given world size
+    pub fn default(size: usize) -> Self {
+        CourseSettings {
+            corridor_width: (size / 50).max(1),
+            wall_thickness: 1,
+            obstacle: CourseObstacle::Wall,
+            checkpoint_spacing: (size / 10).max(1),
+        }
+    }
+
+    /// Creates a new instance of `CourseSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `corridor_width` - The number of Street tiles between two obstacle bands.
+    /// * `wall_thickness` - The number of obstacle tiles between two corridors.
+    /// * `obstacle` - The tile type used for the obstacle bands.
+    /// * `checkpoint_spacing` - The distance, along the path, between two consecutive checkpoints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::course::{CourseObstacle, CourseSettings};
+    ///
+    /// let settings = CourseSettings::new(3, 1, CourseObstacle::Lava, 20);
+    /// ```
+    pub fn new(corridor_width: usize, wall_thickness: usize, obstacle: CourseObstacle, checkpoint_spacing: usize) -> Self {
+        CourseSettings {
+            corridor_width,
+            wall_thickness,
+            obstacle,
+            checkpoint_spacing,
+        }
+    }
+}
+
+impl WorldGenerator {
+    /// Generates a benchmark course instead of a procedural world.
+    ///
+    /// The course is made of corridors of Street flanked by walls (or lava), connected at alternating ends,
+    /// with Coin checkpoints every `checkpoint_spacing` tiles and a Bank at the end of the path.
+    /// The checkpoint coordinates are returned in the artifacts in the order the robot is expected to reach them,
+    /// the last one is always the Bank.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The world side dimension.
+    /// * `settings` - The layout of the course.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings are invalid or the world is too small to contain at least one corridor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::course::CourseSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let (world, artifacts) = WorldGenerator::benchmark_course(200, CourseSettings::default(200)).unwrap();
+    /// let bank = artifacts.checkpoints.last().unwrap();
+    /// ```
+    pub fn benchmark_course(size: usize, settings: CourseSettings) -> Result<(GenResult, GenerationArtifacts), String> {
+        if settings.corridor_width == 0 || settings.wall_thickness == 0 || settings.checkpoint_spacing == 0 {
+            return Err("corridor_width, wall_thickness and checkpoint_spacing must be greater than 0".to_string());
+        }
+        if size < settings.corridor_width + 2 {
+            return Err(format!("The world size {size} is too small for a corridor {} tiles wide", settings.corridor_width));
+        }
+
+        let obstacle = settings.obstacle.tile_type();
+        let mut world: TileMatrix = vec![
+            vec![
+                Tile {
+                    tile_type: obstacle,
+                    content: Content::None,
+                    elevation: 0,
+                };
+                size
+            ];
+            size
+        ];
+
+        // the first and last column of a corridor, the border of the map is always an obstacle
+        let first_col = 1;
+        let last_col = size - 2;
+
+        let corridors: Vec<usize> = (0..).map(|i| 1 + i * (settings.corridor_width + settings.wall_thickness)).take_while(|top| top + settings.corridor_width < size).collect();
+
+        let mut checkpoints = Vec::new();
+        let mut travelled = 0;
+        for (i, &top) in corridors.iter().enumerate() {
+            // dig the corridor
+            for row in world.iter_mut().skip(top).take(settings.corridor_width) {
+                for tile in row.iter_mut().take(last_col + 1).skip(first_col) {
+                    tile.tile_type = TileType::Street;
+                }
+            }
+
+            // open the wall towards the next corridor, right end for even corridors, left end for the odd ones
+            if i + 1 < corridors.len() {
+                let opening = if i.is_multiple_of(2) { last_col + 1 - settings.corridor_width..last_col + 1 } else { first_col..first_col + settings.corridor_width };
+                for row in world.iter_mut().skip(top + settings.corridor_width).take(settings.wall_thickness) {
+                    for tile in row[opening.clone()].iter_mut() {
+                        tile.tile_type = TileType::Street;
+                    }
+                }
+            }
+
+            // place the checkpoints along the center of the corridor, following the direction of the path
+            let center = top + settings.corridor_width / 2;
+            let cols: Vec<usize> = if i.is_multiple_of(2) { (first_col..=last_col).collect() } else { (first_col..=last_col).rev().collect() };
+            for col in cols {
+                travelled += 1;
+                if travelled % settings.checkpoint_spacing == 0 {
+                    world[center][col].content = Coin(1);
+                    checkpoints.push(Coordinate { row: center, col });
+                }
+            }
+        }
+
+        // the bank is at the end of the last corridor
+        let last = corridors.len() - 1;
+        let bank = Coordinate {
+            row: corridors[last] + settings.corridor_width / 2,
+            col: if last.is_multiple_of(2) { last_col } else { first_col },
+        };
+        if checkpoints.last() == Some(&bank) {
+            checkpoints.pop();
+        }
+        world[bank.row][bank.col].content = Bank(0..Bank(0..0).properties().max());
+        checkpoints.push(bank);
+
+        let spawn = Position::from_row_col(corridors[0] + settings.corridor_width / 2, first_col);
+
+        Ok((
+            (world, spawn.spawn(), default_environmental_conditions(), 100.0, None),
+            GenerationArtifacts {
+                checkpoints,
+                ..Default::default()
+            },
+        ))
+    }
+}
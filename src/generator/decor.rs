@@ -0,0 +1,145 @@
+use rand::Rng;
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::generation_rng;
+
+/// A purely visual marker drawn under the content of a tile, not part of the world seen by the robots
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Decor {
+    /// tufts of tall grass on the Grass and Hill tiles
+    Tufts,
+    /// rubble of the collapsed buildings on and along the streets and under the garbage
+    Rubble,
+    /// cracks in the ground scorched by the lava and on the Mountain tiles
+    Cracks,
+}
+
+/// Settings of the decor layer, the visual markers the visualizer draws under the content to render richer images;
+/// the decor is kept apart from the tiles, so it never breaks the rules on the content a tile can hold.
+///
+/// A tile gets at most one marker: the cracks come first, then the rubble and then the tufts, each placed with its
+/// probability on the tiles it fits. The water, the lava, the walls and the teleports get no decor.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct DecorSettings {
+    /// the probability, between 0 and 1, of tufts on a Grass or Hill tile
+    pub tufts_density: f64,
+    /// the probability, between 0 and 1, of rubble on a Street tile, on a tile next to a street or holding garbage
+    pub rubble_density: f64,
+    /// the probability, between 0 and 1, of cracks on a tile next to the lava or on a Mountain tile
+    pub cracks_density: f64,
+}
+
+impl Default for DecorSettings {
+    fn default() -> Self {
+        DecorSettings {
+            tufts_density: 0.25,
+            rubble_density: 0.3,
+            cracks_density: 0.4,
+        }
+    }
+}
+
+impl DecorSettings {
+    /// Creates a new instance of `DecorSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `tufts_density` - The probability of tufts on a Grass or Hill tile.
+    /// * `rubble_density` - The probability of rubble on a Street tile, on a tile next to a street or holding garbage.
+    /// * `cracks_density` - The probability of cracks on a tile next to the lava or on a Mountain tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::decor::{Decor, DecorSettings};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.decor_settings = Some(DecorSettings::new(0.5, 0.2, 0.4));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let tufts = artifacts.decor.unwrap().count(Decor::Tufts);
+    /// ```
+    pub fn new(tufts_density: f64, rubble_density: f64, cracks_density: f64) -> Self {
+        DecorSettings {
+            tufts_density,
+            rubble_density,
+            cracks_density,
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::decor::DecorSettings;
+    ///
+    /// assert!(DecorSettings::default().validate().is_empty());
+    /// assert!(!DecorSettings::new(1.5, 0.2, 0.4).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.probability("DecorSettings::tufts_density", self.tufts_density);
+        issues.probability("DecorSettings::rubble_density", self.rubble_density);
+        issues.probability("DecorSettings::cracks_density", self.cracks_density);
+        issues.0
+    }
+}
+
+/// The decor of each tile of a generated world, consumed only by the visualizer
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DecorLayer {
+    /// the side of the world
+    pub size: usize,
+    /// the decor of each tile, row by row, `None` for the bare tiles
+    pub marks: Vec<Option<Decor>>,
+}
+
+impl DecorLayer {
+    /// Returns the decor of the tile, `None` for a bare tile or a tile out of the world
+    pub fn get(&self, row: usize, col: usize) -> Option<Decor> {
+        if row >= self.size || col >= self.size {
+            return None;
+        }
+        self.marks[row * self.size + col]
+    }
+
+    /// Returns the number of tiles with the given decor
+    pub fn count(&self, decor: Decor) -> usize {
+        self.marks.iter().filter(|mark| **mark == Some(decor)).count()
+    }
+}
+
+/// Scatters the decor over the generated world, which is left untouched
+pub(crate) fn spawn_decor(world: &TileMatrix, settings: &DecorSettings) -> DecorLayer {
+    let size = world.len();
+    let mut rng = generation_rng();
+    let next_to = |row: usize, col: usize, tile_type: TileType| {
+        (row.saturating_sub(1)..=(row + 1).min(size - 1)).any(|r| (col.saturating_sub(1)..=(col + 1).min(size - 1)).any(|c| world[r][c].tile_type == tile_type))
+    };
+
+    let mut marks = vec![None; size * size];
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            if matches!(tile.tile_type, TileType::DeepWater | TileType::ShallowWater | TileType::Lava | TileType::Wall | TileType::Teleport(_)) {
+                continue;
+            }
+            let cracked = tile.tile_type == TileType::Mountain || next_to(row, col, TileType::Lava);
+            let ruined = tile.tile_type == TileType::Street || matches!(tile.content, Content::Garbage(_)) || next_to(row, col, TileType::Street);
+            let grassy = matches!(tile.tile_type, TileType::Grass | TileType::Hill);
+            marks[row * size + col] = if cracked && rng.gen_bool(settings.cracks_density) {
+                Some(Decor::Cracks)
+            } else if ruined && rng.gen_bool(settings.rubble_density) {
+                Some(Decor::Rubble)
+            } else if grassy && rng.gen_bool(settings.tufts_density) {
+                Some(Decor::Tufts)
+            } else {
+                None
+            };
+        }
+    }
+    DecorLayer { size, marks }
+}
@@ -0,0 +1,112 @@
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::generator::preview::tile_color;
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Color of the tiles whose tile type changed in the visual diff
+const TILE_TYPE_CHANGED: [u8; 3] = [230, 30, 30];
+/// Color of the tiles whose content or elevation changed in the visual diff
+const CONTENT_CHANGED: [u8; 3] = [30, 200, 30];
+
+/// Summary of the differences between two worlds, as returned by [`diff_worlds`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WorldDiff {
+    /// the side of the two compared worlds, only their common area is compared
+    pub sizes: (usize, usize),
+    /// the coordinates of every tile which differs, sorted by row then column
+    pub changed: Vec<Coordinate>,
+    /// how many tiles changed from the first tile type to the second one
+    pub tile_types: Vec<(TileType, TileType, usize)>,
+    /// how many tiles changed from the first kind of content to the second one, quantities are zeroed
+    pub contents: Vec<(Content, Content, usize)>,
+    /// the number of tiles with the same kind of content but a different quantity
+    pub quantities: usize,
+    /// the number of tiles with a different elevation
+    pub elevations: usize,
+}
+
+impl WorldDiff {
+    /// Returns true if the two worlds have the same size and the same tiles
+    pub fn is_empty(&self) -> bool {
+        self.sizes.0 == self.sizes.1 && self.changed.is_empty()
+    }
+}
+
+/// Compares two worlds tile by tile, summarizing what changed from `a` to `b`.
+///
+/// Useful to check that a refactor does not alter the output of the generator for the same settings.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::diff::diff_worlds;
+/// use exclusion_zone::generator::{NoiseSettings, WorldGenerator};
+///
+/// let mut generator = WorldGenerator::default(200);
+/// generator.noise_settings = NoiseSettings::from_seed(42);
+/// let (a, ..) = generator.clone().gen_with_artifacts().0;
+/// let (b, ..) = generator.gen_with_artifacts().0;
+///
+/// assert!(diff_worlds(&a, &b).is_empty());
+/// ```
+pub fn diff_worlds(a: &TileMatrix, b: &TileMatrix) -> WorldDiff {
+    let mut diff = WorldDiff {
+        sizes: (a.len(), b.len()),
+        ..Default::default()
+    };
+
+    for (row, (row_a, row_b)) in a.iter().zip(b.iter()).enumerate() {
+        for (col, (tile_a, tile_b)) in row_a.iter().zip(row_b.iter()).enumerate() {
+            if tile_a.tile_type == tile_b.tile_type && tile_a.content == tile_b.content && tile_a.elevation == tile_b.elevation {
+                continue;
+            }
+            diff.changed.push(Coordinate { row, col });
+
+            if tile_a.tile_type != tile_b.tile_type {
+                count(&mut diff.tile_types, tile_a.tile_type, tile_b.tile_type);
+            }
+            if tile_a.content.index() != tile_b.content.index() {
+                count(&mut diff.contents, tile_a.content.to_default(), tile_b.content.to_default());
+            } else if tile_a.content != tile_b.content {
+                diff.quantities += 1;
+            }
+            if tile_a.elevation != tile_b.elevation {
+                diff.elevations += 1;
+            }
+        }
+    }
+    diff
+}
+
+/// Renders the differences between two worlds as a row-major RGB buffer, one pixel per tile of their common area.
+///
+/// Unchanged tiles are drawn dimmed in grey, tiles whose tile type changed in red and tiles
+/// whose content or elevation changed in green.
+pub fn render_diff(a: &TileMatrix, b: &TileMatrix) -> Vec<u8> {
+    a.iter()
+        .zip(b.iter())
+        .flat_map(|(row_a, row_b)| row_a.iter().zip(row_b.iter()).flat_map(|(tile_a, tile_b)| diff_color(tile_a, tile_b)))
+        .collect()
+}
+
+#[inline(always)]
+fn diff_color(a: &Tile, b: &Tile) -> [u8; 3] {
+    if a.tile_type != b.tile_type {
+        TILE_TYPE_CHANGED
+    } else if a.content != b.content || a.elevation != b.elevation {
+        CONTENT_CHANGED
+    } else {
+        let [red, green, blue] = tile_color(b).map(|channel| channel as f32);
+        let grey = ((0.3 * red + 0.59 * green + 0.11 * blue) * 0.5) as u8;
+        [grey; 3]
+    }
+}
+
+#[inline(always)]
+fn count<T: PartialEq>(changes: &mut Vec<(T, T, usize)>, from: T, to: T) {
+    match changes.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+        | Some((.., n)) => *n += 1,
+        | None => changes.push((from, to, 1)),
+    }
+}
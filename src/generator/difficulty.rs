@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::WorldGenerator;
+
+// the level of the default settings
+const DEFAULT_LEVEL: u8 = 5;
+
+/// A difficulty level from 1, the easiest, to 10, the hardest, scaling together the knobs which make a world hard for
+/// the robot, so that the worlds generated at the same level are comparable.
+///
+/// Level 5 keeps the settings as they are, each level above it adds a fifth of the default hazards and takes a sixth
+/// of the default resources away, each level below it does the opposite:
+/// - the hazards, the lava spawn points and the fires, are scaled by `level / 5`, from a fifth to twice as many;
+/// - the resources, the coins and the trees, are scaled by `(11 - level) / 6`, from 5/3 to a sixth as many;
+/// - the terrain gets rougher with the level, the persistence of the noise grows by 0.05 per level.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifficultySettings {
+    /// the difficulty level, between 1 and 10
+    pub level: u8,
+}
+
+impl Default for DifficultySettings {
+    fn default() -> Self {
+        DifficultySettings { level: DEFAULT_LEVEL }
+    }
+}
+
+impl DifficultySettings {
+    /// Creates a new instance of `DifficultySettings` with the given level, between 1 and 10.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::difficulty::DifficultySettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // the same terrain for a beginner and an expert class
+    /// let mut beginner = WorldGenerator::default(300);
+    /// DifficultySettings::new(2).apply(&mut beginner);
+    /// let mut expert = WorldGenerator::default(300);
+    /// DifficultySettings::new(9).apply(&mut expert);
+    /// assert!(beginner.coin_settings.number_of_spawn_points > expert.coin_settings.number_of_spawn_points);
+    /// assert!(beginner.lava_settings.number_of_spawn_points < expert.lava_settings.number_of_spawn_points);
+    /// ```
+    pub fn new(level: u8) -> Self {
+        DifficultySettings { level }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::difficulty::DifficultySettings;
+    ///
+    /// assert!(DifficultySettings::new(10).validate().is_empty());
+    /// assert!(!DifficultySettings::new(0).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check((1..=10).contains(&self.level), "DifficultySettings::level", || format!("{} is not a level, use a value between 1 and 10", self.level));
+        issues.0
+    }
+
+    /// Scales the settings of the generator to the difficulty level, the levels out of range are clamped.
+    ///
+    /// The settings are scaled from their current values, so the difficulty is meant to be applied once to the
+    /// default settings, or to the settings of a preset, before the customizations which must not be scaled.
+    pub fn apply(&self, generator: &mut WorldGenerator) {
+        let level = self.level.clamp(1, 10) as f64;
+        let hazards = level / DEFAULT_LEVEL as f64;
+        let resources = (11.0 - level) / (11.0 - DEFAULT_LEVEL as f64);
+        let scale = |count: usize, factor: f64| (count as f64 * factor).round() as usize;
+
+        generator.lava_settings.number_of_spawn_points = scale(generator.lava_settings.number_of_spawn_points, hazards);
+        generator.fire_settings = generator.fire_settings.scaled(hazards);
+        generator.coin_settings.number_of_spawn_points = scale(generator.coin_settings.number_of_spawn_points, resources);
+        generator.tree_settings = generator.tree_settings.scaled(resources);
+        generator.noise_settings.persistence += 0.05 * (level - DEFAULT_LEVEL as f64);
+    }
+}
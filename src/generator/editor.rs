@@ -0,0 +1,180 @@
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::generator::compose::OffsetRect;
+use crate::generator::conflict::ConflictPolicy;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::TileMatrix;
+use crate::utils::{content_quantity, Coordinate};
+
+/// Editor of a generated world, to hand-adjust it programmatically before saving it, like carving a sea,
+/// clearing the content around a point of interest or drawing a street towards it.
+///
+/// Every edit keeps the world valid: the content is only placed on tile types that can hold it, with a quantity
+/// within its max, changing the tile type of a tile removes the content it cannot hold anymore, and the index of the
+/// occupied tiles used by the spawners stays in sync with the world. The edits out of the world are rejected.
+///
+/// The rects are the `OffsetRect` of the composed worlds, `width` columns and `height` rows starting at (`row`, `col`).
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::compose::OffsetRect;
+/// use exclusion_zone::generator::editor::WorldEditor;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let (world, ..) = MiniWorldGenerator::new(50).gen();
+/// let mut editor = WorldEditor::new(world);
+///
+/// // a grass square in the top left corner, reached by a street, with a coin in the middle
+/// editor.fill_rect(OffsetRect::new(0, 0, 10, 10), TileType::Grass).unwrap();
+/// editor.repaint_streets(&[Coordinate::new(5, 10), Coordinate::new(5, 30), Coordinate::new(40, 30)]).unwrap();
+/// editor.place_content(Coordinate::new(5, 5), Content::Coin(3)).unwrap();
+///
+/// let world = editor.into_world();
+/// assert_eq!(world[5][20].tile_type, TileType::Street);
+/// assert_eq!(world[5][5].content, Content::Coin(3));
+/// ```
+pub struct WorldEditor {
+    world: TileMatrix,
+    occupancy: Occupancy,
+}
+
+impl WorldEditor {
+    /// Creates a new instance of `WorldEditor` editing the given world
+    pub fn new(world: TileMatrix) -> Self {
+        let mut occupancy = Occupancy::build(&world);
+        // the placed content overwrites the content of the tile
+        occupancy.set_policy(ConflictPolicy::Replace);
+        WorldEditor { world, occupancy }
+    }
+
+    /// Returns the edited world
+    pub fn world(&self) -> &TileMatrix {
+        &self.world
+    }
+
+    /// Returns the edited world, ending the editing
+    pub fn into_world(self) -> TileMatrix {
+        self.world
+    }
+
+    /// Returns true if the tile type of the tile can hold the given kind of content, the quantity is ignored
+    pub fn can_hold(&self, c: Coordinate, content: &Content) -> Result<bool, String> {
+        self.check_bounds(c)?;
+        Ok(self.occupancy.can_hold(c, content))
+    }
+
+    /// Sets the tile type of every tile of the rect, removing the content the new tile type cannot hold.
+    ///
+    /// Returns the number of tiles whose content has been removed.
+    pub fn fill_rect(&mut self, rect: OffsetRect, tile_type: TileType) -> Result<usize, String> {
+        self.check_rect(&rect)?;
+        let mut removed = 0;
+        for c in rect_tiles(&rect) {
+            let had_content = self.world[c.row][c.col].content != Content::None;
+            self.occupancy.set_tile_type(&mut self.world, c, tile_type);
+            if had_content && self.world[c.row][c.col].content == Content::None {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Places the content on the tile, replacing the content it already holds; `Content::None` empties the tile.
+    ///
+    /// Fails if the tile type cannot hold the content or if the quantity exceeds the max of the content.
+    pub fn place_content(&mut self, c: Coordinate, content: Content) -> Result<(), String> {
+        self.check_bounds(c)?;
+        if !self.occupancy.can_hold(c, &content) {
+            return Err(format!("{:?} cannot hold {:?}, the tile at {:?}", self.world[c.row][c.col].tile_type, content, c));
+        }
+        let max = content.properties().max();
+        if content != Content::None && content_quantity(&content) > max {
+            return Err(format!("{:?} exceeds the max quantity of the content, {}", content, max));
+        }
+        self.occupancy.place(&mut self.world, c, content);
+        Ok(())
+    }
+
+    /// Removes the content of every tile of the rect, returning the number of tiles emptied
+    pub fn remove_content_in(&mut self, rect: OffsetRect) -> Result<usize, String> {
+        self.check_rect(&rect)?;
+        let mut removed = 0;
+        for c in rect_tiles(&rect) {
+            if !self.occupancy.is_empty(c) {
+                self.occupancy.place(&mut self.world, c, Content::None);
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Paints a street through the waypoints of the path, removing the content the streets cannot hold.
+    ///
+    /// Each waypoint is joined to the next one by a straight segment along its row, then along the column of the next
+    /// one, so that the street is walkable without diagonal steps. Returns the number of tiles turned into streets.
+    pub fn repaint_streets(&mut self, path: &[Coordinate]) -> Result<usize, String> {
+        for c in path {
+            self.check_bounds(*c)?;
+        }
+
+        let mut painted = 0;
+        let mut paint = |editor: &mut WorldEditor, c: Coordinate| {
+            if editor.world[c.row][c.col].tile_type != TileType::Street {
+                editor.occupancy.set_tile_type(&mut editor.world, c, TileType::Street);
+                painted += 1;
+            }
+        };
+
+        if let Some(first) = path.first() {
+            paint(self, *first);
+        }
+        for pair in path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            for col in range_between(from.col, to.col) {
+                paint(self, Coordinate { row: from.row, col });
+            }
+            for row in range_between(from.row, to.row) {
+                paint(self, Coordinate { row, col: to.col });
+            }
+        }
+        Ok(painted)
+    }
+
+    #[inline(always)]
+    fn check_bounds(&self, c: Coordinate) -> Result<(), String> {
+        let size = self.world.len();
+        if c.row >= size || c.col >= size {
+            return Err(format!("{:?} is out of the {size}x{size} world", c));
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn check_rect(&self, rect: &OffsetRect) -> Result<(), String> {
+        let size = self.world.len();
+        if rect.row + rect.height > size || rect.col + rect.width > size {
+            return Err(format!("{:?} does not fit in the {size}x{size} world", rect));
+        }
+        Ok(())
+    }
+}
+
+// the tiles of the rect, row by row
+#[inline(always)]
+fn rect_tiles(rect: &OffsetRect) -> impl Iterator<Item = Coordinate> + '_ {
+    (rect.row..rect.row + rect.height).flat_map(move |row| (rect.col..rect.col + rect.width).map(move |col| Coordinate { row, col }))
+}
+
+// the values from `from` to `to`, both included, in either direction
+#[inline(always)]
+fn range_between(from: usize, to: usize) -> Box<dyn Iterator<Item = usize>> {
+    if from <= to {
+        Box::new(from..=to)
+    } else {
+        Box::new((to..=from).rev())
+    }
+}
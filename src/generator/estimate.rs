@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::time::Duration;
+
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::reachability::ReachabilityCheck;
+use crate::generator::{Spawnables, WorldGenerator};
+use crate::utils::Coordinate;
+
+// rough cost of generating a tile, terrain and content, in a release build on a desktop CPU
+const NANOS_PER_TILE: u64 = 2_000;
+
+/// What a generation with the current settings is expected to produce and cost, computed without building the world.
+///
+/// The figures are estimates: the tile counts assume that the elevation is spread evenly between its min and max
+/// and ignore the streets, the lava and the weather, the content counts of the blob-shaped content, trees and
+/// fires, are upper bounds, and the duration is an order of magnitude.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenerationEstimate {
+    /// the side of the world
+    pub size: usize,
+    /// the expected number of tiles of each tile type assigned by the thresholds
+    pub tiles: Vec<(TileType, usize)>,
+    /// the expected number of tiles holding each spawnable of the spawn order
+    pub content: HashMap<Spawnables, usize>,
+    /// the approximate peak memory used by the generation, in bytes
+    pub peak_memory_bytes: usize,
+    /// the approximate duration of the generation
+    pub duration: Duration,
+}
+
+impl WorldGenerator {
+    /// Estimates the tile counts, the content counts, the peak memory and the duration of the generation
+    /// with the current settings, to sanity-check them before generating a huge world.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let generator = WorldGenerator::default(20000);
+    /// let estimate = generator.estimate();
+    /// if estimate.peak_memory_bytes > 16 * 1024 * 1024 * 1024 {
+    ///     println!("not enough memory, {:?} expected", estimate.duration);
+    /// }
+    /// ```
+    pub fn estimate(&self) -> GenerationEstimate {
+        let area = self.size * self.size;
+        GenerationEstimate {
+            size: self.size,
+            tiles: self.estimate_tiles(area),
+            content: self.spawn_order.iter().map(|spawnable| (*spawnable, self.estimate_content(*spawnable, area))).collect(),
+            peak_memory_bytes: self.estimate_memory(area),
+            duration: Duration::from_nanos(NANOS_PER_TILE.saturating_mul(area as u64)),
+        }
+    }
+
+    // each tile type gets the share of the elevation range between its threshold and the previous one, which is its
+    // exact share of the tiles with the equalized thresholds
+    pub(crate) fn estimate_tiles(&self, area: usize) -> Vec<(TileType, usize)> {
+        let t = &self.thresholds;
+        let bands = [
+            (TileType::DeepWater, 0.0, t.threshold_deep_water),
+            (TileType::ShallowWater, t.threshold_deep_water, t.threshold_shallow_water),
+            (TileType::Sand, t.threshold_shallow_water, t.threshold_sand),
+            (TileType::Grass, t.threshold_sand, t.threshold_grass),
+            (TileType::Hill, t.threshold_grass, t.threshold_hill),
+            (TileType::Mountain, t.threshold_hill, t.threshold_mountain),
+            (TileType::Snow, t.threshold_mountain, 100.0),
+        ];
+        bands
+            .into_iter()
+            .map(|(tile_type, low, high)| {
+                let share = (high.clamp(0.0, 100.0) - low.clamp(0.0, 100.0)).max(0.0) / 100.0;
+                (tile_type, (share * area as f64).round() as usize)
+            })
+            .collect()
+    }
+
+    pub(crate) fn estimate_content(&self, spawnable: Spawnables, area: usize) -> usize {
+        let count = match spawnable {
+            | Spawnables::Rock => self.rock_settings.max_num_rocks,
+            | Spawnables::Tree => self.tree_settings.max_tiles(),
+            | Spawnables::Garbage => {
+                let average = self.garbage_settings.garbage_per_tile_quantity.clamped(&Content::Garbage(0)).mean();
+                (self.garbage_settings.total_garbage_quantity as f64 / average).ceil() as usize
+            }
+            | Spawnables::Fire => self.weather_settings.adjust_fire(&self.fire_settings).max_tiles(),
+            | Spawnables::Bin => self.bin_settings.number_of_spawn_points,
+            | Spawnables::Crate => self.crate_settings.number_of_spawn_points,
+            | Spawnables::Bank => self.bank_settings.number_of_spawn_points,
+            | Spawnables::Coin => self.coin_settings.number_of_spawn_points,
+            | Spawnables::Market => self.market_settings.number_of_spawn_points,
+            | Spawnables::Fish => self.fish_settings.number_of_spawn_points,
+        };
+        count.min(area)
+    }
+
+    // the buffers alive together at the end of the generation: the world, the elevation map as f64 and its f32 copy,
+    // the occupancy masks, the buffers of the reachability check, the content index and the hazard map
+    fn estimate_memory(&self, area: usize) -> usize {
+        let world = area * size_of::<Tile>() + self.size * size_of::<Vec<Tile>>();
+        let elevation = area * (size_of::<f64>() + size_of::<f32>());
+        let occupancy = area * size_of::<u16>();
+        let reachability = match self.reachability_check {
+            | ReachabilityCheck::Disabled => 0,
+            | ReachabilityCheck::Warn => area * size_of::<bool>(),
+            | ReachabilityCheck::Repair => area * (size_of::<bool>() + size_of::<usize>() + size_of::<Option<Coordinate>>()),
+        };
+        let content: usize = self.spawn_order.iter().map(|spawnable| self.estimate_content(*spawnable, area)).sum();
+        let content_index = content * (size_of::<(Coordinate, usize)>() + size_of::<usize>());
+        let hazard_map = area * size_of::<u32>();
+        world + elevation + occupancy + reachability + content_index + hazard_map
+    }
+}
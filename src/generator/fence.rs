@@ -0,0 +1,141 @@
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Where the fence runs
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FenceArea {
+    /// along the border of the world
+    Border,
+    /// around the rectangle between the top left and the bottom right corners, the corners are part of the fence
+    Region(Coordinate, Coordinate),
+}
+
+/// Settings of the fence of the exclusion zone: a ring of Wall tiles opened by gates of Street tiles at regular
+/// intervals, around the whole world or around a region of it.
+///
+/// The fence is built once the terrain is final and before the content is spawned, over any tile type. Where a street
+/// crosses the fence a gate is opened too, so that the street network stays connected to the outside. The gates are
+/// returned in the generation artifacts, for the scenarios checking the robot in and out of the zone.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct FenceSettings {
+    /// where the fence runs
+    pub area: FenceArea,
+    /// the number of Wall tiles between two gates, along the fence
+    pub gate_spacing: usize,
+    /// the number of Street tiles of each gate
+    pub gate_width: usize,
+}
+
+impl FenceSettings {
+    /// Custom version of default that provides an instance of `FenceSettings` with
+    /// reasonable parameters for the given world size, a fence along the border with a gate every half side
+    pub fn default(size: usize) -> Self {
+        FenceSettings {
+            area: FenceArea::Border,
+            gate_spacing: (size / 2).saturating_sub(2).max(1),
+            gate_width: 2,
+        }
+    }
+
+    /// Creates a new instance of `FenceSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - Where the fence runs.
+    /// * `gate_spacing` - The number of Wall tiles between two gates.
+    /// * `gate_width` - The number of Street tiles of each gate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::fence::{FenceArea, FenceSettings};
+    /// use exclusion_zone::generator::{Coordinate, WorldGenerator};
+    ///
+    /// // the inner zone, a gate of 3 tiles every 100 tiles of fence
+    /// let mut generator = WorldGenerator::default(1000);
+    /// let area = FenceArea::Region(Coordinate::new(250, 250), Coordinate::new(749, 749));
+    /// generator.fence_settings = Some(FenceSettings::new(area, 100, 3));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// println!("the zone can be entered from {} tiles", artifacts.fence_gates.len());
+    /// ```
+    pub fn new(area: FenceArea, gate_spacing: usize, gate_width: usize) -> Self {
+        FenceSettings {
+            area,
+            gate_spacing,
+            gate_width,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::fence::{FenceArea, FenceSettings};
+    /// use exclusion_zone::generator::Coordinate;
+    ///
+    /// assert!(FenceSettings::default(100).validate(100).is_empty());
+    /// // the region is out of the world
+    /// let area = FenceArea::Region(Coordinate::new(50, 50), Coordinate::new(150, 150));
+    /// assert!(!FenceSettings::new(area, 10, 2).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        let (top_left, bottom_right) = self.corners(size);
+        issues.check(bottom_right.row < size && bottom_right.col < size, "FenceSettings::area", || {
+            format!("the corner {bottom_right:?} is out of the world of {size} tiles per side")
+        });
+        issues.check(bottom_right.row >= top_left.row + 2 && bottom_right.col >= top_left.col + 2, "FenceSettings::area", || {
+            format!("the fence between {top_left:?} and {bottom_right:?} encloses no tile, the corners must be at least 2 tiles apart in both directions")
+        });
+        issues.check(self.gate_spacing > 0, "FenceSettings::gate_spacing", || "the gates must be at least 1 tile apart".to_string());
+        issues.check(self.gate_width > 0, "FenceSettings::gate_width", || "the gates must be at least 1 tile wide".to_string());
+        issues.0
+    }
+
+    // the top left and the bottom right corners of the fence
+    #[inline(always)]
+    fn corners(&self, size: usize) -> (Coordinate, Coordinate) {
+        match self.area {
+            | FenceArea::Border => (Coordinate::new(0, 0), Coordinate::new(size.saturating_sub(1), size.saturating_sub(1))),
+            | FenceArea::Region(top_left, bottom_right) => (top_left, bottom_right),
+        }
+    }
+}
+
+/// Builds the fence, turning the tiles along it into Wall tiles and the gates into Street tiles.
+///
+/// Returns the tiles of the gates, in order along the fence, clockwise from the top left corner.
+pub(crate) fn build_fence(world: &mut TileMatrix, settings: &FenceSettings) -> Vec<Coordinate> {
+    let (top_left, bottom_right) = settings.corners(world.len());
+    let period = settings.gate_spacing + settings.gate_width;
+    let mut gates = Vec::new();
+
+    for (i, c) in perimeter(top_left, bottom_right).into_iter().enumerate() {
+        let tile = &mut world[c.row][c.col];
+        // the walls first, so that the fence starts at a corner with a full stretch of wall
+        if i % period >= settings.gate_spacing || tile.tile_type == TileType::Street {
+            tile.tile_type = TileType::Street;
+            gates.push(c);
+        } else {
+            tile.tile_type = TileType::Wall;
+            tile.content = Content::None;
+        }
+    }
+    gates
+}
+
+// the tiles of the border of the rectangle, clockwise from the top left corner
+#[inline(always)]
+fn perimeter(top_left: Coordinate, bottom_right: Coordinate) -> Vec<Coordinate> {
+    let (top, left, bottom, right) = (top_left.row, top_left.col, bottom_right.row, bottom_right.col);
+    let top_side = (left..=right).map(|col| Coordinate { row: top, col });
+    let right_side = (top + 1..=bottom).map(|row| Coordinate { row, col: right });
+    let bottom_side = (left..right).rev().map(|col| Coordinate { row: bottom, col });
+    let left_side = (top + 1..bottom).rev().map(|row| Coordinate { row, col: left });
+    top_side.chain(right_side).chain(bottom_side).chain(left_side).collect()
+}
@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+
+use robotics_lib::world::tile::TileType;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+// the currents slower than this are still water
+const STILL: f32 = 1e-3;
+
+/// Settings of the flow field of the water, the direction and the speed of the current on each water tile, for the
+/// simulations drifting the robot or the floating content.
+///
+/// The water flows down the slope of the bed, the elevation map under the water. Near the shore the current is turned
+/// to run along it, keeping the downhill sense, so the narrow channels carry a current along their length like rivers;
+/// the currents are then smoothed by averaging each tile with its water neighbours.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FlowSettings {
+    /// the distance, in tiles, from the shore within which the current runs along it, 0 never turns the current
+    pub shore_distance: usize,
+    /// the number of smoothing passes
+    pub smoothing_passes: usize,
+}
+
+impl Default for FlowSettings {
+    fn default() -> Self {
+        FlowSettings {
+            shore_distance: 2,
+            smoothing_passes: 3,
+        }
+    }
+}
+
+impl FlowSettings {
+    /// Creates a new instance of `FlowSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `shore_distance` - The distance, in tiles, from the shore within which the current runs along it, 2 by default.
+    /// * `smoothing_passes` - The number of smoothing passes, 3 by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::flow::FlowSettings;
+    /// use exclusion_zone::generator::{Coordinate, WorldGenerator};
+    ///
+    /// let mut generator = WorldGenerator::default(300);
+    /// generator.flow_settings = Some(FlowSettings::new(3, 2));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let flow = artifacts.flow_field.unwrap();
+    /// if let Some(next) = flow.drift(Coordinate::new(10, 10)) {
+    ///     println!("the current carries the robot to {:?}", next);
+    /// }
+    /// ```
+    pub fn new(shore_distance: usize, smoothing_passes: usize) -> Self {
+        FlowSettings {
+            shore_distance,
+            smoothing_passes,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::flow::FlowSettings;
+    ///
+    /// assert!(FlowSettings::default().validate(100).is_empty());
+    /// // the whole world is shore
+    /// assert!(!FlowSettings::new(100, 3).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.shore_distance < size, "FlowSettings::shore_distance", || {
+            format!("{} tiles from the shore cover the whole world of {size} tiles", self.shore_distance)
+        });
+        issues.0
+    }
+}
+
+/// The flow field of the water
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FlowField {
+    /// the current of each tile as `[row, col]` components, growing downward and rightward, indexed as
+    /// `currents[row][col]`; the fastest current has speed 1, the tiles which are not water have `[0, 0]`
+    pub currents: Vec<Vec<[f32; 2]>>,
+}
+
+impl FlowField {
+    /// Returns the current on the tile, `None` if the tile is out of the world
+    pub fn current(&self, c: Coordinate) -> Option<[f32; 2]> {
+        self.currents.get(c.row).and_then(|currents| currents.get(c.col)).copied()
+    }
+
+    /// Returns the speed of the current on the tile, between 0 and 1, `None` if the tile is out of the world
+    pub fn speed(&self, c: Coordinate) -> Option<f32> {
+        self.current(c).map(|[row, col]| row.hypot(col))
+    }
+
+    /// Returns the tile sharing a side with the given one toward which the current drifts, `None` on still water,
+    /// out of the water or if the current leaves the world
+    pub fn drift(&self, c: Coordinate) -> Option<Coordinate> {
+        let [row, col] = self.current(c)?;
+        if row.abs().max(col.abs()) < STILL {
+            return None;
+        }
+        let next = if row.abs() >= col.abs() {
+            Coordinate {
+                row: if row > 0.0 { c.row + 1 } else { c.row.checked_sub(1)? },
+                col: c.col,
+            }
+        } else {
+            Coordinate {
+                row: c.row,
+                col: if col > 0.0 { c.col + 1 } else { c.col.checked_sub(1)? },
+            }
+        };
+        self.current(next).map(|_| next)
+    }
+}
+
+/// Computes the flow field of the water tiles of the world from the elevation map.
+pub(crate) fn compute_flow_field(world: &TileMatrix, elevation_map: &[Vec<f64>], settings: &FlowSettings) -> FlowField {
+    let size = world.len();
+    let water: Vec<Vec<bool>> = world
+        .iter()
+        .map(|tiles| tiles.iter().map(|tile| matches!(tile.tile_type, TileType::DeepWater | TileType::ShallowWater)).collect())
+        .collect();
+    let shore = distance_from_shore(&water);
+    // the values out of the world are the ones of the closest tile in it, the border of the world is not a shore
+    let elevation = |row: usize, col: usize| elevation_map[row.min(size - 1)][col.min(size - 1)];
+    let shore_distance = |row: usize, col: usize| shore[row.min(size - 1)][col.min(size - 1)] as f64;
+
+    let mut currents = vec![vec![[0.0f32; 2]; size]; size];
+    for (row, row_currents) in currents.iter_mut().enumerate() {
+        for (col, current) in row_currents.iter_mut().enumerate().filter(|(col, _)| water[row][*col]) {
+            // downhill, the central differences of the elevation
+            let down = [
+                elevation(row.saturating_sub(1), col) - elevation(row + 1, col),
+                elevation(row, col.saturating_sub(1)) - elevation(row, col + 1),
+            ];
+            let mut flow = down;
+            if shore[row][col] <= settings.shore_distance {
+                // the shore runs across the direction away from it, the current takes the downhill sense along it
+                let away = [
+                    shore_distance(row + 1, col) - shore_distance(row.saturating_sub(1), col),
+                    shore_distance(row, col + 1) - shore_distance(row, col.saturating_sub(1)),
+                ];
+                let along = [-away[1], away[0]];
+                let norm = along[0].hypot(along[1]);
+                if norm > 0.0 {
+                    let speed = down[0].hypot(down[1]);
+                    let sense = if along[0] * down[0] + along[1] * down[1] < 0.0 { -1.0 } else { 1.0 };
+                    flow = along.map(|component| component / norm * speed * sense);
+                }
+            }
+            *current = flow.map(|component| component as f32);
+        }
+    }
+
+    for _ in 0..settings.smoothing_passes {
+        currents = smooth(&currents, &water);
+    }
+
+    let fastest = currents.iter().flatten().map(|[row, col]| row.hypot(*col)).fold(0.0, f32::max);
+    if fastest > 0.0 {
+        currents.iter_mut().flatten().for_each(|current| *current = current.map(|component| component / fastest));
+    }
+    FlowField { currents }
+}
+
+// the number of steps from each tile to the nearest land, 0 on the land, `usize::MAX` in a world without land
+#[inline(always)]
+fn distance_from_shore(water: &[Vec<bool>]) -> Vec<Vec<usize>> {
+    let size = water.len();
+    let mut distance = vec![vec![usize::MAX; size]; size];
+    let mut queue = VecDeque::new();
+    for (row, tiles) in water.iter().enumerate() {
+        for (col, _) in tiles.iter().enumerate().filter(|(_, water)| !**water) {
+            distance[row][col] = 0;
+            queue.push_back(Coordinate { row, col });
+        }
+    }
+
+    while let Some(c) = queue.pop_front() {
+        let next = distance[c.row][c.col] + 1;
+        for (row, col) in [(c.row.wrapping_sub(1), c.col), (c.row + 1, c.col), (c.row, c.col.wrapping_sub(1)), (c.row, c.col + 1)] {
+            if row < size && col < size && distance[row][col] == usize::MAX {
+                distance[row][col] = next;
+                queue.push_back(Coordinate { row, col });
+            }
+        }
+    }
+    distance
+}
+
+// averages the current of each water tile with the ones of its water neighbours
+#[inline(always)]
+fn smooth(currents: &[Vec<[f32; 2]>], water: &[Vec<bool>]) -> Vec<Vec<[f32; 2]>> {
+    let size = currents.len();
+    let mut smoothed = currents.to_vec();
+    for (row, row_currents) in smoothed.iter_mut().enumerate() {
+        for (col, current) in row_currents.iter_mut().enumerate().filter(|(col, _)| water[row][*col]) {
+            let mut sum = currents[row][col];
+            let mut count = 1.0;
+            for (r, c) in [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)] {
+                if r < size && c < size && water[r][c] {
+                    sum = [sum[0] + currents[r][c][0], sum[1] + currents[r][c][1]];
+                    count += 1.0;
+                }
+            }
+            *current = sum.map(|component| component / count);
+        }
+    }
+    smoothed
+}
@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+
+use robotics_lib::runner::{Runnable, Runner};
+use robotics_lib::utils::LibError;
+
+use crate::generator::mini::MiniWorldGenerator;
+use crate::generator::validation::SettingsIssue;
+use crate::generator::WorldGenerator;
+
+/// Why the world of a test could not be made
+#[derive(Debug)]
+pub enum HarnessError {
+    /// the settings do not pass `validate_all`, the issues name the settings to fix
+    InvalidSettings(Vec<SettingsIssue>),
+    /// the runner refused the generated world
+    Runner(LibError),
+}
+
+impl Display for HarnessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | HarnessError::InvalidSettings(issues) => {
+                write!(f, "Invalid settings:")?;
+                for issue in issues {
+                    write!(f, "\n{issue}")?;
+                }
+                Ok(())
+            }
+            | HarnessError::Runner(e) => write!(f, "The runner refused the world: {e:?}"),
+        }
+    }
+}
+
+/// Generates a world with the given settings and hands it to a `Runner` driving the robot, the plumbing of the
+/// `Generator` every robot test otherwise repeats.
+///
+/// The settings are checked first, so invalid ones are reported as an error instead of panicking midway; they are
+/// cloned, the same settings can make the world of many tests.
+///
+/// # Errors
+///
+/// Returns the issues of the settings, or the error of the runner if it refuses the world.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::runner::Runnable;
+/// use exclusion_zone::generator::harness::{make_runner, HarnessError};
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// fn survives_100_ticks(robot: Box<dyn Runnable>) -> Result<(), HarnessError> {
+///     let mut runner = make_runner(robot, &WorldGenerator::default(200))?;
+///     for _ in 0..100 {
+///         runner.game_tick().map_err(HarnessError::Runner)?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn make_runner(robot: Box<dyn Runnable>, settings: &WorldGenerator) -> Result<Runner, HarnessError> {
+    settings.validate_all().map_err(HarnessError::InvalidSettings)?;
+    let mut generator = settings.clone();
+    Runner::new(robot, &mut generator).map_err(HarnessError::Runner)
+}
+
+/// Generates a small world with the `MiniWorldGenerator` and hands it to a `Runner` driving the robot, like
+/// `make_runner`; the mini generator takes any settings, so only the runner can refuse the world.
+///
+/// # Errors
+///
+/// Returns the error of the runner if it refuses the world.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::runner::Runnable;
+/// use exclusion_zone::generator::harness::make_mini_runner;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+///
+/// // a different world for each case, always the same for a seed
+/// fn first_tick(make_robot: impl Fn() -> Box<dyn Runnable>) {
+///     for seed in 0..10 {
+///         let mut runner = make_mini_runner(make_robot(), &MiniWorldGenerator::new(20).with_seed(seed)).unwrap();
+///         runner.game_tick().unwrap();
+///     }
+/// }
+/// ```
+pub fn make_mini_runner(robot: Box<dyn Runnable>, settings: &MiniWorldGenerator) -> Result<Runner, HarnessError> {
+    let mut generator = settings.clone();
+    Runner::new(robot, &mut generator).map_err(HarnessError::Runner)
+}
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::reachability::neighbours;
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Marks the tiles the hazards cannot reach, walking from them
+const UNREACHED: u32 = u32::MAX;
+
+/// Distance of each walkable tile from the nearest hazard, a tile holding Fire or a Lava tile.
+///
+/// The distance is the number of steps between tiles sharing a side, walking only on walkable tiles, so that a robot
+/// can tell how far it is from danger along the paths it can take; the hazards are at distance 0.
+/// The tiles which are not walkable, and the walkable tiles no hazard can reach, have no distance.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct HazardMap {
+    size: usize,
+    // row-major distances, UNREACHED for the tiles without distance
+    distances: Vec<u32>,
+}
+
+impl HazardMap {
+    /// Builds the map of the distances from the hazards of the given world, with a breadth first search
+    /// starting from all the hazards at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::hazards::HazardMap;
+    /// use exclusion_zone::generator::{Coordinate, WorldGenerator};
+    ///
+    /// let ((world, spawn, ..), artifacts) = WorldGenerator::default(200).gen_with_artifacts();
+    /// let danger = artifacts.hazard_map.distance(Coordinate::new(spawn.0, spawn.1));
+    /// assert_eq!(HazardMap::build(&world), artifacts.hazard_map);
+    /// ```
+    pub fn build(world: &TileMatrix) -> Self {
+        let size = world.len();
+        let mut distances = vec![UNREACHED; size * size];
+        let mut queue = VecDeque::new();
+
+        for (row, tiles) in world.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if is_hazard(tile) {
+                    distances[row * size + col] = 0;
+                    queue.push_back(Coordinate { row, col });
+                }
+            }
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[current.row * size + current.col];
+            for next in neighbours(current, size) {
+                let i = next.row * size + next.col;
+                if distances[i] == UNREACHED && world[next.row][next.col].tile_type.properties().walk() {
+                    distances[i] = distance + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        HazardMap { size, distances }
+    }
+
+    /// Returns the side of the mapped world
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the distance of the tile from the nearest hazard, 0 for the hazards themselves,
+    /// `None` if the tile is not walkable, no hazard can reach it or it is out of the world
+    pub fn distance(&self, c: Coordinate) -> Option<usize> {
+        if c.row >= self.size || c.col >= self.size {
+            return None;
+        }
+        match self.distances[c.row * self.size + c.col] {
+            | UNREACHED => None,
+            | distance => Some(distance as usize),
+        }
+    }
+}
+
+// a tile that damages the robot: burning or made of lava
+#[inline(always)]
+fn is_hazard(tile: &Tile) -> bool {
+    tile.content == Content::Fire || tile.tile_type == TileType::Lava
+}
@@ -0,0 +1,415 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::iter::successors;
+
+use chrono::Utc;
+use noise::NoiseFn;
+use rand::Rng;
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+use tracing::field::Empty;
+use tracing::{debug_span, info_span};
+
+use crate::content::bank::spawn_bank;
+use crate::content::bin::spawn_bin;
+use crate::content::blob::BlobSettings;
+use crate::content::coin::spawn_coin;
+use crate::content::fish::spawn_fish;
+use crate::content::garbage::GarbageSettings;
+use crate::content::market::spawn_market;
+use crate::content::rock::spawn_rock;
+use crate::content::wood_crate::spawn_crate;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::seeds::StageSeeds;
+use crate::generator::transaction::WorldTransaction;
+use crate::generator::{elevation_noise, in_stage, remove_duplicates_spawnables, robot_spawn, Spawnables, TileMatrix, WorldGenerator};
+use crate::tile_type::lava::{flow_lava, get_yx_mountain_tiles, LavaSettings};
+use crate::tile_type::street::{get_local_maxima, StreetSettings};
+use crate::utils::{find_max_value, find_min_value, generation_rng, into_maybe_par_iter, seed_generation_rng, Coordinate};
+
+// the distance between the centers of two rows of hexes, the distance between two hexes of the same row is 1
+const ROW_HEIGHT: f64 = 0.866_025_403_784_438_6;
+
+// the steps to the six neighbours in axial coordinates, counterclockwise from the east
+const DIRECTIONS: [(isize, isize); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// A hex of a grid of pointy-topped hexes, in axial coordinates: `q` grows toward the east and `r` toward the
+/// south-east, the third cube coordinate is `-q - r`.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::hex::HexCoordinate;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let hex = HexCoordinate::new(2, 1);
+/// assert!(hex.neighbours().iter().all(|neighbour| hex.distance(*neighbour) == 1));
+/// assert_eq!(hex.distance(HexCoordinate::new(0, 0)), 3);
+///
+/// // the odd rows of the tile matrix are shifted by half a hex
+/// assert_eq!(hex.to_offset(), Some(Coordinate::new(1, 2)));
+/// assert_eq!(HexCoordinate::from_offset(Coordinate::new(1, 2)), hex);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HexCoordinate {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl HexCoordinate {
+    pub fn new(q: isize, r: isize) -> Self {
+        HexCoordinate { q, r }
+    }
+
+    /// Returns the hex stored at the given row and column of the tile matrix of a `HexWorld`
+    pub fn from_offset(c: Coordinate) -> Self {
+        let row = c.row as isize;
+        HexCoordinate {
+            q: c.col as isize - (row - (row & 1)) / 2,
+            r: row,
+        }
+    }
+
+    /// Returns the row and column of the hex in the tile matrix of a `HexWorld`, `None` if they would be negative
+    pub fn to_offset(self) -> Option<Coordinate> {
+        let col = self.q + (self.r - (self.r & 1)) / 2;
+        (self.r >= 0 && col >= 0).then_some(Coordinate {
+            row: self.r as usize,
+            col: col as usize,
+        })
+    }
+
+    /// Returns the six hexes sharing a side with the hex, counterclockwise from the east
+    pub fn neighbours(self) -> [HexCoordinate; 6] {
+        DIRECTIONS.map(|(q, r)| HexCoordinate::new(self.q + q, self.r + r))
+    }
+
+    /// Returns the number of steps between the two hexes
+    pub fn distance(self, other: HexCoordinate) -> usize {
+        let (q, r) = (self.q - other.q, self.r - other.r);
+        (q.unsigned_abs() + r.unsigned_abs() + (q + r).unsigned_abs()) / 2
+    }
+
+    /// Returns the center of the hex on a plane where the hexes have the given size, from the center to a corner,
+    /// and the hex `(0, 0)` is centered on the origin, with `y` growing downward
+    pub fn to_pixel(self, hex_size: f64) -> (f64, f64) {
+        let x = hex_size * 3f64.sqrt() * (self.q as f64 + self.r as f64 / 2.0);
+        let y = hex_size * 1.5 * self.r as f64;
+        (x, y)
+    }
+
+    /// Returns the hex containing the point of the plane of `to_pixel`
+    pub fn from_pixel(x: f64, y: f64, hex_size: f64) -> Self {
+        let q = (3f64.sqrt() / 3.0 * x - y / 3.0) / hex_size;
+        let r = 2.0 / 3.0 * y / hex_size;
+
+        // rounds the cube coordinates, fixing the one rounded the most so that they still sum to 0
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        HexCoordinate::new(rq as isize, rr as isize)
+    }
+}
+
+/// A world of pointy-topped hexes generated by `gen_hex`.
+///
+/// The hexes are stored in a square tile matrix, row by row, with the odd rows shifted east by half a hex: the tile
+/// at `[row][col]` is the hex `HexCoordinate::from_offset(Coordinate::new(row, col))`. The tile types and the contents
+/// are the ones of the square worlds, only the neighbours change.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HexWorld {
+    /// the hexes, in the layout described above
+    pub tiles: TileMatrix,
+    /// the robot spawn point, the first walkable hex row by row
+    pub spawn: HexCoordinate,
+    /// the environmental conditions of the world
+    pub environmental_conditions: EnvironmentalConditions,
+}
+
+impl HexWorld {
+    /// Returns the number of rows, and of hexes per row
+    pub fn size(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns the tile of the hex, `None` if the hex is out of the world
+    pub fn get(&self, hex: HexCoordinate) -> Option<&Tile> {
+        let c = hex.to_offset()?;
+        self.tiles.get(c.row)?.get(c.col)
+    }
+
+    /// Returns the neighbours of the hex which are in the world
+    pub fn neighbours(&self, hex: HexCoordinate) -> impl Iterator<Item = HexCoordinate> + '_ {
+        hex.neighbours().into_iter().filter(|neighbour| self.get(*neighbour).is_some())
+    }
+}
+
+impl WorldGenerator {
+    /// Generates a world of hexes from the same settings as `gen`, for the robots moving on hex grids.
+    ///
+    /// This mode is experimental: the elevation is sampled at the centers of the hexes and the terrain is classified
+    /// like in `gen`, then the lava flows, the streets connect the junctions and the trees, the fires and the garbage
+    /// spread along the six neighbours of each hex. The other content is placed like in `gen`, since it does not
+    /// depend on the neighbours. The stages after the content, like the landmarks, the vaults and the reachability
+    /// check, are not run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(200);
+    /// let world = generator.gen_hex();
+    /// let spawn = world.get(world.spawn).unwrap();
+    /// assert!(spawn.tile_type.properties().walk());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic, before generating anything, if the settings do not pass `validate_all`.
+    pub fn gen_hex(&mut self) -> HexWorld {
+        if let Err(issues) = self.validate_all() {
+            panic!("Invalid settings:\n{}", issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>().join("\n"));
+        }
+
+        let generation = info_span!("generate_hex", size = self.size, seed = self.noise_settings.seed, elapsed_ms = Empty);
+        let _generation = generation.enter();
+        let tot = Utc::now();
+
+        let noise_map = in_stage(debug_span!("noise_map", size = self.size, elapsed_ms = Empty), || generate_hex_elevation_map(self));
+        let (min_value, max_value) = (find_min_value(&noise_map).unwrap_or(f64::MAX), find_max_value(&noise_map).unwrap_or(f64::MIN));
+        let mut world = in_stage(debug_span!("terrain", size = self.size, elapsed_ms = Empty), || self.generate_terrain(&noise_map, min_value, max_value));
+
+        seed_generation_rng(self.noise_settings.seed as u64);
+        remove_duplicates_spawnables(&mut self.spawn_order);
+
+        in_stage(debug_span!("streets", elapsed_ms = Empty), || place_hex_streets(&mut world, &noise_map, &self.street_settings));
+        in_stage(debug_span!("lava", spawn_points = self.lava_settings.number_of_spawn_points, elapsed_ms = Empty), || {
+            spawn_hex_lava(&mut world, &noise_map, &self.lava_settings)
+        });
+
+        let mut occupancy = Occupancy::build(&world);
+        let stage_seeds = self.stage_seeds.clone().unwrap_or_else(|| StageSeeds::from_seed(self.noise_settings.seed));
+        for content in &self.spawn_order {
+            seed_generation_rng(stage_seeds.get(*content));
+            occupancy.set_policy(self.conflict_settings.policy(*content));
+            in_stage(debug_span!("spawn", content = ?content, elapsed_ms = Empty), || match content {
+                | Spawnables::Tree => spawn_hex_blobs(&mut world, &mut occupancy, &self.tree_settings.settings, Content::Tree(0)),
+                | Spawnables::Fire => {
+                    let fire_settings = self.weather_settings.adjust_fire(&self.fire_settings);
+                    spawn_hex_blobs(&mut world, &mut occupancy, &fire_settings.settings, Content::Fire)
+                }
+                | Spawnables::Garbage => spawn_hex_garbage(&mut world, &mut occupancy, &self.garbage_settings),
+                | Spawnables::Rock => spawn_rock(&mut world, &mut occupancy, &self.rock_settings, &noise_map),
+                | Spawnables::Bin => spawn_bin(&mut world, &mut occupancy, self.bin_settings),
+                | Spawnables::Crate => spawn_crate(&mut world, &mut occupancy, self.crate_settings),
+                | Spawnables::Bank => spawn_bank(&mut world, &mut occupancy, self.bank_settings),
+                | Spawnables::Coin => spawn_coin(&mut world, &mut occupancy, self.coin_settings),
+                | Spawnables::Market => spawn_market(&mut world, &mut occupancy, self.market_settings),
+                | Spawnables::Fish => spawn_fish(&mut world, &mut occupancy, self.fish_settings),
+            });
+        }
+
+        let spawn = HexCoordinate::from_offset(robot_spawn(&world).coordinate());
+        let environmental_conditions = self.weather_settings.environmental_conditions_or_default();
+
+        generation.record("elapsed_ms", (Utc::now() - tot).num_milliseconds());
+
+        HexWorld {
+            tiles: world,
+            spawn,
+            environmental_conditions,
+        }
+    }
+}
+
+// samples the noise at the centers of the hexes, scaled like `generate_elevation_map` so that the hexes of a row
+// span the same [0, 1) range as the tiles of a square world
+#[inline(always)]
+fn generate_hex_elevation_map(generator: &WorldGenerator) -> Vec<Vec<f64>> {
+    let size = generator.size;
+    let noise = elevation_noise(&generator.noise_settings);
+
+    into_maybe_par_iter(0..size)
+        .map(|row| {
+            let y_normalized = row as f64 * ROW_HEIGHT / size as f64;
+            let shift = (row % 2) as f64 / 2.0;
+            (0..size)
+                .map(|col| {
+                    let x_normalized = (col as f64 + shift) / size as f64;
+                    noise.get([x_normalized, y_normalized])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// the neighbours of the hex stored at the given coordinate which are in a world of the given size
+#[inline(always)]
+fn hex_neighbours(c: Coordinate, size: usize) -> impl Iterator<Item = Coordinate> {
+    HexCoordinate::from_offset(c)
+        .neighbours()
+        .into_iter()
+        .filter_map(HexCoordinate::to_offset)
+        .filter(move |n| n.row < size && n.col < size)
+}
+
+// the hexes within the given number of steps from the center which are in the world, with their distance from it
+#[inline(always)]
+fn hexes_within(center: Coordinate, radius: usize, size: usize) -> impl Iterator<Item = (Coordinate, usize)> {
+    let center = HexCoordinate::from_offset(center);
+    let radius = radius as isize;
+    (-radius..=radius)
+        .flat_map(move |q| ((-radius).max(-q - radius)..=radius.min(-q + radius)).map(move |r| (q, r)))
+        .map(move |(q, r)| HexCoordinate::new(center.q + q, center.r + r))
+        .filter_map(move |hex| hex.to_offset().filter(|c| c.row < size && c.col < size).map(|c| (c, hex.distance(center))))
+}
+
+// connects each junction, a local maximum of the elevation, to the closest junction before it with the shortest
+// path of hexes that can be paved, the junctions no path reaches are left alone
+#[inline(always)]
+fn place_hex_streets(world: &mut TileMatrix, elevation_map: &[Vec<f64>], settings: &StreetSettings) {
+    let junctions = get_local_maxima(elevation_map, settings.n_slice_side, settings.lower_threshold);
+    for (i, junction) in junctions.iter().enumerate().skip(1) {
+        let hex = HexCoordinate::from_offset(*junction);
+        let Some(closest) = junctions[..i].iter().min_by_key(|other| hex.distance(HexCoordinate::from_offset(**other))) else {
+            continue;
+        };
+        for c in hex_path(world, *closest, *junction, settings).unwrap_or_default() {
+            world[c.row][c.col].tile_type = TileType::Street;
+        }
+    }
+}
+
+// the shortest path of hexes that can be paved between the two hexes, both included, found with A*
+#[inline(always)]
+fn hex_path(world: &TileMatrix, from: Coordinate, to: Coordinate, settings: &StreetSettings) -> Option<Vec<Coordinate>> {
+    let size = world.len();
+    let target = HexCoordinate::from_offset(to);
+    let mut previous: HashMap<Coordinate, Coordinate> = HashMap::new();
+    let mut steps: HashMap<Coordinate, usize> = HashMap::from([(from, 0)]);
+    let mut open = BinaryHeap::from([Reverse((HexCoordinate::from_offset(from).distance(target), from))]);
+
+    while let Some(Reverse((_, c))) = open.pop() {
+        if c == to {
+            let mut path: Vec<Coordinate> = successors(Some(to), |c| previous.get(c).copied()).collect();
+            path.reverse();
+            return Some(path);
+        }
+        let next = steps[&c] + 1;
+        for n in hex_neighbours(c, size) {
+            if !settings.can_pave(world[n.row][n.col].tile_type) || steps.get(&n).is_some_and(|&known| known <= next) {
+                continue;
+            }
+            steps.insert(n, next);
+            previous.insert(n, c);
+            open.push(Reverse((next + HexCoordinate::from_offset(n).distance(target), n)));
+        }
+    }
+    None
+}
+
+// flows the lava from the mountain hexes to the lowest neighbour at each step, like `spawn_lava`
+#[inline(always)]
+fn spawn_hex_lava(world: &mut TileMatrix, elevation_map: &[Vec<f64>], settings: &LavaSettings) {
+    let size = world.len();
+    let spawn_points = get_yx_mountain_tiles(world);
+    // the hex worlds have no tags
+    let mut steam = Vec::new();
+    for start in spawn_points.into_iter().take(settings.number_of_spawn_points) {
+        flow_lava(world, elevation_map, start, settings.lava_flow_range.len(), settings, &mut steam, |c| hex_neighbours(c, size));
+    }
+}
+
+// spreads the blobs of content like `spawn_blob`: each blob covers the hexes which can hold the content connected to
+// its center and within its radius, give or take half a hex for a ragged border
+#[inline(always)]
+fn spawn_hex_blobs(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &BlobSettings, content: Content) {
+    let size = world.len();
+    let mut rng = generation_rng();
+    let (mut tiles_left, mut blobs_left) = (settings.n_tiles.end, settings.n_blob.end);
+    let mut centers: Vec<Coordinate> = (0..size)
+        .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+        .filter(|c| occupancy.can_hold(*c, &content))
+        .collect();
+
+    while blobs_left > 0 && !centers.is_empty() {
+        let center = centers.swap_remove(rng.gen_range(0..centers.len()));
+        let radius = rng.gen_range(settings.radius_range.clone());
+
+        let mut blob = vec![center];
+        let mut seen = HashSet::from([center]);
+        let mut queue = VecDeque::from([center]);
+        while let Some(c) = queue.pop_front() {
+            for n in hex_neighbours(c, size) {
+                if !seen.insert(n) {
+                    continue;
+                }
+                let distance = HexCoordinate::from_offset(n).distance(HexCoordinate::from_offset(center)) as f32;
+                if distance <= radius + rng.gen_range(-0.5..0.5) && occupancy.can_hold(n, &content) {
+                    blob.push(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        if blob.len() > tiles_left {
+            break;
+        }
+        tiles_left -= blob.len();
+        blobs_left -= 1;
+        let mut transaction = WorldTransaction::default();
+        for c in blob {
+            transaction.stage(world, occupancy, c, content.clone());
+        }
+        transaction.commit(world, occupancy);
+    }
+}
+
+// drops the piles of garbage like `spawn_garbage`: the center of a pile is always filled, its rings of hexes less
+// and less likely by `probability_step_by` each
+#[inline(always)]
+fn spawn_hex_garbage(world: &mut TileMatrix, occupancy: &mut Occupancy, settings: &GarbageSettings) {
+    let size = world.len();
+    let mut rng = generation_rng();
+    let per_tile_quantity = settings.garbage_per_tile_quantity.clamped(&Content::Garbage(0));
+    let mut placed = 0;
+    // the piles may land where no garbage can lie, give up after as many piles as the garbage to place
+    for _ in 0..settings.total_garbage_quantity {
+        if placed >= settings.total_garbage_quantity {
+            break;
+        }
+        let radius = rng.gen_range(settings.garbage_pile_size.clone()) / 2;
+        let center = Coordinate {
+            row: rng.gen_range(0..size),
+            col: rng.gen_range(0..size),
+        };
+        let (mut pile, mut amount) = (WorldTransaction::default(), 0);
+        for (c, distance) in hexes_within(center, radius, size) {
+            let probability = if distance == 0 {
+                1.0
+            } else {
+                settings.spawn_in_near_tiles_probability * (1.0 - settings.probability_step_by * distance as f64).max(0.0)
+            };
+            if !rng.gen_bool(probability.clamp(0.0, 1.0)) {
+                continue;
+            }
+            let quantity = per_tile_quantity.sample(&mut rng);
+            if occupancy.can_hold(c, &Content::Garbage(0)) {
+                pile.stage(world, occupancy, c, Content::Garbage(quantity));
+                amount += quantity;
+            }
+        }
+        if pile.commit(world, occupancy) {
+            placed += amount;
+        }
+    }
+}
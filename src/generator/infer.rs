@@ -0,0 +1,167 @@
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::{generate_elevation_map, NoiseSettings, Thresholds, TileMatrix, WorldGenerator};
+
+// the max side of the reference elevation map the thresholds are read from
+const REFERENCE_SIZE: usize = 256;
+// the seed of the reference elevation map, fixed so that the same world gives the same settings
+const REFERENCE_SEED: u32 = 0;
+
+/// The settings estimated from a world, to generate more worlds like it when only the world is available, like a
+/// world file saved by another tool without the settings bundled.
+///
+/// The estimate is approximate: the noise and the seed cannot be recovered, the thresholds only reproduce the share of
+/// each tile type and the content is spawned in the same amount, not in the same places.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InferredSettings {
+    /// the side of the world
+    pub size: usize,
+    /// the thresholds giving, on the default noise, the same share of each tile type as the world
+    pub thresholds: Thresholds,
+    /// the share of the tiles of each tile type, the tile types missing from the world are left out
+    pub tile_shares: Vec<(TileType, f64)>,
+    /// the share of the tiles holding each kind of content, the kind is given by the default value of the content
+    pub content_densities: Vec<(Content, f64)>,
+    /// the total quantity of garbage
+    pub garbage_quantity: usize,
+}
+
+impl InferredSettings {
+    /// Returns the number of tiles of the world holding the given kind of content, whatever its quantity
+    pub fn content_count(&self, content: &Content) -> usize {
+        let kind = content.to_default();
+        let density = self.content_densities.iter().find(|(other, _)| *other == kind).map_or(0.0, |(_, density)| *density);
+        (density * (self.size * self.size) as f64).round() as usize
+    }
+
+    /// Creates a generator with the default settings for the size of the world, changed to the estimated ones.
+    ///
+    /// The thresholds are replaced, and so are the amounts of the content placed on single tiles: the spawn points
+    /// of the banks, the bins, the crates, the markets and the coins, the schools of fish, the max number of rocks and
+    /// the total quantity of garbage. The trees and the fire grow in blobs whose size cannot be told from a count,
+    /// they keep the default settings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use exclusion_zone::generator::infer::infer_settings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let (_, world) = WorldGenerator::load_saved("worlds/downloaded.zst").expect("unable to load the world");
+    /// let mut generator = infer_settings(&world.0).to_generator();
+    /// generator.generate_and_save("worlds/more_like_this").expect("unable to save the world");
+    /// ```
+    pub fn to_generator(&self) -> WorldGenerator {
+        let mut generator = WorldGenerator::default(self.size);
+        generator.thresholds = self.thresholds;
+        generator.bank_settings.number_of_spawn_points = self.content_count(&Content::Bank(0..0));
+        generator.bin_settings.number_of_spawn_points = self.content_count(&Content::Bin(0..0));
+        generator.crate_settings.number_of_spawn_points = self.content_count(&Content::Crate(0..0));
+        generator.market_settings.number_of_spawn_points = self.content_count(&Content::Market(0));
+        generator.coin_settings.number_of_spawn_points = self.content_count(&Content::Coin(0));
+        generator.fish_settings.number_of_spawn_points = self.content_count(&Content::Fish(0)).div_ceil(generator.fish_settings.school_size.max(1));
+        generator.rock_settings.max_num_rocks = self.content_count(&Content::Rock(0));
+        generator.garbage_settings.total_garbage_quantity = self.garbage_quantity;
+        generator
+    }
+}
+
+/// Estimates the settings a world was generated with, from its tile types and its content.
+///
+/// The thresholds are the percentiles of the elevation matching the share of each tile type: the elevation of the
+/// default noise is sampled and the threshold of each tile type is placed where the share of the lower tiles equals
+/// the share of that tile type and the ones below it in the world. The streets, the walls and the teleports are built
+/// over the terrain and are left out, the lava flows over the mountains and counts as Mountain.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::Content;
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::infer::infer_settings;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+///
+/// let (world, ..) = MiniWorldGenerator::new(50).gen();
+/// let inferred = infer_settings(&world);
+/// println!("deep water below {}%", inferred.thresholds.threshold_deep_water);
+/// println!("{} coins", inferred.content_count(&Content::Coin(0)));
+/// ```
+pub fn infer_settings(world: &TileMatrix) -> InferredSettings {
+    let size = world.len();
+    let tiles = (size * size).max(1) as f64;
+
+    let mut tile_counts: Vec<(TileType, usize)> = Vec::new();
+    let mut content_counts: Vec<(Content, usize)> = Vec::new();
+    let mut garbage_quantity = 0;
+    for tile in world.iter().flatten() {
+        match tile_counts.iter_mut().find(|(tile_type, _)| *tile_type == tile.tile_type) {
+            | Some((_, count)) => *count += 1,
+            | None => tile_counts.push((tile.tile_type, 1)),
+        }
+        if let Content::Garbage(quantity) = tile.content {
+            garbage_quantity += quantity;
+        }
+        if tile.content == Content::None {
+            continue;
+        }
+        let kind = tile.content.to_default();
+        match content_counts.iter_mut().find(|(content, _)| *content == kind) {
+            | Some((_, count)) => *count += 1,
+            | None => content_counts.push((kind, 1)),
+        }
+    }
+
+    InferredSettings {
+        size,
+        thresholds: infer_thresholds(&tile_counts, size),
+        tile_shares: tile_counts.iter().map(|(tile_type, count)| (*tile_type, *count as f64 / tiles)).collect(),
+        content_densities: content_counts.iter().map(|(content, count)| (content.clone(), *count as f64 / tiles)).collect(),
+        garbage_quantity,
+    }
+}
+
+// the thresholds splitting the elevation of the default noise in the shares of the natural tile types of the world
+fn infer_thresholds(tile_counts: &[(TileType, usize)], size: usize) -> Thresholds {
+    let count = |tile_types: &[TileType]| tile_counts.iter().filter(|(tile_type, _)| tile_types.contains(tile_type)).map(|(_, count)| count).sum::<usize>();
+    // from the lowest to the highest, the last one is above every threshold
+    let layers = [
+        count(&[TileType::DeepWater]),
+        count(&[TileType::ShallowWater]),
+        count(&[TileType::Sand]),
+        count(&[TileType::Grass]),
+        count(&[TileType::Hill]),
+        count(&[TileType::Mountain, TileType::Lava]),
+        count(&[TileType::Snow]),
+    ];
+    let natural = layers.iter().sum::<usize>();
+    if natural == 0 {
+        return Thresholds::default();
+    }
+
+    let mut elevation: Vec<f64> = generate_elevation_map(&NoiseSettings::from_seed(REFERENCE_SEED), size.clamp(1, REFERENCE_SIZE))
+        .into_iter()
+        .flatten()
+        .collect();
+    elevation.sort_by(f64::total_cmp);
+    let (min, max) = (elevation[0], elevation[elevation.len() - 1]);
+    let range = if max > min { max - min } else { 1.0 };
+
+    let mut below = 0;
+    let mut thresholds = [0.0; 6];
+    for (threshold, layer) in thresholds.iter_mut().zip(layers) {
+        below += layer;
+        *threshold = match below {
+            | 0 => 0.0,
+            | below if below == natural => 100.0,
+            | below => {
+                // the elevation of the first sample above the share of the lower tiles
+                let index = (below as f64 / natural as f64 * elevation.len() as f64).round() as usize;
+                (elevation[index.min(elevation.len() - 1)] - min) / range * 100.0
+            }
+        };
+    }
+
+    let [deep_water, shallow_water, sand, grass, hill, mountain] = thresholds;
+    Thresholds::new(deep_water, shallow_water, sand, grass, hill, mountain)
+}
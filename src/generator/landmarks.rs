@@ -0,0 +1,210 @@
+use rand::Rng;
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::content::quantity::QuantityRange;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, Coordinate};
+
+// number of lowest tiles tried as reactor center before giving up
+const REACTOR_CANDIDATES: usize = 10_000;
+
+/// The kind of a landmark structure
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LandmarkKind {
+    /// the abandoned reactor, a square complex of Wall tiles with fire and garbage inside, built in the lowest dry basin
+    Reactor,
+    /// a radio mast, a Wall tile on one of the highest peaks
+    RadioMast,
+}
+
+/// A landmark placed in the world
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Landmark {
+    /// the kind of the landmark
+    pub kind: LandmarkKind,
+    /// the center of the landmark
+    pub position: Coordinate,
+}
+
+/// Settings defining the unique landmark structures placed on notable features of the elevation map
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct LandmarkSettings {
+    /// the number of radio masts, placed on the highest peaks
+    pub radio_masts: usize,
+    /// the min distance, in tiles, between two radio masts
+    pub mast_spacing: usize,
+    /// the side of the reactor complex, walls included, 0 disables the reactor
+    pub reactor_size: usize,
+    /// the probability that a tile inside the reactor is on fire
+    pub reactor_fire_probability: f64,
+    /// the probability that a tile inside the reactor, not on fire, holds garbage
+    pub reactor_garbage_probability: f64,
+}
+
+impl LandmarkSettings {
+    /// Custom version of default that provides an instance of `LandmarkSettings` with
+    /// reasonable parameters for the given world size
+    pub fn default(size: usize) -> Self {
+        LandmarkSettings {
+            radio_masts: 3,
+            mast_spacing: size / 5,
+            reactor_size: 9,
+            reactor_fire_probability: 0.2,
+            reactor_garbage_probability: 0.4,
+        }
+    }
+
+    /// Creates a new instance of `LandmarkSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `radio_masts` - The number of radio masts, placed on the highest peaks.
+    /// * `mast_spacing` - The min distance, in tiles, between two radio masts.
+    /// * `reactor_size` - The side of the reactor complex, walls included, 0 disables the reactor.
+    /// * `reactor_fire_probability` - The probability that a tile inside the reactor is on fire.
+    /// * `reactor_garbage_probability` - The probability that a tile inside the reactor, not on fire, holds garbage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::landmarks::LandmarkSettings;
+    ///
+    /// // a big reactor and no radio masts
+    /// let settings = LandmarkSettings::new(0, 0, 15, 0.3, 0.5);
+    /// ```
+    pub fn new(radio_masts: usize, mast_spacing: usize, reactor_size: usize, reactor_fire_probability: f64, reactor_garbage_probability: f64) -> Self {
+        LandmarkSettings {
+            radio_masts,
+            mast_spacing,
+            reactor_size,
+            reactor_fire_probability,
+            reactor_garbage_probability,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::landmarks::LandmarkSettings;
+    ///
+    /// // a reactor bigger than the world
+    /// assert!(!LandmarkSettings::new(0, 0, 150, 0.3, 0.5).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.reactor_size <= size, "LandmarkSettings::reactor_size", || {
+            format!("{} is bigger than the world side of {size} tiles", self.reactor_size)
+        });
+        issues.probability("LandmarkSettings::reactor_fire_probability", self.reactor_fire_probability);
+        issues.probability("LandmarkSettings::reactor_garbage_probability", self.reactor_garbage_probability);
+        issues.0
+    }
+}
+
+/// Places the reactor and the radio masts, returning where they have been placed.
+///
+/// The elevation map is the one used to classify the terrain; landmarks are never placed on water or lava.
+pub(crate) fn spawn_landmarks(world: &mut TileMatrix, elevation_map: &[Vec<f64>], settings: &LandmarkSettings) -> Vec<Landmark> {
+    let mut landmarks = Vec::new();
+
+    let mut by_elevation: Vec<Coordinate> = (0..world.len())
+        .flat_map(|row| (0..world.len()).map(move |col| Coordinate { row, col }))
+        .filter(|c| is_dry(world, *c))
+        .collect();
+    by_elevation.sort_by(|a, b| elevation_map[a.row][a.col].total_cmp(&elevation_map[b.row][b.col]));
+
+    if settings.reactor_size >= 3 {
+        match by_elevation.iter().take(REACTOR_CANDIDATES).find(|c| reactor_fits(world, **c, settings.reactor_size)) {
+            | Some(center) => {
+                build_reactor(world, *center, settings);
+                landmarks.push(Landmark {
+                    kind: LandmarkKind::Reactor,
+                    position: *center,
+                });
+            }
+            | None => warn!(reactor_size = settings.reactor_size, "LandmarkNotPlaced: no dry area fits the reactor"),
+        }
+    }
+
+    let mut masts: Vec<Coordinate> = Vec::new();
+    for c in by_elevation.iter().rev() {
+        if masts.len() == settings.radio_masts {
+            break;
+        }
+        let far_enough = masts.iter().all(|m| m.row.abs_diff(c.row).max(m.col.abs_diff(c.col)) >= settings.mast_spacing);
+        if far_enough && is_dry(world, *c) {
+            world[c.row][c.col].tile_type = TileType::Wall;
+            world[c.row][c.col].content = Content::None;
+            masts.push(*c);
+        }
+    }
+    landmarks.extend(masts.into_iter().map(|position| Landmark {
+        kind: LandmarkKind::RadioMast,
+        position,
+    }));
+
+    landmarks
+}
+
+// landmarks are built on land, away from lava and out of the existing walls
+#[inline(always)]
+fn is_dry(world: &TileMatrix, c: Coordinate) -> bool {
+    !matches!(
+        world[c.row][c.col].tile_type,
+        TileType::DeepWater | TileType::ShallowWater | TileType::Lava | TileType::Wall | TileType::Teleport(_)
+    )
+}
+
+// the footprint of the reactor is inside the world, on dry tiles and does not cut any street
+#[inline(always)]
+fn reactor_fits(world: &TileMatrix, center: Coordinate, reactor_size: usize) -> bool {
+    let half = reactor_size / 2;
+    if center.row < half || center.col < half || center.row + half >= world.len() || center.col + half >= world.len() {
+        return false;
+    }
+    (center.row - half..=center.row + half).all(|row| {
+        (center.col - half..=center.col + half).all(|col| {
+            let c = Coordinate { row, col };
+            is_dry(world, c) && world[row][col].tile_type != TileType::Street
+        })
+    })
+}
+
+// a ring of walls with a door in the middle of the southern side, the floor is overgrown and full of debris
+#[inline(always)]
+fn build_reactor(world: &mut TileMatrix, center: Coordinate, settings: &LandmarkSettings) {
+    let mut rng = generation_rng();
+    let half = settings.reactor_size / 2;
+    let side = 2 * half + 1;
+    for (row, tiles) in world.iter_mut().enumerate().skip(center.row - half).take(side) {
+        for (col, tile) in tiles.iter_mut().enumerate().skip(center.col - half).take(side) {
+            let on_border = row == center.row - half || row == center.row + half || col == center.col - half || col == center.col + half;
+            let door = row == center.row + half && col == center.col;
+            tile.content = Content::None;
+            if on_border && !door {
+                tile.tile_type = TileType::Wall;
+                continue;
+            }
+
+            tile.tile_type = TileType::Grass;
+            if door {
+                continue;
+            }
+            let content = if rng.gen_bool(settings.reactor_fire_probability.clamp(0.0, 1.0)) {
+                Content::Fire
+            } else if rng.gen_bool(settings.reactor_garbage_probability.clamp(0.0, 1.0)) {
+                Content::Garbage(QuantityRange::full(&Content::Garbage(0)).sample(&mut rng))
+            } else {
+                Content::None
+            };
+            if tile.tile_type.properties().can_hold(&content) {
+                tile.content = content;
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::mem::discriminant;
+
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
+use robotics_lib::world::tile::{Content, Tile};
+
+use crate::generator::TileMatrix;
+use crate::utils::{into_maybe_par_iter, Coordinate};
+
+/// Helpers iterating over the tiles of a tile matrix with their coordinates, instead of nested index loops.
+///
+/// The tiles are visited row by row, from the top left corner.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::matrix::TileMatrixExt;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let (world, ..) = MiniWorldGenerator::new(50).gen();
+/// let coins = world.count_content(&Content::Coin(0));
+/// assert_eq!(coins, world.iter_where(|tile| matches!(tile.content, Content::Coin(_))).count());
+///
+/// // the sand closest to the top left corner
+/// let sand = world.find_nearest(Coordinate::new(0, 0), |tile| tile.tile_type == TileType::Sand);
+/// ```
+pub trait TileMatrixExt {
+    /// Returns the tiles with their coordinates
+    fn iter_coords(&self) -> impl Iterator<Item = (Coordinate, &Tile)>;
+
+    /// Returns the tiles matching the predicate with their coordinates
+    fn iter_where(&self, predicate: impl FnMut(&Tile) -> bool) -> impl Iterator<Item = (Coordinate, &Tile)>;
+
+    /// Returns the number of tiles holding the kind of content, whatever its quantity, counted on multiple threads
+    /// if the `parallel` feature is enabled
+    fn count_content(&self, content: &Content) -> usize;
+
+    /// Returns the tile matching the predicate with the fewest steps along rows and columns from `from`,
+    /// `None` if no tile matches.
+    ///
+    /// The tiles are visited in rings of growing distance, so the cost depends on the distance of the tile found
+    /// rather than on the size of the world.
+    fn find_nearest(&self, from: Coordinate, predicate: impl FnMut(&Tile) -> bool) -> Option<Coordinate>;
+}
+
+impl TileMatrixExt for TileMatrix {
+    fn iter_coords(&self) -> impl Iterator<Item = (Coordinate, &Tile)> {
+        self.iter()
+            .enumerate()
+            .flat_map(|(row, tiles)| tiles.iter().enumerate().map(move |(col, tile)| (Coordinate { row, col }, tile)))
+    }
+
+    fn iter_where(&self, mut predicate: impl FnMut(&Tile) -> bool) -> impl Iterator<Item = (Coordinate, &Tile)> {
+        self.iter_coords().filter(move |(_, tile)| predicate(tile))
+    }
+
+    fn count_content(&self, content: &Content) -> usize {
+        let kind = discriminant(content);
+        into_maybe_par_iter(self).map(|tiles| tiles.iter().filter(|tile| discriminant(&tile.content) == kind).count()).sum()
+    }
+
+    fn find_nearest(&self, from: Coordinate, mut predicate: impl FnMut(&Tile) -> bool) -> Option<Coordinate> {
+        let rows = self.len();
+        let cols = self.iter().map(|tiles| tiles.len()).max().unwrap_or(0);
+        if rows == 0 || cols == 0 {
+            return None;
+        }
+
+        let max_distance = from.row.max(rows - 1 - from.row.min(rows - 1)) + from.col.max(cols - 1 - from.col.min(cols - 1));
+        for distance in 0..=max_distance {
+            // the ring of the tiles at `distance` steps, from the top
+            let (first, last) = (from.row.saturating_sub(distance), from.row.saturating_add(distance).min(rows - 1));
+            for (row, tiles) in self.iter().enumerate().take(last + 1).skip(first) {
+                let offset = distance - row.abs_diff(from.row);
+                let left = from.col.checked_sub(offset);
+                let right = Some(from.col + offset).filter(|_| offset > 0);
+                for col in [left, right].into_iter().flatten() {
+                    if tiles.get(col).is_some_and(&mut predicate) {
+                        return Some(Coordinate { row, col });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,107 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// the bytes allocated by the process, and the most allocated at once since the last measure started
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator counting the bytes allocated, with a couple of atomic operations per allocation, to report the
+/// memory of each generation stage.
+///
+/// The library does not install it: the binary declares it as its global allocator, otherwise every stage reports no
+/// memory. Only one global allocator can be installed, so a binary setting its own cannot measure the stages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use exclusion_zone::generator::memory::TrackingAllocator;
+///
+/// #[global_allocator]
+/// static GLOBAL: TrackingAllocator = TrackingAllocator;
+/// ```
+pub struct TrackingAllocator;
+
+#[inline(always)]
+fn track_alloc(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            track_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Returns the bytes currently allocated by the process, approximate while other threads allocate, 0 unless the
+/// `TrackingAllocator` is the global allocator.
+///
+/// # Examples
+///
+/// ```no_run
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::memory::{allocated_bytes, TrackingAllocator};
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// #[global_allocator]
+/// static GLOBAL: TrackingAllocator = TrackingAllocator;
+///
+/// let world = WorldGenerator::default(1000).gen();
+/// println!("{} MB allocated holding the world", allocated_bytes() / 1_000_000);
+/// ```
+pub fn allocated_bytes() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+// the memory allocated by a generation stage, in bytes, logged in the span of the stage
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct StageMemory {
+    // the bytes still allocated at the end of the stage minus the ones at its start, like the noise map it returns;
+    // negative if the stage freed more than it kept
+    pub(crate) retained_bytes: isize,
+    // the most bytes allocated at once during the stage above the ones at its start, temporaries included
+    pub(crate) peak_bytes: usize,
+    // the bytes allocated by the process at the end of the stage
+    pub(crate) total_bytes: usize,
+}
+
+// runs the stage measuring its memory, the peak of an enclosing stage still accounts for the one of the stage
+#[inline(always)]
+pub(crate) fn measure<T>(stage: impl FnOnce() -> T) -> (T, StageMemory) {
+    let start = ALLOCATED.load(Ordering::Relaxed);
+    let enclosing_peak = PEAK.swap(start, Ordering::Relaxed);
+    let result = stage();
+    let (end, peak) = (ALLOCATED.load(Ordering::Relaxed), PEAK.fetch_max(enclosing_peak, Ordering::Relaxed));
+    let memory = StageMemory {
+        retained_bytes: end as isize - start as isize,
+        peak_bytes: peak.saturating_sub(start),
+        total_bytes: end,
+    };
+    (result, memory)
+}
@@ -0,0 +1,285 @@
+#[cfg(feature = "io")]
+use std::collections::HashMap;
+use std::ops::Range;
+
+#[cfg(feature = "io")]
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "io")]
+use crate::generator::GenResult;
+use crate::generator::TileMatrix;
+#[cfg(feature = "io")]
+use crate::utils::{read_compressed, Position};
+
+/// The tile types of the robotics_lib versions before the Teleport and the Wall, in the same order, so the worlds
+/// saved with them can still be deserialized
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LegacyTileType {
+    DeepWater,
+    ShallowWater,
+    Sand,
+    Grass,
+    Street,
+    Hill,
+    Mountain,
+    Snow,
+    Lava,
+}
+
+/// The content of the robotics_lib versions before the Market and the Fish, in the same order, so the worlds saved
+/// with them can still be deserialized
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LegacyContent {
+    Rock(usize),
+    Tree(usize),
+    Garbage(usize),
+    Fire,
+    Coin(usize),
+    Bin(Range<usize>),
+    Crate(Range<usize>),
+    Bank(Range<usize>),
+    Water(usize),
+    Building,
+    Bush(usize),
+    JollyBlock(usize),
+    Scarecrow,
+    None,
+}
+
+/// A tile of the robotics_lib versions before the Teleport, the Wall, the Market and the Fish
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LegacyTile {
+    /// the tile type, without the Teleport and the Wall
+    pub tile_type: LegacyTileType,
+    /// the content, without the Market and the Fish
+    pub content: LegacyContent,
+    /// the elevation, unchanged between the versions
+    pub elevation: usize,
+}
+
+/// Alias for the tile matrix of the robotics_lib versions before the Teleport, the Wall, the Market and the Fish
+pub type LegacyTileMatrix = Vec<Vec<LegacyTile>>;
+
+/// What was lost converting a world to the legacy tile types and content
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// the Teleport tiles turned into Street and the Wall tiles turned into Mountain
+    pub substituted_tile_types: usize,
+    /// the Markets turned into Buildings
+    pub substituted_contents: usize,
+    /// the Fish removed, the legacy water holds no fish
+    pub dropped_contents: usize,
+}
+
+impl From<LegacyTileType> for TileType {
+    fn from(tile_type: LegacyTileType) -> Self {
+        match tile_type {
+            | LegacyTileType::DeepWater => TileType::DeepWater,
+            | LegacyTileType::ShallowWater => TileType::ShallowWater,
+            | LegacyTileType::Sand => TileType::Sand,
+            | LegacyTileType::Grass => TileType::Grass,
+            | LegacyTileType::Street => TileType::Street,
+            | LegacyTileType::Hill => TileType::Hill,
+            | LegacyTileType::Mountain => TileType::Mountain,
+            | LegacyTileType::Snow => TileType::Snow,
+            | LegacyTileType::Lava => TileType::Lava,
+        }
+    }
+}
+
+impl From<LegacyContent> for Content {
+    fn from(content: LegacyContent) -> Self {
+        match content {
+            | LegacyContent::Rock(quantity) => Content::Rock(quantity),
+            | LegacyContent::Tree(quantity) => Content::Tree(quantity),
+            | LegacyContent::Garbage(quantity) => Content::Garbage(quantity),
+            | LegacyContent::Fire => Content::Fire,
+            | LegacyContent::Coin(quantity) => Content::Coin(quantity),
+            | LegacyContent::Bin(range) => Content::Bin(range),
+            | LegacyContent::Crate(range) => Content::Crate(range),
+            | LegacyContent::Bank(range) => Content::Bank(range),
+            | LegacyContent::Water(quantity) => Content::Water(quantity),
+            | LegacyContent::Building => Content::Building,
+            | LegacyContent::Bush(quantity) => Content::Bush(quantity),
+            | LegacyContent::JollyBlock(quantity) => Content::JollyBlock(quantity),
+            | LegacyContent::Scarecrow => Content::Scarecrow,
+            | LegacyContent::None => Content::None,
+        }
+    }
+}
+
+impl From<LegacyTile> for Tile {
+    fn from(tile: LegacyTile) -> Self {
+        Tile {
+            tile_type: tile.tile_type.into(),
+            content: tile.content.into(),
+            elevation: tile.elevation,
+        }
+    }
+}
+
+/// Converts a world of the legacy tile types and content to the current ones, every legacy variant has its
+/// counterpart, so nothing is lost.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::migrate::{upgrade_world, LegacyContent, LegacyTile, LegacyTileType};
+/// use robotics_lib::world::tile::{Content, TileType};
+///
+/// let legacy = vec![vec![LegacyTile { tile_type: LegacyTileType::Grass, content: LegacyContent::Tree(3), elevation: 0 }]];
+/// let world = upgrade_world(legacy);
+/// assert_eq!((world[0][0].tile_type, world[0][0].content.clone()), (TileType::Grass, Content::Tree(3)));
+/// ```
+pub fn upgrade_world(legacy: LegacyTileMatrix) -> TileMatrix {
+    legacy.into_iter().map(|row| row.into_iter().map(Tile::from).collect()).collect()
+}
+
+/// Converts a world to the legacy tile types and content, for the robots and the tools still built on the
+/// robotics_lib versions before the Teleport, the Wall, the Market and the Fish.
+///
+/// The variants missing from the legacy sets are substituted with the closest ones: the Teleport tiles become Street,
+/// walkable like a teleport pad, the Wall tiles become Mountain and the Markets become Buildings; the Fish are dropped.
+/// The report counts what was changed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::migrate::downgrade_world;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let (world, ..) = WorldGenerator::default(200).gen();
+/// let (legacy, report) = downgrade_world(&world);
+/// println!("{} fish dropped", report.dropped_contents);
+/// ```
+pub fn downgrade_world(world: &TileMatrix) -> (LegacyTileMatrix, MigrationReport) {
+    let mut report = MigrationReport::default();
+    let legacy = world
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| LegacyTile {
+                    tile_type: downgrade_tile_type(&tile.tile_type, &mut report),
+                    content: downgrade_content(&tile.content, &mut report),
+                    elevation: tile.elevation,
+                })
+                .collect()
+        })
+        .collect();
+    (legacy, report)
+}
+
+// the legacy tile type, the closest one if it is missing from the legacy set
+#[inline(always)]
+fn downgrade_tile_type(tile_type: &TileType, report: &mut MigrationReport) -> LegacyTileType {
+    match tile_type {
+        | TileType::DeepWater => LegacyTileType::DeepWater,
+        | TileType::ShallowWater => LegacyTileType::ShallowWater,
+        | TileType::Sand => LegacyTileType::Sand,
+        | TileType::Grass => LegacyTileType::Grass,
+        | TileType::Street => LegacyTileType::Street,
+        | TileType::Hill => LegacyTileType::Hill,
+        | TileType::Mountain => LegacyTileType::Mountain,
+        | TileType::Snow => LegacyTileType::Snow,
+        | TileType::Lava => LegacyTileType::Lava,
+        | TileType::Teleport(_) => {
+            report.substituted_tile_types += 1;
+            LegacyTileType::Street
+        }
+        | TileType::Wall => {
+            report.substituted_tile_types += 1;
+            LegacyTileType::Mountain
+        }
+    }
+}
+
+// the legacy content, the closest one if it is missing from the legacy set, or none
+#[inline(always)]
+fn downgrade_content(content: &Content, report: &mut MigrationReport) -> LegacyContent {
+    match content {
+        | Content::Rock(quantity) => LegacyContent::Rock(*quantity),
+        | Content::Tree(quantity) => LegacyContent::Tree(*quantity),
+        | Content::Garbage(quantity) => LegacyContent::Garbage(*quantity),
+        | Content::Fire => LegacyContent::Fire,
+        | Content::Coin(quantity) => LegacyContent::Coin(*quantity),
+        | Content::Bin(range) => LegacyContent::Bin(range.clone()),
+        | Content::Crate(range) => LegacyContent::Crate(range.clone()),
+        | Content::Bank(range) => LegacyContent::Bank(range.clone()),
+        | Content::Water(quantity) => LegacyContent::Water(*quantity),
+        | Content::Building => LegacyContent::Building,
+        | Content::Bush(quantity) => LegacyContent::Bush(*quantity),
+        | Content::JollyBlock(quantity) => LegacyContent::JollyBlock(*quantity),
+        | Content::Scarecrow => LegacyContent::Scarecrow,
+        | Content::None => LegacyContent::None,
+        | Content::Market(_) => {
+            report.substituted_contents += 1;
+            LegacyContent::Building
+        }
+        | Content::Fish(_) => {
+            report.dropped_contents += 1;
+            LegacyContent::None
+        }
+    }
+}
+
+/// The `GenResult` of the legacy saves, the robot spawn point is stored as `(x, y)`, the column then the row
+#[cfg(feature = "io")]
+pub type LegacyGenResult = (LegacyTileMatrix, (usize, usize), EnvironmentalConditions, f32, Option<HashMap<LegacyContent, f32>>);
+
+// the world of a legacy save, the settings following it are left unread, their fields changed since
+#[cfg(feature = "io")]
+#[derive(Deserialize)]
+struct LegacyWorld {
+    world: LegacyGenResult,
+}
+
+/// Loads a world saved with the robotics_lib versions before the Teleport, the Wall, the Market and the Fish,
+/// converted to the current tile types and content with `upgrade_world`.
+///
+/// Only the world is read: the settings saved with it, of an older version of the generator, are skipped, so unlike
+/// `load_saved` no `WorldGenerator` is returned. The robot spawn point, saved as `(x, y)`, is converted to the
+/// `(row, col)` of the current `GenResult`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or does not hold a legacy world.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+/// use robotics_lib::world::environmental_conditions::WeatherType::Sunny;
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use exclusion_zone::generator::migrate::{load_legacy_saved, LegacyContent, LegacyGenResult, LegacyTile, LegacyTileType};
+///
+/// // a legacy world of 2x2 tiles with a tree, the robot spawns at x 1, y 0
+/// let tile = |content| LegacyTile { tile_type: LegacyTileType::Grass, content, elevation: 0 };
+/// let tiles = vec![vec![tile(LegacyContent::None), tile(LegacyContent::None)], vec![tile(LegacyContent::Tree(2)), tile(LegacyContent::None)]];
+/// let conditions = EnvironmentalConditions::new(&[Sunny], 15, 12).unwrap();
+/// let legacy: LegacyGenResult = (tiles, (1, 0), conditions, 10.0, Some([(LegacyContent::Tree(0), 2.0)].into()));
+///
+/// let file_path = std::env::temp_dir().join("legacy_world.zst");
+/// let bytes = bincode::serialize(&legacy).unwrap();
+/// std::fs::write(&file_path, zstd::encode_all(bytes.as_slice(), 0).unwrap()).unwrap();
+///
+/// let (world, spawn, _, max_score, score_table) = load_legacy_saved(file_path.to_str().unwrap()).expect("unable to load the world");
+/// assert_eq!((world[1][0].tile_type, world[1][0].content.clone()), (TileType::Grass, Content::Tree(2)));
+/// // the (row, col) of the tile at x 1, y 0
+/// assert_eq!(spawn, (0, 1));
+/// assert_eq!(max_score, 10.0);
+/// assert_eq!(score_table.unwrap()[&Content::Tree(0)], 2.0);
+/// ```
+#[cfg(feature = "io")]
+pub fn load_legacy_saved(file_path: &str) -> Result<GenResult, String> {
+    let buffer = read_compressed(file_path).map_err(|e| format!("Unable to load world file {file_path}:\n{e}"))?;
+    // the trailing settings are allowed, bincode reads only the fields of the world
+    let legacy: LegacyWorld = bincode::deserialize(&buffer).map_err(|e| format!("Unable to load world file {file_path}:\nDeserialization failed: {e}"))?;
+    let (tiles, robot_position, environmental_conditions, max_score, score_table) = legacy.world;
+    let score_table = score_table.map(|table| table.into_iter().map(|(content, score)| (content.into(), score)).collect());
+    // the legacy saves store the spawn as (x, y)
+    let robot_position = Position::from_xy(robot_position.0, robot_position.1).spawn();
+    Ok((upgrade_world(tiles), robot_position, environmental_conditions, max_score, score_table))
+}
@@ -0,0 +1,186 @@
+use rand::seq::SliceRandom;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use robotics_lib::world::world_generator::Generator;
+use serde::{Deserialize, Serialize};
+
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::weather::WeatherSettings;
+use crate::generator::{default_environmental_conditions, generate_elevation_map, get_default_spawn_order, robot_spawn, GenResult, NoiseSettings, SpawnOrder, Thresholds, TileMatrix};
+use crate::utils::{find_max_value, find_min_value, generation_rng, seed_generation_rng, Coordinate};
+
+/// The smallest world side generated by the `MiniWorldGenerator`
+pub const MINI_WORLD_MIN_SIZE: usize = 10;
+/// The biggest world side generated by the `MiniWorldGenerator`
+pub const MINI_WORLD_MAX_SIZE: usize = 100;
+
+/// Generator of small worlds, from 10x10 to 100x100 tiles, for the unit tests of the robots.
+///
+/// The terrain comes from the same noise map and thresholds of the `WorldGenerator`, but the heavyweight stages,
+/// lava, streets, blobs, garbage piles, landmarks and the checks on the result, are skipped: each spawnable of the
+/// spawn order gets the same share of the content, scattered on random tiles that can hold it.
+/// The same settings always give the same world, and no setting makes it panic: the size is clamped between
+/// `MINI_WORLD_MIN_SIZE` and `MINI_WORLD_MAX_SIZE` and the robot always spawns on a walkable tile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MiniWorldGenerator {
+    /// the side of the world, clamped between 10 and 100
+    pub size: usize,
+    /// the spawnables to place, each one gets the same number of tiles
+    pub spawn_order: SpawnOrder,
+    /// the settings of the noise map, its seed also defines the content
+    pub noise_settings: NoiseSettings,
+    /// the thresholds within which tile types are assigned
+    pub thresholds: Thresholds,
+    /// the fraction of the tiles holding content, between 0 and 1, 0.2 by default
+    pub content_density: f64,
+    /// the weather forecast of the world, only used for the environmental conditions; invalid settings fall back to the default ones
+    pub weather_settings: WeatherSettings,
+}
+
+impl MiniWorldGenerator {
+    /// Creates a new instance of `MiniWorldGenerator` with every spawnable and the default settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The side of the world, clamped between 10 and 100.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::mini::MiniWorldGenerator;
+    /// use exclusion_zone::generator::Position;
+    ///
+    /// let (world, spawn, ..) = MiniWorldGenerator::new(20).gen();
+    /// let spawn = Position::from_spawn(spawn);
+    /// assert_eq!(world.len(), 20);
+    /// assert!(world[spawn.row()][spawn.col()].tile_type.properties().walk());
+    /// ```
+    pub fn new(size: usize) -> Self {
+        MiniWorldGenerator {
+            size: size.clamp(MINI_WORLD_MIN_SIZE, MINI_WORLD_MAX_SIZE),
+            spawn_order: get_default_spawn_order(),
+            noise_settings: NoiseSettings::default(),
+            thresholds: Thresholds::default(),
+            content_density: 0.2,
+            weather_settings: WeatherSettings::default(),
+        }
+    }
+
+    /// Sets the seed of the noise map and of the content, to get a different world for each test case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::mini::MiniWorldGenerator;
+    ///
+    /// for seed in 0..100 {
+    ///     let (world, ..) = MiniWorldGenerator::new(16).with_seed(seed).gen();
+    ///     assert_eq!(world.len(), 16);
+    /// }
+    /// ```
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.noise_settings = NoiseSettings::from_seed(seed);
+        self
+    }
+
+    // scatter the content, the number of tiles of each spawnable is the same share of the density
+    fn spawn_content(&self, world: &mut TileMatrix) {
+        let size = world.len();
+        let mut occupancy = Occupancy::build(world);
+        let tiles = (self.content_density.clamp(0.0, 1.0) * (size * size) as f64) as usize;
+        let per_spawnable = tiles / self.spawn_order.len().max(1);
+
+        for spawnable in &self.spawn_order {
+            let kind = spawnable.content();
+            let mut candidates: Vec<Coordinate> = (0..size)
+                .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+                .filter(|c| occupancy.can_hold(*c, &kind) && occupancy.is_empty(*c))
+                .collect();
+            candidates.shuffle(&mut generation_rng());
+            for c in candidates.into_iter().take(per_spawnable) {
+                occupancy.place(world, c, with_random_quantity(&kind));
+            }
+        }
+    }
+}
+
+impl Generator for MiniWorldGenerator {
+    /// Generates a small world, see `MiniWorldGenerator`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::{Content, TileType};
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::mini::MiniWorldGenerator;
+    /// use exclusion_zone::generator::properties::{assert_world_properties, ExpectedProperties};
+    ///
+    /// let (world, ..) = MiniWorldGenerator::new(50).gen();
+    /// // no lava nor streets, and each of the 10 spawnables on at most 50 tiles, its share of a fifth of the tiles
+    /// let expected = ExpectedProperties::default()
+    ///     .with_tile_type_share(TileType::Grass, 0.2..=0.7)
+    ///     .with_tile_type_share(TileType::Lava, 0.0..=0.0)
+    ///     .with_tile_type_share(TileType::Street, 0.0..=0.0)
+    ///     .with_content_count(Content::Coin(0), 1..=50)
+    ///     .with_content_count(Content::Tree(0), 1..=50);
+    /// assert_world_properties(&world, &expected);
+    /// ```
+    fn gen(&mut self) -> GenResult {
+        let size = self.size.clamp(MINI_WORLD_MIN_SIZE, MINI_WORLD_MAX_SIZE);
+        seed_generation_rng(self.noise_settings.seed as u64);
+
+        let elevation_map = generate_elevation_map(&self.noise_settings, size);
+        let (min, max) = (find_min_value(&elevation_map).unwrap_or(f64::MAX), find_max_value(&elevation_map).unwrap_or(f64::MIN));
+        let thresholds = self.thresholds.resolve(&elevation_map, min, max);
+        let cuts = thresholds.cuts(min, max);
+        let mut tile_types: Vec<Vec<TileType>> = elevation_map.iter().map(|row| row.iter().map(|&value| cuts.classify(value)).collect()).collect();
+        thresholds.smooth(&mut tile_types);
+        thresholds.frame(&mut tile_types);
+        let mut world: TileMatrix = tile_types
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|tile_type| Tile {
+                        tile_type,
+                        content: Content::None,
+                        elevation: 0,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.spawn_content(&mut world);
+
+        // a world of water has no walkable tile, the robot gets a tile of grass in the corner
+        let spawn = robot_spawn(&world);
+        let tile = &mut world[spawn.row()][spawn.col()];
+        if !tile.tile_type.properties().walk() {
+            tile.tile_type = TileType::Grass;
+            tile.content = Content::None;
+        }
+
+        let environmental_conditions = self.weather_settings.environmental_conditions().unwrap_or_else(|_| default_environmental_conditions());
+        (world, spawn.spawn(), environmental_conditions, 100.0, None)
+    }
+}
+
+#[inline(always)]
+fn with_random_quantity(kind: &Content) -> Content {
+    let mut rng = generation_rng();
+    let max = kind.properties().max().max(2);
+    let (quantity, capacity) = (QuantityRange::new(1, max), QuantityRange::new(2, max));
+    match kind {
+        | Content::Rock(_) => Content::Rock(quantity.sample(&mut rng)),
+        | Content::Tree(_) => Content::Tree(quantity.sample(&mut rng)),
+        | Content::Garbage(_) => Content::Garbage(quantity.sample(&mut rng)),
+        | Content::Coin(_) => Content::Coin(quantity.sample(&mut rng)),
+        | Content::Market(_) => Content::Market(quantity.sample(&mut rng)),
+        | Content::Fish(_) => Content::Fish(quantity.sample(&mut rng)),
+        | Content::Bin(_) => Content::Bin(1..capacity.sample(&mut rng)),
+        | Content::Crate(_) => Content::Crate(1..capacity.sample(&mut rng)),
+        | Content::Bank(_) => Content::Bank(1..capacity.sample(&mut rng)),
+        | _ => kind.clone(),
+    }
+}
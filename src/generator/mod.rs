@@ -1,37 +1,197 @@
 use std::collections::{HashMap, HashSet};
 
 use chrono::Utc;
-use debug_print::debug_println;
 use noise::MultiFractal;
 use noise::NoiseFn;
 use noise::{Fbm, Perlin, RidgedMulti};
 use rand::seq::SliceRandom;
 use rand::{thread_rng, RngCore, Rng};
-use rayon::iter::IntoParallelIterator;
-use rayon::iter::*;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
 use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
 use robotics_lib::world::environmental_conditions::WeatherType::{Foggy, Rainy, Sunny, TrentinoSnow, TropicalMonsoon};
 use robotics_lib::world::tile::{Content, Tile, TileType};
 use robotics_lib::world::tile::TileType::Teleport;
 use robotics_lib::world::world_generator::Generator;
 use serde::{Deserialize, Serialize};
+use tracing::field::Empty;
+use tracing::{debug, debug_span, info_span, Span};
 
 use crate::content::bank::{spawn_bank, BankSettings};
 use crate::content::bin::{spawn_bin, BinSettings};
 use crate::content::coin::{CoinSettings, spawn_coin};
-use crate::content::fire::{spawn_fire, FireSettings};
+use crate::content::custom::{spawn_extra_content, ExtraContent};
+use crate::content::fire::{spawn_fire_with_occupancy, FireSettings};
 use crate::content::fish::{FishSettings, spawn_fish};
 use crate::content::garbage::{spawn_garbage, GarbageSettings};
 use crate::content::market::{MarketSettings, spawn_market};
 use crate::content::rock::{RockSettings, spawn_rock};
-use crate::content::tree::{spawn_tree, TreeSettings};
-use crate::content::wood_crate::{spawn_crate, CrateSettings};
-use crate::tile_type::lava::{spawn_lava, LavaSettings};
-use crate::tile_type::street::street_spawn;
-use crate::utils::{find_max_value, find_min_value, percentage, SerializedWorld};
+use crate::content::tree::{spawn_tree_with_occupancy, TreeSettings};
+use crate::content::wood_crate::{assign_loot, spawn_crate, CrateSettings};
+use crate::tile_type::lava::{spawn_lava, spawn_lava_boundary, LavaSettings};
+use crate::tile_type::street::{line_streets, place_streets, prune_streets, route_streets, street_spawn, StreetRouting, StreetSettings};
+use crate::generator::access::{check_access, AccessSettings};
+use crate::generator::artifacts::GenerationArtifacts;
+use crate::generator::balance::{balance_score, ScoreBalance};
+use crate::generator::conflict::{ConflictPolicy, ConflictSettings};
+use crate::generator::constraints::{constrained_base_terrain, ConstraintReport, ConstraintSettings};
+use crate::generator::contamination::{spread_contamination, ContaminationSettings};
+use crate::generator::content_index::ContentIndex;
+use crate::generator::decor::{spawn_decor, DecorSettings};
+use crate::generator::fence::{build_fence, FenceSettings};
+use crate::generator::flow::{compute_flow_field, FlowSettings};
+use crate::generator::hazards::HazardMap;
+use crate::generator::landmarks::{spawn_landmarks, LandmarkSettings};
+use crate::generator::occupancy::Occupancy;
+use crate::generator::placement::PlacementMasks;
+use crate::generator::pyramid::ElevationPyramid;
+use crate::generator::quantity_field::{apply_quantity_fields, QuantityFieldSettings};
+#[cfg(feature = "io")]
+use crate::generator::cache::cached_terrain;
+#[cfg(feature = "io")]
+use crate::generator::save::{SaveOptions, SaveReport};
+use crate::generator::options::{GenerationOptions, TimeBudget};
+use crate::generator::reachability::{check_reachability, ReachabilityCheck, ReachabilityReport};
+use crate::generator::reconcile::reconcile_content;
+use crate::generator::regions::{find_regions, RegionSettings};
+use crate::generator::seeds::{extra_content_seed, StageSeeds};
+use crate::generator::slope::{analyze_slope, SlopeSettings};
+use crate::generator::spawn_view::{spawn_with_view, SpawnViewSettings};
+use crate::generator::tags::{TagMap, TagSettings};
+use crate::generator::timed::{schedule_timed_spawns, TimedSpawnSettings};
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::vaults::{spawn_vaults, VaultSettings};
+use crate::generator::water_source::{ensure_water_source, WaterSourceSettings};
+use crate::generator::weather::{apply_weather_to_terrain, spawn_weather_content, WeatherSettings};
+use crate::generator::wind::WindSettings;
+#[cfg(feature = "io")]
+use crate::utils::SerializedWorld;
+use crate::utils::{find_max_value, find_min_value, into_maybe_par_iter, percentage, seed_generation_rng};
+
+pub use crate::utils::{Coordinate, Position};
+
+/// Contains the access check of the content the robots interact with
+pub mod access;
+/// Contains the statistics of the worlds generated from the same settings with many seeds
+pub mod analysis;
+/// Contains the extra data produced during generation which is not part of the `GenResult`
+pub mod artifacts;
+/// Contains the balancing pass matching the content quantities to a target max score
+pub mod balance;
+/// Contains the generation of many worlds from the same settings and different seeds, on multiple threads
+pub mod batch;
+/// Contains the cache of the noise map and the terrain, keyed by the settings defining them
+#[cfg(feature = "io")]
+pub(crate) mod cache;
+/// Contains the functions to stitch multiple generated regions into one world and to split a world into parts
+pub mod compose;
+/// Contains the policies applied when content is spawned on a tile already holding content
+pub mod conflict;
+/// Contains the constraints on the terrain, demanding an archetype of world like an island
+pub mod constraints;
+/// Contains the contamination spreading from the epicenter of the disaster
+pub mod contamination;
+/// Contains the sparse index of the content of a world and its queries
+pub mod content_index;
+/// Contains the benchmark course generation mode, for automated robot testing
+pub mod course;
+/// Contains the decor layer, the visual markers the visualizer draws under the content
+pub mod decor;
+/// Contains the comparison of two generated worlds
+pub mod diff;
+/// Contains the difficulty levels scaling the hazards, the resources and the roughness of the terrain together
+pub mod difficulty;
+/// Contains the editor of generated worlds, to hand-adjust them before saving
+pub mod editor;
+/// Contains the estimate of the tiles, content, memory and time of a generation, computed without building the world
+pub mod estimate;
+/// Contains the fence of Wall tiles and gates around the exclusion zone
+pub mod fence;
+/// Contains the flow field of the currents of the water
+pub mod flow;
+/// Contains the helpers handing a generated world to a robotics_lib `Runner`, for the tests of the robots
+pub mod harness;
+/// Contains the map of the distances of the walkable tiles from the fire and the lava
+pub mod hazards;
+/// Contains the experimental hex-grid generation mode
+pub mod hex;
+/// Contains the estimate of the settings of a loaded world, to generate more worlds like it
+pub mod infer;
+/// Contains the unique landmark structures, such as the reactor and the radio masts
+pub mod landmarks;
+/// Contains the helpers iterating over the tiles of a tile matrix with their coordinates
+pub mod matrix;
+/// Contains the tracking allocator measuring the memory of each generation stage, to install in the binary
+#[cfg(feature = "memory_stats")]
+pub mod memory;
+/// Contains the conversion of the worlds between the tile types and the content of the robotics_lib versions
+pub mod migrate;
+/// Contains the generator of small worlds, fast enough for the unit tests of the robots
+pub mod mini;
+pub(crate) mod occupancy;
+/// Contains the options controlling how the generation runs, like its time budget
+pub mod options;
+/// Contains the masks restricting where each spawnable may be placed
+pub mod placement;
+/// Contains the plan of the spawn stages, worked out from the settings before generating
+pub mod plan;
+/// Contains the functions to preview the elevation and the terrain without generating the whole world
+pub mod preview;
+/// Contains the checks of the statistical properties of a generated world, for the integration tests
+pub mod properties;
+/// Contains the pyramid of the elevation map at decreasing resolutions
+pub mod pyramid;
+/// Contains the fields modulating the quantity of the content across the world
+pub mod quantity_field;
+/// Contains the check that the robot can reach the content it has to interact with
+pub mod reachability;
+/// Contains the reconciliation of the content with the tile types changed after it was spawned
+pub mod reconcile;
+/// Contains the segmentation of the world in named regions
+pub mod regions;
+/// Contains the options of the world saves and the report of their throughput
+#[cfg(feature = "io")]
+pub mod save;
+/// Contains the seeds of the spawn stages, derived from the master seed
+pub mod seeds;
+/// Contains the settings of the generator without the world size, to save and load presets
+pub mod settings;
+/// Contains the slope analysis of the elevation map, which marks steep tiles and cliffs
+pub mod slope;
+/// Contains the heatmaps of the tiles considered, accepted and rejected by each spawn stage
+pub mod spawn_heatmap;
+/// Contains the reports of the content requested and actually placed by each spawn stage
+pub mod spawn_report;
+/// Contains the choice of the robot spawn with something notable in its view
+pub mod spawn_view;
+/// Contains the utilities removing content and hazards from a generated world
+pub mod strip;
+/// Contains the summary stored at the start of the saved worlds, with a thumbnail, readable without loading the world
+#[cfg(feature = "io")]
+pub mod summary;
+/// Contains the tags of the tiles, the generator-level facts about them like being part of the city or a bridge
+pub mod tags;
+/// Contains the content spawned while the simulation runs, like fire outbreaks
+pub mod timed;
+pub(crate) mod transaction;
+/// Contains the validation of the settings, collecting their issues before the generation starts
+pub mod validation;
+/// Contains the vault and key pairs of the bank robbery scenario
+pub mod vaults;
+/// Contains the water source guaranteed near the robot spawn
+pub mod water_source;
+/// Contains the weather forecast of the world and its effects on the terrain and the content
+pub mod weather;
+/// Contains the wind elongating the fires and drifting the garbage downwind
+pub mod wind;
+
+/// The smallest world side the `WorldGenerator` generates, the lower sizes are rejected by `validate_all`
+pub const MIN_WORLD_SIZE: usize = 100;
+/// The smallest world side the `WorldGenerator` generates in small world mode, see `GenerationOptions::small_world_mode`
+pub const SMALL_WORLD_MIN_SIZE: usize = 20;
 
 /// Contains the tile types and the content used to define generation order
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
 pub enum Spawnables {
     Rock,
     Tree,
@@ -45,6 +205,39 @@ pub enum Spawnables {
     Fish
 }
 
+impl Spawnables {
+    // the kind of content placed by the spawnable, its quantity is meaningless
+    #[inline(always)]
+    pub(crate) fn content(self) -> Content {
+        match self {
+            | Spawnables::Rock => Content::Rock(0),
+            | Spawnables::Tree => Content::Tree(0),
+            | Spawnables::Garbage => Content::Garbage(0),
+            | Spawnables::Fire => Content::Fire,
+            | Spawnables::Coin => Content::Coin(0),
+            | Spawnables::Bin => Content::Bin(0..0),
+            | Spawnables::Crate => Content::Crate(0..0),
+            | Spawnables::Bank => Content::Bank(0..0),
+            | Spawnables::Market => Content::Market(0),
+            | Spawnables::Fish => Content::Fish(0),
+        }
+    }
+}
+
+/// Defines the trade-off between generation speed and visual quality of the spawners that rely on rejection sampling
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum QualityProfile {
+    /// One random roll per garbage pile ring and blobs shaped as plain discs:
+    /// piles look like concentric squares and forests/fires lose their irregular border, but large worlds are generated much faster
+    Fast,
+    /// One random roll per 2x2 block of a garbage pile and blob borders sampled every few degrees:
+    /// piles look slightly blocky, blobs are almost indistinguishable from the detailed ones
+    Balanced,
+    /// One random roll per tile and blob borders sampled every degree, the most natural looking but slowest option
+    #[default]
+    Detailed,
+}
+
 /// Set of content and tile type defining the order of element generation,
 /// if an element appears twice, the second is ignored and <b>if an element does not appear, it is not spawned</b>
 pub type SpawnOrder = Vec<Spawnables>;
@@ -79,9 +272,15 @@ pub type SpawnOrder = Vec<Spawnables>;
 /// use exclusion_zone::content::rock::RockSettings;
 /// use exclusion_zone::content::tree::TreeSettings;
 /// use exclusion_zone::content::wood_crate::CrateSettings;
-/// use exclusion_zone::generator::{get_default_spawn_order, NoiseSettings, Thresholds, WorldGenerator};
+/// use exclusion_zone::generator::{get_default_spawn_order, NoiseSettings, QualityProfile, Thresholds, WorldGenerator};
+/// use exclusion_zone::generator::conflict::ConflictSettings;
+/// use exclusion_zone::generator::options::GenerationOptions;
+/// use exclusion_zone::generator::quantity_field::QuantityFieldSettings;
+/// use exclusion_zone::generator::reachability::ReachabilityCheck;
 /// use exclusion_zone::generator::Spawnables::Tree;
+/// use exclusion_zone::generator::weather::WeatherSettings;
 /// use exclusion_zone::tile_type::lava::LavaSettings;
+/// use exclusion_zone::tile_type::street::StreetSettings;
 /// let size = 1000;
 /// let world_gen = WorldGenerator {
 ///             size,
@@ -99,6 +298,33 @@ pub type SpawnOrder = Vec<Spawnables>;
 ///             market_settings: MarketSettings::default(size),
 ///             fish_settings: FishSettings::default(size),
 ///             rock_settings: RockSettings::default(size),
+///             quality_profile: QualityProfile::default(),
+///             street_settings: StreetSettings::default(),
+///             reachability_check: ReachabilityCheck::default(),
+///             cache_dir: None,
+///             score_balance: None,
+///             stage_seeds: None,
+///             landmark_settings: None,
+///             slope_settings: None,
+///             region_settings: None,
+///             generation_options: GenerationOptions::default(),
+///             conflict_settings: ConflictSettings::default(),
+///             weather_settings: WeatherSettings::default(),
+///             quantity_field_settings: None,
+///             placement_masks: None,
+///             vault_settings: None,
+///             tag_settings: None,
+///             constraint_settings: None,
+///             timed_spawn_settings: None,
+///             contamination_settings: None,
+///             spawn_view_settings: None,
+///             flow_settings: None,
+///             fence_settings: None,
+///             decor_settings: None,
+///             access_settings: None,
+///             extra_content: Vec::new(),
+///             wind_settings: None,
+///             water_source_settings: None,
 ///         };
 /// // The `spawn_order` now contains a randomized order of elements to be spawned.
 /// ```
@@ -120,6 +346,17 @@ pub fn get_default_spawn_order() -> SpawnOrder {
     elements
 }
 
+// Detect the first walkable tile, in row-major order, and set the initial position of the robot, the top left corner if none
+#[inline(always)]
+pub(crate) fn robot_spawn(world: &TileMatrix) -> Position {
+    for (row, tiles) in world.iter().enumerate() {
+        if let Some(col) = tiles.iter().position(|tile| tile.tile_type.properties().walk()) {
+            return Position::from_row_col(row, col);
+        }
+    }
+    Position::from_row_col(0, 0)
+}
+
 #[inline(always)]
 fn remove_duplicates_spawnables(order: &mut SpawnOrder) {
     let mut seen = HashSet::with_capacity(order.len());
@@ -155,7 +392,7 @@ impl Default for NoiseSettings {
 }
 
 /// Defines the settings that the noise generator uses to give rise to the noise map
-#[derive(Serialize, Deserialize, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub struct NoiseSettings {
     /// define the world generator seed, used to build the noise map, normally a random value
     seed: u32,
@@ -208,10 +445,29 @@ impl NoiseSettings {
             attenuation,
         }
     }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::NoiseSettings;
+    ///
+    /// assert!(!NoiseSettings::new(123, 5, 0.0, 2.0, 0.5, 1.0).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.octaves > 0, "NoiseSettings::octaves", || "the noise needs at least an octave".to_string());
+        issues.check(self.frequency.is_finite() && self.frequency > 0.0, "NoiseSettings::frequency", || format!("{} gives a flat noise map, use a value greater than 0", self.frequency));
+        for (setting, value) in [("NoiseSettings::lacunarity", self.lacunarity), ("NoiseSettings::persistence", self.persistence), ("NoiseSettings::attenuation", self.attenuation)] {
+            issues.check(value.is_finite(), setting, || format!("{value} is not a finite number"));
+        }
+        issues.0
+    }
 }
 
 /// Define the thresholds within which tile types are assigned
-#[derive(Serialize, Deserialize, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub struct Thresholds {
     /// define at what depth the land will be considered deep water
     pub threshold_deep_water: f64,
@@ -225,6 +481,40 @@ pub struct Thresholds {
     pub threshold_hill: f64,
     /// define at what height the land will be considered mountain
     pub threshold_mountain: f64,
+    /// the number of smoothing passes applied after the classification, 0 by default; each pass turns the tiles with
+    /// at most one neighbour of their tile type, like a lone Snow tile inside the mountains, into the tile type of the
+    /// majority of their neighbours, while the larger features are preserved
+    pub smoothing_passes: usize,
+    /// the thresholds are percentages of the tiles, the share of the world below each tile type, rather than
+    /// percentages of the elevation range, `false` by default, see `targets`
+    pub equalized: bool,
+    /// the frame of the world, the outer tiles forced to the ocean or to the cliffs after the classification,
+    /// `BorderStyle::None` by default, leaving the terrain cut by the edge of the world
+    pub border: BorderStyle,
+}
+
+/// The frame forced on the outer tiles of the world, so the robots cannot walk along its edge and the map looks framed
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum BorderStyle {
+    /// the terrain reaches the edge of the world
+    #[default]
+    None,
+    /// the outer tiles, as many as the width from each side, are DeepWater
+    Ocean(usize),
+    /// the outer tiles, as many as the width from each side, are Wall, which no robot can walk; unlike the Mountain
+    /// tiles, which can be walked at a higher cost
+    Cliff(usize),
+}
+
+impl BorderStyle {
+    // the width of the frame, 0 for none
+    #[inline(always)]
+    pub(crate) fn width(&self) -> usize {
+        match self {
+            | BorderStyle::None => 0,
+            | BorderStyle::Ocean(width) | BorderStyle::Cliff(width) => *width,
+        }
+    }
 }
 
 impl Default for Thresholds {
@@ -237,11 +527,69 @@ impl Default for Thresholds {
             threshold_grass: 45.0,
             threshold_hill: 65.0,
             threshold_mountain: 77.5,
+            smoothing_passes: 0,
+            equalized: false,
+            border: BorderStyle::None,
         }
     }
 }
 
+// the tile types assigned by the thresholds, from the lowest elevation to the highest
+const CLASSIFIED_TILE_TYPES: [TileType; 7] = [TileType::DeepWater, TileType::ShallowWater, TileType::Sand, TileType::Grass, TileType::Hill, TileType::Mountain, TileType::Snow];
+
+// the elevation values of the thresholds, computed once for the whole elevation map; each cut is at least the previous
+// one, so that a threshold below a previous one assigns no tile, as when the first threshold reached decides the tile type
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct ElevationCuts([f64; 6]);
+
+impl ElevationCuts {
+    // assign the tile type to an elevation value, the number of cuts it reaches is the index of its tile type, counted
+    // without branching; a value which is not a number reaches no cut and is Snow, the last tile type
+    #[inline(always)]
+    pub(crate) fn classify(&self, value: f64) -> TileType {
+        if value.is_nan() {
+            return TileType::Snow;
+        }
+        let index = self.0.iter().map(|&cut| (value >= cut) as usize).sum::<usize>();
+        CLASSIFIED_TILE_TYPES[index]
+    }
+}
+
 impl Thresholds {
+    // the elevation values of the thresholds, given the min and max value of the elevation map
+    #[inline(always)]
+    pub(crate) fn cuts(&self, min: f64, max: f64) -> ElevationCuts {
+        let mut cuts = [
+            percentage(self.threshold_deep_water, min, max),
+            percentage(self.threshold_shallow_water, min, max),
+            percentage(self.threshold_sand, min, max),
+            percentage(self.threshold_grass, min, max),
+            percentage(self.threshold_hill, min, max),
+            percentage(self.threshold_mountain, min, max),
+        ];
+        // the running maxima of the cuts
+        for i in 1..cuts.len() {
+            cuts[i] = cuts[i].max(cuts[i - 1]);
+        }
+        ElevationCuts(cuts)
+    }
+
+    // a made-up elevation of the tile type, between 0 and 100, the middle of its band, the inverse of `classify`; the
+    // tile types the thresholds do not assign are given the band of the terrain they usually lie on
+    #[inline(always)]
+    pub(crate) fn elevation(&self, tile_type: &TileType) -> f64 {
+        let (low, high) = match tile_type {
+            | TileType::DeepWater => (0.0, self.threshold_deep_water),
+            | TileType::ShallowWater => (self.threshold_deep_water, self.threshold_shallow_water),
+            | TileType::Sand => (self.threshold_shallow_water, self.threshold_sand),
+            | TileType::Grass | TileType::Street | TileType::Teleport(_) => (self.threshold_sand, self.threshold_grass),
+            | TileType::Hill | TileType::Wall => (self.threshold_grass, self.threshold_hill),
+            | TileType::Mountain | TileType::Lava => (self.threshold_hill, self.threshold_mountain),
+            | TileType::Snow => (self.threshold_mountain, 100.0),
+        };
+        (low + high) / 2.0
+    }
+
     /// Creates a new instance of `Thresholds` with the provided parameters.
     ///
     /// # Arguments
@@ -271,12 +619,225 @@ impl Thresholds {
             threshold_grass,
             threshold_hill,
             threshold_mountain,
+            smoothing_passes: 0,
+            equalized: false,
+            border: BorderStyle::None,
+        }
+    }
+
+    /// Creates a new instance of `Thresholds` giving each tile type the requested percentage of the tiles, the Snow
+    /// the rest, whatever the distribution of the elevation.
+    ///
+    /// The thresholds of `new` split the range between the lowest and the highest elevation, so the share of each
+    /// tile type depends on the noise; these are matched to the sorted elevation of the generated world instead, like
+    /// a histogram equalization, so the tile types get their percentage of the tiles exactly, within a tile. The
+    /// smoothing passes and the changes to the terrain after the classification, like the streets and the lava, are
+    /// not accounted for.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent_deep_water` - The percentage of the tiles of DeepWater.
+    /// * `percent_shallow_water` - The percentage of the tiles of ShallowWater.
+    /// * `percent_sand` - The percentage of the tiles of Sand.
+    /// * `percent_grass` - The percentage of the tiles of Grass.
+    /// * `percent_hill` - The percentage of the tiles of Hill.
+    /// * `percent_mountain` - The percentage of the tiles of Mountain.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exclusion_zone::generator::preview::preview_terrain;
+    /// use exclusion_zone::generator::{NoiseSettings, Thresholds};
+    /// use robotics_lib::world::tile::TileType;
+    ///
+    /// // a fifth of the tiles are water, 10% are Snow
+    /// let thresholds = Thresholds::targets(5.0, 15.0, 10.0, 40.0, 15.0, 5.0);
+    /// let terrain = preview_terrain(&NoiseSettings::from_seed(42), &thresholds, 100);
+    /// let snow = terrain.iter().flatten().filter(|tile_type| **tile_type == TileType::Snow).count();
+    /// assert!((999..=1001).contains(&snow));
+    /// ```
+    pub fn targets(percent_deep_water: f64, percent_shallow_water: f64, percent_sand: f64, percent_grass: f64, percent_hill: f64, percent_mountain: f64) -> Self {
+        let threshold_shallow_water = percent_deep_water + percent_shallow_water;
+        let threshold_sand = threshold_shallow_water + percent_sand;
+        let threshold_grass = threshold_sand + percent_grass;
+        let threshold_hill = threshold_grass + percent_hill;
+        Thresholds {
+            threshold_deep_water: percent_deep_water,
+            threshold_shallow_water,
+            threshold_sand,
+            threshold_grass,
+            threshold_hill,
+            threshold_mountain: threshold_hill + percent_mountain,
+            smoothing_passes: 0,
+            equalized: true,
+            border: BorderStyle::None,
         }
     }
+
+    // the thresholds as percentages of the elevation range of the map, matched to its sorted elevation if equalized,
+    // so that `classify` gives each tile type its share of the tiles
+    pub(crate) fn resolve(&self, elevation_map: &[Vec<f64>], min: f64, max: f64) -> Thresholds {
+        if !self.equalized {
+            return *self;
+        }
+        let mut sorted: Vec<f64> = elevation_map.iter().flatten().copied().collect();
+        sorted.sort_unstable_by(f64::total_cmp);
+        let range = (max - min).max(f64::EPSILON);
+        // halfway between the last tile below the threshold and the first one above, past the range to take them all
+        let resolve = |threshold: f64| {
+            let below = (threshold.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).round() as usize;
+            let cut = match below {
+                | 0 => min,
+                | n if n >= sorted.len() => max + range,
+                | n => (sorted[n - 1] + sorted[n]) / 2.0,
+            };
+            (cut - min) / range * 100.0
+        };
+        Thresholds {
+            threshold_deep_water: resolve(self.threshold_deep_water),
+            threshold_shallow_water: resolve(self.threshold_shallow_water),
+            threshold_sand: resolve(self.threshold_sand),
+            threshold_grass: resolve(self.threshold_grass),
+            threshold_hill: resolve(self.threshold_hill),
+            threshold_mountain: resolve(self.threshold_mountain),
+            smoothing_passes: self.smoothing_passes,
+            equalized: false,
+            border: self.border,
+        }
+    }
+
+    /// Sets the number of smoothing passes applied after the classification, removing the single-tile speckles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::Thresholds;
+    ///
+    /// let thresholds = Thresholds::default().with_smoothing_passes(2);
+    /// ```
+    pub fn with_smoothing_passes(mut self, smoothing_passes: usize) -> Self {
+        self.smoothing_passes = smoothing_passes;
+        self
+    }
+
+    /// Sets the frame forced on the outer tiles of the world after the classification and the smoothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::preview::preview_terrain;
+    /// use exclusion_zone::generator::{BorderStyle, NoiseSettings, Thresholds};
+    /// use robotics_lib::world::tile::TileType;
+    ///
+    /// let thresholds = Thresholds::default().with_border(BorderStyle::Ocean(3));
+    /// let terrain = preview_terrain(&NoiseSettings::from_seed(42), &thresholds, 100);
+    /// assert!(terrain[2].iter().chain(&terrain[97]).all(|tile_type| *tile_type == TileType::DeepWater));
+    /// ```
+    pub fn with_border(mut self, border: BorderStyle) -> Self {
+        self.border = border;
+        self
+    }
+
+    // forces the outer tiles to the tile type of the border
+    pub(crate) fn frame(&self, tile_types: &mut [Vec<TileType>]) {
+        let frame_type = match self.border {
+            | BorderStyle::None => return,
+            | BorderStyle::Ocean(_) => TileType::DeepWater,
+            | BorderStyle::Cliff(_) => TileType::Wall,
+        };
+        let (size, width) = (tile_types.len(), self.border.width());
+        for (row, tiles) in tile_types.iter_mut().enumerate() {
+            for (col, tile_type) in tiles.iter_mut().enumerate() {
+                if row.min(col).min(size - 1 - row).min(size - 1 - col) < width {
+                    *tile_type = frame_type;
+                }
+            }
+        }
+    }
+
+    // majority filter over the 8 neighbours of each tile, stopping early once a pass changes nothing
+    pub(crate) fn smooth(&self, tile_types: &mut [Vec<TileType>]) {
+        for _ in 0..self.smoothing_passes {
+            let previous = tile_types.to_vec();
+            let mut changed = false;
+            for (row, tiles) in tile_types.iter_mut().enumerate() {
+                for (col, tile_type) in tiles.iter_mut().enumerate() {
+                    if let Some(majority) = speckle_majority(&previous, row, col) {
+                        *tile_type = majority;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Checks the thresholds, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::Thresholds;
+    ///
+    /// // the shallow water is below the deep water
+    /// assert!(!Thresholds::new(10.0, 4.0, 15.0, 45.0, 65.0, 77.5).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        let thresholds = [
+            ("Thresholds::threshold_deep_water", self.threshold_deep_water),
+            ("Thresholds::threshold_shallow_water", self.threshold_shallow_water),
+            ("Thresholds::threshold_sand", self.threshold_sand),
+            ("Thresholds::threshold_grass", self.threshold_grass),
+            ("Thresholds::threshold_hill", self.threshold_hill),
+            ("Thresholds::threshold_mountain", self.threshold_mountain),
+        ];
+        let of = if self.equalized { "the tiles" } else { "the elevation range" };
+        for (setting, value) in thresholds {
+            issues.check((0.0..=100.0).contains(&value), setting, || format!("{value} is not a percentage of {of}, use a value between 0 and 100"));
+        }
+        // the previous threshold is reached first, so no elevation falls between them and the tile type below a lower
+        // threshold is left out of the world
+        for (tile_type, pair) in CLASSIFIED_TILE_TYPES[1..].iter().zip(thresholds.windows(2)) {
+            let ((previous_setting, previous), (setting, value)) = (pair[0], pair[1]);
+            issues.check(value >= previous, setting, || format!("{value} is lower than {previous_setting}, {previous}, no tile would be {tile_type:?}, use a value of at least {previous}"));
+        }
+        issues.0
+    }
+}
+
+// the tile type of the majority of the neighbours of a tile with at most one neighbour of its own tile type,
+// None if the tile is part of a larger feature or if no tile type is shared by more than half of its neighbours
+#[inline(always)]
+fn speckle_majority(tile_types: &[Vec<TileType>], row: usize, col: usize) -> Option<TileType> {
+    let size = tile_types.len();
+    let current = tile_types[row][col];
+    let mut counts: Vec<(TileType, usize)> = Vec::with_capacity(8);
+    let mut neighbours = 0;
+    let (rows, cols) = (row.saturating_sub(1)..(row + 2).min(size), col.saturating_sub(1)..(col + 2).min(size));
+    for (r, tiles) in tile_types.iter().enumerate().take(rows.end).skip(rows.start) {
+        for (c, &tile_type) in tiles.iter().enumerate().take(cols.end).skip(cols.start) {
+            if (r, c) == (row, col) {
+                continue;
+            }
+            neighbours += 1;
+            match counts.iter_mut().find(|(t, _)| *t == tile_type) {
+                | Some((_, count)) => *count += 1,
+                | None => counts.push((tile_type, 1)),
+            }
+        }
+    }
+
+    if counts.iter().any(|&(t, count)| t == current && count > 1) {
+        return None;
+    }
+    counts.into_iter().find(|&(_, count)| count * 2 > neighbours).map(|(t, _)| t)
 }
 
 /// Groups all submodule settings of the world generator, allowing the various aspects to be customised
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct WorldGenerator {
     /// the world side dimension, final size will be size²
     pub size: usize,
@@ -307,67 +868,121 @@ pub struct WorldGenerator {
     /// define how fish will spawn
     pub fish_settings: FishSettings,
     /// define how rocks will spawn
-    pub rock_settings: RockSettings
+    pub rock_settings: RockSettings,
+    /// define the trade-off between speed and quality of the spawners, `Detailed` by default
+    pub quality_profile: QualityProfile,
+    /// define how streets will spawn
+    pub street_settings: StreetSettings,
+    /// define whether the generator checks that Banks, Markets and Bins can be reached by the robot, `Disabled` by default
+    pub reachability_check: ReachabilityCheck,
+    /// directory where the noise map and the terrain are cached, keyed by seed, noise settings, size and thresholds.
+    /// When iterating on content settings the terrain is loaded instead of being generated again, `None` disables the cache.
+    /// Ignored when the `io` feature is disabled
+    pub cache_dir: Option<String>,
+    /// scales coins, bank capacities and trees so that the achievable score matches a target max score,
+    /// `None` disables the balancing and the max score is always 100
+    pub score_balance: Option<ScoreBalance>,
+    /// the seed of each spawn stage, `None` derives them from the seed of the noise settings
+    pub stage_seeds: Option<StageSeeds>,
+    /// the reactor and the radio masts placed on notable features of the elevation map, `None` disables them
+    pub landmark_settings: Option<LandmarkSettings>,
+    /// the slope analysis of the elevation map, `None` skips it
+    pub slope_settings: Option<SlopeSettings>,
+    /// the segmentation of the world in named regions, `None` skips it
+    pub region_settings: Option<RegionSettings>,
+    /// define how the generation runs, like its time budget
+    pub generation_options: GenerationOptions,
+    /// define what happens when content is spawned on a tile already holding content
+    pub conflict_settings: ConflictSettings,
+    /// define the weather forecast of the world, which also shapes its terrain and content
+    pub weather_settings: WeatherSettings,
+    /// the fields modulating the quantity of the content across the world, `None` keeps the random quantities
+    pub quantity_field_settings: Option<QuantityFieldSettings>,
+    /// restricts where each spawnable may be placed, `None` places them on every tile that can hold them
+    pub placement_masks: Option<PlacementMasks>,
+    /// the vault and key pairs of the bank robbery scenario, `None` disables them
+    pub vault_settings: Option<VaultSettings>,
+    /// the settings of the tags of the tiles, `None` disables the tags
+    pub tag_settings: Option<TagSettings>,
+    /// the constraints on the terrain, `None` accepts any terrain
+    pub constraint_settings: Option<ConstraintSettings>,
+    /// the settings of the content spawned while the simulation runs, `None` disables the schedule
+    pub timed_spawn_settings: Option<TimedSpawnSettings>,
+    /// the settings of the contamination spreading from the epicenter of the disaster, `None` disables it
+    pub contamination_settings: Option<ContaminationSettings>,
+    /// what the robot must see at the spawn, `None` spawns it at the first walkable tile
+    pub spawn_view_settings: Option<SpawnViewSettings>,
+    /// the settings of the flow field of the water, `None` disables it
+    pub flow_settings: Option<FlowSettings>,
+    /// the fence of Wall tiles with gates around the world or a region of it, `None` builds no fence
+    pub fence_settings: Option<FenceSettings>,
+    /// the settings of the decor layer, the visual markers drawn by the visualizer under the content, `None` to skip it
+    pub decor_settings: Option<DecorSettings>,
+    /// the settings of the access check moving the Banks, the Markets and the Bins enclosed by tiles the robots cannot walk, `None` to skip it
+    pub access_settings: Option<AccessSettings>,
+    /// the content which is not one of the `Spawnables`, spawned after the spawn order in the order it is registered, see `define_content_settings!`
+    pub extra_content: Vec<ExtraContent>,
+    /// the wind elongating the fires and drifting the garbage downwind, `None` for a windless world
+    pub wind_settings: Option<WindSettings>,
+    /// the water source guaranteed near the robot spawn, `None` leaves the water where the terrain puts it
+    pub water_source_settings: Option<WaterSourceSettings>,
 }
 
 impl WorldGenerator {
     #[inline(always)]
     fn generate_terrain(&self, noise_map: &[Vec<f64>], min: f64, max: f64) -> TileMatrix {
-        let mut world = vec![
-            vec![
-                Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                };
-                self.size
-            ];
-            self.size
-        ];
-        for (y, row) in noise_map.iter().enumerate() {
-            for (x, &value) in row.iter().enumerate() {
-                let tile_type = match value {
-                    | v if v < percentage(self.thresholds.threshold_deep_water, min, max) => TileType::DeepWater,
-                    | v if v < percentage(self.thresholds.threshold_shallow_water, min, max) => TileType::ShallowWater,
-                    | v if v < percentage(self.thresholds.threshold_sand, min, max) => TileType::Sand,
-                    | v if v < percentage(self.thresholds.threshold_grass, min, max) => TileType::Grass,
-                    | v if v < percentage(self.thresholds.threshold_hill, min, max) => TileType::Hill,
-                    | v if v < percentage(self.thresholds.threshold_mountain, min, max) => TileType::Mountain,
-                    | _ => TileType::Snow,
-                };
-
-                world[y][x] = Tile {
-                    tile_type,
-                    content: Content::None,
-                    elevation: 0,
-                };
-            }
-        }
+        self.generate_terrain_into(noise_map, min, max, Vec::new())
+    }
+
+    // classify the noise map in the tiles of the given world, whose rows are reused, the missing ones are added
+    #[inline(always)]
+    fn generate_terrain_into(&self, noise_map: &[Vec<f64>], min: f64, max: f64, mut world: TileMatrix) -> TileMatrix {
+        let thresholds = self.thresholds.resolve(noise_map, min, max);
+        let cuts = thresholds.cuts(min, max);
+        let mut tile_types: Vec<Vec<TileType>> = into_maybe_par_iter(noise_map).map(|row| row.iter().map(|&value| cuts.classify(value)).collect()).collect();
+        thresholds.smooth(&mut tile_types);
+        thresholds.frame(&mut tile_types);
 
+        world.resize_with(tile_types.len(), Vec::new);
+        into_maybe_par_iter(world.as_mut_slice()).zip(tile_types).for_each(|(row, tile_types)| {
+            row.clear();
+            row.extend(tile_types.into_iter().map(|tile_type| Tile {
+                tile_type,
+                content: Content::None,
+                elevation: 0,
+            }));
+        });
         world
     }
 
     #[inline(always)]
     fn generate_elevation_map(&self) -> Vec<Vec<f64>> {
-        let noise = RidgedMulti::<Fbm<Perlin>>::new(self.noise_settings.seed)
-            .set_octaves(self.noise_settings.octaves)
-            .set_frequency(self.noise_settings.frequency)
-            .set_lacunarity(self.noise_settings.lacunarity)
-            .set_persistence(self.noise_settings.persistence)
-            .set_attenuation(self.noise_settings.attenuation);
-
-        (0..self.size)
-            .into_par_iter()
-            .map(|y| {
-                let y_normalized = y as f64 / self.size as f64;
-                (0..self.size)
-                    .map(|x| {
-                        let x_normalized = x as f64 / self.size as f64;
-                        noise.get([x_normalized, y_normalized])
-                    })
-                    .collect()
-            })
-            .collect()
+        generate_elevation_map(&self.noise_settings, self.size)
+    }
+
+    // generate the noise map and classify it in tile types, the part of the generation that only depends on
+    // size, noise settings and thresholds; the tiles are written in the rows of the given world
+    #[inline(always)]
+    fn generate_base_terrain(&self, world: TileMatrix) -> (Vec<Vec<f64>>, TileMatrix) {
+        let noise_map = in_stage(debug_span!("noise_map", size = self.size, elapsed_ms = Empty), || self.generate_elevation_map());
+
+        let (min_value, max_value) = in_stage(debug_span!("min_max", elapsed_ms = Empty), || {
+            (find_min_value(&noise_map).unwrap_or(f64::MAX), find_max_value(&noise_map).unwrap_or(f64::MIN))
+        });
+
+        let world = in_stage(debug_span!("terrain", size = self.size, elapsed_ms = Empty), || self.generate_terrain_into(&noise_map, min_value, max_value, world));
+
+        (noise_map, world)
+    }
+
+    // the base terrain, read from the cache directory if set, otherwise generated in the rows of the given world
+    #[inline(always)]
+    fn cached_base_terrain(&self, world: TileMatrix) -> (Vec<Vec<f64>>, TileMatrix) {
+        match &self.cache_dir {
+            #[cfg(feature = "io")]
+            | Some(dir) => cached_terrain(dir, self.size, &self.noise_settings, &self.thresholds, || self.generate_base_terrain(world)),
+            | _ => self.generate_base_terrain(world),
+        }
     }
 
     /// Provides an instance of `WorldGenerator` given the world settings
@@ -461,7 +1076,34 @@ impl WorldGenerator {
             coin_settings,
             market_settings,
             fish_settings,
-            rock_settings
+            rock_settings,
+            quality_profile: QualityProfile::default(),
+            street_settings: StreetSettings::default(),
+            reachability_check: ReachabilityCheck::default(),
+            cache_dir: None,
+            score_balance: None,
+            stage_seeds: None,
+            landmark_settings: None,
+            slope_settings: None,
+            region_settings: None,
+            generation_options: GenerationOptions::default(),
+            conflict_settings: ConflictSettings::default(),
+            weather_settings: WeatherSettings::default(),
+            quantity_field_settings: None,
+            placement_masks: None,
+            vault_settings: None,
+            tag_settings: None,
+            constraint_settings: None,
+            timed_spawn_settings: None,
+            contamination_settings: None,
+            spawn_view_settings: None,
+            flow_settings: None,
+            fence_settings: None,
+            decor_settings: None,
+            access_settings: None,
+            extra_content: Vec::new(),
+            wind_settings: None,
+            water_source_settings: None,
         }
     }
 
@@ -498,7 +1140,34 @@ impl WorldGenerator {
             coin_settings: CoinSettings::default(size),
             market_settings: MarketSettings::default(size),
             fish_settings: FishSettings::default(size),
-            rock_settings: RockSettings::default(size)
+            rock_settings: RockSettings::default(size),
+            quality_profile: QualityProfile::default(),
+            street_settings: StreetSettings::default(),
+            reachability_check: ReachabilityCheck::default(),
+            cache_dir: None,
+            score_balance: None,
+            stage_seeds: None,
+            landmark_settings: None,
+            slope_settings: None,
+            region_settings: None,
+            generation_options: GenerationOptions::default(),
+            conflict_settings: ConflictSettings::default(),
+            weather_settings: WeatherSettings::default(),
+            quantity_field_settings: None,
+            placement_masks: None,
+            vault_settings: None,
+            tag_settings: None,
+            constraint_settings: None,
+            timed_spawn_settings: None,
+            contamination_settings: None,
+            spawn_view_settings: None,
+            flow_settings: None,
+            fence_settings: None,
+            decor_settings: None,
+            access_settings: None,
+            extra_content: Vec::new(),
+            wind_settings: None,
+            water_source_settings: None,
         }
     }
     /// Generates a new world based on the current settings and serializes it.
@@ -556,12 +1225,14 @@ impl WorldGenerator {
     /// );
     /// world_generator.generate_and_save("file/path/name").expect("Unable to save the world");
     /// ```
+    #[cfg(feature = "io")]
     pub fn generate_and_save(&mut self, file_path: &str) -> Result<(), String> {
         SerializedWorld {
             settings: self.clone(),
             world: self.gen(),
         }
-            .serialize(file_path, 11)
+            .serialize(file_path, &SaveOptions::default())
+            .map(|_| ())
     }
 
     /// Saves the current world settings along with the provided world data to a file.
@@ -572,9 +1243,9 @@ impl WorldGenerator {
     /// the specified file path with a `.bsw` extension.
     ///
     /// The serialization and compression are handled by the `serialize` method of
-    /// `SerializedWorld`. The compression level is set to `11`, but this can be
-    /// adjusted based on the desired balance between compression efficiency and
-    /// performance.
+    /// `SerializedWorld`. The compression level is set to `11` and the world is
+    /// compressed on all the available threads, use `save_with_options` to adjust
+    /// the balance between compression efficiency and performance.
     ///
     /// # Arguments
     ///
@@ -636,12 +1307,57 @@ impl WorldGenerator {
     /// This function may return an error if it encounters issues during the
     /// serialization process or while writing to the file. The error message will
     /// provide details on the nature of the problem encountered.
+    #[cfg(feature = "io")]
     pub fn save(&mut self, file_path: &str, world: GenResult) -> Result<(), String> {
         SerializedWorld {
             settings: self.clone(),
             world,
         }
-            .serialize(file_path, 11)
+            .serialize(file_path, &SaveOptions::default())
+            .map(|_| ())
+    }
+
+    /// Saves the current world settings along with the provided world data to a file, like `save`, with the given
+    /// compression level and number of compression threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path of the file, the `.zst` extension is appended to it.
+    /// * `world` - The world data to be saved.
+    /// * `options` - The compression level and the number of threads compressing the world.
+    ///
+    /// # Returns
+    ///
+    /// The sizes of the world before and after the compression and the throughput of the save.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::save::SaveOptions;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(3000);
+    /// let world = generator.gen();
+    /// let options = SaveOptions {
+    ///     threads: 16,
+    ///     ..SaveOptions::default()
+    /// };
+    /// let file_path = std::env::temp_dir().join("world.zst");
+    /// let report = generator.save_with_options(file_path.to_str().unwrap(), world, &options).expect("unable to save the world");
+    /// println!("{} bytes saved in {} ms, {:.1} MB/s", report.compressed_bytes, report.elapsed_ms, report.throughput);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the world cannot be serialized, compressed or written to the file.
+    #[cfg(feature = "io")]
+    pub fn save_with_options(&mut self, file_path: &str, world: GenResult, options: &SaveOptions) -> Result<SaveReport, String> {
+        SerializedWorld {
+            settings: self.clone(),
+            world,
+        }
+            .serialize(file_path, options)
     }
 
     /// Loads a previously saved world from file.
@@ -687,6 +1403,7 @@ impl WorldGenerator {
     /// the deserialization process, such as problems with reading the file,
     /// decompression, or deserialization itself. The error string will contain
     /// details about the specific problem encountered.
+    #[cfg(feature = "io")]
     pub fn load_saved(file_path: &str) -> Result<(WorldGenerator, GenResult), String> {
         match SerializedWorld::deserialize(file_path) {
             | Ok(c) => Ok((c.settings, c.world)),
@@ -695,10 +1412,547 @@ impl WorldGenerator {
     }
 }
 
+// the noise the elevation is sampled from
+#[inline(always)]
+pub(crate) fn elevation_noise(noise_settings: &NoiseSettings) -> RidgedMulti<Fbm<Perlin>> {
+    RidgedMulti::<Fbm<Perlin>>::new(noise_settings.seed)
+        .set_octaves(noise_settings.octaves)
+        .set_frequency(noise_settings.frequency)
+        .set_lacunarity(noise_settings.lacunarity)
+        .set_persistence(noise_settings.persistence)
+        .set_attenuation(noise_settings.attenuation)
+}
+
+// sample the noise over the normalized [0, 1) square, so the same settings give the same map at every resolution
+#[inline(always)]
+pub(crate) fn generate_elevation_map(noise_settings: &NoiseSettings, size: usize) -> Vec<Vec<f64>> {
+    let noise = elevation_noise(noise_settings);
+
+    into_maybe_par_iter(0..size)
+        .map(|y| {
+            let y_normalized = y as f64 / size as f64;
+            (0..size)
+                .map(|x| {
+                    let x_normalized = x as f64 / size as f64;
+                    noise.get([x_normalized, y_normalized])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// runs a generation stage inside its span, recording in the span how long the stage took; with the `memory_stats`
+// feature the memory it allocated is logged in the span as it ends, so it is known even if the next stage runs out of it
+#[inline(always)]
+fn in_stage<T>(span: Span, stage: impl FnOnce() -> T) -> T {
+    let start = Utc::now();
+    #[cfg(feature = "memory_stats")]
+    let (result, memory) = memory::measure(|| span.in_scope(stage));
+    #[cfg(not(feature = "memory_stats"))]
+    let result = span.in_scope(stage);
+    span.record("elapsed_ms", (Utc::now() - start).num_milliseconds());
+    #[cfg(feature = "memory_stats")]
+    span.in_scope(|| debug!(retained_bytes = memory.retained_bytes, peak_bytes = memory.peak_bytes, total_bytes = memory.total_bytes, "stage memory"));
+    result
+}
+
+// the number of tiles holding the content spawned by the given spawnable
+#[inline(always)]
+fn count_spawned(world: &TileMatrix, spawnable: &Spawnables) -> usize {
+    world
+        .iter()
+        .flatten()
+        .filter(|tile| match spawnable {
+            | Spawnables::Rock => matches!(tile.content, Content::Rock(_)),
+            | Spawnables::Tree => matches!(tile.content, Content::Tree(_)),
+            | Spawnables::Garbage => matches!(tile.content, Content::Garbage(_)),
+            | Spawnables::Fire => matches!(tile.content, Content::Fire),
+            | Spawnables::Coin => matches!(tile.content, Content::Coin(_)),
+            | Spawnables::Bin => matches!(tile.content, Content::Bin(_)),
+            | Spawnables::Crate => matches!(tile.content, Content::Crate(_)),
+            | Spawnables::Bank => matches!(tile.content, Content::Bank(_)),
+            | Spawnables::Market => matches!(tile.content, Content::Market(_)),
+            | Spawnables::Fish => matches!(tile.content, Content::Fish(_)),
+        })
+        .count()
+}
+
 /// Alias for `Vec<Vec<Tile>>` which is the Tile matrix representing the world
 pub type TileMatrix = Vec<Vec<Tile>>;
 
-pub(crate) type GenResult = (TileMatrix, (usize, usize), EnvironmentalConditions, f32, Option<HashMap<Content, f32>>);
+/// Alias for the tuple returned by the `Generator` trait: tile matrix, robot spawn point, environmental conditions, max score and optional score table
+pub type GenResult = (TileMatrix, (usize, usize), EnvironmentalConditions, f32, Option<HashMap<Content, f32>>);
+
+/// Alias for what `gen_into` returns besides the tiles: robot spawn point, environmental conditions, max score and optional score table
+pub type GenMetadata = ((usize, usize), EnvironmentalConditions, f32, Option<HashMap<Content, f32>>);
+
+impl WorldGenerator {
+    /// Generates a new world based on the specified settings, like `gen`, also returning the
+    /// intermediate data computed during the generation.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the same `GenResult` returned by `gen` and the `GenerationArtifacts`, such as the
+    /// elevation map, stored as `f32` to halve its memory footprint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::{Content, TileType};
+    /// use exclusion_zone::generator::properties::{assert_world_properties, ExpectedProperties};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut world_generator = WorldGenerator::default(1000);
+    /// let (world, artifacts) = world_generator.gen_with_artifacts();
+    /// let height = artifacts.elevation_map[0][0];
+    ///
+    /// // mostly grass, a little lava, every kind of content and the walkable tiles connected
+    /// let expected = ExpectedProperties::default()
+    ///     .with_tile_type_share(TileType::Grass, 0.2..=0.7)
+    ///     .with_tile_type_share(TileType::Lava, 0.0..=0.1)
+    ///     .with_content_count(Content::Fire, 1..=10_000)
+    ///     .with_content_count(Content::Bank(0..0), 1..=100_000)
+    ///     .with_min_connected_share(0.8);
+    /// assert_world_properties(&world.0, &expected);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic, before generating anything, if the settings do not pass `validate_all`.
+    pub fn gen_with_artifacts(&mut self) -> (GenResult, GenerationArtifacts) {
+        match self.try_gen_with_artifacts() {
+            | Ok(generated) => generated,
+            | Err(issues) => panic!("Invalid settings:\n{}", issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>().join("\n")),
+        }
+    }
+
+    /// Generates a new world like `gen_with_artifacts`, returning the issues of the settings instead of panicking
+    /// when they do not pass `validate_all`, like a world smaller than the min size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // too small without the small world mode
+    /// let issues = WorldGenerator::default(50).try_gen_with_artifacts().unwrap_err();
+    /// assert_eq!(issues[0].setting, "WorldGenerator::size");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the issues found by `validate_all`, before generating anything.
+    pub fn try_gen_with_artifacts(&mut self) -> Result<(GenResult, GenerationArtifacts), Vec<SettingsIssue>> {
+        self.validate_all()?;
+        Ok(self.gen_reusing(Vec::new()))
+    }
+
+    // generates the world like `try_gen_with_artifacts`, the terrain in the rows of the given world, the settings are
+    // validated by the caller
+    fn gen_reusing(&mut self, world: TileMatrix) -> (GenResult, GenerationArtifacts) {
+        let generation = info_span!("generate", size = self.size, seed = self.noise_settings.seed, elapsed_ms = Empty);
+        let _generation = generation.enter();
+        let tot = Utc::now();
+        let budget = TimeBudget::start(&self.generation_options);
+
+        let (constraints, (noise_map, mut world)) = match self.constraint_settings.clone() {
+            | Some(settings) => {
+                let (terrain, report) = constrained_base_terrain(self, &settings);
+                generation.record("seed", self.noise_settings.seed);
+                (Some(report), terrain)
+            }
+            | None => (None, self.cached_base_terrain(world)),
+        };
+
+        in_stage(debug_span!("weather", elapsed_ms = Empty), || {
+            apply_weather_to_terrain(&mut world, &noise_map, &self.thresholds, &self.weather_settings);
+        });
+
+        let generated = self.spawn_on_terrain(noise_map, world, constraints, budget);
+        generation.record("elapsed_ms", (Utc::now() - tot).num_milliseconds());
+        generated
+    }
+
+    /// Populates an existing terrain, like the one of another world generator or imported from a map editor, running
+    /// only the spawn pipeline of `gen_with_artifacts` over it: the streets, the lava and the content of the spawn
+    /// order, with the settings of the generator, while the noise and the terrain generation are skipped.
+    ///
+    /// Only the tile types of the given tiles are kept, their content is replaced by the spawned one. The streets, the
+    /// lava and the rocks follow the elevation; without one, it is made up from the tile types, each one at the middle
+    /// of its band of the `thresholds`, so the streets run along the hills and the lava spawns in the mountains.
+    ///
+    /// # Arguments
+    ///
+    /// * `existing_tiles` - The terrain to populate, a square of the size of the generator.
+    /// * `elevation` - The elevation of each tile, of the size of the terrain, or `None` to make it up from the tile types.
+    ///
+    /// # Returns
+    ///
+    /// The same `GenResult` and `GenerationArtifacts` as `gen_with_artifacts`, the elevation map being the one given or
+    /// made up.
+    ///
+    /// # Errors
+    ///
+    /// Returns the issues found by `validate_all`, or the terrain or the elevation not being of the size of the
+    /// generator, before spawning anything.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::mini::MiniWorldGenerator;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // the terrain of one generator, the streets and the content of another
+    /// let (terrain, ..) = MiniWorldGenerator::new(100).with_seed(7).gen();
+    /// let ((world, spawn, ..), artifacts) = WorldGenerator::default(100).populate(terrain, None).unwrap();
+    /// ```
+    pub fn populate(&mut self, existing_tiles: TileMatrix, elevation: Option<Vec<Vec<f64>>>) -> Result<(GenResult, GenerationArtifacts), Vec<SettingsIssue>> {
+        let size = self.size;
+        let mut issues = Issues::default();
+        issues.check(existing_tiles.len() == size && existing_tiles.iter().all(|row| row.len() == size), "WorldGenerator::populate::existing_tiles", || {
+            format!("the terrain must be {size}x{size}, the size of the generator")
+        });
+        if let Some(elevation) = &elevation {
+            issues.check(elevation.len() == size && elevation.iter().all(|row| row.len() == size), "WorldGenerator::populate::elevation", || {
+                format!("the elevation must be {size}x{size}, the size of the terrain")
+            });
+        }
+        let mut all = self.validate_all().err().unwrap_or_default();
+        all.append(&mut issues.0);
+        if !all.is_empty() {
+            return Err(all);
+        }
+
+        let generation = info_span!("populate", size, seed = self.noise_settings.seed, elapsed_ms = Empty);
+        let _generation = generation.enter();
+        let tot = Utc::now();
+        let budget = TimeBudget::start(&self.generation_options);
+
+        let mut world = existing_tiles;
+        for tile in world.iter_mut().flatten() {
+            tile.content = Content::None;
+        }
+        let noise_map = elevation.unwrap_or_else(|| world.iter().map(|row| row.iter().map(|tile| self.thresholds.elevation(&tile.tile_type)).collect()).collect());
+
+        let generated = self.spawn_on_terrain(noise_map, world, None, budget);
+        generation.record("elapsed_ms", (Utc::now() - tot).num_milliseconds());
+        Ok(generated)
+    }
+
+    // the spawn pipeline, run over the terrain generated or given to `populate`
+    fn spawn_on_terrain(&mut self, noise_map: Vec<Vec<f64>>, mut world: TileMatrix, constraints: Option<ConstraintReport>, mut budget: TimeBudget) -> (GenResult, GenerationArtifacts) {
+        // every random choice depends on the seed, so the same settings always give the same world
+        seed_generation_rng(self.noise_settings.seed as u64);
+
+        remove_duplicates_spawnables(&mut self.spawn_order);
+
+        let (polygons, bridges) = in_stage(debug_span!("streets", polygons = Empty, elapsed_ms = Empty), || {
+            let mut polygons = street_spawn(&noise_map, self.street_settings.n_slice_side, self.street_settings.lower_threshold, self.generation_options.small_world_mode);
+            if let Some(max_tiles) = self.street_settings.max_street_tiles {
+                polygons = prune_streets(polygons, &noise_map, max_tiles);
+            }
+            if self.street_settings.routing == StreetRouting::WalkCost {
+                polygons = route_streets(&world, polygons, &self.street_settings);
+            }
+            let bridges = place_streets(&mut world, &polygons, &self.street_settings);
+            Span::current().record("polygons", polygons.len());
+            (polygons, bridges)
+        });
+
+        let steam = in_stage(debug_span!("lava", spawn_points = self.lava_settings.number_of_spawn_points, elapsed_ms = Empty), || {
+            spawn_lava(&mut world, &noise_map, self.lava_settings.clone())
+        });
+
+        let slope = self.slope_settings.filter(|_| budget.allows("slope")).map(|settings| {
+            in_stage(debug_span!("slope", steep = Empty, cliffs = Empty, elapsed_ms = Empty), || {
+                let map = analyze_slope(&mut world, &noise_map, &settings);
+                Span::current().record("steep", map.steep.len());
+                Span::current().record("cliffs", map.cliffs.len());
+                map
+            })
+        });
+
+        let fence_gates = match &self.fence_settings {
+            | Some(settings) => in_stage(debug_span!("fence", gates = Empty, elapsed_ms = Empty), || {
+                let gates = build_fence(&mut world, settings);
+                Span::current().record("gates", gates.len());
+                gates
+            }),
+            | None => Vec::new(),
+        };
+
+        // the terrain is final, from now on the spawners check and mark the tiles through the occupancy
+        let mut occupancy = Occupancy::build(&world);
+
+        if let Some(lining) = &self.street_settings.tree_lining {
+            if let Some(masks) = &self.placement_masks {
+                masks.restrict(&world, &mut occupancy, Spawnables::Tree);
+            }
+            in_stage(debug_span!("tree_lining", planted = Empty, elapsed_ms = Empty), || {
+                let planted = line_streets(&mut world, &mut occupancy, &polygons, lining);
+                Span::current().record("planted", planted);
+            });
+        }
+
+        let stage_seeds = self.stage_seeds.clone().unwrap_or_else(|| StageSeeds::from_seed(self.noise_settings.seed));
+        let mut spawn_reports = Vec::with_capacity(self.spawn_order.len());
+        let mut spawn_heatmaps = Vec::new();
+        for content in &self.spawn_order {
+            // the content placed before, like the trees along the streets, is not part of the report of the stage
+            occupancy.take_report();
+            occupancy.take_heatmap();
+            let span = debug_span!("spawn", content = ?content, seed = stage_seeds.get(*content), count = Empty, elapsed_ms = Empty);
+            seed_generation_rng(stage_seeds.get(*content));
+            occupancy.set_policy(self.conflict_settings.policy(*content));
+            if let Some(masks) = &self.placement_masks {
+                masks.restrict(&world, &mut occupancy, *content);
+            }
+            in_stage(span.clone(), || match content {
+                | Spawnables::Rock => spawn_rock(&mut world, &mut occupancy, &self.rock_settings, &noise_map),
+                | Spawnables::Tree => spawn_tree_with_occupancy(&mut world, &mut occupancy, &mut self.tree_settings, budget.quality(*content, self.quality_profile)),
+                | Spawnables::Garbage => spawn_garbage(&mut world, &mut occupancy, &self.garbage_settings, budget.quality(*content, self.quality_profile), self.wind_settings.as_ref()),
+                | Spawnables::Fire => {
+                    let mut fire_settings = self.weather_settings.adjust_fire(&self.fire_settings);
+                    spawn_fire_with_occupancy(&mut world, &mut occupancy, &mut fire_settings, budget.quality(*content, self.quality_profile), self.wind_settings.as_ref())
+                }
+                | Spawnables::Bin => spawn_bin(&mut world, &mut occupancy, self.bin_settings),
+                | Spawnables::Crate => spawn_crate(&mut world, &mut occupancy, self.crate_settings),
+                | Spawnables::Bank => spawn_bank(&mut world, &mut occupancy, self.bank_settings),
+                | Spawnables::Coin => spawn_coin(&mut world, &mut occupancy, self.coin_settings),
+                | Spawnables::Market => spawn_market(&mut world, &mut occupancy, self.market_settings),
+                | Spawnables::Fish => spawn_fish(&mut world, &mut occupancy, self.fish_settings),
+            });
+            // counting needs a full scan of the world, skip it when nobody is listening
+            if !span.is_disabled() {
+                span.record("count", count_spawned(&world, content));
+            }
+            let report = occupancy.take_report();
+            if self.generation_options.spawn_warnings {
+                report.warn(*content);
+            }
+            spawn_reports.push((*content, report));
+            spawn_heatmaps.extend(occupancy.take_heatmap().map(|heatmap| (*content, heatmap)));
+        }
+
+        for (index, extra) in self.extra_content.iter().enumerate() {
+            seed_generation_rng(extra_content_seed(self.noise_settings.seed, index));
+            occupancy.set_policy(ConflictPolicy::Skip);
+            in_stage(debug_span!("extra_content", name = extra.name, elapsed_ms = Empty), || spawn_extra_content(&mut world, &mut occupancy, extra));
+        }
+        // the spawners of the extra content do not report
+        occupancy.take_report();
+        occupancy.take_heatmap();
+
+        in_stage(debug_span!("weather_content", elapsed_ms = Empty), || spawn_weather_content(&mut world, &mut occupancy, &self.weather_settings));
+
+        if let Some(settings) = &self.quantity_field_settings {
+            in_stage(debug_span!("quantity_fields", elapsed_ms = Empty), || apply_quantity_fields(&mut world, &noise_map, self.noise_settings.seed, settings));
+        }
+
+        // the lava interacts with the content spawned around it
+        let mut relocated_content = in_stage(debug_span!("lava_boundary", elapsed_ms = Empty), || spawn_lava_boundary(&mut world, &self.lava_settings));
+
+        // the content enclosed by the lava boundary is moved too
+        let access = self.access_settings.filter(|_| budget.allows("access")).map(|settings| {
+            in_stage(debug_span!("access", relocated = Empty, enclosed = Empty, elapsed_ms = Empty), || {
+                let report = check_access(&mut world, &settings);
+                Span::current().record("relocated", report.relocated.len());
+                Span::current().record("enclosed", report.enclosed.len());
+                report
+            })
+        });
+
+        // the landmarks do not depend on the seed of the last spawn stage
+        seed_generation_rng(self.noise_settings.seed as u64);
+        let landmarks = match self.landmark_settings.filter(|_| budget.allows("landmarks")) {
+            | Some(settings) => in_stage(debug_span!("landmarks", elapsed_ms = Empty), || spawn_landmarks(&mut world, &noise_map, &settings)),
+            | None => Vec::new(),
+        };
+
+        let epicenter = self.contamination_settings.and_then(|settings| {
+            in_stage(debug_span!("contamination", radius = settings.radius, elapsed_ms = Empty), || {
+                spread_contamination(&mut world, &landmarks, self.noise_settings.seed, &settings)
+            })
+        });
+
+        let vault_pairs = match &self.vault_settings {
+            | Some(settings) => in_stage(debug_span!("vaults", pairs = settings.pairs, elapsed_ms = Empty), || spawn_vaults(&mut world, settings)),
+            | None => Vec::new(),
+        };
+
+        let (spawn, spawn_view) = match &self.spawn_view_settings {
+            | Some(settings) => in_stage(debug_span!("spawn_view", elapsed_ms = Empty), || spawn_with_view(&world, settings)),
+            | None => (robot_spawn(&world), None),
+        };
+
+        let water_source = self.water_source_settings.and_then(|settings| {
+            in_stage(debug_span!("water_source", max_distance = settings.max_distance, elapsed_ms = Empty), || {
+                ensure_water_source(&mut world, spawn.coordinate(), &settings)
+            })
+        });
+
+        let reachability = if self.reachability_check != ReachabilityCheck::Disabled && !budget.allows("reachability") {
+            ReachabilityReport::default()
+        } else {
+            in_stage(debug_span!("reachability", unreachable = Empty, carved = Empty, elapsed_ms = Empty), || {
+                let report = check_reachability(&mut world, spawn.coordinate(), self.reachability_check, &self.street_settings);
+                relocated_content.extend(reconcile_content(&mut world, &report.carved));
+                Span::current().record("unreachable", report.unreachable.len());
+                Span::current().record("carved", report.carved.len());
+                report
+            })
+        };
+
+        let crate_manifest = match &self.crate_settings.loot {
+            | Some(settings) => in_stage(debug_span!("crate_loot", elapsed_ms = Empty), || assign_loot(&mut world, settings)),
+            | None => Vec::new(),
+        };
+
+        let balance = self.score_balance.filter(|_| budget.allows("balance")).map(|settings| {
+            in_stage(debug_span!("balance", target = settings.target_max_score, achieved = Empty, elapsed_ms = Empty), || {
+                let report = balance_score(&mut world, &settings);
+                Span::current().record("achieved", report.achieved_score);
+                report
+            })
+        });
+        let (max_score, score_table) = match (&self.score_balance, &balance) {
+            | (Some(settings), Some(report)) => (report.achieved_score, Some(settings.score_table())),
+            | _ => (100.0, None),
+        };
+
+        let regions = self.region_settings.filter(|_| budget.allows("regions")).map(|settings| {
+            in_stage(debug_span!("regions", count = Empty, elapsed_ms = Empty), || {
+                let map = find_regions(&world, &landmarks, &settings);
+                Span::current().record("count", map.regions.len());
+                map
+            })
+        });
+
+        let flow_field = self
+            .flow_settings
+            .filter(|_| budget.allows("flow_field"))
+            .map(|settings| in_stage(debug_span!("flow_field", elapsed_ms = Empty), || compute_flow_field(&world, &noise_map, &settings)));
+
+        let content_index = if budget.allows("content_index") {
+            in_stage(debug_span!("content_index", elapsed_ms = Empty), || ContentIndex::build(&world))
+        } else {
+            ContentIndex::default()
+        };
+
+        let hazard_map = if budget.allows("hazard_map") {
+            in_stage(debug_span!("hazard_map", elapsed_ms = Empty), || HazardMap::build(&world))
+        } else {
+            HazardMap::default()
+        };
+
+        let tags = self
+            .tag_settings
+            .filter(|_| budget.allows("tags"))
+            .map(|settings| in_stage(debug_span!("tags", elapsed_ms = Empty), || TagMap::build(&world, &bridges, &steam, &landmarks, &hazard_map, &settings)));
+
+        let timed_spawns = match &self.timed_spawn_settings {
+            | Some(settings) => in_stage(debug_span!("timed_spawns", elapsed_ms = Empty), || schedule_timed_spawns(&world, spawn.coordinate(), settings)),
+            | None => Vec::new(),
+        };
+
+        // the decor is drawn last, so enabling it leaves the world unchanged
+        let decor = self.decor_settings.filter(|_| budget.allows("decor")).map(|settings| {
+            seed_generation_rng(self.noise_settings.seed as u64);
+            in_stage(debug_span!("decor", elapsed_ms = Empty), || spawn_decor(&world, &settings))
+        });
+
+        let environmental_conditions = self.weather_settings.environmental_conditions_or_default();
+
+        debug!("Check world integrity");
+        check_world(&world);
+
+        let elevation_map: Vec<Vec<f32>> = into_maybe_par_iter(noise_map).map(|row| row.into_iter().map(|value| value as f32).collect()).collect();
+        let elevation_pyramid = (self.generation_options.elevation_pyramid && budget.allows("elevation_pyramid"))
+            .then(|| in_stage(debug_span!("elevation_pyramid", elapsed_ms = Empty), || ElevationPyramid::build(&elevation_map)));
+
+        (
+            (world, spawn.spawn(), environmental_conditions, max_score, score_table),
+            GenerationArtifacts {
+                elevation_map,
+                elevation_pyramid,
+                reachability,
+                access,
+                balance,
+                content_index,
+                hazard_map,
+                tags,
+                spawn_reports,
+                spawn_heatmaps,
+                constraints,
+                timed_spawns,
+                epicenter,
+                crate_manifest,
+                spawn_view,
+                water_source,
+                relocated_content,
+                stage_seeds: Some(stage_seeds),
+                landmarks,
+                vault_pairs,
+                slope,
+                flow_field,
+                fence_gates,
+                regions,
+                decor,
+                degraded: budget.degraded,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Generates a new world like `gen`, writing its tiles in the caller-provided `world` instead of returning a new tile
+    /// matrix.
+    ///
+    /// The terrain is classified straight into the rows of the world, which are cleared and reused, and the following
+    /// stages change the tiles in place, so repeated generations of the same size, like property tests and fuzzing,
+    /// allocate the tile matrix only the first time. The maps of the stages, like the noise map, are still allocated by
+    /// each generation; the rows are not reused if the terrain is read from the cache directory or regenerated to meet
+    /// the constraints.
+    ///
+    /// The storage is a tile matrix rather than a flat buffer of `width` x `height` tiles: the stages work on the rows
+    /// of a `TileMatrix`, so a flat buffer would be copied into a new matrix and back on each generation, and the world
+    /// is always a square of the size of the generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The storage for the tiles, resized to the size of the generator, its rows grown only if they cannot
+    ///   hold a row of the world.
+    ///
+    /// # Returns
+    ///
+    /// The robot spawn point, the environmental conditions, the max score and the optional score table.
+    ///
+    /// # Errors
+    ///
+    /// Returns the issues found by `validate_all`, before generating anything; the world is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::{NoiseSettings, WorldGenerator};
+    ///
+    /// let mut world = Vec::new();
+    /// for seed in 0..3 {
+    ///     let mut generator = WorldGenerator::default(200);
+    ///     generator.noise_settings = NoiseSettings::from_seed(seed);
+    ///     let (spawn_point, ..) = generator.gen_into(&mut world).unwrap();
+    ///     assert_eq!(world.len(), 200);
+    ///     assert!(world.iter().all(|row| row.len() == 200));
+    /// }
+    /// ```
+    pub fn gen_into(&mut self, world: &mut TileMatrix) -> Result<GenMetadata, Vec<SettingsIssue>> {
+        // checked before the world is taken, so that it is left untouched
+        self.validate_all()?;
+        let ((tiles, robot_position, environmental_conditions, max_score, score_table), _) = self.gen_reusing(std::mem::take(world));
+        *world = tiles;
+        Ok((robot_position, environmental_conditions, max_score, score_table))
+    }
+}
 
 impl Generator for WorldGenerator {
     /// Generates a new world based on the specified settings.
@@ -735,7 +1989,7 @@ impl Generator for WorldGenerator {
     ///     world_size,
     ///     get_default_spawn_order(),
     ///     NoiseSettings::default(),
-    ///     Thresholds::def(),
+    ///     Thresholds::default(),
     ///     LavaSettings::default(world_size),
     ///     BankSettings::default(world_size),
     ///     BinSettings::default(world_size),
@@ -752,141 +2006,15 @@ impl Generator for WorldGenerator {
     /// let generated = world_generator.gen();
     /// ```
     fn gen(&mut self) -> GenResult {
-        if self.size < 100 {
-            panic!("The world size must be at least 100");
-        }
-
-        let tot = Utc::now();
-
-        debug_println!("Start: Noise map generation");
-        let mut start = Utc::now();
-        let noise_map = self.generate_elevation_map();
-        debug_println!("Done: Generate noise map: {} ms", (Utc::now() - start).num_milliseconds());
-
-        debug_println!("Start: Calculate min and max value");
-        start = Utc::now();
-        let min_value = find_min_value(&noise_map).unwrap_or(f64::MAX);
-        let max_value = find_max_value(&noise_map).unwrap_or(f64::MIN);
-        debug_println!("Done: Calculate min and max value: {} ms", (Utc::now() - start).num_milliseconds());
-
-        debug_println!("Start: Generate terrain");
-        start = Utc::now();
-        let mut world = self.generate_terrain(&noise_map, min_value, max_value);
-        debug_println!("Done: Generate terrain: {} ms", (Utc::now() - start).num_milliseconds());
-
-        remove_duplicates_spawnables(&mut self.spawn_order);
-
-        debug_println!("Start: Spawn streets");
-        start = Utc::now();
-        //color local maxima black
-        let polygons = street_spawn(&noise_map, 10, 0.0);
-
-        for polygon in polygons.iter() {
-            for c in polygon {
-                world[c.row][c.col].tile_type = TileType::Street;
-            }
-        }
-        debug_println!("Done: Spawn streets: {} ms", (Utc::now() - start).num_milliseconds());
-
-        debug_println!("Start: Spawn lava");
-        start = Utc::now();
-        spawn_lava(&mut world, &noise_map, self.lava_settings.clone());
-        debug_println!("Done: Spawn lava: {} ms", (Utc::now() - start).num_milliseconds());
-
-        for content in &self.spawn_order {
-            match content {
-                | Spawnables::Rock => {
-                    debug_println!("Start: Spawn rocks");
-                    start = Utc::now();
-                    spawn_rock(&mut world, self.rock_settings);
-                    debug_println!("Done: Spawn rocks: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Tree => {
-                    debug_println!("Start: Spawn trees");
-                    start = Utc::now();
-                    spawn_tree(&mut world, &mut self.tree_settings);
-                    debug_println!("Done: Spawn trees in {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Garbage => {
-                    debug_println!("Start: Spawn garbage");
-                    start = Utc::now();
-                    spawn_garbage(&mut world, &self.garbage_settings);
-                    debug_println!("Done: Spawn garbage in {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Fire => {
-                    debug_println!("Start: Spawn fire");
-                    start = Utc::now();
-                    spawn_fire(&mut world, &mut self.fire_settings);
-                    debug_println!("Done: Spawn fire in {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Bin => {
-                    debug_println!("Start: Spawn bin");
-                    start = Utc::now();
-                    spawn_bin(&mut world, self.bin_settings);
-                    debug_println!("Done: Spawn bin: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Crate => {
-                    debug_println!("Start: Spawn crate");
-                    start = Utc::now();
-                    spawn_crate(&mut world, self.crate_settings);
-                    debug_println!("Done: Spawn crate: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Bank => {
-                    debug_println!("Start: Spawn bank");
-                    start = Utc::now();
-                    spawn_bank(&mut world, self.bank_settings);
-                    debug_println!("Done: Spawn bank: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Coin => {
-                    debug_println!("Start: Spawn coins");
-                    start = Utc::now();
-                    spawn_coin(&mut world, self.coin_settings);
-                    debug_println!("Done: Spawn coins: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Market => {
-                    debug_println!("Start: Spawn market");
-                    start = Utc::now();
-                    spawn_market(&mut world, self.market_settings);
-                    debug_println!("Done: Spawn market: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-                | Spawnables::Fish => {
-                    debug_println!("Start: Spawn fish");
-                    start = Utc::now();
-                    spawn_fish(&mut world, self.fish_settings);
-                    debug_println!("Done: Spawn fish: {} ms", (Utc::now() - start).num_milliseconds());
-                }
-            }
-        }
-
-        // Detect the first walkable tile and set the initial position of the robot
-        let mut robot_position = (0, 0);
-        for (y, row) in world.iter().enumerate() {
-            for (x, tile) in row.iter().enumerate() {
-                if tile.tile_type.properties().walk() {
-                    robot_position = (x, y);
-                    break;
-                }
-            }
-        }
-
-        debug_println!("World completed in: {} ms", (Utc::now() - tot).num_milliseconds());
-
-        debug_println!("Check world integrity:");
-
-
-
-        check_world(&world);
-
-        (
-            world,
-            robot_position,
-            EnvironmentalConditions::new(&[Rainy, Sunny, Foggy, TropicalMonsoon, TrentinoSnow], 15, 9).unwrap(),
-            100.0,
-            None,
-        )
+        self.gen_with_artifacts().0
     }
 }
 
+#[inline(always)]
+pub(crate) fn default_environmental_conditions() -> EnvironmentalConditions {
+    EnvironmentalConditions::new(&[Rainy, Sunny, Foggy, TropicalMonsoon, TrentinoSnow], 15, 9).unwrap()
+}
+
 pub fn check_world(world: &Vec<Vec<Tile>>){
     for row in world {
         // Check for square world
@@ -0,0 +1,288 @@
+#[cfg(feature = "spawn_telemetry")]
+use std::cell::RefCell;
+
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::generator::conflict::{stack, ConflictPolicy};
+use crate::generator::spawn_heatmap::SpawnHeatmap;
+use crate::generator::spawn_report::{SkipReason, SpawnReport};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Bit set on the tiles holding any content
+const OCCUPIED: u16 = 1 << 15;
+/// One instance of each kind of content, in the order of their bit in the masks
+const CONTENTS: [Content; 15] = [
+    Content::Rock(0),
+    Content::Tree(0),
+    Content::Garbage(0),
+    Content::Fire,
+    Content::Coin(0),
+    Content::Bin(0..0),
+    Content::Crate(0..0),
+    Content::Bank(0..0),
+    Content::Water(0),
+    Content::Market(0),
+    Content::Fish(0),
+    Content::Building,
+    Content::Bush(0),
+    Content::JollyBlock(0),
+    Content::Scarecrow,
+];
+/// One instance of each tile type, in the order of `tile_type_index`
+const TILE_TYPES: [TileType; 11] = [
+    TileType::DeepWater,
+    TileType::ShallowWater,
+    TileType::Sand,
+    TileType::Grass,
+    TileType::Street,
+    TileType::Hill,
+    TileType::Mountain,
+    TileType::Snow,
+    TileType::Lava,
+    TileType::Teleport(false),
+    TileType::Wall,
+];
+
+/// Compact per-tile view of which content each tile can hold and whether it already holds any.
+///
+/// Each tile is a `u16`: the first 15 bits are the kinds of content its tile type can hold, in the order of `CONTENTS`,
+/// the last one is set if the tile is occupied. It is built once the terrain is final, right before the content is
+/// spawned, so that the spawners do not look up the tile type properties and compare the content of the tiles in
+/// their hot loops; content must be placed with `place` to keep the occupied flag in sync.
+///
+/// `place` also applies the conflict policy of the spawnable being spawned, set with `set_policy`, and counts the
+/// placed content in the report of the spawn stage, taken with `take_report`. With the `spawn_telemetry` feature the
+/// checks of `can_hold` and `accepts` and the placements of `place` are also counted per tile, in the heatmap of the
+/// spawn stage taken with `take_heatmap`.
+pub(crate) struct Occupancy {
+    size: usize,
+    masks: Vec<u16>,
+    // [tile_type_index] -> the kinds of content the tile type can hold
+    holdable: Vec<u16>,
+    policy: ConflictPolicy,
+    report: SpawnReport,
+    // a cell, the checks which only read the occupancy count as attempts too
+    #[cfg(feature = "spawn_telemetry")]
+    heatmap: RefCell<SpawnHeatmap>,
+}
+
+impl Occupancy {
+    pub(crate) fn build(world: &TileMatrix) -> Self {
+        // the properties of each tile type are looked up only once
+        let holdable: Vec<u16> = TILE_TYPES
+            .iter()
+            .map(|tile_type| {
+                CONTENTS
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, content)| tile_type.properties().can_hold(content))
+                    .fold(0, |mask, (bit, _)| mask | 1 << bit)
+            })
+            .collect();
+
+        let masks = world
+            .iter()
+            .flatten()
+            .map(|tile| {
+                let occupied = if tile.content == Content::None { 0 } else { OCCUPIED };
+                holdable[tile_type_index(&tile.tile_type)] | occupied
+            })
+            .collect();
+
+        Occupancy {
+            size: world.len(),
+            masks,
+            holdable,
+            policy: ConflictPolicy::default(),
+            report: SpawnReport::default(),
+            #[cfg(feature = "spawn_telemetry")]
+            heatmap: RefCell::new(SpawnHeatmap::new(world.len())),
+        }
+    }
+
+    /// Returns the side of the world
+    #[inline(always)]
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the tile type of the tile can hold the given kind of content, the quantity is ignored
+    #[inline(always)]
+    pub(crate) fn can_hold(&self, c: Coordinate, content: &Content) -> bool {
+        self.record(c, false, self.holds(c, content))
+    }
+
+    #[inline(always)]
+    fn holds(&self, c: Coordinate, content: &Content) -> bool {
+        match content_bit(content) {
+            | Some(bit) => self.masks[c.row * self.size + c.col] & bit != 0,
+            | None => true,
+        }
+    }
+
+    /// Returns true if the tile holds no content
+    #[inline(always)]
+    pub(crate) fn is_empty(&self, c: Coordinate) -> bool {
+        self.masks[c.row * self.size + c.col] & OCCUPIED == 0
+    }
+
+    /// Returns the number of tiles without content whose tile type can hold the given kind of content
+    pub(crate) fn free_capacity(&self, content: &Content) -> usize {
+        let bit = content_bit(content).unwrap_or(0);
+        self.masks.iter().filter(|&&mask| mask & OCCUPIED == 0 && (bit == 0 || mask & bit != 0)).count()
+    }
+
+    /// Changes the tile type of the tile, removing its content if the new tile type cannot hold it
+    pub(crate) fn set_tile_type(&mut self, world: &mut TileMatrix, c: Coordinate, tile_type: TileType) {
+        let tile = &mut world[c.row][c.col];
+        let mask = self.holdable[tile_type_index(&tile_type)];
+        tile.tile_type = tile_type;
+        if content_bit(&tile.content).is_some_and(|bit| mask & bit == 0) {
+            tile.content = Content::None;
+        }
+        self.masks[c.row * self.size + c.col] = if tile.content == Content::None { mask } else { mask | OCCUPIED };
+    }
+
+    /// Prevents the given kind of content from being spawned on the tile from now on
+    #[inline(always)]
+    pub(crate) fn forbid(&mut self, c: Coordinate, content: &Content) {
+        if let Some(bit) = content_bit(content) {
+            self.masks[c.row * self.size + c.col] &= !bit;
+        }
+    }
+
+    /// Sets the conflict policy applied by `place` from now on
+    #[inline(always)]
+    pub(crate) fn set_policy(&mut self, policy: ConflictPolicy) {
+        self.policy = policy;
+    }
+
+    /// Records the requested tiles the spawner could not fill for the given reason
+    #[inline(always)]
+    pub(crate) fn skip(&mut self, reason: SkipReason, count: usize) {
+        self.report.skip(reason, count);
+    }
+
+    /// Returns the report of the content placed since the last call, starting a new one
+    #[inline(always)]
+    pub(crate) fn take_report(&mut self) -> SpawnReport {
+        std::mem::take(&mut self.report)
+    }
+
+    /// Returns the heatmap of the attempts made since the last call, starting a new one, None without the
+    /// `spawn_telemetry` feature
+    #[cfg(feature = "spawn_telemetry")]
+    #[inline(always)]
+    pub(crate) fn take_heatmap(&mut self) -> Option<SpawnHeatmap> {
+        Some(self.heatmap.replace(SpawnHeatmap::new(self.size)))
+    }
+
+    #[cfg(not(feature = "spawn_telemetry"))]
+    #[inline(always)]
+    pub(crate) fn take_heatmap(&mut self) -> Option<SpawnHeatmap> {
+        None
+    }
+
+    // counts the check, or the placement, of the content on the tile in the heatmap and returns whether it succeeded
+    #[cfg(feature = "spawn_telemetry")]
+    #[inline(always)]
+    fn record(&self, c: Coordinate, placed: bool, accepted: bool) -> bool {
+        self.heatmap.borrow_mut().record(c.row * self.size + c.col, placed, accepted);
+        accepted
+    }
+
+    #[cfg(not(feature = "spawn_telemetry"))]
+    #[inline(always)]
+    fn record(&self, _: Coordinate, _: bool, accepted: bool) -> bool {
+        accepted
+    }
+
+    /// Returns true if `place` would put the given content on the tile
+    #[inline(always)]
+    pub(crate) fn accepts(&self, world: &TileMatrix, c: Coordinate, content: &Content) -> bool {
+        let accepted = self.holds(c, content)
+            && (self.is_empty(c)
+                || match self.policy {
+                    | ConflictPolicy::Skip => false,
+                    | ConflictPolicy::Replace => true,
+                    | ConflictPolicy::Stack => stack(&world[c.row][c.col].content, content).is_some(),
+                });
+        self.record(c, false, accepted)
+    }
+
+    /// Places the content on the tile following the conflict policy and updates the occupied flag,
+    /// returns false if the tile already holds content that the policy keeps
+    #[inline(always)]
+    pub(crate) fn place(&mut self, world: &mut TileMatrix, c: Coordinate, content: Content) -> bool {
+        let tile = &mut world[c.row][c.col];
+        let mask = &mut self.masks[c.row * self.size + c.col];
+        if content == Content::None {
+            *mask &= !OCCUPIED;
+            tile.content = content;
+            return true;
+        }
+
+        let content = if *mask & OCCUPIED == 0 {
+            content
+        } else {
+            let kept = match self.policy {
+                | ConflictPolicy::Skip => None,
+                | ConflictPolicy::Replace => Some(content),
+                | ConflictPolicy::Stack => stack(&tile.content, &content),
+            };
+            match kept {
+                | Some(content) => content,
+                | None => {
+                    self.report.skip(SkipReason::Occupied, 1);
+                    return self.record(c, true, false);
+                }
+            }
+        };
+        self.report.place();
+        *mask |= OCCUPIED;
+        tile.content = content;
+        self.record(c, true, true)
+    }
+}
+
+#[inline(always)]
+fn tile_type_index(tile_type: &TileType) -> usize {
+    match tile_type {
+        | TileType::DeepWater => 0,
+        | TileType::ShallowWater => 1,
+        | TileType::Sand => 2,
+        | TileType::Grass => 3,
+        | TileType::Street => 4,
+        | TileType::Hill => 5,
+        | TileType::Mountain => 6,
+        | TileType::Snow => 7,
+        | TileType::Lava => 8,
+        | TileType::Teleport(_) => 9,
+        | TileType::Wall => 10,
+    }
+}
+
+// the bit of the kind of content in the masks, None can be held by every tile
+#[inline(always)]
+fn content_bit(content: &Content) -> Option<u16> {
+    let bit = match content {
+        | Content::Rock(_) => 0,
+        | Content::Tree(_) => 1,
+        | Content::Garbage(_) => 2,
+        | Content::Fire => 3,
+        | Content::Coin(_) => 4,
+        | Content::Bin(_) => 5,
+        | Content::Crate(_) => 6,
+        | Content::Bank(_) => 7,
+        | Content::Water(_) => 8,
+        | Content::Market(_) => 9,
+        | Content::Fish(_) => 10,
+        | Content::Building => 11,
+        | Content::Bush(_) => 12,
+        | Content::JollyBlock(_) => 13,
+        | Content::Scarecrow => 14,
+        | Content::None => return None,
+    };
+    Some(1 << bit)
+}
@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::generator::{QualityProfile, Spawnables, MIN_WORLD_SIZE, SMALL_WORLD_MIN_SIZE};
+
+/// Options controlling how the generation runs rather than what it generates
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationOptions {
+    /// the max duration of the generation, `None` by default for no limit.
+    ///
+    /// Once exceeded, the remaining content is spawned with the `Fast` quality profile and the optional stages,
+    /// like the slope analysis, the landmarks, the reachability check, the score balance, the regions and the
+    /// content index, are skipped. What was degraded is reported in `GenerationArtifacts::degraded`.
+    /// The terrain and the content are always generated, so the budget can be exceeded by a single slow stage.
+    pub time_budget: Option<Duration>,
+    /// log a warning for each spawn stage placing less content than requested, `false` by default.
+    ///
+    /// The content requested and placed by each stage is always reported in `GenerationArtifacts::spawn_reports`.
+    pub spawn_warnings: bool,
+    /// build the pyramid of the elevation map in `GenerationArtifacts::elevation_pyramid`, `false` by default.
+    ///
+    /// The pyramid takes about 16 bytes per tile, it is skipped when the time budget is exceeded.
+    pub elevation_pyramid: bool,
+    /// allow the worlds from `SMALL_WORLD_MIN_SIZE` to `MIN_WORLD_SIZE` tiles wide, `false` by default.
+    ///
+    /// The heuristics sized on the world which break on small worlds are turned off: the nearby local maxima are not
+    /// merged before laying the streets, in a band `size / 100` tiles wide, and a world too small for a street network
+    /// gets none. The default blobs of trees and fires keep a radius of at least 2 tiles instead of `size / 50`.
+    pub small_world_mode: bool,
+}
+
+impl GenerationOptions {
+    /// Creates a new instance of `GenerationOptions` with the given time budget.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use exclusion_zone::generator::options::GenerationOptions;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.generation_options = GenerationOptions::with_time_budget(Duration::from_millis(500));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// for degradation in artifacts.degraded {
+    ///     println!("{:?}", degradation);
+    /// }
+    /// ```
+    pub fn with_time_budget(time_budget: Duration) -> Self {
+        GenerationOptions {
+            time_budget: Some(time_budget),
+            spawn_warnings: false,
+            elevation_pyramid: false,
+            small_world_mode: false,
+        }
+    }
+
+    /// Enables or disables the warnings logged for each spawn stage placing less content than requested,
+    /// the settings the world cannot satisfy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::options::GenerationOptions;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.generation_options = GenerationOptions::default().with_spawn_warnings(true);
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// for (spawnable, report) in artifacts.spawn_reports.iter().filter(|(_, report)| !report.is_satisfied()) {
+    ///     println!("{:?}: {} of {}", spawnable, report.placed, report.requested);
+    /// }
+    /// ```
+    pub fn with_spawn_warnings(mut self, spawn_warnings: bool) -> Self {
+        self.spawn_warnings = spawn_warnings;
+        self
+    }
+
+    /// Enables or disables the pyramid of the elevation map, to query the elevation of whole regions at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::options::GenerationOptions;
+    /// use exclusion_zone::generator::{Coordinate, WorldGenerator};
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.generation_options = GenerationOptions::default().with_elevation_pyramid(true);
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// // the highest point of the top left quarter
+    /// let peak = artifacts.elevation_pyramid.unwrap().max_in(Coordinate::new(0, 0), Coordinate::new(499, 499));
+    /// ```
+    pub fn with_elevation_pyramid(mut self, elevation_pyramid: bool) -> Self {
+        self.elevation_pyramid = elevation_pyramid;
+        self
+    }
+
+    /// Enables or disables the small world mode, for the tutorials and the tests needing worlds narrower than
+    /// `MIN_WORLD_SIZE` but more complete than the ones of the `MiniWorldGenerator`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use exclusion_zone::generator::options::GenerationOptions;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(30);
+    /// generator.generation_options = GenerationOptions::default().with_small_world_mode(true);
+    /// match generator.try_gen_with_artifacts() {
+    ///     | Ok(((world, ..), _)) => assert_eq!(world.len(), 30),
+    ///     | Err(issues) => issues.iter().for_each(|issue| println!("{issue}")),
+    /// }
+    /// ```
+    pub fn with_small_world_mode(mut self, small_world_mode: bool) -> Self {
+        self.small_world_mode = small_world_mode;
+        self
+    }
+
+    /// Returns the min side of the worlds generated with these options, `SMALL_WORLD_MIN_SIZE` in small world mode
+    /// and `MIN_WORLD_SIZE` otherwise
+    pub fn min_world_size(&self) -> usize {
+        if self.small_world_mode {
+            SMALL_WORLD_MIN_SIZE
+        } else {
+            MIN_WORLD_SIZE
+        }
+    }
+}
+
+/// A stage of the generation degraded because the time budget was exceeded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Degradation {
+    /// the content was spawned with the `Fast` quality profile instead of the configured one
+    Simplified(Spawnables),
+    /// the optional stage with the given name was skipped
+    Skipped(String),
+}
+
+// keeps track of the time spent by the generation and of the stages degraded to stay within the budget
+pub(crate) struct TimeBudget {
+    start: DateTime<Utc>,
+    limit: Option<Duration>,
+    pub(crate) degraded: Vec<Degradation>,
+}
+
+impl TimeBudget {
+    #[inline(always)]
+    pub(crate) fn start(options: &GenerationOptions) -> Self {
+        TimeBudget {
+            start: Utc::now(),
+            limit: options.time_budget,
+            degraded: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn exceeded(&self) -> bool {
+        self.limit.is_some_and(|limit| (Utc::now() - self.start).to_std().is_ok_and(|elapsed| elapsed > limit))
+    }
+
+    // true if the optional stage can run, otherwise the stage is reported as skipped
+    #[inline(always)]
+    pub(crate) fn allows(&mut self, stage: &str) -> bool {
+        if !self.exceeded() {
+            return true;
+        }
+        debug!("Time budget exceeded, skipping {}", stage);
+        self.degraded.push(Degradation::Skipped(stage.to_string()));
+        false
+    }
+
+    // the quality profile to spawn the content with, Fast once the budget is exceeded
+    #[inline(always)]
+    pub(crate) fn quality(&mut self, content: Spawnables, quality: QualityProfile) -> QualityProfile {
+        if quality == QualityProfile::Fast || !self.exceeded() {
+            return quality;
+        }
+        debug!("Time budget exceeded, spawning {:?} with the fast quality profile", content);
+        self.degraded.push(Degradation::Simplified(content));
+        QualityProfile::Fast
+    }
+}
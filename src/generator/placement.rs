@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use robotics_lib::world::tile::Tile;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{Spawnables, TileMatrix};
+use crate::utils::Coordinate;
+
+/// A rule telling whether a spawnable may be placed on a tile, given the row and the column of the tile and the tile
+/// itself, with the terrain final and the content spawned by the previous stages
+pub type PlacementRule = Arc<dyn Fn(usize, usize, &Tile) -> bool + Send + Sync>;
+
+/// Restricts where each spawnable may be placed, like garbage only in the left half of the world or no fire near the spawn.
+///
+/// A spawnable may be restricted by a grid, a `size` x `size` matrix indexed by row and column where `true` marks the
+/// allowed tiles, by a rule, or by both, in which case a tile must be allowed by each of them. The spawnables without
+/// a mask are placed as usual; a mask only narrows the tiles the spawnable can already hold, it never allows more.
+///
+/// The grids are saved with the settings, the rules are closures and are not: a loaded `PlacementMasks` has no rule.
+///
+/// The rules cannot be printed nor compared: `Debug` lists the spawnables with a rule and two masks are equal if their
+/// grids are equal and they share the same rules, cloned from the same `Arc`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PlacementMasks {
+    /// the grid of the allowed tiles of each spawnable
+    pub grids: HashMap<Spawnables, Vec<Vec<bool>>>,
+    /// the rule of the allowed tiles of each spawnable, not saved
+    #[serde(skip)]
+    pub rules: HashMap<Spawnables, PlacementRule>,
+}
+
+impl std::fmt::Debug for PlacementMasks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlacementMasks").field("grids", &self.grids).field("rules", &self.rules.keys().collect::<Vec<_>>()).finish()
+    }
+}
+
+impl PartialEq for PlacementMasks {
+    fn eq(&self, other: &Self) -> bool {
+        self.grids == other.grids
+            && self.rules.len() == other.rules.len()
+            && self.rules.iter().all(|(spawnable, rule)| other.rules.get(spawnable).is_some_and(|other| Arc::ptr_eq(rule, other)))
+    }
+}
+
+impl PlacementMasks {
+    /// Creates a new instance of `PlacementMasks` without any mask.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::TileType;
+    /// use exclusion_zone::generator::placement::PlacementMasks;
+    /// use exclusion_zone::generator::{Spawnables, WorldGenerator};
+    ///
+    /// let size = 1000;
+    /// let mut generator = WorldGenerator::default(size);
+    /// generator.placement_masks = Some(
+    ///     PlacementMasks::new()
+    ///         // garbage only in the left half
+    ///         .with_rule(Spawnables::Garbage, move |_, col, _| col < size / 2)
+    ///         // no fire on the hills
+    ///         .with_rule(Spawnables::Fire, |_, _, tile| tile.tile_type != TileType::Hill),
+    /// );
+    /// ```
+    pub fn new() -> Self {
+        PlacementMasks::default()
+    }
+
+    /// Restricts the spawnable to the tiles set to `true` in the grid, indexed by row and column
+    pub fn with_grid(mut self, spawnable: Spawnables, grid: Vec<Vec<bool>>) -> Self {
+        self.grids.insert(spawnable, grid);
+        self
+    }
+
+    /// Restricts the spawnable to the tiles for which the rule, called with the row, the column and the tile, returns `true`
+    pub fn with_rule(mut self, spawnable: Spawnables, rule: impl Fn(usize, usize, &Tile) -> bool + Send + Sync + 'static) -> Self {
+        self.rules.insert(spawnable, Arc::new(rule));
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        for (spawnable, grid) in &self.grids {
+            let rows = grid.len();
+            issues.check(rows == size && grid.iter().all(|row| row.len() == size), "PlacementMasks::grids", || {
+                format!("the grid of {spawnable:?} does not match a {size}x{size} world, it must have {size} rows of {size} tiles")
+            });
+        }
+        issues.0
+    }
+
+    /// Returns true if the masks allow the spawnable on the tile
+    #[inline(always)]
+    pub fn allows(&self, spawnable: Spawnables, row: usize, col: usize, tile: &Tile) -> bool {
+        let grid = self.grids.get(&spawnable).is_none_or(|grid| grid.get(row).and_then(|r| r.get(col)).copied().unwrap_or(false));
+        grid && self.rules.get(&spawnable).is_none_or(|rule| rule(row, col, tile))
+    }
+
+    // forbids the spawnable on the tiles the masks do not allow, right before its spawn stage
+    pub(crate) fn restrict(&self, world: &TileMatrix, occupancy: &mut Occupancy, spawnable: Spawnables) {
+        if !self.grids.contains_key(&spawnable) && !self.rules.contains_key(&spawnable) {
+            return;
+        }
+
+        let content = spawnable.content();
+        for (row, tiles) in world.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if !self.allows(spawnable, row, col, tile) {
+                    occupancy.forbid(Coordinate { row, col }, &content);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use robotics_lib::world::tile::TileType;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::seeds::{StageSeeds, STAGES};
+use crate::generator::{Spawnables, WorldGenerator};
+
+/// Why a spawnable will place no content
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// the spawnable is missing from the spawn order
+    NotInOrder,
+    /// its settings place nothing, like no spawn points or no total quantity
+    NothingToSpawn,
+    /// none of the tile types expected from the thresholds, nor the streets, can hold its content
+    NoSuitableTiles,
+}
+
+/// A spawn stage the generator will run
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PlannedStage {
+    /// the spawnable of the stage
+    pub spawnable: Spawnables,
+    /// the seed the generator is re-seeded with before the stage
+    pub seed: u64,
+    /// the expected number of tiles holding the content, an upper bound for the trees and the fires, see `estimate`
+    pub estimated_count: usize,
+}
+
+/// What the generator will do with the spawn order and the settings, worked out before generating anything, to
+/// catch a misconfiguration, like the trees missing from the order, before a long generation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenerationPlan {
+    /// the spawn stages in the order they run, after removing the repeated spawnables; the skipped ones are listed
+    /// too, they run but place nothing
+    pub stages: Vec<PlannedStage>,
+    /// the spawnables repeated in the spawn order, only their first occurrence runs
+    pub duplicates: Vec<Spawnables>,
+    /// the spawnables which will place no content and why, in the order of their stage, the ones missing from the
+    /// spawn order last
+    pub skipped: Vec<(Spawnables, SkipReason)>,
+}
+
+impl GenerationPlan {
+    /// Returns the spawnables in the order their stages run
+    pub fn effective_order(&self) -> Vec<Spawnables> {
+        self.stages.iter().map(|stage| stage.spawnable).collect()
+    }
+
+    /// Returns why the spawnable will place no content, None if it will place some
+    pub fn skip_reason(&self, spawnable: Spawnables) -> Option<SkipReason> {
+        self.skipped.iter().find(|(other, _)| *other == spawnable).map(|(_, reason)| *reason)
+    }
+}
+
+impl Display for GenerationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, stage) in self.stages.iter().enumerate() {
+            write!(f, "{}. {:?}: ~{} tiles, seed {}", i + 1, stage.spawnable, stage.estimated_count, stage.seed)?;
+            match self.skip_reason(stage.spawnable) {
+                | Some(reason) => writeln!(f, ", skipped: {:?}", reason)?,
+                | None => writeln!(f)?,
+            }
+        }
+        for spawnable in &self.duplicates {
+            writeln!(f, "repeated: {:?}, only the first occurrence runs", spawnable)?;
+        }
+        for (spawnable, reason) in self.skipped.iter().filter(|(_, reason)| *reason == SkipReason::NotInOrder) {
+            writeln!(f, "missing: {:?}, skipped: {:?}", spawnable, reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl WorldGenerator {
+    /// Works out the spawn stages the generator will run with the current settings, without generating anything:
+    /// the spawn order after removing the repeated spawnables, the seed and the expected content of each stage, and
+    /// the spawnables which will place nothing, because they are missing from the order, their settings place
+    /// nothing or no tile can hold their content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::plan::SkipReason;
+    /// use exclusion_zone::generator::{Spawnables, WorldGenerator};
+    ///
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.spawn_order.retain(|spawnable| *spawnable != Spawnables::Tree);
+    /// generator.spawn_order.push(Spawnables::Rock);
+    ///
+    /// let plan = generator.plan();
+    /// assert_eq!(plan.skip_reason(Spawnables::Tree), Some(SkipReason::NotInOrder));
+    /// assert_eq!(plan.duplicates, vec![Spawnables::Rock]);
+    /// println!("{plan}");
+    /// ```
+    pub fn plan(&self) -> GenerationPlan {
+        let area = self.size * self.size;
+        let stage_seeds = self.stage_seeds.clone().unwrap_or_else(|| StageSeeds::from_seed(self.noise_settings.seed));
+        // the streets are built over any tile type, so they are always available
+        let mut tile_types: Vec<TileType> = self.estimate_tiles(area).into_iter().filter(|(_, count)| *count > 0).map(|(tile_type, _)| tile_type).collect();
+        tile_types.push(TileType::Street);
+
+        let mut seen = HashSet::with_capacity(self.spawn_order.len());
+        let mut stages = Vec::with_capacity(self.spawn_order.len());
+        let mut duplicates = Vec::new();
+        let mut skipped = Vec::new();
+        for spawnable in &self.spawn_order {
+            if !seen.insert(*spawnable) {
+                if !duplicates.contains(spawnable) {
+                    duplicates.push(*spawnable);
+                }
+                continue;
+            }
+            let estimated_count = self.estimate_content(*spawnable, area);
+            if estimated_count == 0 {
+                skipped.push((*spawnable, SkipReason::NothingToSpawn));
+            } else if !tile_types.iter().any(|tile_type| tile_type.properties().can_hold(&spawnable.content())) {
+                skipped.push((*spawnable, SkipReason::NoSuitableTiles));
+            }
+            stages.push(PlannedStage {
+                spawnable: *spawnable,
+                seed: stage_seeds.get(*spawnable),
+                estimated_count,
+            });
+        }
+        skipped.extend(STAGES.iter().filter(|spawnable| !seen.contains(*spawnable)).map(|spawnable| (*spawnable, SkipReason::NotInOrder)));
+
+        GenerationPlan {
+            stages,
+            duplicates,
+            skipped,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::generator::{generate_elevation_map, NoiseSettings, Thresholds, WorldGenerator};
+use crate::utils::{find_max_value, find_min_value, into_maybe_par_iter};
+
+/// Generates the elevation map only, at the given resolution.
+///
+/// The noise is sampled over the same normalized area used by the generator, so a preview with a
+/// small `size` shows the same map a full generation would produce, at a lower resolution.
+///
+/// # Arguments
+///
+/// * `settings` - The noise settings to preview.
+/// * `size` - The side of the preview, independent of the size of the world that will be generated.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::preview::preview_elevation;
+/// use exclusion_zone::generator::NoiseSettings;
+///
+/// let elevation = preview_elevation(&NoiseSettings::from_seed(42), 128);
+/// ```
+pub fn preview_elevation(settings: &NoiseSettings, size: usize) -> Vec<Vec<f64>> {
+    generate_elevation_map(settings, size)
+}
+
+/// Generates the terrain only, at the given resolution, without streets, lava and content.
+///
+/// # Arguments
+///
+/// * `settings` - The noise settings to preview.
+/// * `thresholds` - The thresholds used to assign tile types.
+/// * `size` - The side of the preview, independent of the size of the world that will be generated.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::preview::preview_terrain;
+/// use exclusion_zone::generator::{NoiseSettings, Thresholds};
+///
+/// let terrain = preview_terrain(&NoiseSettings::from_seed(42), &Thresholds::default(), 128);
+/// ```
+pub fn preview_terrain(settings: &NoiseSettings, thresholds: &Thresholds, size: usize) -> Vec<Vec<TileType>> {
+    let elevation = preview_elevation(settings, size);
+    let min = find_min_value(&elevation).unwrap_or(f64::MAX);
+    let max = find_max_value(&elevation).unwrap_or(f64::MIN);
+
+    let thresholds = thresholds.resolve(&elevation, min, max);
+    let cuts = thresholds.cuts(min, max);
+    let mut terrain: Vec<Vec<TileType>> = into_maybe_par_iter(&elevation).map(|row| row.iter().map(|&value| cuts.classify(value)).collect()).collect();
+    thresholds.smooth(&mut terrain);
+    thresholds.frame(&mut terrain);
+    terrain
+}
+
+/// Converts a terrain preview in a row-major RGB buffer, one pixel per tile,
+/// ready to be drawn by a GUI without depending on an image crate.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::preview::{preview_terrain, preview_to_rgb};
+/// use exclusion_zone::generator::{NoiseSettings, Thresholds};
+///
+/// let terrain = preview_terrain(&NoiseSettings::from_seed(42), &Thresholds::default(), 128);
+/// let pixels = preview_to_rgb(&terrain);
+/// assert_eq!(pixels.len(), 128 * 128 * 3);
+/// ```
+pub fn preview_to_rgb(terrain: &[Vec<TileType>]) -> Vec<u8> {
+    terrain.iter().flat_map(|row| row.iter().flat_map(tile_type_color)).collect()
+}
+
+/// Generates a world and converts it in a row-major RGBA buffer, one opaque pixel per tile,
+/// tiles holding content are drawn with the color of their content.
+///
+/// This is the minimal entry point for a browser preview, the buffer can be copied as it is in the
+/// `ImageData` of a canvas. It is available with every combination of features, including the `wasm` build.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::preview::generate_to_rgba;
+/// use exclusion_zone::generator::WorldGenerator;
+///
+/// let pixels = generate_to_rgba(&WorldGenerator::default(200));
+/// assert_eq!(pixels.len(), 200 * 200 * 4);
+/// ```
+pub fn generate_to_rgba(settings: &WorldGenerator) -> Vec<u8> {
+    let ((world, ..), _) = settings.clone().gen_with_artifacts();
+
+    world
+        .iter()
+        .flat_map(|row| {
+            row.iter().flat_map(|tile| {
+                let [r, g, b] = tile_color(tile);
+                [r, g, b, 255]
+            })
+        })
+        .collect()
+}
+
+/// The preview color of a tile, the color of its content if any, the color of its tile type otherwise
+#[inline(always)]
+pub(crate) fn tile_color(tile: &Tile) -> [u8; 3] {
+    content_color(&tile.content).unwrap_or_else(|| tile_type_color(&tile.tile_type))
+}
+
+/// Associates each content with its preview color, `None` for no content
+#[inline(always)]
+pub(crate) fn content_color(content: &Content) -> Option<[u8; 3]> {
+    match content {
+        | Content::Rock(_) => Some([50, 50, 50]),
+        | Content::Tree(_) => Some([0, 77, 0]),
+        | Content::Garbage(_) => Some([0, 0, 0]),
+        | Content::Fire => Some([255, 0, 0]),
+        | Content::Coin(_) => Some([243, 199, 13]),
+        | Content::Bin(_) => Some([57, 60, 65]),
+        | Content::Crate(_) => Some([228, 199, 148]),
+        | Content::Bank(_) => Some([227, 224, 205]),
+        | Content::Water(_) => Some([45, 100, 160]),
+        | Content::Market(_) => Some([255, 105, 180]),
+        | Content::Fish(_) => Some([127, 0, 255]),
+        | Content::Building => Some([203, 205, 205]),
+        | Content::Bush(_) => Some([17, 64, 46]),
+        | Content::JollyBlock(_) => Some([79, 120, 143]),
+        | Content::Scarecrow => Some([218, 197, 134]),
+        | Content::None => None,
+    }
+}
+
+/// Associates each tile type with its preview color
+#[inline(always)]
+pub(crate) fn tile_type_color(tile_type: &TileType) -> [u8; 3] {
+    match tile_type {
+        | TileType::DeepWater => [5, 25, 90],
+        | TileType::ShallowWater => [45, 100, 160],
+        | TileType::Sand => [240, 230, 140],
+        | TileType::Grass => [126, 200, 80],
+        | TileType::Street => [90, 90, 90],
+        | TileType::Hill => [146, 104, 41],
+        | TileType::Mountain => [160, 160, 160],
+        | TileType::Snow => [250, 249, 246],
+        | TileType::Lava => [255, 129, 0],
+        | TileType::Wall => [188, 74, 60],
+        | TileType::Teleport(_) => [0, 0, 0],
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::generator::matrix::TileMatrixExt;
+use crate::generator::reachability::neighbours;
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Statistical properties a generated world is expected to have, checked by `assert_world_properties`
+/// in the integration tests of the generator settings and of the robots.
+///
+/// Only the properties added to it are checked, the default instance accepts any world.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::properties::{assert_world_properties, check_world_properties, ExpectedProperties};
+///
+/// let (world, ..) = MiniWorldGenerator::new(50).gen();
+/// let expected = ExpectedProperties::default()
+///     .with_tile_type_share(TileType::Lava, 0.0..=0.1)
+///     .with_content_count(Content::Coin(0), 1..=2500)
+///     .with_min_connected_share(0.0);
+/// assert_world_properties(&world, &expected);
+///
+/// // a world made only of snow
+/// let snow = ExpectedProperties::default().with_tile_type_share(TileType::Snow, 1.0..=1.0);
+/// assert_eq!(check_world_properties(&world, &snow).unwrap_err().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedProperties {
+    /// the range of the share of the tiles, between 0 and 1, of each tile type
+    pub tile_type_shares: Vec<(TileType, RangeInclusive<f64>)>,
+    /// the range of the number of tiles holding each kind of content, the kind is given by the default value of the content
+    pub content_counts: Vec<(Content, RangeInclusive<usize>)>,
+    /// the min share, between 0 and 1, of the walkable tiles in the largest area of walkable tiles sharing a side
+    pub min_connected_share: Option<f64>,
+}
+
+impl ExpectedProperties {
+    /// Expects the share of the tiles of the tile type, between 0 and 1, to be in the range
+    pub fn with_tile_type_share(mut self, tile_type: TileType, share: RangeInclusive<f64>) -> Self {
+        self.tile_type_shares.push((tile_type, share));
+        self
+    }
+
+    /// Expects the number of tiles holding the kind of content to be in the range, whatever the quantity of the content
+    pub fn with_content_count(mut self, content: Content, count: RangeInclusive<usize>) -> Self {
+        self.content_counts.push((content.to_default(), count));
+        self
+    }
+
+    /// Expects at least the given share of the walkable tiles, between 0 and 1, to be connected in a single area,
+    /// so that a robot can walk between them
+    pub fn with_min_connected_share(mut self, share: f64) -> Self {
+        self.min_connected_share = Some(share);
+        self
+    }
+}
+
+/// Checks that the world has the expected properties.
+///
+/// # Errors
+///
+/// Returns a description of each property the world does not have, with the value found.
+pub fn check_world_properties(world: &TileMatrix, expected: &ExpectedProperties) -> Result<(), Vec<String>> {
+    let area = world.iter().map(|row| row.len()).sum::<usize>().max(1) as f64;
+    let mut violations = Vec::new();
+
+    for (tile_type, range) in &expected.tile_type_shares {
+        let share = world.iter().flatten().filter(|tile| tile.tile_type == *tile_type).count() as f64 / area;
+        if !range.contains(&share) {
+            violations.push(format!("{:?} covers {:.4} of the tiles, expected between {} and {}", tile_type, share, range.start(), range.end()));
+        }
+    }
+
+    for (content, range) in &expected.content_counts {
+        let count = world.count_content(content);
+        if !range.contains(&count) {
+            violations.push(format!("{:?} is held by {} tiles, expected between {} and {}", content, count, range.start(), range.end()));
+        }
+    }
+
+    if let Some(min_share) = expected.min_connected_share {
+        let share = connected_share(world);
+        if share < min_share {
+            violations.push(format!("the largest walkable area holds {:.4} of the walkable tiles, expected at least {}", share, min_share));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Asserts that the world has the expected properties, like `check_world_properties`.
+///
+/// # Panics
+///
+/// Panics listing each property the world does not have.
+pub fn assert_world_properties(world: &TileMatrix, expected: &ExpectedProperties) {
+    if let Err(violations) = check_world_properties(world, expected) {
+        panic!("The world does not have the expected properties:\n{}", violations.join("\n"));
+    }
+}
+
+// share of the walkable tiles in the largest area of walkable tiles sharing a side, 1 if no tile is walkable
+#[inline(always)]
+fn connected_share(world: &TileMatrix) -> f64 {
+    let size = world.len();
+    let mut visited = vec![vec![false; size]; size];
+    let (mut walkable, mut largest) = (0, 0);
+
+    for row in 0..size {
+        for col in 0..size {
+            if visited[row][col] || !world[row][col].tile_type.properties().walk() {
+                continue;
+            }
+            // flood fill of the area
+            let mut area = 0;
+            let mut queue = VecDeque::from([Coordinate { row, col }]);
+            visited[row][col] = true;
+            while let Some(current) = queue.pop_front() {
+                area += 1;
+                for next in neighbours(current, size) {
+                    if !visited[next.row][next.col] && world[next.row][next.col].tile_type.properties().walk() {
+                        visited[next.row][next.col] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            walkable += area;
+            largest = largest.max(area);
+        }
+    }
+
+    match walkable {
+        | 0 => 1.0,
+        | _ => largest as f64 / walkable as f64,
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Coordinate;
+
+/// The elevation of a square block of tiles
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq)]
+pub struct PyramidCell {
+    /// the lowest elevation of the block
+    pub min: f32,
+    /// the highest elevation of the block
+    pub max: f32,
+    /// the mean elevation of the block
+    pub mean: f32,
+}
+
+/// A level of the elevation pyramid, the world divided in square blocks of `cell_size` tiles per side
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PyramidLevel {
+    /// the number of tiles per side of each block; the last blocks of each row and column are smaller when the world
+    /// side is not a multiple of it
+    pub cell_size: usize,
+    /// the number of blocks per side
+    pub side: usize,
+    // row-major blocks
+    cells: Vec<PyramidCell>,
+}
+
+impl PyramidLevel {
+    /// Returns the block at the given row and column of blocks, `None` if it is out of the level
+    pub fn cell(&self, row: usize, col: usize) -> Option<PyramidCell> {
+        if row >= self.side || col >= self.side {
+            return None;
+        }
+        Some(self.cells[row * self.side + col])
+    }
+
+    /// Returns the block containing the tile, `None` if the tile is out of the world
+    pub fn cell_at(&self, c: Coordinate) -> Option<PyramidCell> {
+        self.cell(c.row / self.cell_size, c.col / self.cell_size)
+    }
+}
+
+/// Pyramid of the elevation map at decreasing resolutions, to answer queries on regions, like their highest point,
+/// or to plan coarse paths without scanning each tile.
+///
+/// The first level has a block per tile, each next level halves the resolution, merging 2x2 blocks of the previous
+/// level, down to the last level with a single block for the whole world.
+///
+/// # Examples
+///
+/// ```
+/// use exclusion_zone::generator::pyramid::ElevationPyramid;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let elevation_map = vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0], vec![6.0, 7.0, 8.0]];
+/// let pyramid = ElevationPyramid::build(&elevation_map);
+/// assert_eq!(pyramid.levels().len(), 3);
+/// assert_eq!(pyramid.levels()[2].cell(0, 0).unwrap().mean, 4.0);
+/// assert_eq!(pyramid.max_in(Coordinate::new(0, 0), Coordinate::new(1, 1)), Some(4.0));
+/// assert_eq!(pyramid.min_in(Coordinate::new(1, 1), Coordinate::new(5, 5)), Some(4.0));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ElevationPyramid {
+    // the world side in tiles
+    size: usize,
+    levels: Vec<PyramidLevel>,
+}
+
+impl ElevationPyramid {
+    /// Builds the pyramid of the elevation map of a square world, indexed as `[row][col]`, like the one in the
+    /// generation artifacts
+    pub fn build(elevation_map: &[Vec<f32>]) -> Self {
+        let size = elevation_map.len();
+        if size == 0 {
+            return ElevationPyramid::default();
+        }
+
+        let cells = elevation_map
+            .iter()
+            .flatten()
+            .map(|&value| PyramidCell {
+                min: value,
+                max: value,
+                mean: value,
+            })
+            .collect();
+        let mut levels = vec![PyramidLevel {
+            cell_size: 1,
+            side: size,
+            cells,
+        }];
+
+        while let Some(previous) = levels.last().filter(|level| level.side > 1) {
+            let side = previous.side.div_ceil(2);
+            let mut cells = Vec::with_capacity(side * side);
+            for row in 0..side {
+                for col in 0..side {
+                    let mut merged = PyramidCell {
+                        min: f32::MAX,
+                        max: f32::MIN,
+                        mean: 0.0,
+                    };
+                    let mut tiles = 0;
+                    for (r, c) in [(2 * row, 2 * col), (2 * row, 2 * col + 1), (2 * row + 1, 2 * col), (2 * row + 1, 2 * col + 1)] {
+                        let Some(cell) = previous.cell(r, c) else {
+                            continue;
+                        };
+                        // the mean is weighted by the tiles of the block, fewer on the last row and column
+                        let weight = extent(r, previous.cell_size, size) * extent(c, previous.cell_size, size);
+                        merged.min = merged.min.min(cell.min);
+                        merged.max = merged.max.max(cell.max);
+                        merged.mean += cell.mean * weight as f32;
+                        tiles += weight;
+                    }
+                    merged.mean /= tiles as f32;
+                    cells.push(merged);
+                }
+            }
+            let cell_size = previous.cell_size * 2;
+            levels.push(PyramidLevel {
+                cell_size,
+                side,
+                cells,
+            });
+        }
+
+        ElevationPyramid { size, levels }
+    }
+
+    /// Returns the levels, from the full resolution to the single block
+    pub fn levels(&self) -> &[PyramidLevel] {
+        &self.levels
+    }
+
+    /// Returns the highest elevation in the rectangle of tiles between the two corners, both included,
+    /// `None` if the rectangle is out of the world
+    pub fn max_in(&self, top_left: Coordinate, bottom_right: Coordinate) -> Option<f32> {
+        self.fold_in(top_left, bottom_right, &|cell| cell.max, &f32::max)
+    }
+
+    /// Returns the lowest elevation in the rectangle of tiles between the two corners, both included,
+    /// `None` if the rectangle is out of the world
+    pub fn min_in(&self, top_left: Coordinate, bottom_right: Coordinate) -> Option<f32> {
+        self.fold_in(top_left, bottom_right, &|cell| cell.min, &f32::min)
+    }
+
+    // combines the value of the largest blocks covering the rectangle, descending to the smaller levels
+    // only along its border
+    #[inline(always)]
+    fn fold_in(&self, top_left: Coordinate, bottom_right: Coordinate, value: &dyn Fn(&PyramidCell) -> f32, combine: &dyn Fn(f32, f32) -> f32) -> Option<f32> {
+        if self.levels.is_empty() || top_left.row > bottom_right.row || top_left.col > bottom_right.col || top_left.row >= self.size || top_left.col >= self.size {
+            return None;
+        }
+        let rows = top_left.row..=bottom_right.row.min(self.size - 1);
+        let cols = top_left.col..=bottom_right.col.min(self.size - 1);
+
+        let mut result = None;
+        let mut stack = vec![(self.levels.len() - 1, 0, 0)];
+        while let Some((level, row, col)) = stack.pop() {
+            let cell_size = self.levels[level].cell_size;
+            let (first_row, first_col) = (row * cell_size, col * cell_size);
+            let (last_row, last_col) = ((first_row + cell_size).min(self.size) - 1, (first_col + cell_size).min(self.size) - 1);
+            if last_row < *rows.start() || first_row > *rows.end() || last_col < *cols.start() || first_col > *cols.end() {
+                continue;
+            }
+
+            let inside = rows.contains(&first_row) && rows.contains(&last_row) && cols.contains(&first_col) && cols.contains(&last_col);
+            match self.levels[level].cell(row, col) {
+                | Some(cell) if inside => result = Some(result.map_or(value(&cell), |result| combine(result, value(&cell)))),
+                | Some(_) => stack.extend([
+                    (level - 1, 2 * row, 2 * col),
+                    (level - 1, 2 * row, 2 * col + 1),
+                    (level - 1, 2 * row + 1, 2 * col),
+                    (level - 1, 2 * row + 1, 2 * col + 1),
+                ]),
+                | None => {}
+            }
+        }
+        result
+    }
+}
+
+// the number of tiles along a side of the block at the given index, the last one is cut by the border of the world
+#[inline(always)]
+fn extent(index: usize, cell_size: usize, size: usize) -> usize {
+    cell_size.min(size.saturating_sub(index * cell_size))
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use noise::{NoiseFn, Perlin};
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{Spawnables, TileMatrix};
+
+/// The spatial pattern followed by the quantity of a content, as a value between 0, the poorest tiles, and 1, the richest ones
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum QuantityField {
+    /// low-frequency Perlin noise, with the given number of cycles per side of the world, patches of rich and poor tiles
+    Noise { frequency: f64 },
+    /// a linear gradient growing toward the given direction, in degrees counterclockwise from the east: 0 is richer in the east,
+    /// 90 in the north
+    Gradient { direction: f64 },
+    /// the elevation of the tile, richer on the high ground, or in the valleys if inverted
+    Elevation { inverted: bool },
+}
+
+/// Settings of the fields modulating the quantity of the content across the world, so that the quantities are not
+/// independent random draws but form gradients the robots can learn and exploit, like coins richer in the east
+/// or bigger trees in the valleys.
+///
+/// Once the content is spawned, the quantity drawn by the spawner for each tile is multiplied by a factor growing with the
+/// field, from `1 - strength` on the poorest tiles to `1 + strength` on the richest ones, within the limits of the content.
+/// The capacity of banks, bins and crates is modulated in the same way; fires have no quantity and are never modulated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QuantityFieldSettings {
+    /// the field followed by each spawnable, the spawnables without a field keep their random quantities
+    pub fields: HashMap<Spawnables, QuantityField>,
+    /// how much the fields modulate the quantities, between 0 and 1, 0.75 by default
+    pub strength: f64,
+}
+
+impl Default for QuantityFieldSettings {
+    fn default() -> Self {
+        QuantityFieldSettings {
+            fields: HashMap::new(),
+            strength: 0.75,
+        }
+    }
+}
+
+impl QuantityFieldSettings {
+    /// Creates a new instance of `QuantityFieldSettings` with the given strength and no field.
+    ///
+    /// # Arguments
+    ///
+    /// * `strength` - How much the fields modulate the quantities, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::quantity_field::{QuantityField, QuantityFieldSettings};
+    /// use exclusion_zone::generator::Spawnables;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // coins richer in the east and bigger trees in the valleys
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.quantity_field_settings = Some(
+    ///     QuantityFieldSettings::new(0.8)
+    ///         .with_field(Spawnables::Coin, QuantityField::Gradient { direction: 0.0 })
+    ///         .with_field(Spawnables::Tree, QuantityField::Elevation { inverted: true }),
+    /// );
+    /// ```
+    pub fn new(strength: f64) -> Self {
+        QuantityFieldSettings {
+            fields: HashMap::new(),
+            strength,
+        }
+    }
+
+    /// Sets the field followed by the quantity of the given spawnable
+    pub fn with_field(mut self, spawnable: Spawnables, field: QuantityField) -> Self {
+        self.fields.insert(spawnable, field);
+        self
+    }
+
+    /// Checks the settings, returning the issues found.
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.probability("QuantityFieldSettings::strength", self.strength);
+        for (spawnable, field) in &self.fields {
+            match field {
+                | QuantityField::Noise { frequency } => issues.check(frequency.is_finite() && *frequency > 0.0, "QuantityFieldSettings::fields", || {
+                    format!("the frequency {frequency} of the field of {spawnable:?} gives a flat field, use a value greater than 0")
+                }),
+                | QuantityField::Gradient { direction } => issues.check(direction.is_finite(), "QuantityFieldSettings::fields", || {
+                    format!("the direction {direction} of the field of {spawnable:?} is not a finite angle")
+                }),
+                | QuantityField::Elevation { .. } => {}
+            }
+        }
+        issues.0
+    }
+}
+
+/// Multiplies the quantity of the content of each tile by the factor given by the field of its spawnable
+pub(crate) fn apply_quantity_fields(world: &mut TileMatrix, elevation_map: &[Vec<f64>], seed: u32, settings: &QuantityFieldSettings) {
+    if settings.fields.is_empty() {
+        return;
+    }
+
+    let size = world.len();
+    let (min, max) = elevation_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), &v| (min.min(v), max.max(v)));
+    // each spawnable gets its own noise, so that two noise fields do not overlap
+    let noises: HashMap<Spawnables, Perlin> = settings.fields.keys().map(|spawnable| (*spawnable, Perlin::new(seed.wrapping_add(*spawnable as u32 + 1)))).collect();
+    let strength = settings.strength.clamp(0.0, 1.0);
+
+    for (row, tiles) in world.iter_mut().enumerate() {
+        for (col, tile) in tiles.iter_mut().enumerate() {
+            let Some(spawnable) = spawnable_of(&tile.content) else {
+                continue;
+            };
+            let Some(field) = settings.fields.get(&spawnable) else {
+                continue;
+            };
+
+            let (x, y) = (col as f64 / size as f64, row as f64 / size as f64);
+            let value = match field {
+                | QuantityField::Noise { frequency } => (noises[&spawnable].get([x * frequency, y * frequency]) + 1.0) / 2.0,
+                | QuantityField::Gradient { direction } => {
+                    let (sin, cos) = direction.to_radians().sin_cos();
+                    // the rows grow toward the south, the projection is rescaled so that the corners are 0 and 1
+                    0.5 + ((x - 0.5) * cos - (y - 0.5) * sin) / (cos.abs() + sin.abs())
+                }
+                | QuantityField::Elevation { inverted } => {
+                    let elevation = if max > min { (elevation_map[row][col] - min) / (max - min) } else { 0.5 };
+                    if *inverted {
+                        1.0 - elevation
+                    } else {
+                        elevation
+                    }
+                }
+            };
+            tile.content = scaled(&tile.content, 1.0 - strength + 2.0 * strength * value.clamp(0.0, 1.0));
+        }
+    }
+}
+
+#[inline(always)]
+fn spawnable_of(content: &Content) -> Option<Spawnables> {
+    match content {
+        | Content::Rock(_) => Some(Spawnables::Rock),
+        | Content::Tree(_) => Some(Spawnables::Tree),
+        | Content::Garbage(_) => Some(Spawnables::Garbage),
+        | Content::Coin(_) => Some(Spawnables::Coin),
+        | Content::Bin(_) => Some(Spawnables::Bin),
+        | Content::Crate(_) => Some(Spawnables::Crate),
+        | Content::Bank(_) => Some(Spawnables::Bank),
+        | Content::Market(_) => Some(Spawnables::Market),
+        | Content::Fish(_) => Some(Spawnables::Fish),
+        | _ => None,
+    }
+}
+
+// the content with its quantity, or the end of its capacity, multiplied by the factor, kept between 1 and the max of the content
+#[inline(always)]
+fn scaled(content: &Content, factor: f64) -> Content {
+    let max = content.properties().max().max(1);
+    let scale = |quantity: usize| ((quantity as f64 * factor).round() as usize).clamp(1, max);
+    match content {
+        | Content::Rock(q) => Content::Rock(scale(*q)),
+        | Content::Tree(q) => Content::Tree(scale(*q)),
+        | Content::Garbage(q) => Content::Garbage(scale(*q)),
+        | Content::Coin(q) => Content::Coin(scale(*q)),
+        | Content::Market(q) => Content::Market(scale(*q)),
+        | Content::Fish(q) => Content::Fish(scale(*q)),
+        | Content::Bin(range) => Content::Bin(range.start..scale(range.end).max(range.start + 1)),
+        | Content::Crate(range) => Content::Crate(range.start..scale(range.end).max(range.start + 1)),
+        | Content::Bank(range) => Content::Bank(range.start..scale(range.end).max(range.start + 1)),
+        | _ => content.clone(),
+    }
+}
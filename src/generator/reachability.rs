@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::TileMatrix;
+use crate::tile_type::street::StreetSettings;
+use crate::utils::Coordinate;
+
+/// Defines what the generator does when the robot cannot reach a Bank, a Market or a Bin from its spawn point
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum ReachabilityCheck {
+    /// the check is skipped
+    #[default]
+    Disabled,
+    /// unreachable content is only reported
+    Warn,
+    /// a street is carved from the reachable area to each unreachable content, making the fewest tiles walkable; only
+    /// the tiles the street settings can pave are carved, so the content beyond the DeepWater stays unreachable
+    /// without bridges
+    Repair,
+}
+
+/// Result of the reachability check
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ReachabilityReport {
+    /// the content the robot cannot reach from its spawn point, after the repair if enabled
+    pub unreachable: Vec<Coordinate>,
+    /// the tiles converted to Street to make the content reachable
+    pub carved: Vec<Coordinate>,
+}
+
+// the content the robot must be able to interact with
+#[inline(always)]
+fn is_key_content(content: &Content) -> bool {
+    matches!(content, Content::Bank(_) | Content::Market(_) | Content::Bin(_))
+}
+
+#[inline(always)]
+pub(crate) fn neighbours(c: Coordinate, size: usize) -> impl Iterator<Item = Coordinate> {
+    let up = (c.row > 0).then(|| Coordinate {
+        row: c.row - 1,
+        col: c.col,
+    });
+    let down = (c.row + 1 < size).then(|| Coordinate {
+        row: c.row + 1,
+        col: c.col,
+    });
+    let left = (c.col > 0).then(|| Coordinate {
+        row: c.row,
+        col: c.col - 1,
+    });
+    let right = (c.col + 1 < size).then(|| Coordinate {
+        row: c.row,
+        col: c.col + 1,
+    });
+    [up, down, left, right].into_iter().flatten()
+}
+
+// flood fill of the walkable tiles reachable from the start
+#[inline(always)]
+fn reachable_from(world: &TileMatrix, start: Coordinate) -> Vec<Vec<bool>> {
+    let size = world.len();
+    let mut reached = vec![vec![false; size]; size];
+    if !world[start.row][start.col].tile_type.properties().walk() {
+        return reached;
+    }
+
+    let mut queue = VecDeque::from([start]);
+    reached[start.row][start.col] = true;
+    while let Some(current) = queue.pop_front() {
+        for next in neighbours(current, size) {
+            if !reached[next.row][next.col] && world[next.row][next.col].tile_type.properties().walk() {
+                reached[next.row][next.col] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+// content can be interacted with from its own tile or from a neighbour
+#[inline(always)]
+fn is_reached(reached: &[Vec<bool>], c: Coordinate) -> bool {
+    reached[c.row][c.col] || neighbours(c, reached.len()).any(|n| reached[n.row][n.col])
+}
+
+/// Checks that a walkable path exists between the robot spawn and each Bank, Market and Bin.
+///
+/// In `Repair` mode a 0-1 breadth first search from the reachable area finds, for each unreachable content,
+/// the path crossing the fewest non-walkable tiles, then those tiles are converted to Street; the content they cannot
+/// hold anymore is left to `reconcile_content`. The path only crosses the tiles the street settings can pave, so no
+/// bridge is built when they are disabled, and the content no such path leads to stays unreachable.
+pub(crate) fn check_reachability(world: &mut TileMatrix, spawn: Coordinate, mode: ReachabilityCheck, street_settings: &StreetSettings) -> ReachabilityReport {
+    let mut report = ReachabilityReport::default();
+    if mode == ReachabilityCheck::Disabled {
+        return report;
+    }
+
+    let reached = reachable_from(world, spawn);
+    let mut unreachable = Vec::new();
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            let c = Coordinate { row, col };
+            if is_key_content(&tile.content) && !is_reached(&reached, c) {
+                unreachable.push(c);
+            }
+        }
+    }
+
+    if mode == ReachabilityCheck::Warn || unreachable.is_empty() {
+        for c in unreachable.iter() {
+            warn_unreachable(world, *c);
+        }
+        report.unreachable = unreachable;
+        return report;
+    }
+
+    // 0-1 bfs: walking on a walkable tile is free, crossing a non-walkable one costs a carved tile, the tiles which
+    // cannot be paved are not crossed
+    let size = world.len();
+    let mut cost = vec![vec![usize::MAX; size]; size];
+    let mut parent: Vec<Vec<Option<Coordinate>>> = vec![vec![None; size]; size];
+    let mut deque = VecDeque::new();
+    for (row, tiles) in reached.iter().enumerate() {
+        for (col, &r) in tiles.iter().enumerate() {
+            if r {
+                cost[row][col] = 0;
+                deque.push_back(Coordinate { row, col });
+            }
+        }
+    }
+    // without a walkable spawn the search starts from the spawn itself
+    if deque.is_empty() {
+        cost[spawn.row][spawn.col] = 0;
+        deque.push_back(spawn);
+    }
+
+    while let Some(current) = deque.pop_front() {
+        for next in neighbours(current, size) {
+            let tile_type = world[next.row][next.col].tile_type;
+            let step = if tile_type.properties().walk() {
+                0
+            } else if street_settings.can_pave(tile_type) {
+                1
+            } else {
+                continue;
+            };
+            if cost[current.row][current.col] + step < cost[next.row][next.col] {
+                cost[next.row][next.col] = cost[current.row][current.col] + step;
+                parent[next.row][next.col] = Some(current);
+                if step == 0 {
+                    deque.push_front(next);
+                } else {
+                    deque.push_back(next);
+                }
+            }
+        }
+    }
+
+    for target in unreachable {
+        // the content tile itself is never carved, reaching its cheapest neighbour is enough
+        let Some(nearest) = neighbours(target, size).filter(|n| cost[n.row][n.col] != usize::MAX).min_by_key(|n| cost[n.row][n.col]) else {
+            warn_unreachable(world, target);
+            report.unreachable.push(target);
+            continue;
+        };
+        let mut step = Some(nearest);
+        while let Some(c) = step {
+            if !world[c.row][c.col].tile_type.properties().walk() {
+                world[c.row][c.col].tile_type = TileType::Street;
+                report.carved.push(c);
+            }
+            step = parent[c.row][c.col];
+        }
+    }
+
+    report
+}
+
+#[inline(always)]
+fn warn_unreachable(world: &TileMatrix, c: Coordinate) {
+    warn!(
+        content = ?world[c.row][c.col].content,
+        row = c.row,
+        col = c.col,
+        "ContentNotReachable: no walkable path leads from the spawn to the content"
+    );
+}
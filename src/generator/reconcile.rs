@@ -0,0 +1,70 @@
+use std::collections::{HashSet, VecDeque};
+
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::reachability::neighbours;
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Max number of steps, between tiles sharing a side, from the original tile to the new tile of a relocated content
+pub const RELOCATION_RADIUS: usize = 6;
+
+/// Content that its tile could not hold anymore after the tile type changed, like a Tree under a new Street
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContentRelocation {
+    /// the content, with its quantity
+    pub content: Content,
+    /// the tile which held the content before its tile type changed
+    pub from: Coordinate,
+    /// the nearest free tile which can hold the content, `None` if the content has been removed
+    pub to: Option<Coordinate>,
+}
+
+/// Moves the content the changed tiles cannot hold anymore to the nearest free tile which can hold it,
+/// within `RELOCATION_RADIUS` steps, removing it if there is none; returns what was moved or removed.
+///
+/// The content is never moved to one of the changed tiles, so that a carved path stays clear.
+pub(crate) fn reconcile_content(world: &mut TileMatrix, changed: &[Coordinate]) -> Vec<ContentRelocation> {
+    let excluded: HashSet<Coordinate> = changed.iter().copied().collect();
+    let mut relocations = Vec::new();
+
+    for &from in changed {
+        let tile = &world[from.row][from.col];
+        if tile.content == Content::None || tile.tile_type.properties().can_hold(&tile.content.to_default()) {
+            continue;
+        }
+
+        let content = std::mem::replace(&mut world[from.row][from.col].content, Content::None);
+        let to = nearest_free_tile(world, from, &content, &excluded);
+        if let Some(to) = to {
+            world[to.row][to.col].content = content.clone();
+        }
+        relocations.push(ContentRelocation { content, from, to });
+    }
+    relocations
+}
+
+// breadth first search of the nearest empty tile whose tile type can hold the content
+#[inline(always)]
+fn nearest_free_tile(world: &TileMatrix, start: Coordinate, content: &Content, excluded: &HashSet<Coordinate>) -> Option<Coordinate> {
+    let size = world.len();
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((current, distance)) = queue.pop_front() {
+        let tile = &world[current.row][current.col];
+        if current != start && !excluded.contains(&current) && tile.content == Content::None && tile.tile_type.properties().can_hold(&content.to_default()) {
+            return Some(current);
+        }
+        if distance == RELOCATION_RADIUS {
+            continue;
+        }
+        for next in neighbours(current, size) {
+            if visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    None
+}
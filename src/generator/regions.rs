@@ -0,0 +1,357 @@
+use std::collections::{HashMap, VecDeque};
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::landmarks::{Landmark, LandmarkKind};
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Id of the tiles not assigned to any region yet
+const UNASSIGNED: u32 = u32::MAX;
+/// Fraction of the world side, around its center, considered central when naming the regions
+const CENTRAL_BAND: f64 = 1.0 / 6.0;
+/// Fraction of the tiles of a grassland region holding a tree above which it is named a forest
+const FOREST_DENSITY: f64 = 0.2;
+
+/// The group of tile types a region is made of
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Biome {
+    /// DeepWater and ShallowWater
+    Water,
+    /// Sand
+    Beach,
+    /// Grass
+    Grassland,
+    /// Hill
+    Hills,
+    /// Mountain and Snow
+    Mountains,
+    /// Lava
+    Lava,
+    /// Street, Wall and Teleport
+    Urban,
+}
+
+impl Biome {
+    /// Returns the biome of the given tile type
+    pub fn of(tile_type: &TileType) -> Self {
+        match tile_type {
+            | TileType::DeepWater | TileType::ShallowWater => Biome::Water,
+            | TileType::Sand => Biome::Beach,
+            | TileType::Grass => Biome::Grassland,
+            | TileType::Hill => Biome::Hills,
+            | TileType::Mountain | TileType::Snow => Biome::Mountains,
+            | TileType::Lava => Biome::Lava,
+            | TileType::Street | TileType::Wall | TileType::Teleport(_) => Biome::Urban,
+        }
+    }
+}
+
+/// Settings of the segmentation of the world in named regions
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct RegionSettings {
+    /// the min number of tiles of a region, smaller areas are merged in the neighbouring region they touch the most
+    pub min_region_size: usize,
+}
+
+impl RegionSettings {
+    /// Custom version of default that provides an instance of `RegionSettings` with
+    /// reasonable parameters for the given world size
+    pub fn default(size: usize) -> Self {
+        RegionSettings {
+            min_region_size: (size * size / 2000).max(1),
+        }
+    }
+
+    /// Creates a new instance of `RegionSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_region_size` - The min number of tiles of a region, smaller areas are merged in a neighbouring region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::regions::RegionSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.region_settings = Some(RegionSettings::new(5000));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// for region in artifacts.regions.unwrap().regions {
+    ///     println!("{}: {} tiles", region.name, region.tiles);
+    /// }
+    /// ```
+    pub fn new(min_region_size: usize) -> Self {
+        RegionSettings { min_region_size }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("RegionSettings::min_region_size", self.min_region_size, size);
+        issues.0
+    }
+}
+
+/// A connected area of the world made of tiles of the same biome
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Region {
+    /// the id of the region, its index in `RegionMap::regions`
+    pub id: u32,
+    /// the name of the region, like "Northern Lake" or "Reactor Forest", unique in the world
+    pub name: String,
+    /// the biome of the region
+    pub biome: Biome,
+    /// the top left and bottom right corners of the smallest rectangle containing the region
+    pub bounding_box: (Coordinate, Coordinate),
+    /// the most common tile type of the region
+    pub dominant_tile_type: TileType,
+    /// the number of tiles of the region
+    pub tiles: usize,
+    /// how many tiles of the region hold each kind of content, quantities are zeroed
+    pub content_counts: Vec<(Content, usize)>,
+}
+
+/// The world segmented in named regions
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct RegionMap {
+    /// the id of the region of each tile, indexed as `ids[row][col]`
+    pub ids: Vec<Vec<u32>>,
+    /// the regions, sorted by id
+    pub regions: Vec<Region>,
+}
+
+impl RegionMap {
+    /// Returns the region containing the given tile, `None` if the tile is outside the world
+    pub fn region_at(&self, c: Coordinate) -> Option<&Region> {
+        let id = *self.ids.get(c.row)?.get(c.col)?;
+        self.regions.get(id as usize)
+    }
+
+    /// Returns the region with the given name
+    pub fn find(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+}
+
+/// Flood-fills the world by biome and names the resulting regions after their position, their biome and the landmarks they contain
+pub(crate) fn find_regions(world: &TileMatrix, landmarks: &[Landmark], settings: &RegionSettings) -> RegionMap {
+    let size = world.len();
+    let mut ids = vec![vec![UNASSIGNED; size]; size];
+
+    // connected areas of the same biome
+    let mut areas: Vec<Vec<Coordinate>> = Vec::new();
+    for row in 0..size {
+        for col in 0..size {
+            if ids[row][col] == UNASSIGNED {
+                let area = flood_fill(world, &mut ids, Coordinate { row, col }, areas.len() as u32);
+                areas.push(area);
+            }
+        }
+    }
+
+    // the small areas join the big neighbour they share the longest border with
+    let big: Vec<bool> = areas.iter().map(|area| area.len() >= settings.min_region_size).collect();
+    let mut owner: Vec<u32> = (0..areas.len() as u32).collect();
+    for (id, area) in areas.iter().enumerate() {
+        if big[id] {
+            continue;
+        }
+        let mut borders: HashMap<u32, usize> = HashMap::new();
+        for c in area {
+            for n in neighbours(*c, size) {
+                let other = ids[n.row][n.col];
+                if big[other as usize] {
+                    *borders.entry(other).or_default() += 1;
+                }
+            }
+        }
+        if let Some((other, _)) = borders.into_iter().max_by_key(|(other, length)| (*length, u32::MAX - other)) {
+            owner[id] = other;
+        }
+    }
+
+    // compact ids, in order of first appearance
+    let mut compact: Vec<u32> = vec![UNASSIGNED; areas.len()];
+    let mut next = 0;
+    for id in 0..areas.len() {
+        let owner = owner[id] as usize;
+        if compact[owner] == UNASSIGNED {
+            compact[owner] = next;
+            next += 1;
+        }
+        compact[id] = compact[owner];
+    }
+    for row in ids.iter_mut() {
+        for id in row.iter_mut() {
+            *id = compact[*id as usize];
+        }
+    }
+
+    let regions = describe_regions(world, &ids, next as usize, landmarks);
+    RegionMap { ids, regions }
+}
+
+// breadth first search of the tiles of the same biome connected to the start
+#[inline(always)]
+fn flood_fill(world: &TileMatrix, ids: &mut [Vec<u32>], start: Coordinate, id: u32) -> Vec<Coordinate> {
+    let biome = Biome::of(&world[start.row][start.col].tile_type);
+    let mut area = vec![start];
+    let mut queue = VecDeque::from([start]);
+    ids[start.row][start.col] = id;
+    while let Some(current) = queue.pop_front() {
+        for next in neighbours(current, world.len()) {
+            if ids[next.row][next.col] == UNASSIGNED && Biome::of(&world[next.row][next.col].tile_type) == biome {
+                ids[next.row][next.col] = id;
+                area.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+    area
+}
+
+#[inline(always)]
+fn neighbours(c: Coordinate, size: usize) -> impl Iterator<Item = Coordinate> {
+    [(c.row.wrapping_sub(1), c.col), (c.row + 1, c.col), (c.row, c.col.wrapping_sub(1)), (c.row, c.col + 1)]
+        .into_iter()
+        .filter(move |(row, col)| *row < size && *col < size)
+        .map(|(row, col)| Coordinate { row, col })
+}
+
+// statistics of a region while scanning the world
+struct RegionStats {
+    min: Coordinate,
+    max: Coordinate,
+    row_sum: usize,
+    col_sum: usize,
+    tiles: usize,
+    tile_types: Vec<(TileType, usize)>,
+    content_counts: Vec<(Content, usize)>,
+}
+
+#[inline(always)]
+fn describe_regions(world: &TileMatrix, ids: &[Vec<u32>], number_of_regions: usize, landmarks: &[Landmark]) -> Vec<Region> {
+    let size = world.len();
+    let mut stats: Vec<RegionStats> = (0..number_of_regions)
+        .map(|_| RegionStats {
+            min: Coordinate { row: size, col: size },
+            max: Coordinate { row: 0, col: 0 },
+            row_sum: 0,
+            col_sum: 0,
+            tiles: 0,
+            tile_types: Vec::new(),
+            content_counts: Vec::new(),
+        })
+        .collect();
+
+    for (row, tiles) in world.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            let s = &mut stats[ids[row][col] as usize];
+            s.min = Coordinate {
+                row: s.min.row.min(row),
+                col: s.min.col.min(col),
+            };
+            s.max = Coordinate {
+                row: s.max.row.max(row),
+                col: s.max.col.max(col),
+            };
+            s.row_sum += row;
+            s.col_sum += col;
+            s.tiles += 1;
+            count(&mut s.tile_types, tile.tile_type);
+            if tile.content != Content::None {
+                count(&mut s.content_counts, tile.content.to_default());
+            }
+        }
+    }
+
+    let mut used_names: HashMap<String, usize> = HashMap::new();
+    stats
+        .into_iter()
+        .enumerate()
+        .map(|(id, s)| {
+            let dominant_tile_type = s.tile_types.iter().max_by_key(|(_, n)| *n).map_or(TileType::Grass, |(tile_type, _)| *tile_type);
+            let biome = Biome::of(&dominant_tile_type);
+            let centroid = (s.row_sum as f64 / s.tiles as f64, s.col_sum as f64 / s.tiles as f64);
+            let has_reactor = landmarks
+                .iter()
+                .any(|landmark| landmark.kind == LandmarkKind::Reactor && ids[landmark.position.row][landmark.position.col] == id as u32);
+
+            let prefix = if has_reactor { "Reactor".to_string() } else { direction(centroid, size) };
+            let base = format!("{} {}", prefix, noun(biome, dominant_tile_type, &s));
+            let occurrences = used_names.entry(base.clone()).or_default();
+            *occurrences += 1;
+            let name = if *occurrences == 1 { base } else { format!("{} {}", base, occurrences) };
+
+            Region {
+                id: id as u32,
+                name,
+                biome,
+                bounding_box: (s.min, s.max),
+                dominant_tile_type,
+                tiles: s.tiles,
+                content_counts: s.content_counts,
+            }
+        })
+        .collect()
+}
+
+#[inline(always)]
+fn count<T: PartialEq>(counts: &mut Vec<(T, usize)>, value: T) {
+    match counts.iter_mut().find(|(v, _)| *v == value) {
+        | Some((_, n)) => *n += 1,
+        | None => counts.push((value, 1)),
+    }
+}
+
+// the cardinal position of the centroid of a region with respect to the center of the world
+#[inline(always)]
+fn direction(centroid: (f64, f64), size: usize) -> String {
+    let dy = centroid.0 / size as f64 - 0.5;
+    let dx = centroid.1 / size as f64 - 0.5;
+    let vertical = if dy < -CENTRAL_BAND {
+        Some("North")
+    } else if dy > CENTRAL_BAND {
+        Some("South")
+    } else {
+        None
+    };
+    let horizontal = if dx < -CENTRAL_BAND {
+        Some("West")
+    } else if dx > CENTRAL_BAND {
+        Some("East")
+    } else {
+        None
+    };
+
+    match (vertical, horizontal) {
+        | (Some(v), Some(h)) => format!("{}-{}ern", v, h),
+        | (Some(d), None) | (None, Some(d)) => format!("{}ern", d),
+        | (None, None) => "Central".to_string(),
+    }
+}
+
+#[inline(always)]
+fn noun(biome: Biome, dominant_tile_type: TileType, stats: &RegionStats) -> &'static str {
+    match biome {
+        | Biome::Water => "Lake",
+        | Biome::Beach => "Shore",
+        | Biome::Grassland => {
+            let trees = stats.content_counts.iter().find(|(content, _)| matches!(content, Content::Tree(_))).map_or(0, |(_, n)| *n);
+            if trees as f64 > stats.tiles as f64 * FOREST_DENSITY {
+                "Forest"
+            } else {
+                "Plains"
+            }
+        }
+        | Biome::Hills => "Hills",
+        | Biome::Mountains if dominant_tile_type == TileType::Snow => "Peaks",
+        | Biome::Mountains => "Mountains",
+        | Biome::Lava => "Lava Field",
+        | Biome::Urban => "District",
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::thread::available_parallelism;
+
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::WorldGenerator;
+use crate::utils::SerializedWorld;
+
+/// Prefix of the columnar saves, never the start of a bincode save, which starts with the number of rows of the world
+pub(crate) const COLUMNAR_MAGIC: [u8; 8] = *b"EZCOLUMN";
+
+/// The encoding of the world in the saved files, the binary ones are then compressed with zstd
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SaveFormat {
+    /// the tile matrix as nested `Tile` structs serialized with bincode
+    #[default]
+    Bincode,
+    /// the tile matrix as separate run-length encoded columns of tile types, contents, quantities and elevations,
+    /// much smaller for mostly uniform worlds, like seas and plains without content
+    Columnar,
+    /// uncompressed and readable, to inspect and diff what was generated: the settings and everything but the tiles as
+    /// indented JSON in `<file>.json`, and the tiles in `<file>.tiles.txt`, a row per line as runs of equal tiles like
+    /// `12 Grass, 1 Grass+Tree(3)`; the elevation is left out. These saves are for debugging and cannot be loaded
+    JsonPretty,
+}
+
+/// Options of the world saves, trading the size of the file for the time spent compressing it
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// the zstd compression level, from 1, the fastest, to 22, the smallest, 11 by default
+    pub compression_level: i32,
+    /// the number of threads compressing the world, 1 compresses it on the calling thread,
+    /// the available parallelism by default
+    pub threads: usize,
+    /// the encoding of the world, `Bincode` by default; the format of the binary saves is detected when loading
+    pub format: SaveFormat,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        SaveOptions {
+            compression_level: 11,
+            threads: available_parallelism().map_or(1, |n| n.get()),
+            format: SaveFormat::default(),
+        }
+    }
+}
+
+impl SaveOptions {
+    /// Creates a new instance of `SaveOptions` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression_level` - The zstd compression level, from 1 to 22.
+    /// * `threads` - The number of threads compressing the world, 1 compresses it on the calling thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::world_generator::Generator;
+    /// use exclusion_zone::generator::save::SaveOptions;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// let world = generator.gen();
+    /// let file_path = std::env::temp_dir().join("world_options.zst");
+    /// let report = generator.save_with_options(file_path.to_str().unwrap(), world, &SaveOptions::new(11, 8)).expect("unable to save the world");
+    /// println!("{:.1} MB/s", report.throughput);
+    /// ```
+    pub fn new(compression_level: i32, threads: usize) -> Self {
+        SaveOptions {
+            compression_level,
+            threads,
+            format: SaveFormat::default(),
+        }
+    }
+
+    /// Sets the encoding of the world.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::save::{SaveFormat, SaveOptions};
+    ///
+    /// let options = SaveOptions::default().with_format(SaveFormat::Columnar);
+    ///
+    /// // world.json and world.tiles.txt, to see what changed between two versions of the settings
+    /// let debug = SaveOptions::default().with_format(SaveFormat::JsonPretty);
+    /// ```
+    pub fn with_format(mut self, format: SaveFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// Report of a world save
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct SaveReport {
+    /// the size of the serialized world before the compression, in bytes
+    pub uncompressed_bytes: u64,
+    /// the size of the saved file, summary included, in bytes; of both files for the JSON saves, which are uncompressed
+    pub compressed_bytes: u64,
+    /// the number of threads which compressed the world, 1 for the JSON saves
+    pub threads: usize,
+    /// the time spent serializing, compressing and writing the world, in milliseconds
+    pub elapsed_ms: i64,
+    /// the uncompressed megabytes saved per second
+    pub throughput: f64,
+}
+
+/// The world of a JSON save, everything but the tiles, which are dumped in a separate text file
+#[derive(Serialize)]
+pub(crate) struct JsonWorld<'a> {
+    size: usize,
+    // the name of the file with the tiles, next to the JSON file
+    tiles_file: String,
+    robot_position: (usize, usize),
+    environmental_conditions: &'a EnvironmentalConditions,
+    max_score: f32,
+    // a list rather than a map, the keys of JSON maps are strings
+    score_table: Option<Vec<(&'a Content, &'a f32)>>,
+    settings: &'a WorldGenerator,
+}
+
+impl<'a> JsonWorld<'a> {
+    pub(crate) fn new(world: &'a SerializedWorld, tiles_file: String) -> Self {
+        let (tiles, robot_position, environmental_conditions, max_score, score_table) = &world.world;
+        JsonWorld {
+            size: tiles.len(),
+            tiles_file,
+            robot_position: *robot_position,
+            environmental_conditions,
+            max_score: *max_score,
+            score_table: score_table.as_ref().map(|table| table.iter().collect()),
+            settings: &world.settings,
+        }
+    }
+}
+
+/// Dumps the tiles as text, a row per line, each row as the runs of equal tiles, like `12 Grass, 1 Grass+Tree(3)`
+pub(crate) fn tile_dump(tiles: &[Vec<Tile>]) -> String {
+    let mut dump = String::new();
+    for row in tiles {
+        let runs = runs(row.iter().map(|tile| (&tile.tile_type, &tile.content)));
+        let line: Vec<String> = runs
+            .into_iter()
+            .map(|((tile_type, content), count)| match content {
+                | Content::None => format!("{count} {tile_type:?}"),
+                | content => format!("{count} {tile_type:?}+{content:?}"),
+            })
+            .collect();
+        dump.push_str(&line.join(", "));
+        dump.push('\n');
+    }
+    dump
+}
+
+/// The world of a columnar save: each property of the tiles is a separate column, in row-major order,
+/// stored as runs of equal values
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ColumnarWorld {
+    size: usize,
+    tile_types: Vec<(u8, u32)>,
+    contents: Vec<(u8, u32)>,
+    // the quantity of the content, the end of the range for Bank, Bin and Crate
+    quantities: Vec<(u32, u32)>,
+    // the start of the range for Bank, Bin and Crate, 0 for the other contents
+    range_starts: Vec<(u32, u32)>,
+    elevations: Vec<(u32, u32)>,
+    robot_position: (usize, usize),
+    environmental_conditions: EnvironmentalConditions,
+    max_score: f32,
+    score_table: Option<HashMap<Content, f32>>,
+    settings: WorldGenerator,
+}
+
+impl ColumnarWorld {
+    pub(crate) fn encode(world: &SerializedWorld) -> Self {
+        let (tiles, robot_position, environmental_conditions, max_score, score_table) = &world.world;
+        let flat = || tiles.iter().flatten();
+        ColumnarWorld {
+            size: tiles.len(),
+            tile_types: runs(flat().map(|tile| tile_type_code(&tile.tile_type))),
+            contents: runs(flat().map(|tile| content_code(&tile.content))),
+            quantities: runs(flat().map(|tile| content_quantities(&tile.content).1)),
+            range_starts: runs(flat().map(|tile| content_quantities(&tile.content).0)),
+            elevations: runs(flat().map(|tile| tile.elevation as u32)),
+            robot_position: *robot_position,
+            environmental_conditions: environmental_conditions.clone(),
+            max_score: *max_score,
+            score_table: score_table.clone(),
+            settings: world.settings.clone(),
+        }
+    }
+
+    pub(crate) fn decode(self) -> Result<SerializedWorld, String> {
+        let area = self.size * self.size;
+        let tile_types = expand(&self.tile_types, area)?;
+        let contents = expand(&self.contents, area)?;
+        let quantities = expand(&self.quantities, area)?;
+        let range_starts = expand(&self.range_starts, area)?;
+        let elevations = expand(&self.elevations, area)?;
+
+        let mut tiles = Vec::with_capacity(self.size);
+        for row in 0..self.size {
+            let mut tiles_row = Vec::with_capacity(self.size);
+            for i in row * self.size..(row + 1) * self.size {
+                tiles_row.push(Tile {
+                    tile_type: tile_type_from_code(tile_types[i])?,
+                    content: content_from_code(contents[i], range_starts[i] as usize, quantities[i] as usize)?,
+                    elevation: elevations[i] as usize,
+                });
+            }
+            tiles.push(tiles_row);
+        }
+
+        Ok(SerializedWorld {
+            world: (tiles, self.robot_position, self.environmental_conditions, self.max_score, self.score_table),
+            settings: self.settings,
+        })
+    }
+}
+
+// run-length encoding, each run is the value and the number of times it is repeated
+#[inline(always)]
+fn runs<T: PartialEq + Copy>(values: impl Iterator<Item = T>) -> Vec<(T, u32)> {
+    let mut runs: Vec<(T, u32)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            | Some((last, count)) if *last == value && *count < u32::MAX => *count += 1,
+            | _ => runs.push((value, 1)),
+        }
+    }
+    runs
+}
+
+#[inline(always)]
+fn expand<T: Copy>(runs: &[(T, u32)], len: usize) -> Result<Vec<T>, String> {
+    let mut values = Vec::with_capacity(len);
+    for &(value, count) in runs {
+        values.extend(std::iter::repeat_n(value, count as usize));
+    }
+    if values.len() != len {
+        return Err(format!("Corrupted columnar save, a column has {} tiles instead of {len}", values.len()));
+    }
+    Ok(values)
+}
+
+#[inline(always)]
+fn tile_type_code(tile_type: &TileType) -> u8 {
+    match tile_type {
+        | TileType::DeepWater => 0,
+        | TileType::ShallowWater => 1,
+        | TileType::Sand => 2,
+        | TileType::Grass => 3,
+        | TileType::Street => 4,
+        | TileType::Hill => 5,
+        | TileType::Mountain => 6,
+        | TileType::Snow => 7,
+        | TileType::Lava => 8,
+        | TileType::Teleport(false) => 9,
+        | TileType::Teleport(true) => 10,
+        | TileType::Wall => 11,
+    }
+}
+
+#[inline(always)]
+fn tile_type_from_code(code: u8) -> Result<TileType, String> {
+    Ok(match code {
+        | 0 => TileType::DeepWater,
+        | 1 => TileType::ShallowWater,
+        | 2 => TileType::Sand,
+        | 3 => TileType::Grass,
+        | 4 => TileType::Street,
+        | 5 => TileType::Hill,
+        | 6 => TileType::Mountain,
+        | 7 => TileType::Snow,
+        | 8 => TileType::Lava,
+        | 9 => TileType::Teleport(false),
+        | 10 => TileType::Teleport(true),
+        | 11 => TileType::Wall,
+        | _ => return Err(format!("Corrupted columnar save, unknown tile type {code}")),
+    })
+}
+
+#[inline(always)]
+fn content_code(content: &Content) -> u8 {
+    match content {
+        | Content::None => 0,
+        | Content::Rock(_) => 1,
+        | Content::Tree(_) => 2,
+        | Content::Garbage(_) => 3,
+        | Content::Fire => 4,
+        | Content::Coin(_) => 5,
+        | Content::Bin(_) => 6,
+        | Content::Crate(_) => 7,
+        | Content::Bank(_) => 8,
+        | Content::Water(_) => 9,
+        | Content::Market(_) => 10,
+        | Content::Fish(_) => 11,
+        | Content::Building => 12,
+        | Content::Bush(_) => 13,
+        | Content::JollyBlock(_) => 14,
+        | Content::Scarecrow => 15,
+    }
+}
+
+// the start of the range and the quantity, or the end of the range, held by the content
+#[inline(always)]
+fn content_quantities(content: &Content) -> (u32, u32) {
+    match content {
+        | Content::Rock(q) | Content::Tree(q) | Content::Garbage(q) | Content::Coin(q) | Content::Water(q) | Content::Market(q) | Content::Fish(q) | Content::Bush(q) | Content::JollyBlock(q) => {
+            (0, *q as u32)
+        }
+        | Content::Bin(range) | Content::Crate(range) | Content::Bank(range) => (range.start as u32, range.end as u32),
+        | Content::Fire | Content::Building | Content::Scarecrow | Content::None => (0, 0),
+    }
+}
+
+#[inline(always)]
+fn content_from_code(code: u8, start: usize, quantity: usize) -> Result<Content, String> {
+    Ok(match code {
+        | 0 => Content::None,
+        | 1 => Content::Rock(quantity),
+        | 2 => Content::Tree(quantity),
+        | 3 => Content::Garbage(quantity),
+        | 4 => Content::Fire,
+        | 5 => Content::Coin(quantity),
+        | 6 => Content::Bin(start..quantity),
+        | 7 => Content::Crate(start..quantity),
+        | 8 => Content::Bank(start..quantity),
+        | 9 => Content::Water(quantity),
+        | 10 => Content::Market(quantity),
+        | 11 => Content::Fish(quantity),
+        | 12 => Content::Building,
+        | 13 => Content::Bush(quantity),
+        | 14 => Content::JollyBlock(quantity),
+        | 15 => Content::Scarecrow,
+        | _ => return Err(format!("Corrupted columnar save, unknown content {code}")),
+    })
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::generator::Spawnables;
+
+/// Every spawn stage, in the order used to derive and print their seeds
+pub(crate) const STAGES: [Spawnables; 10] = [
+    Spawnables::Rock,
+    Spawnables::Tree,
+    Spawnables::Garbage,
+    Spawnables::Fire,
+    Spawnables::Coin,
+    Spawnables::Bin,
+    Spawnables::Crate,
+    Spawnables::Bank,
+    Spawnables::Market,
+    Spawnables::Fish,
+];
+
+/// The seed of the random number generator of each spawn stage.
+///
+/// Before spawning each content the generator is re-seeded with the seed of its stage, so changing the seed
+/// of a single stage re-rolls only that content, leaving the terrain and the random choices of the other stages untouched.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StageSeeds {
+    seeds: HashMap<Spawnables, u64>,
+}
+
+impl StageSeeds {
+    /// Derives the seed of each stage from the master seed, the seed of the noise settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::seeds::StageSeeds;
+    /// use exclusion_zone::generator::{NoiseSettings, Spawnables, WorldGenerator};
+    ///
+    /// let seed = 7;
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.noise_settings = NoiseSettings::from_seed(seed);
+    /// let mut seeds = StageSeeds::from_seed(seed);
+    /// println!("{seeds}");
+    ///
+    /// // same world, different garbage
+    /// seeds.set(Spawnables::Garbage, 42);
+    /// generator.stage_seeds = Some(seeds);
+    /// ```
+    pub fn from_seed(master_seed: u32) -> Self {
+        StageSeeds {
+            seeds: STAGES.iter().enumerate().map(|(i, stage)| (*stage, splitmix64(((master_seed as u64) << 32) | i as u64))).collect(),
+        }
+    }
+
+    /// Returns the seed of the given stage
+    pub fn get(&self, stage: Spawnables) -> u64 {
+        self.seeds.get(&stage).copied().unwrap_or_default()
+    }
+
+    /// Overrides the seed of the given stage
+    pub fn set(&mut self, stage: Spawnables, seed: u64) {
+        self.seeds.insert(stage, seed);
+    }
+}
+
+impl Display for StageSeeds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, stage) in STAGES.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}: {}", stage, self.get(*stage))?;
+        }
+        Ok(())
+    }
+}
+
+// the seed of the extra content registered at the given index, derived like the seeds of the stages after them
+#[inline(always)]
+pub(crate) fn extra_content_seed(master_seed: u32, index: usize) -> u64 {
+    splitmix64(((master_seed as u64) << 32) | (STAGES.len() + index) as u64)
+}
+
+// spreads close master seeds and stage indexes to unrelated seeds
+#[inline(always)]
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
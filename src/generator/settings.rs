@@ -0,0 +1,321 @@
+#[cfg(feature = "io")]
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::bank::BankSettings;
+use crate::content::bin::BinSettings;
+use crate::content::coin::CoinSettings;
+use crate::content::custom::ExtraContent;
+use crate::content::fire::FireSettings;
+use crate::content::fish::FishSettings;
+use crate::content::garbage::GarbageSettings;
+use crate::content::market::MarketSettings;
+use crate::content::rock::RockSettings;
+use crate::content::tree::TreeSettings;
+use crate::content::wood_crate::CrateSettings;
+use crate::generator::access::AccessSettings;
+use crate::generator::balance::ScoreBalance;
+use crate::generator::conflict::ConflictSettings;
+use crate::generator::constraints::ConstraintSettings;
+use crate::generator::contamination::ContaminationSettings;
+use crate::generator::decor::DecorSettings;
+use crate::generator::fence::FenceSettings;
+use crate::generator::flow::FlowSettings;
+use crate::generator::landmarks::LandmarkSettings;
+use crate::generator::options::GenerationOptions;
+use crate::generator::placement::PlacementMasks;
+use crate::generator::quantity_field::QuantityFieldSettings;
+use crate::generator::reachability::ReachabilityCheck;
+use crate::generator::regions::RegionSettings;
+use crate::generator::seeds::StageSeeds;
+use crate::generator::slope::SlopeSettings;
+use crate::generator::spawn_view::SpawnViewSettings;
+use crate::generator::tags::TagSettings;
+use crate::generator::timed::TimedSpawnSettings;
+use crate::generator::vaults::VaultSettings;
+use crate::generator::water_source::WaterSourceSettings;
+use crate::generator::weather::WeatherSettings;
+use crate::generator::wind::WindSettings;
+use crate::generator::{NoiseSettings, QualityProfile, SpawnOrder, Thresholds, WorldGenerator};
+use crate::tile_type::lava::LavaSettings;
+use crate::tile_type::street::StreetSettings;
+
+/// Every setting of a `WorldGenerator` except the world size, to keep presets without saving a whole world.
+///
+/// The number of spawn points of the content settings are absolute, so a preset keeps the density
+/// of the world size it was tuned for only when applied to a world of the same size.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorldGeneratorSettings {
+    /// set of content and tile type defining the order with which elements are generated
+    pub spawn_order: SpawnOrder,
+    /// settings of the noise generator uses to give rise to the noise map
+    pub noise_settings: NoiseSettings,
+    /// thresholds within which tile types are assigned
+    pub thresholds: Thresholds,
+    /// define how the lava will spawn
+    pub lava_settings: LavaSettings,
+    /// define how banks will spawn
+    pub bank_settings: BankSettings,
+    /// define how bin will spawn
+    pub bin_settings: BinSettings,
+    /// define how wood crate will spawn
+    pub crate_settings: CrateSettings,
+    /// define how garbage will spawn
+    pub garbage_settings: GarbageSettings,
+    /// define how fire will spawn
+    pub fire_settings: FireSettings,
+    /// define how trees will spawn
+    pub tree_settings: TreeSettings,
+    /// define how coins will spawn
+    pub coin_settings: CoinSettings,
+    /// define how the market will spawn
+    pub market_settings: MarketSettings,
+    /// define how fish will spawn
+    pub fish_settings: FishSettings,
+    /// define how rocks will spawn
+    pub rock_settings: RockSettings,
+    /// define the trade-off between speed and quality of the spawners
+    pub quality_profile: QualityProfile,
+    /// define how streets will spawn
+    pub street_settings: StreetSettings,
+    /// define whether the generator checks that Banks, Markets and Bins can be reached by the robot
+    pub reachability_check: ReachabilityCheck,
+    /// directory where the noise map and the terrain are cached
+    pub cache_dir: Option<String>,
+    /// scales coins, bank capacities and trees so that the achievable score matches a target max score
+    pub score_balance: Option<ScoreBalance>,
+    /// the seed of each spawn stage
+    pub stage_seeds: Option<StageSeeds>,
+    /// the reactor and the radio masts placed on notable features of the elevation map
+    pub landmark_settings: Option<LandmarkSettings>,
+    /// the slope analysis of the elevation map
+    pub slope_settings: Option<SlopeSettings>,
+    /// the segmentation of the world in named regions
+    pub region_settings: Option<RegionSettings>,
+    /// define how the generation runs, like its time budget
+    pub generation_options: GenerationOptions,
+    /// define what happens when content is spawned on a tile already holding content
+    pub conflict_settings: ConflictSettings,
+    /// define the weather forecast of the world, which also shapes its terrain and content
+    pub weather_settings: WeatherSettings,
+    /// the fields modulating the quantity of the content across the world
+    pub quantity_field_settings: Option<QuantityFieldSettings>,
+    /// restricts where each spawnable may be placed, the rules are not saved
+    pub placement_masks: Option<PlacementMasks>,
+    /// the vault and key pairs of the bank robbery scenario
+    pub vault_settings: Option<VaultSettings>,
+    /// the settings of the tags of the tiles, `None` disables the tags
+    pub tag_settings: Option<TagSettings>,
+    /// the constraints on the terrain, `None` accepts any terrain
+    pub constraint_settings: Option<ConstraintSettings>,
+    /// the settings of the content spawned while the simulation runs, `None` disables the schedule
+    pub timed_spawn_settings: Option<TimedSpawnSettings>,
+    /// the settings of the contamination spreading from the epicenter of the disaster, `None` disables it
+    pub contamination_settings: Option<ContaminationSettings>,
+    /// what the robot must see at the spawn, `None` spawns it at the first walkable tile
+    pub spawn_view_settings: Option<SpawnViewSettings>,
+    /// the settings of the flow field of the water
+    pub flow_settings: Option<FlowSettings>,
+    /// the fence of the exclusion zone
+    pub fence_settings: Option<FenceSettings>,
+    /// the settings of the decor layer, `None` to skip it
+    pub decor_settings: Option<DecorSettings>,
+    /// the settings of the access check of the Banks, the Markets and the Bins, `None` to skip it
+    pub access_settings: Option<AccessSettings>,
+    /// the content which is not one of the `Spawnables`, spawned after the spawn order
+    pub extra_content: Vec<ExtraContent>,
+    /// the wind elongating the fires and drifting the garbage downwind, `None` for a windless world
+    pub wind_settings: Option<WindSettings>,
+    /// the water source guaranteed near the robot spawn, `None` leaves the water where the terrain puts it
+    pub water_source_settings: Option<WaterSourceSettings>,
+}
+
+impl From<&WorldGenerator> for WorldGeneratorSettings {
+    fn from(generator: &WorldGenerator) -> Self {
+        // destructured so that a new field of the generator cannot be forgotten here
+        let WorldGenerator {
+            size: _,
+            spawn_order,
+            noise_settings,
+            thresholds,
+            lava_settings,
+            bank_settings,
+            bin_settings,
+            crate_settings,
+            garbage_settings,
+            fire_settings,
+            tree_settings,
+            coin_settings,
+            market_settings,
+            fish_settings,
+            rock_settings,
+            quality_profile,
+            street_settings,
+            reachability_check,
+            cache_dir,
+            score_balance,
+            stage_seeds,
+            landmark_settings,
+            slope_settings,
+            region_settings,
+            generation_options,
+            conflict_settings,
+            weather_settings,
+            quantity_field_settings,
+            placement_masks,
+            vault_settings,
+            tag_settings,
+            constraint_settings,
+            timed_spawn_settings,
+            contamination_settings,
+            spawn_view_settings,
+            flow_settings,
+            fence_settings,
+            decor_settings,
+            access_settings,
+            extra_content,
+            wind_settings,
+            water_source_settings,
+        } = generator.clone();
+
+        WorldGeneratorSettings {
+            spawn_order,
+            noise_settings,
+            thresholds,
+            lava_settings,
+            bank_settings,
+            bin_settings,
+            crate_settings,
+            garbage_settings,
+            fire_settings,
+            tree_settings,
+            coin_settings,
+            market_settings,
+            fish_settings,
+            rock_settings,
+            quality_profile,
+            street_settings,
+            reachability_check,
+            cache_dir,
+            score_balance,
+            stage_seeds,
+            landmark_settings,
+            slope_settings,
+            region_settings,
+            generation_options,
+            conflict_settings,
+            weather_settings,
+            quantity_field_settings,
+            placement_masks,
+            vault_settings,
+            tag_settings,
+            constraint_settings,
+            timed_spawn_settings,
+            contamination_settings,
+            spawn_view_settings,
+            flow_settings,
+            fence_settings,
+            decor_settings,
+            access_settings,
+            extra_content,
+            wind_settings,
+            water_source_settings,
+        }
+    }
+}
+
+impl WorldGeneratorSettings {
+    /// Creates a `WorldGenerator` of the given size with these settings
+    pub fn into_generator(self, size: usize) -> WorldGenerator {
+        WorldGenerator {
+            size,
+            spawn_order: self.spawn_order,
+            noise_settings: self.noise_settings,
+            thresholds: self.thresholds,
+            lava_settings: self.lava_settings,
+            bank_settings: self.bank_settings,
+            bin_settings: self.bin_settings,
+            crate_settings: self.crate_settings,
+            garbage_settings: self.garbage_settings,
+            fire_settings: self.fire_settings,
+            tree_settings: self.tree_settings,
+            coin_settings: self.coin_settings,
+            market_settings: self.market_settings,
+            fish_settings: self.fish_settings,
+            rock_settings: self.rock_settings,
+            quality_profile: self.quality_profile,
+            street_settings: self.street_settings,
+            reachability_check: self.reachability_check,
+            cache_dir: self.cache_dir,
+            score_balance: self.score_balance,
+            stage_seeds: self.stage_seeds,
+            landmark_settings: self.landmark_settings,
+            slope_settings: self.slope_settings,
+            region_settings: self.region_settings,
+            generation_options: self.generation_options,
+            conflict_settings: self.conflict_settings,
+            weather_settings: self.weather_settings,
+            quantity_field_settings: self.quantity_field_settings,
+            placement_masks: self.placement_masks,
+            vault_settings: self.vault_settings,
+            tag_settings: self.tag_settings,
+            constraint_settings: self.constraint_settings,
+            timed_spawn_settings: self.timed_spawn_settings,
+            contamination_settings: self.contamination_settings,
+            spawn_view_settings: self.spawn_view_settings,
+            flow_settings: self.flow_settings,
+            fence_settings: self.fence_settings,
+            decor_settings: self.decor_settings,
+            access_settings: self.access_settings,
+            extra_content: self.extra_content,
+            wind_settings: self.wind_settings,
+            water_source_settings: self.water_source_settings,
+        }
+    }
+
+    /// Saves the settings to the given file, without any world data.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path of the file, used as is.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use exclusion_zone::generator::settings::WorldGeneratorSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let generator = WorldGenerator::default(1000);
+    /// WorldGeneratorSettings::from(&generator).save_settings("presets/default.bin").expect("unable to save the preset");
+    ///
+    /// let generator = WorldGeneratorSettings::load_settings("presets/default.bin").expect("unable to load the preset").into_generator(1000);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings cannot be serialized or the file cannot be written.
+    #[cfg(feature = "io")]
+    pub fn save_settings(&self, file_path: &str) -> Result<(), String> {
+        let serialized = bincode::serialize(self).map_err(|e| format!("{e}"))?;
+        fs::write(file_path, serialized).map_err(|e| format!("Unable to save settings file {file_path}:\n{e}"))
+    }
+
+    /// Loads the settings saved with `save_settings` from the given file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or does not contain valid settings.
+    #[cfg(feature = "io")]
+    pub fn load_settings(file_path: &str) -> Result<Self, String> {
+        let serialized = fs::read(file_path).map_err(|e| format!("Unable to load settings file {file_path}:\n{e}"))?;
+        bincode::deserialize(&serialized).map_err(|e| format!("Unable to load settings file {file_path}:\n{e}"))
+    }
+}
+
+impl WorldGenerator {
+    /// Returns every setting of the generator except the world size
+    pub fn settings(&self) -> WorldGeneratorSettings {
+        WorldGeneratorSettings::from(self)
+    }
+}
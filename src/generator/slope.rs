@@ -0,0 +1,151 @@
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Settings of the slope analysis, which marks the steep transitions of the elevation map.
+///
+/// The slope of a tile is the biggest elevation difference with its four neighbours, as a fraction of the
+/// elevation range of the world, for each hundredth of the world side; this way the same thresholds
+/// select about the same share of tiles whatever the size of the world.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SlopeSettings {
+    /// the slope above which a tile is steep, a hint that it is hard to climb
+    pub steep_threshold: f64,
+    /// the slope above which a tile is a cliff
+    pub cliff_threshold: f64,
+    /// the tile type the cliffs are converted to, `None` only reports them
+    pub cliff_tile_type: Option<TileType>,
+}
+
+impl Default for SlopeSettings {
+    fn default() -> Self {
+        SlopeSettings {
+            steep_threshold: 0.3,
+            cliff_threshold: 0.5,
+            cliff_tile_type: None,
+        }
+    }
+}
+
+impl SlopeSettings {
+    /// Creates a new instance of `SlopeSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `steep_threshold` - The slope above which a tile is steep, by default 0.3, roughly the steepest 2% of the tiles.
+    /// * `cliff_threshold` - The slope above which a tile is a cliff, by default 0.5.
+    /// * `cliff_tile_type` - The tile type the cliffs are converted to, usually Mountain or Wall, `None` only reports them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::TileType;
+    /// use exclusion_zone::generator::slope::SlopeSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.slope_settings = Some(SlopeSettings::new(0.3, 0.45, Some(TileType::Mountain)));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let cliffs = artifacts.slope.unwrap().cliffs.len();
+    /// ```
+    pub fn new(steep_threshold: f64, cliff_threshold: f64, cliff_tile_type: Option<TileType>) -> Self {
+        SlopeSettings {
+            steep_threshold,
+            cliff_threshold,
+            cliff_tile_type,
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::slope::SlopeSettings;
+    ///
+    /// // every cliff would not be steep
+    /// assert!(!SlopeSettings::new(0.5, 0.3, None).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.steep_threshold >= 0.0, "SlopeSettings::steep_threshold", || {
+            format!("{} is not a slope, use a value not lower than 0", self.steep_threshold)
+        });
+        issues.check(self.cliff_threshold >= self.steep_threshold, "SlopeSettings::cliff_threshold", || {
+            format!("{} is lower than the steep threshold {}, the cliffs must be steep", self.cliff_threshold, self.steep_threshold)
+        });
+        issues.0
+    }
+}
+
+/// Result of the slope analysis
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SlopeMap {
+    /// the slope of each tile, indexed as `slopes[row][col]`
+    pub slopes: Vec<Vec<f32>>,
+    /// the tiles whose slope is above the steep threshold, cliffs included
+    pub steep: Vec<Coordinate>,
+    /// the tiles whose slope is above the cliff threshold, converted to the cliff tile type if any
+    pub cliffs: Vec<Coordinate>,
+}
+
+/// Computes the slope of each tile from the elevation map and, if enabled, converts the cliffs.
+///
+/// Water, lava, streets and the tiles which are not part of the terrain, like walls and teleports, are never converted.
+pub(crate) fn analyze_slope(world: &mut TileMatrix, elevation_map: &[Vec<f64>], settings: &SlopeSettings) -> SlopeMap {
+    let size = elevation_map.len();
+    let (min, max) = elevation_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), &value| (min.min(value), max.max(value)));
+    let scale = if max > min { size as f64 / (100.0 * (max - min)) } else { 0.0 };
+
+    let mut map = SlopeMap {
+        slopes: vec![vec![0.0; size]; size],
+        ..Default::default()
+    };
+
+    for row in 0..size {
+        for col in 0..size {
+            let value = elevation_map[row][col];
+            let neighbours = [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)];
+            let delta = neighbours
+                .into_iter()
+                .filter(|(r, c)| *r < size && *c < size)
+                .map(|(r, c)| (elevation_map[r][c] - value).abs())
+                .fold(0.0, f64::max);
+
+            let slope = delta * scale;
+            map.slopes[row][col] = slope as f32;
+            if slope <= settings.steep_threshold {
+                continue;
+            }
+
+            let c = Coordinate { row, col };
+            map.steep.push(c);
+            if slope > settings.cliff_threshold {
+                map.cliffs.push(c);
+                if let Some(tile_type) = settings.cliff_tile_type {
+                    make_cliff(world, c, tile_type);
+                }
+            }
+        }
+    }
+    map
+}
+
+#[inline(always)]
+fn make_cliff(world: &mut TileMatrix, c: Coordinate, tile_type: TileType) {
+    let tile = &mut world[c.row][c.col];
+    if matches!(
+        tile.tile_type,
+        TileType::DeepWater | TileType::ShallowWater | TileType::Lava | TileType::Street | TileType::Wall | TileType::Teleport(_)
+    ) {
+        return;
+    }
+
+    tile.tile_type = tile_type;
+    if !tile_type.properties().can_hold(&tile.content.to_default()) {
+        tile.content = Content::None;
+    }
+}
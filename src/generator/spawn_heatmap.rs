@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// The placement attempts of a spawn stage on a tile
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PlacementAttempts {
+    /// the number of times the spawner looked at the tile, checking whether it could take the content or placing it
+    pub considered: u32,
+    /// the number of times the content was placed on the tile
+    pub accepted: u32,
+    /// the number of checks and placements refused, because the tile type cannot hold the content, the placement
+    /// masks rule the tile out or the tile already holds content kept by the conflict policy
+    pub rejected: u32,
+}
+
+impl PlacementAttempts {
+    /// Returns true if the spawner never looked at the tile
+    pub fn is_untouched(&self) -> bool {
+        self.considered == 0
+    }
+}
+
+/// Where a spawn stage looked for room for its content and where it placed it, tile by tile, to see why a content
+/// is missing from a part of the world, like the banks in the north; see `render_spawn_heatmap` in the visualizer.
+///
+/// The attempts are recorded only when the crate is built with the `spawn_telemetry` feature, otherwise the heatmaps
+/// are not produced and the spawners pay nothing for them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SpawnHeatmap {
+    /// the side of the world
+    pub size: usize,
+    /// the attempts on each tile, row by row
+    pub attempts: Vec<PlacementAttempts>,
+}
+
+impl SpawnHeatmap {
+    #[cfg(feature = "spawn_telemetry")]
+    pub(crate) fn new(size: usize) -> Self {
+        SpawnHeatmap {
+            size,
+            attempts: vec![PlacementAttempts::default(); size * size],
+        }
+    }
+
+    /// Returns the attempts on the tile, none if the tile is out of the world
+    pub fn get(&self, row: usize, col: usize) -> PlacementAttempts {
+        if row >= self.size || col >= self.size {
+            return PlacementAttempts::default();
+        }
+        self.attempts[row * self.size + col]
+    }
+
+    /// Returns the sum of the attempts on every tile
+    pub fn totals(&self) -> PlacementAttempts {
+        self.attempts.iter().fold(PlacementAttempts::default(), |totals, attempts| PlacementAttempts {
+            considered: totals.considered + attempts.considered,
+            accepted: totals.accepted + attempts.accepted,
+            rejected: totals.rejected + attempts.rejected,
+        })
+    }
+
+    /// Returns the max number of attempts of each kind on a single tile, to scale the colors of a render
+    pub fn peaks(&self) -> PlacementAttempts {
+        self.attempts.iter().fold(PlacementAttempts::default(), |peaks, attempts| PlacementAttempts {
+            considered: peaks.considered.max(attempts.considered),
+            accepted: peaks.accepted.max(attempts.accepted),
+            rejected: peaks.rejected.max(attempts.rejected),
+        })
+    }
+
+    // the spawner looked at the tile with the given index, `placed` if it was placing the content rather than checking
+    #[cfg(feature = "spawn_telemetry")]
+    #[inline(always)]
+    pub(crate) fn record(&mut self, index: usize, placed: bool, accepted: bool) {
+        let attempts = &mut self.attempts[index];
+        attempts.considered += 1;
+        if !accepted {
+            attempts.rejected += 1;
+        } else if placed {
+            attempts.accepted += 1;
+        }
+    }
+}
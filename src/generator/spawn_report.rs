@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::Spawnables;
+
+/// Why a spawner placed less content than requested
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// more spawn points were requested than half of the free tiles which can hold the content
+    CapacityClamped,
+    /// no room was left to keep the min spacing between the spawn points
+    MinSpacing,
+    /// the tile already held content, which the conflict policy kept
+    Occupied,
+    /// no free tile which can hold the content was left where the spawner looked for one
+    NoRoom,
+    /// the tile was part of a structure, like a blob or a pile, dropped whole because another of its tiles was taken
+    Incomplete,
+}
+
+/// The content requested and actually placed by a spawn stage, in tiles.
+///
+/// The requested tiles are the spawn points of the settings, for the content spawned on random tiles, and the tiles
+/// the spawner tried to fill for the content spawned in shapes, the blobs of trees and fires, the rock fields and the
+/// garbage piles, whose settings are upper bounds rather than requests; so `requested` is always `placed` plus the
+/// skipped tiles.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SpawnReport {
+    /// the number of tiles the content was requested on
+    pub requested: usize,
+    /// the number of tiles the content was placed on
+    pub placed: usize,
+    /// the number of tiles not filled for each reason, the reasons which never happened are left out
+    pub skipped: Vec<(SkipReason, usize)>,
+}
+
+impl SpawnReport {
+    /// Returns the number of tiles not filled for the given reason
+    pub fn skipped(&self, reason: SkipReason) -> usize {
+        self.skipped.iter().find(|(r, _)| *r == reason).map_or(0, |(_, count)| *count)
+    }
+
+    /// Returns true if the content was placed on every requested tile
+    pub fn is_satisfied(&self) -> bool {
+        self.placed == self.requested
+    }
+
+    // a tile the content has been placed on
+    #[inline(always)]
+    pub(crate) fn place(&mut self) {
+        self.requested += 1;
+        self.placed += 1;
+    }
+
+    // requested tiles not filled for the given reason
+    #[inline(always)]
+    pub(crate) fn skip(&mut self, reason: SkipReason, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.requested += count;
+        match self.skipped.iter_mut().find(|(r, _)| *r == reason) {
+            | Some((_, skipped)) => *skipped += count,
+            | None => self.skipped.push((reason, count)),
+        }
+    }
+
+    // the warning logged when the spawn warnings are enabled and the content did not fit
+    #[inline(always)]
+    pub(crate) fn warn(&self, spawnable: Spawnables) {
+        if !self.is_satisfied() {
+            warn!(
+                stage = ?spawnable,
+                requested = self.requested,
+                placed = self.placed,
+                skipped = ?self.skipped,
+                "SpawnShortfall: the content was placed on less tiles than requested"
+            );
+        }
+    }
+}
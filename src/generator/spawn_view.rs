@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{robot_spawn, TileMatrix};
+use crate::utils::{Coordinate, Position};
+
+/// The direction the robot faces at the spawn, named like the directions of the robot interface
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Facing {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Settings of what the robot must see at the spawn: the 3x3 view around the spawn point contains at least a Street
+/// tile or a tile holding one of the notable contents, so that the demos start with something visible.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpawnViewSettings {
+    /// true if a Street tile is enough
+    pub streets: bool,
+    /// the kinds of content enough to be seen, the kind is given by the default value of the content
+    pub notable_contents: Vec<Content>,
+}
+
+impl Default for SpawnViewSettings {
+    fn default() -> Self {
+        SpawnViewSettings {
+            streets: true,
+            notable_contents: vec![Content::Bank(0..0), Content::Market(0), Content::Bin(0..0), Content::Crate(0..0), Content::Coin(0)],
+        }
+    }
+}
+
+impl SpawnViewSettings {
+    /// Creates a new instance of `SpawnViewSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `streets` - True if a Street tile in the view is enough.
+    /// * `notable_contents` - The kinds of content enough to be seen, whatever their quantity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::tile::Content;
+    /// use exclusion_zone::generator::spawn_view::SpawnViewSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // the robot starts next to a bank
+    /// let mut generator = WorldGenerator::default(300);
+    /// generator.spawn_view_settings = Some(SpawnViewSettings::new(false, vec![Content::Bank(0..0)]));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// if let Some(view) = artifacts.spawn_view {
+    ///     println!("the robot faces {:?} toward {:?}", view.facing, view.target);
+    /// }
+    /// ```
+    pub fn new(streets: bool, notable_contents: Vec<Content>) -> Self {
+        SpawnViewSettings {
+            streets,
+            notable_contents: notable_contents.iter().map(|content| content.to_default()).collect(),
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::spawn_view::SpawnViewSettings;
+    ///
+    /// // nothing to see
+    /// assert!(!SpawnViewSettings::new(false, Vec::new()).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.streets || !self.notable_contents.is_empty(), "SpawnViewSettings::notable_contents", || {
+            "no tile is notable without the streets and the notable contents".to_string()
+        });
+        issues.0
+    }
+
+    // true if the robot is meant to notice the tile
+    #[inline(always)]
+    fn is_notable(&self, world: &TileMatrix, c: Coordinate) -> bool {
+        let tile = &world[c.row][c.col];
+        (self.streets && tile.tile_type == TileType::Street) || self.notable_contents.contains(&tile.content.to_default())
+    }
+}
+
+/// What the robot sees at the spawn chosen for the view
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpawnView {
+    /// the spawn point
+    pub position: Coordinate,
+    /// the notable tile in the view of the robot
+    pub target: Coordinate,
+    /// the direction from the spawn point to the notable tile, the vertical one for a diagonal tile, `None` if the robot stands on it
+    pub facing: Option<Facing>,
+}
+
+/// Chooses the robot spawn, the first walkable tile, row by row, with a notable tile in its view.
+///
+/// Returns the default spawn, the first walkable tile, and `None` if no walkable tile sees a notable tile.
+pub(crate) fn spawn_with_view(world: &TileMatrix, settings: &SpawnViewSettings) -> (Position, Option<SpawnView>) {
+    let size = world.len();
+    for row in 0..size {
+        for col in 0..size {
+            if !world[row][col].tile_type.properties().walk() {
+                continue;
+            }
+            let position = Coordinate { row, col };
+            let mut view = (row.saturating_sub(1)..(row + 2).min(size)).flat_map(|r| (col.saturating_sub(1)..(col + 2).min(size)).map(move |c| Coordinate { row: r, col: c }));
+            // the tile of the robot first, then the others of the view
+            let target = Some(position).filter(|c| settings.is_notable(world, *c)).or_else(|| view.find(|c| settings.is_notable(world, *c)));
+            if let Some(target) = target {
+                let facing = match (target.row.cmp(&row), target.col.cmp(&col)) {
+                    | (Ordering::Less, _) => Some(Facing::Up),
+                    | (Ordering::Greater, _) => Some(Facing::Down),
+                    | (_, Ordering::Less) => Some(Facing::Left),
+                    | (_, Ordering::Greater) => Some(Facing::Right),
+                    | _ => None,
+                };
+                return (
+                    Position::from_row_col(row, col),
+                    Some(SpawnView {
+                        position,
+                        target,
+                        facing,
+                    }),
+                );
+            }
+        }
+    }
+
+    warn!("SpawnViewNotFound: no walkable tile sees a notable tile, the robot spawns at the first walkable tile");
+    (robot_spawn(world), None)
+}
@@ -0,0 +1,73 @@
+use std::mem::discriminant;
+
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::generator::TileMatrix;
+
+/// Removes the given kinds of content from the world, to derive simpler variants of a generated world from the same
+/// terrain, like a world without garbage for the first runs of a robot.
+///
+/// The kinds are matched whatever their quantity, so `Content::Garbage(0)` removes all the garbage.
+///
+/// # Returns
+///
+/// The number of tiles emptied.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::Content;
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::matrix::TileMatrixExt;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::strip::strip_content;
+///
+/// let (mut world, ..) = MiniWorldGenerator::new(50).gen();
+/// strip_content(&mut world, &[Content::Garbage(0), Content::Rock(0)]);
+/// assert_eq!(world.count_content(&Content::Garbage(0)), 0);
+/// assert_eq!(world.count_content(&Content::Rock(0)), 0);
+/// ```
+pub fn strip_content(world: &mut TileMatrix, kinds: &[Content]) -> usize {
+    let kinds: Vec<_> = kinds.iter().map(discriminant).collect();
+    let mut stripped = 0;
+    for tile in world.iter_mut().flatten() {
+        if tile.content != Content::None && kinds.contains(&discriminant(&tile.content)) {
+            tile.content = Content::None;
+            stripped += 1;
+        }
+    }
+    stripped
+}
+
+/// Removes what harms the robot from the world: the fires are put out and the lava cools down to Mountain.
+///
+/// # Returns
+///
+/// The number of tiles changed.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::tile::{Content, TileType};
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::matrix::TileMatrixExt;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::strip::strip_hazards;
+///
+/// let (mut world, ..) = MiniWorldGenerator::new(50).gen();
+/// strip_hazards(&mut world);
+/// assert_eq!(world.count_content(&Content::Fire), 0);
+/// assert_eq!(world.iter_where(|tile| tile.tile_type == TileType::Lava).count(), 0);
+/// ```
+pub fn strip_hazards(world: &mut TileMatrix) -> usize {
+    let mut stripped = strip_content(world, &[Content::Fire]);
+    for tile in world.iter_mut().flatten().filter(|tile| tile.tile_type == TileType::Lava) {
+        tile.tile_type = TileType::Mountain;
+        // the lava holds nothing, but a hand-edited world could
+        if !TileType::Mountain.properties().can_hold(&tile.content.to_default()) {
+            tile.content = Content::None;
+        }
+        stripped += 1;
+    }
+    stripped
+}
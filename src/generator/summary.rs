@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{Read, Write};
+
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::preview::tile_color;
+use crate::generator::{GenResult, TileMatrix, WorldGenerator};
+
+/// Max side, in pixels, of the thumbnail stored in the saved worlds
+const THUMBNAIL_SIZE: usize = 128;
+/// Magic number of the zstd skippable frame holding the summary, ignored by the decoders of the world
+const SUMMARY_FRAME_MAGIC: u32 = 0x184D2A5E;
+
+/// Header of a saved world, with a thumbnail and the stats a launcher needs to show it in a gallery.
+///
+/// It is stored uncompressed before the compressed world, so `peek_save` reads it without decompressing the world.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SaveSummary {
+    /// the side of the world
+    pub size: usize,
+    /// the seed of the noise map
+    pub seed: u32,
+    /// the spawn point of the robot, as `(row, col)`
+    pub robot_spawn: (usize, usize),
+    /// the max score of the world
+    pub max_score: f32,
+    /// the number of tiles of each tile type
+    pub tile_counts: Vec<(TileType, usize)>,
+    /// the number of tiles holding each kind of content, the kind is given by the default value of the content
+    pub content_counts: Vec<(Content, usize)>,
+    /// the world drawn as a PNG image of at most 128x128 pixels, `None` if it could not be encoded
+    pub thumbnail: Option<Vec<u8>>,
+}
+
+impl SaveSummary {
+    pub(crate) fn new(world: &GenResult, settings: &WorldGenerator) -> Self {
+        let (tiles, robot_spawn, _, max_score, _) = world;
+        let mut tile_counts: Vec<(TileType, usize)> = Vec::new();
+        let mut content_counts: Vec<(Content, usize)> = Vec::new();
+        for tile in tiles.iter().flatten() {
+            match tile_counts.iter_mut().find(|(tile_type, _)| *tile_type == tile.tile_type) {
+                | Some((_, count)) => *count += 1,
+                | None => tile_counts.push((tile.tile_type, 1)),
+            }
+            if tile.content == Content::None {
+                continue;
+            }
+            let kind = tile.content.to_default();
+            match content_counts.iter_mut().find(|(content, _)| *content == kind) {
+                | Some((_, count)) => *count += 1,
+                | None => content_counts.push((kind, 1)),
+            }
+        }
+
+        SaveSummary {
+            size: tiles.len(),
+            seed: settings.noise_settings.seed,
+            robot_spawn: *robot_spawn,
+            max_score: *max_score,
+            tile_counts,
+            content_counts,
+            thumbnail: thumbnail(tiles),
+        }
+    }
+
+    // writes the summary as a zstd skippable frame: magic number, little endian length and payload
+    pub(crate) fn write_frame(&self, file: &mut File) -> Result<(), String> {
+        let payload = bincode::serialize(self).map_err(|e| format!("{e}"))?;
+        let length = u32::try_from(payload.len()).map_err(|e| format!("{e}"))?;
+        file.write_all(&SUMMARY_FRAME_MAGIC.to_le_bytes()).map_err(|e| format!("{e}"))?;
+        file.write_all(&length.to_le_bytes()).map_err(|e| format!("{e}"))?;
+        file.write_all(&payload).map_err(|e| format!("{e}"))
+    }
+}
+
+// the world scaled down to the thumbnail size, sampling a tile for each pixel
+fn thumbnail(world: &TileMatrix) -> Option<Vec<u8>> {
+    let size = world.len();
+    let side = size.min(THUMBNAIL_SIZE);
+    if side == 0 {
+        return None;
+    }
+
+    let pixels: Vec<u8> = (0..side).flat_map(|y| (0..side).flat_map(move |x| tile_color(&world[y * size / side][x * size / side]))).collect();
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(&pixels, side as u32, side as u32, ColorType::Rgb8).ok()?;
+    Some(png)
+}
+
+/// Reads the summary of a saved world, without decompressing the world.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the saved world, including the `.zst` extension.
+///
+/// # Examples
+///
+/// ```no_run
+/// use exclusion_zone::generator::summary::peek_save;
+///
+/// let summary = peek_save("worlds/chernobyl.zst").expect("unable to read the summary");
+/// println!("{}x{} world, seed {}", summary.size, summary.size, summary.seed);
+/// if let Some(png) = summary.thumbnail {
+///     std::fs::write("chernobyl.png", png).expect("unable to write the thumbnail");
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or it was saved without a summary, by an older version.
+pub fn peek_save(file_path: &str) -> Result<SaveSummary, String> {
+    let mut file = File::open(file_path).map_err(|e| format!("Unable to open world file {file_path}:\n{e}"))?;
+    let mut header = [0u8; 8];
+    file.read_exact(&mut header).map_err(|e| format!("Unable to read world file {file_path}:\n{e}"))?;
+
+    let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    if magic != SUMMARY_FRAME_MAGIC {
+        return Err(format!("World file {file_path} has no summary"));
+    }
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let mut payload = vec![0u8; length];
+    file.read_exact(&mut payload).map_err(|e| format!("Unable to read world file {file_path}:\n{e}"))?;
+    bincode::deserialize(&payload).map_err(|e| format!("Unable to read the summary of {file_path}:\n{e}"))
+}
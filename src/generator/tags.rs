@@ -0,0 +1,269 @@
+use std::ops::{BitOr, BitOrAssign};
+
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::hazards::HazardMap;
+use crate::generator::landmarks::{Landmark, LandmarkKind};
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Set of tags of a tile, the generator-level facts about it which its tile type and its content cannot express
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TileTags(u8);
+
+impl TileTags {
+    /// No tag
+    pub const NONE: TileTags = TileTags(0);
+    /// The tile is a street, or close enough to one to be part of the city
+    pub const IN_CITY: TileTags = TileTags(1);
+    /// The tile holds garbage or is close to the reactor
+    pub const CONTAMINATED: TileTags = TileTags(1 << 1);
+    /// The tile is a street laid over water
+    pub const BRIDGE: TileTags = TileTags(1 << 2);
+    /// The tile is walkable and far enough from fire and lava to spawn a robot on it
+    pub const SPAWN_SAFE: TileTags = TileTags(1 << 3);
+    /// The tile is water the lava flowed into, covered by steam
+    pub const STEAM: TileTags = TileTags(1 << 4);
+
+    /// Returns the raw bits of the tags
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if all the given tags are set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::tags::TileTags;
+    ///
+    /// let tags = TileTags::IN_CITY | TileTags::BRIDGE;
+    /// assert!(tags.contains(TileTags::BRIDGE));
+    /// assert!(!tags.contains(TileTags::BRIDGE | TileTags::CONTAMINATED));
+    /// ```
+    pub fn contains(self, other: TileTags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if no tag is set
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Sets the given tags
+    pub fn insert(&mut self, other: TileTags) {
+        self.0 |= other.0;
+    }
+
+    /// Clears the given tags
+    pub fn remove(&mut self, other: TileTags) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for TileTags {
+    type Output = TileTags;
+
+    fn bitor(self, other: TileTags) -> TileTags {
+        TileTags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for TileTags {
+    fn bitor_assign(&mut self, other: TileTags) {
+        self.insert(other);
+    }
+}
+
+/// Settings of the tags of the tiles, the distances defining them
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct TagSettings {
+    /// the max distance, in tiles along any direction, from a street of the tiles in the city
+    pub city_reach: usize,
+    /// the radius, in tiles, of the contaminated area around the reactor
+    pub contamination_radius: usize,
+    /// the min number of steps from the nearest Fire or Lava of the tiles safe to spawn on
+    pub safe_distance: usize,
+}
+
+impl Default for TagSettings {
+    fn default() -> Self {
+        TagSettings {
+            city_reach: 2,
+            contamination_radius: 15,
+            safe_distance: 10,
+        }
+    }
+}
+
+impl TagSettings {
+    /// Creates a new instance of `TagSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `city_reach` - The max distance, in tiles along any direction, from a street of the tiles in the city, by default 2.
+    /// * `contamination_radius` - The radius, in tiles, of the contaminated area around the reactor, by default 15.
+    /// * `safe_distance` - The min number of steps from the nearest Fire or Lava of the tiles safe to spawn on, by default 10.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::tags::{TagSettings, TileTags};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.tag_settings = Some(TagSettings::new(3, 20, 15));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// let safe_tiles = artifacts.tags.unwrap().tiles_with(TileTags::SPAWN_SAFE).count();
+    /// ```
+    pub fn new(city_reach: usize, contamination_radius: usize, safe_distance: usize) -> Self {
+        TagSettings {
+            city_reach,
+            contamination_radius,
+            safe_distance,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::tags::TagSettings;
+    ///
+    /// assert!(TagSettings::default().validate(100).is_empty());
+    /// assert!(!TagSettings::new(2, 500, 10).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.city_reach <= size, "TagSettings::city_reach", || {
+            format!("{} is bigger than the world side of {size} tiles", self.city_reach)
+        });
+        issues.check(self.contamination_radius <= size, "TagSettings::contamination_radius", || {
+            format!("{} is bigger than the world side of {size} tiles", self.contamination_radius)
+        });
+        issues.0
+    }
+}
+
+/// The tags of each tile of a generated world, a grid parallel to the tile matrix
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TagMap {
+    size: usize,
+    // row-major tags
+    tags: Vec<TileTags>,
+}
+
+impl TagMap {
+    /// Tags the tiles of the generated world; the bridges are the tiles paved over water by the streets and the steam
+    /// the water tiles the lava flowed into, those which are not streets, or water, anymore are not tagged.
+    pub(crate) fn build(world: &TileMatrix, bridges: &[Coordinate], steam: &[Coordinate], landmarks: &[Landmark], hazard_map: &HazardMap, settings: &TagSettings) -> Self {
+        let size = world.len();
+        let mut tags = vec![TileTags::NONE; size * size];
+
+        let streets: Vec<bool> = world.iter().flatten().map(|tile| tile.tile_type == TileType::Street).collect();
+        for (i, in_city) in dilate(&streets, size, settings.city_reach).into_iter().enumerate() {
+            if in_city {
+                tags[i].insert(TileTags::IN_CITY);
+            }
+        }
+
+        for c in bridges {
+            if world[c.row][c.col].tile_type == TileType::Street {
+                tags[c.row * size + c.col].insert(TileTags::BRIDGE);
+            }
+        }
+
+        for c in steam {
+            if matches!(world[c.row][c.col].tile_type, TileType::DeepWater | TileType::ShallowWater) {
+                tags[c.row * size + c.col].insert(TileTags::STEAM);
+            }
+        }
+
+        let radius = settings.contamination_radius;
+        for reactor in landmarks.iter().filter(|landmark| landmark.kind == LandmarkKind::Reactor) {
+            let center = reactor.position;
+            for row in center.row.saturating_sub(radius)..(center.row + radius + 1).min(size) {
+                for col in center.col.saturating_sub(radius)..(center.col + radius + 1).min(size) {
+                    if row.abs_diff(center.row).pow(2) + col.abs_diff(center.col).pow(2) <= radius * radius {
+                        tags[row * size + col].insert(TileTags::CONTAMINATED);
+                    }
+                }
+            }
+        }
+
+        // the hazard map is skipped when the time budget is exceeded
+        let rebuilt;
+        let hazard_map = if hazard_map.size() == size {
+            hazard_map
+        } else {
+            rebuilt = HazardMap::build(world);
+            &rebuilt
+        };
+
+        for (row, tiles) in world.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                let tile_tags = &mut tags[row * size + col];
+                if matches!(tile.content, Content::Garbage(_)) {
+                    tile_tags.insert(TileTags::CONTAMINATED);
+                }
+                let far_from_hazards = hazard_map.distance(Coordinate { row, col }).is_none_or(|distance| distance >= settings.safe_distance);
+                if tile.tile_type.properties().walk() && far_from_hazards {
+                    tile_tags.insert(TileTags::SPAWN_SAFE);
+                }
+            }
+        }
+
+        TagMap { size, tags }
+    }
+
+    /// Returns the side of the tagged world
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the tags of the tile, no tag if it is out of the world
+    pub fn get(&self, c: Coordinate) -> TileTags {
+        if c.row >= self.size || c.col >= self.size {
+            return TileTags::NONE;
+        }
+        self.tags[c.row * self.size + c.col]
+    }
+
+    /// Returns the tiles having all the given tags, row by row
+    pub fn tiles_with(&self, tags: TileTags) -> impl Iterator<Item = Coordinate> + '_ {
+        self.tags.iter().enumerate().filter(move |(_, tile_tags)| tile_tags.contains(tags)).map(|(i, _)| Coordinate {
+            row: i / self.size,
+            col: i % self.size,
+        })
+    }
+}
+
+// the tiles at most `reach` tiles away, along rows and columns, from a marked tile: a running count of the marked tiles
+// in the window around each tile, first along the rows, then along the columns
+#[inline(always)]
+fn dilate(marked: &[bool], size: usize, reach: usize) -> Vec<bool> {
+    let along = |marked: &[bool], index: &dyn Fn(usize, usize) -> usize| {
+        let mut dilated = vec![false; size * size];
+        for line in 0..size {
+            let mut count = 0;
+            // the window covers the positions from `pos - reach` to `pos + reach`
+            for pos in 0..size + reach {
+                if pos < size && marked[index(line, pos)] {
+                    count += 1;
+                }
+                if pos > 2 * reach && marked[index(line, pos - 2 * reach - 1)] {
+                    count -= 1;
+                }
+                if pos >= reach {
+                    dilated[index(line, pos - reach)] = count > 0;
+                }
+            }
+        }
+        dilated
+    };
+    let rows = along(marked, &|row, col| row * size + col);
+    along(&rows, &|col, row| row * size + col)
+}
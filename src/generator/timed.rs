@@ -0,0 +1,205 @@
+use rand::Rng;
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+
+use crate::content::quantity::QuantityRange;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, Coordinate};
+
+/// The kind of event which spawns content after the start of the simulation
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TimedEvent {
+    /// Fire breaks out next to the trees, or anywhere it can burn if the world has no trees
+    FireOutbreak,
+    /// Garbage is dumped next to the streets, or anywhere it can lie if the world has no streets
+    GarbageDelivery,
+}
+
+impl TimedEvent {
+    // the content spawned by the event, with the given quantity
+    #[inline(always)]
+    fn content(self, quantity: usize) -> Content {
+        match self {
+            | TimedEvent::FireOutbreak => Content::Fire,
+            | TimedEvent::GarbageDelivery => Content::Garbage(quantity),
+        }
+    }
+
+    // true if the tile is next to what attracts the event
+    #[inline(always)]
+    fn attracted_to(self, world: &TileMatrix, c: Coordinate) -> bool {
+        let size = world.len();
+        (c.row.saturating_sub(1)..(c.row + 2).min(size)).any(|row| {
+            (c.col.saturating_sub(1)..(c.col + 2).min(size)).any(|col| match self {
+                | TimedEvent::FireOutbreak => matches!(world[row][col].content, Content::Tree(_)),
+                | TimedEvent::GarbageDelivery => world[row][col].tile_type == TileType::Street,
+            })
+        })
+    }
+}
+
+/// A recurring event, spawning content on some tiles at each wave
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimedSpawn {
+    /// the kind of event
+    pub event: TimedEvent,
+    /// the tick of the first wave
+    pub first_tick: u64,
+    /// the number of ticks between two waves
+    pub interval: u64,
+    /// the number of waves
+    pub waves: usize,
+    /// the number of tiles the content is spawned on at each wave
+    pub tiles_per_wave: usize,
+    /// the range of the quantity of content on each tile, clamped to the max a tile can hold, ignored by the content
+    /// without a quantity like Fire
+    pub quantity: QuantityRange,
+}
+
+impl TimedSpawn {
+    /// Creates a new instance of `TimedSpawn` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The kind of event.
+    /// * `first_tick` - The tick of the first wave.
+    /// * `interval` - The number of ticks between two waves.
+    /// * `waves` - The number of waves.
+    /// * `tiles_per_wave` - The number of tiles the content is spawned on at each wave.
+    /// * `quantity` - The range of the quantity of content on each tile, both bounds included, ignored by Fire.
+    pub fn new(event: TimedEvent, first_tick: u64, interval: u64, waves: usize, tiles_per_wave: usize, quantity: QuantityRange) -> Self {
+        TimedSpawn {
+            event,
+            first_tick,
+            interval,
+            waves,
+            tiles_per_wave,
+            quantity,
+        }
+    }
+}
+
+/// Settings of the content which is not placed in the generated world but spawned while the simulation runs,
+/// like fire outbreaks and garbage deliveries.
+///
+/// The generator schedules the events on the tiles of the generated world which can hold their content and are
+/// free, each tile at most once, so a simulation can apply the schedule tick by tick; the schedule is in the
+/// `timed_spawns` of the generation artifacts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimedSpawnSettings {
+    /// the recurring events
+    pub spawns: Vec<TimedSpawn>,
+}
+
+impl TimedSpawnSettings {
+    /// Custom version of default that provides an instance of `TimedSpawnSettings` with
+    /// reasonable parameters for the given world size: a fire outbreak every 100 ticks and
+    /// a garbage delivery every 50 ticks
+    pub fn default(size: usize) -> Self {
+        TimedSpawnSettings {
+            spawns: vec![
+                TimedSpawn::new(TimedEvent::FireOutbreak, 100, 100, 10, (size / 100).max(1), QuantityRange::new(0, 0)),
+                TimedSpawn::new(TimedEvent::GarbageDelivery, 50, 50, 20, (size / 50).max(1), QuantityRange::full(&Content::Garbage(0))),
+            ],
+        }
+    }
+
+    /// Creates a new instance of `TimedSpawnSettings` with the given events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::quantity::QuantityRange;
+    /// use exclusion_zone::generator::timed::{TimedEvent, TimedSpawn, TimedSpawnSettings};
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(300);
+    /// // a single outbreak of 5 fires at tick 1000
+    /// let outbreak = TimedSpawn::new(TimedEvent::FireOutbreak, 1000, 0, 1, 5, QuantityRange::new(0, 0));
+    /// generator.timed_spawn_settings = Some(TimedSpawnSettings::new(vec![outbreak]));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// assert!(artifacts.timed_spawns.iter().all(|(tick, ..)| *tick == 1000));
+    /// ```
+    pub fn new(spawns: Vec<TimedSpawn>) -> Self {
+        TimedSpawnSettings { spawns }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::content::quantity::QuantityRange;
+    /// use exclusion_zone::generator::timed::{TimedEvent, TimedSpawn, TimedSpawnSettings};
+    ///
+    /// assert!(TimedSpawnSettings::default(100).validate().is_empty());
+    /// // all the waves at the same tick
+    /// let outbreaks = TimedSpawn::new(TimedEvent::FireOutbreak, 10, 0, 3, 1, QuantityRange::new(0, 0));
+    /// assert!(!TimedSpawnSettings::new(vec![outbreaks]).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        for spawn in &self.spawns {
+            issues.check(spawn.waves <= 1 || spawn.interval > 0, "TimedSpawn::interval", || {
+                format!("the {} waves of {:?} cannot all happen at tick {}", spawn.waves, spawn.event, spawn.first_tick)
+            });
+            if spawn.event == TimedEvent::GarbageDelivery {
+                issues.quantity("TimedSpawn::quantity", &spawn.quantity);
+            }
+        }
+        issues.0
+    }
+}
+
+/// Schedules the events on the generated world, returning the spawns sorted by tick.
+///
+/// The tiles are drawn among the free tiles which can hold the content, preferring those next to what attracts the
+/// event; the robot spawn point is never picked. The waves which find no free tile left are cut short.
+pub(crate) fn schedule_timed_spawns(world: &TileMatrix, robot_spawn: Coordinate, settings: &TimedSpawnSettings) -> Vec<(u64, Coordinate, Content)> {
+    let size = world.len();
+    let mut rng = generation_rng();
+    // the tiles already scheduled, the content of two events cannot share a tile
+    let mut taken = vec![vec![false; size]; size];
+    taken[robot_spawn.row][robot_spawn.col] = true;
+    let mut schedule = Vec::new();
+
+    for spawn in &settings.spawns {
+        let content = spawn.event.content(0);
+        let per_tile = spawn.quantity.clamped(&content);
+        let free: Vec<Coordinate> = (0..size)
+            .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+            .filter(|c| matches!(world[c.row][c.col].content, Content::None) && world[c.row][c.col].tile_type.properties().can_hold(&content))
+            .collect();
+        let (mut attracted, mut others): (Vec<Coordinate>, Vec<Coordinate>) = free.into_iter().partition(|c| spawn.event.attracted_to(world, *c));
+
+        for wave in 0..spawn.waves {
+            let tick = spawn.first_tick.saturating_add(spawn.interval.saturating_mul(wave as u64));
+            for _ in 0..spawn.tiles_per_wave {
+                let Some(c) = draw(&mut attracted, &taken, &mut rng).or_else(|| draw(&mut others, &taken, &mut rng)) else {
+                    break;
+                };
+                taken[c.row][c.col] = true;
+                // the fire has no quantity, no value is drawn for it
+                let quantity = if spawn.event == TimedEvent::FireOutbreak { 0 } else { per_tile.sample(&mut rng) };
+                schedule.push((tick, c, spawn.event.content(quantity)));
+            }
+        }
+    }
+
+    // stable, the spawns of the same tick keep the order of the settings
+    schedule.sort_by_key(|(tick, ..)| *tick);
+    schedule
+}
+
+// removes a random tile not taken from the candidates, dropping the taken ones drawn before it
+#[inline(always)]
+fn draw(candidates: &mut Vec<Coordinate>, taken: &[Vec<bool>], rng: &mut impl Rng) -> Option<Coordinate> {
+    while !candidates.is_empty() {
+        let c = candidates.swap_remove(rng.gen_range(0..candidates.len()));
+        if !taken[c.row][c.col] {
+            return Some(c);
+        }
+    }
+    None
+}
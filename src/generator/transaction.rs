@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use robotics_lib::world::tile::Content;
+
+use crate::generator::occupancy::Occupancy;
+use crate::generator::spawn_report::SkipReason;
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// A multi-tile placement, like a blob of trees or a pile of garbage, staged tile by tile and committed only if the
+/// occupancy accepts the content on every tile, so that the structure is placed whole or not at all instead of being
+/// cut where it meets a tile it cannot take.
+///
+/// The tiles are checked against the world as it is when they are staged, so the world must not change between the
+/// first `stage` and the `commit`.
+#[derive(Default)]
+pub(crate) struct WorldTransaction {
+    staged: Vec<(Coordinate, Content)>,
+    // [coordinate] -> the index of the tile in `staged`
+    indexes: HashMap<Coordinate, usize>,
+    // the number of tiles which did not accept their content
+    rejected: usize,
+}
+
+impl WorldTransaction {
+    /// Stages the content on the tile, replacing the content staged before on the same tile; returns false, and dooms
+    /// the transaction, if the occupancy would not place the content on the tile
+    pub(crate) fn stage(&mut self, world: &TileMatrix, occupancy: &Occupancy, c: Coordinate, content: Content) -> bool {
+        if !occupancy.accepts(world, c, &content) {
+            self.rejected += 1;
+            return false;
+        }
+        match self.indexes.get(&c) {
+            | Some(&index) => self.staged[index].1 = content,
+            | None => {
+                self.indexes.insert(c, self.staged.len());
+                self.staged.push((c, content));
+            }
+        }
+        true
+    }
+
+    /// Places the staged content and returns true if every tile accepted it, otherwise places nothing, records the
+    /// tiles in the report of the spawn stage as skipped and returns false
+    pub(crate) fn commit(self, world: &mut TileMatrix, occupancy: &mut Occupancy) -> bool {
+        if self.rejected > 0 {
+            occupancy.skip(SkipReason::Occupied, self.rejected);
+            occupancy.skip(SkipReason::Incomplete, self.staged.len());
+            return false;
+        }
+        for (c, content) in self.staged {
+            occupancy.place(world, c, content);
+        }
+        true
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::quantity::QuantityRange;
+use crate::generator::{WorldGenerator, SMALL_WORLD_MIN_SIZE};
+
+/// A problem found in the settings, which would make the generation panic, hang or give a degenerate world
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SettingsIssue {
+    /// the setting with the problem, as `Settings::field`
+    pub setting: String,
+    /// what is wrong with the setting and how to fix it
+    pub message: String,
+}
+
+impl Display for SettingsIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.setting, self.message)
+    }
+}
+
+// collects the issues found by the validate methods of the settings
+#[derive(Default)]
+pub(crate) struct Issues(pub(crate) Vec<SettingsIssue>);
+
+impl Issues {
+    #[inline(always)]
+    pub(crate) fn check(&mut self, valid: bool, setting: &str, message: impl FnOnce() -> String) {
+        if !valid {
+            self.0.push(SettingsIssue {
+                setting: setting.to_string(),
+                message: message(),
+            });
+        }
+    }
+
+    // the range is sampled, so it must hold at least a value
+    #[inline(always)]
+    pub(crate) fn not_empty(&mut self, setting: &str, range: &Range<usize>) {
+        self.check(!range.is_empty(), setting, || {
+            format!("the range {range:?} is empty, the end is excluded so use at least {}..{}", range.start, range.start + 1)
+        });
+    }
+
+    #[inline(always)]
+    pub(crate) fn probability(&mut self, setting: &str, value: f64) {
+        self.check((0.0..=1.0).contains(&value), setting, || format!("{value} is not a probability, use a value between 0 and 1"));
+    }
+
+    // a range placing no content cannot be told apart from a typo, the max above what a tile holds is clamped instead
+    #[inline(always)]
+    pub(crate) fn quantity(&mut self, setting: &str, range: &QuantityRange) {
+        self.check(range.min >= 1 && !range.is_empty(), setting, || {
+            format!("the range {range} places no content, use a min of at least 1 and not above the max")
+        });
+    }
+
+    // more spawn points than tiles cannot be told apart from a typo
+    #[inline(always)]
+    pub(crate) fn fits(&mut self, setting: &str, count: usize, size: usize) {
+        self.check(count <= size * size, setting, || format!("{count} is more than the {} tiles of a {size}x{size} world", size * size));
+    }
+}
+
+impl WorldGenerator {
+    /// Checks every setting of the generator, collecting all the issues instead of stopping at the first one.
+    ///
+    /// The generation runs the same checks before starting and panics listing the issues, rather than
+    /// panicking or hanging midway.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.garbage_settings.garbage_pile_size = 5..5;
+    /// if let Err(issues) = generator.validate_all() {
+    ///     for issue in issues {
+    ///         println!("{issue}");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the issues found, each naming the setting and how to fix it.
+    pub fn validate_all(&self) -> Result<(), Vec<SettingsIssue>> {
+        let size = self.size;
+        let mut issues = Issues::default();
+        let min_size = self.generation_options.min_world_size();
+        issues.check(size >= min_size, "WorldGenerator::size", || {
+            if self.generation_options.small_world_mode {
+                format!("{size} is too small, the world size must be at least {min_size}")
+            } else {
+                format!("{size} is too small, the world size must be at least {min_size}, or {SMALL_WORLD_MIN_SIZE} in small world mode")
+            }
+        });
+        let border = self.thresholds.border.width();
+        issues.check(border * 2 < size, "Thresholds::border", || format!("a border {border} tiles wide leaves nothing of a {size}x{size} world"));
+
+        let mut all = vec![
+            issues.0,
+            self.noise_settings.validate(),
+            self.thresholds.validate(),
+            self.lava_settings.validate(size),
+            self.bank_settings.validate(size),
+            self.bin_settings.validate(size),
+            self.crate_settings.validate(size),
+            self.garbage_settings.validate(size),
+            self.fire_settings.validate(),
+            self.tree_settings.validate(),
+            self.coin_settings.validate(size),
+            self.market_settings.validate(size),
+            self.fish_settings.validate(size),
+            self.rock_settings.validate(size),
+            self.street_settings.validate(size),
+            self.weather_settings.validate(),
+        ];
+        all.extend(self.score_balance.as_ref().map(|settings| settings.validate()));
+        all.extend(self.landmark_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.slope_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.region_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.quantity_field_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.placement_masks.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.vault_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.tag_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.constraint_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.timed_spawn_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.contamination_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.spawn_view_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.flow_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.fence_settings.as_ref().map(|settings| settings.validate(size)));
+        all.extend(self.decor_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.access_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.wind_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.water_source_settings.as_ref().map(|settings| settings.validate()));
+        all.extend(self.extra_content.iter().map(|extra| extra.validate(size)));
+
+        let all: Vec<SettingsIssue> = all.into_iter().flatten().collect();
+        if all.is_empty() {
+            Ok(())
+        } else {
+            Err(all)
+        }
+    }
+}
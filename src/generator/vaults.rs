@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use rand::seq::SliceRandom;
+use robotics_lib::world::tile::Content;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::reachability::neighbours;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, Coordinate};
+
+// number of vault positions tried for each pair before giving up
+const VAULT_ATTEMPTS: usize = 20;
+
+/// A vault and the key opening it, placed for a bank robbery scenario
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VaultPair {
+    /// the tile of the vault, a Bank with the highest capacity
+    pub vault: Coordinate,
+    /// the tile of the key, a JollyBlock
+    pub key: Coordinate,
+    /// the length, in tiles, of the shortest walkable path between the vault and the key
+    pub path_length: usize,
+}
+
+/// Settings of the bank robbery scenario: pairs of a vault, a Bank with the highest capacity, and of its key,
+/// a JollyBlock placed at a given walking distance from it, so that quest-like evaluations can be generated procedurally.
+///
+/// The pairs are placed once the content is spawned, on walkable empty tiles, and returned in the generation artifacts.
+/// The path length is measured along the walkable tiles sharing a side, the key is always reachable from its vault.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct VaultSettings {
+    /// the number of vault and key pairs
+    pub pairs: usize,
+    /// the shortest walkable path allowed between a vault and its key
+    pub min_path_length: usize,
+    /// the longest walkable path allowed between a vault and its key
+    pub max_path_length: usize,
+}
+
+impl VaultSettings {
+    /// Custom version of default that provides an instance of `VaultSettings` with
+    /// reasonable parameters for the given world size
+    pub fn default(size: usize) -> Self {
+        VaultSettings {
+            pairs: 3,
+            min_path_length: (size / 10).max(1),
+            max_path_length: (size / 4).max(1),
+        }
+    }
+
+    /// Creates a new instance of `VaultSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - The number of vault and key pairs.
+    /// * `min_path_length` - The shortest walkable path allowed between a vault and its key.
+    /// * `max_path_length` - The longest walkable path allowed between a vault and its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::vaults::VaultSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // a single vault, its key between 100 and 200 steps away
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.vault_settings = Some(VaultSettings::new(1, 100, 200));
+    /// ```
+    pub fn new(pairs: usize, min_path_length: usize, max_path_length: usize) -> Self {
+        VaultSettings {
+            pairs,
+            min_path_length,
+            max_path_length,
+        }
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::vaults::VaultSettings;
+    ///
+    /// // the key would be closer than the min distance allowed
+    /// assert!(!VaultSettings::new(1, 50, 20).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        // each pair takes two tiles
+        issues.fits("VaultSettings::pairs", self.pairs * 2, size);
+        issues.check(self.min_path_length >= 1, "VaultSettings::min_path_length", || {
+            "the key cannot be on the tile of its vault, use at least 1".to_string()
+        });
+        issues.check(self.min_path_length <= self.max_path_length, "VaultSettings::max_path_length", || {
+            format!("{} is shorter than the min path length {}", self.max_path_length, self.min_path_length)
+        });
+        issues.0
+    }
+}
+
+/// Places the vault and key pairs, returning where they have been placed.
+///
+/// Each vault is tried on up to 20 random walkable empty tiles, its key is placed on a random walkable
+/// empty tile whose path length from the vault is within the limits; the pairs that do not fit are skipped.
+pub(crate) fn spawn_vaults(world: &mut TileMatrix, settings: &VaultSettings) -> Vec<VaultPair> {
+    let size = world.len();
+    let mut rng = generation_rng();
+    let vault = Content::Bank(0..0);
+    let key = Content::JollyBlock(0);
+
+    let mut candidates: Vec<Coordinate> = (0..size)
+        .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+        .filter(|c| is_free(world, *c, &vault))
+        .collect();
+    candidates.shuffle(&mut rng);
+
+    let mut pairs = Vec::with_capacity(settings.pairs);
+    while pairs.len() < settings.pairs {
+        let mut attempts = 0;
+        let pair = loop {
+            if attempts == VAULT_ATTEMPTS {
+                break None;
+            }
+            let Some(v) = candidates.pop() else {
+                break None;
+            };
+            attempts += 1;
+            // an earlier pair may have taken the tile
+            if !is_free(world, v, &vault) {
+                continue;
+            }
+
+            let keys: Vec<(Coordinate, usize)> = path_lengths(world, v, settings.max_path_length)
+                .into_iter()
+                .filter(|(c, length)| *length >= settings.min_path_length && is_free(world, *c, &key))
+                .collect();
+            if let Some(&(k, path_length)) = keys.choose(&mut rng) {
+                break Some(VaultPair {
+                    vault: v,
+                    key: k,
+                    path_length,
+                });
+            }
+        };
+
+        let Some(pair) = pair else {
+            warn!(
+                placed = pairs.len(),
+                requested = settings.pairs,
+                min_path_length = settings.min_path_length,
+                max_path_length = settings.max_path_length,
+                "VaultNotPlaced: no key fits within the path lengths from the vaults tried"
+            );
+            break;
+        };
+        world[pair.vault.row][pair.vault.col].content = Content::Bank(1..vault.properties().max().max(2));
+        world[pair.key.row][pair.key.col].content = Content::JollyBlock(1);
+        pairs.push(pair);
+    }
+    pairs
+}
+
+// a walkable tile without content whose tile type can hold the content
+#[inline(always)]
+fn is_free(world: &TileMatrix, c: Coordinate, content: &Content) -> bool {
+    let tile = &world[c.row][c.col];
+    tile.content == Content::None && tile.tile_type.properties().walk() && tile.tile_type.properties().can_hold(content)
+}
+
+// breadth first search of the walkable tiles within the max length from the start, with their path length
+#[inline(always)]
+fn path_lengths(world: &TileMatrix, start: Coordinate, max_length: usize) -> Vec<(Coordinate, usize)> {
+    let size = world.len();
+    let mut visited = vec![vec![false; size]; size];
+    let mut reached = Vec::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    visited[start.row][start.col] = true;
+
+    while let Some((current, length)) = queue.pop_front() {
+        reached.push((current, length));
+        if length == max_length {
+            continue;
+        }
+        for next in neighbours(current, size) {
+            if !visited[next.row][next.col] && world[next.row][next.col].tile_type.properties().walk() {
+                visited[next.row][next.col] = true;
+                queue.push_back((next, length + 1));
+            }
+        }
+    }
+    reached
+}
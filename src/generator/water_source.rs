@@ -0,0 +1,167 @@
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::generator::reachability::neighbours;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::Coordinate;
+
+/// Settings of the water source guaranteed near the robot spawn: a ShallowWater tile within the given distance from
+/// the spawn, with Water content on it or next to it, so that the tasks collecting water can start without crossing
+/// the world.
+///
+/// The nearest ShallowWater tile within the distance is the source if any, otherwise the nearest empty tile which can
+/// be dug, a DeepWater tile or a tile of natural land, is turned into ShallowWater; the streets, the lava, the walls
+/// and the teleports are left untouched.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WaterSourceSettings {
+    /// the max distance of the source from the spawn, in tiles moving between the tiles sharing a side
+    pub max_distance: usize,
+    /// true to place Water content on the source, or next to it if the source cannot hold it, unless some is already
+    /// within the distance
+    pub water_content: bool,
+}
+
+impl Default for WaterSourceSettings {
+    /// Provides an instance of `WaterSourceSettings` with a source at most 10 tiles from the spawn, holding Water content
+    fn default() -> Self {
+        WaterSourceSettings {
+            max_distance: 10,
+            water_content: true,
+        }
+    }
+}
+
+impl WaterSourceSettings {
+    /// Creates a new instance of `WaterSourceSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_distance` - The max distance of the source from the spawn, in tiles moving between the tiles sharing a side.
+    /// * `water_content` - True to place Water content on the source or next to it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use exclusion_zone::generator::water_source::WaterSourceSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // water at most 5 tiles from the spawn
+    /// let mut generator = WorldGenerator::default(500);
+    /// generator.water_source_settings = Some(WaterSourceSettings::new(5, true));
+    /// let (_, artifacts) = generator.gen_with_artifacts();
+    /// if let Some(source) = artifacts.water_source {
+    ///     println!("water at {:?}, {} tiles from the spawn", source.source, source.distance);
+    /// }
+    /// ```
+    pub fn new(max_distance: usize, water_content: bool) -> Self {
+        WaterSourceSettings {
+            max_distance,
+            water_content,
+        }
+    }
+
+    /// Checks the settings, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::generator::water_source::WaterSourceSettings;
+    ///
+    /// assert!(WaterSourceSettings::default().validate().is_empty());
+    /// // the source would be the spawn itself
+    /// assert!(!WaterSourceSettings::new(0, true).validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check(self.max_distance > 0, "WaterSourceSettings::max_distance", || {
+            "the source cannot be the tile of the spawn, use a distance of at least 1".to_string()
+        });
+        issues.0
+    }
+}
+
+/// The water source near the robot spawn
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WaterSource {
+    /// the ShallowWater tile
+    pub source: Coordinate,
+    /// the distance of the source from the spawn, in tiles moving between the tiles sharing a side
+    pub distance: usize,
+    /// true if the tile has been turned into ShallowWater, false if it already was
+    pub dug: bool,
+    /// the tile with the Water content near the spawn, `None` if the content is disabled or no tile next to the source
+    /// can hold it
+    pub water: Option<Coordinate>,
+}
+
+// the tile types turned into ShallowWater when no source is near the spawn, the built and the volcanic ones are kept
+#[inline(always)]
+fn can_dig(tile_type: &TileType) -> bool {
+    matches!(tile_type, TileType::DeepWater | TileType::Sand | TileType::Grass | TileType::Hill | TileType::Mountain | TileType::Snow)
+}
+
+// the tiles within the distance from the spawn, the nearest first and row by row at the same distance
+#[inline(always)]
+fn near_spawn(spawn: Coordinate, max_distance: usize, size: usize) -> Vec<(usize, Coordinate)> {
+    let rows = spawn.row.saturating_sub(max_distance)..(spawn.row + max_distance + 1).min(size);
+    let cols = spawn.col.saturating_sub(max_distance)..(spawn.col + max_distance + 1).min(size);
+    let mut tiles: Vec<(usize, Coordinate)> = rows
+        .flat_map(|row| cols.clone().map(move |col| Coordinate { row, col }))
+        .map(|c| (c.row.abs_diff(spawn.row) + c.col.abs_diff(spawn.col), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    tiles.sort_by_key(|(distance, c)| (*distance, c.row, c.col));
+    tiles
+}
+
+/// Makes sure a ShallowWater tile is within the distance of the settings from the spawn, digging the nearest tile
+/// which can be dug if none is, then places the Water content on it or next to it if required.
+///
+/// Returns `None` if no tile near the spawn is ShallowWater or can be dug.
+pub(crate) fn ensure_water_source(world: &mut TileMatrix, spawn: Coordinate, settings: &WaterSourceSettings) -> Option<WaterSource> {
+    let size = world.len();
+    let tiles = near_spawn(spawn, settings.max_distance, size);
+
+    let shallow = tiles.iter().find(|(_, c)| world[c.row][c.col].tile_type == TileType::ShallowWater);
+    // the robot keeps the tile it spawns on
+    let dig = || {
+        tiles
+            .iter()
+            .find(|(distance, c)| *distance > 0 && can_dig(&world[c.row][c.col].tile_type) && world[c.row][c.col].content == Content::None)
+    };
+    let Some(&(distance, source)) = shallow.or_else(dig) else {
+        warn!(
+            max_distance = settings.max_distance,
+            "WaterSourceNotFound: no tile near the spawn can be dug, the robot spawns without water"
+        );
+        return None;
+    };
+    let dug = world[source.row][source.col].tile_type != TileType::ShallowWater;
+    world[source.row][source.col].tile_type = TileType::ShallowWater;
+
+    let water = settings.water_content.then(|| place_water(world, &tiles, spawn, source)).flatten();
+    Some(WaterSource {
+        source,
+        distance,
+        dug,
+        water,
+    })
+}
+
+// the Water content already near the spawn, or a full one placed on the source or on the first of its empty neighbours
+// which can hold it, the robot keeps an empty tile to spawn on
+#[inline(always)]
+fn place_water(world: &mut TileMatrix, tiles: &[(usize, Coordinate)], spawn: Coordinate, source: Coordinate) -> Option<Coordinate> {
+    if let Some(&(_, c)) = tiles.iter().find(|(_, c)| matches!(world[c.row][c.col].content, Content::Water(_))) {
+        return Some(c);
+    }
+
+    let water = Content::Water(0);
+    let c = std::iter::once(source)
+        .chain(neighbours(source, world.len()))
+        .find(|c| *c != spawn && world[c.row][c.col].content == Content::None && world[c.row][c.col].tile_type.properties().can_hold(&water))?;
+    world[c.row][c.col].content = Content::Water(water.properties().max());
+    Some(c)
+}
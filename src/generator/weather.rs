@@ -0,0 +1,172 @@
+use rand::seq::SliceRandom;
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::environmental_conditions::WeatherType::{self, Foggy, Rainy, Sunny, TrentinoSnow, TropicalMonsoon};
+use robotics_lib::world::tile::Content;
+use robotics_lib::world::tile::TileType;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::content::fire::FireSettings;
+use crate::content::quantity::QuantityRange;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::{default_environmental_conditions, Thresholds, TileMatrix};
+use crate::utils::{generation_rng, Coordinate};
+
+/// The weather forecast of the world and how it shapes the generation.
+///
+/// A forecast dominated by rain, `Rainy` and `TropicalMonsoon` in more than half of its entries, extends the
+/// shallow water over the sand, scatters water on the land and makes fires fewer; a forecast dominated by
+/// `TrentinoSnow` extends the snow over the mountains. The stronger the dominance, the stronger the effect, so that the
+/// seed and the weather fully define the character of the map. Forecasts without a dominant weather, like the default one,
+/// leave the generation unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WeatherSettings {
+    /// the weather forecast cycled by the robot as time passes
+    pub forecast: Vec<WeatherType>,
+    /// the minutes passing at each tick of the robot
+    pub time_progression_minutes: u8,
+    /// the hour of the day at which the robot starts
+    pub time_of_day_start: u8,
+}
+
+impl Default for WeatherSettings {
+    fn default() -> Self {
+        WeatherSettings {
+            forecast: vec![Rainy, Sunny, Foggy, TropicalMonsoon, TrentinoSnow],
+            time_progression_minutes: 15,
+            time_of_day_start: 9,
+        }
+    }
+}
+
+impl WeatherSettings {
+    /// Creates a new instance of `WeatherSettings` with the given forecast.
+    ///
+    /// # Arguments
+    ///
+    /// * `forecast` - The weather forecast cycled by the robot as time passes.
+    /// * `time_progression_minutes` - The minutes passing at each tick of the robot, by default 15.
+    /// * `time_of_day_start` - The hour of the day at which the robot starts, by default 9.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use robotics_lib::world::environmental_conditions::WeatherType::{Rainy, TropicalMonsoon};
+    /// use exclusion_zone::generator::weather::WeatherSettings;
+    /// use exclusion_zone::generator::WorldGenerator;
+    ///
+    /// // a wet world, with more water and fewer fires
+    /// let mut generator = WorldGenerator::default(1000);
+    /// generator.weather_settings = WeatherSettings::new(vec![Rainy, TropicalMonsoon, Rainy], 15, 9);
+    /// ```
+    pub fn new(forecast: Vec<WeatherType>, time_progression_minutes: u8, time_of_day_start: u8) -> Self {
+        WeatherSettings {
+            forecast,
+            time_progression_minutes,
+            time_of_day_start,
+        }
+    }
+
+    /// Returns the environmental conditions the robot starts with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the forecast is empty or the time of the day is not valid.
+    pub fn environmental_conditions(&self) -> Result<EnvironmentalConditions, String> {
+        EnvironmentalConditions::new(&self.forecast, self.time_progression_minutes, self.time_of_day_start)
+    }
+
+    // the environmental conditions of the settings, the default ones if they are not valid
+    pub(crate) fn environmental_conditions_or_default(&self) -> EnvironmentalConditions {
+        self.environmental_conditions().unwrap_or_else(|e| {
+            warn!(error = %e, "InvalidWeatherSettings: the robot starts with the default weather");
+            default_environmental_conditions()
+        })
+    }
+
+    /// Checks the settings, returning the issues found.
+    pub fn validate(&self) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        if let Err(e) = self.environmental_conditions() {
+            issues.check(false, "WeatherSettings", || format!("the robot cannot start with this weather: {e}"));
+        }
+        issues.0
+    }
+
+    // how much the given weathers dominate the forecast, from 0 when they are at most half of it to 1 when they are all of it
+    #[inline(always)]
+    fn dominance(&self, weathers: &[WeatherType]) -> f64 {
+        if self.forecast.is_empty() {
+            return 0.0;
+        }
+        let share = self.forecast.iter().filter(|w| weathers.contains(w)).count() as f64 / self.forecast.len() as f64;
+        ((share - 0.5) * 2.0).max(0.0)
+    }
+
+    #[inline(always)]
+    fn rain(&self) -> f64 {
+        self.dominance(&[Rainy, TropicalMonsoon])
+    }
+
+    #[inline(always)]
+    fn snow(&self) -> f64 {
+        self.dominance(&[TrentinoSnow])
+    }
+
+    // the fire settings with fewer fires the rainier the forecast, no fire at all in a world of only rain
+    pub(crate) fn adjust_fire(&self, fire_settings: &FireSettings) -> FireSettings {
+        fire_settings.scaled(1.0 - self.rain())
+    }
+}
+
+/// Extends the shallow water over the sand and the snow over the mountains following the forecast.
+///
+/// The tile types are assigned again with thresholds moved toward the next band, up to all of it,
+/// but only the sand that becomes shallow water and the mountains that become snow are changed.
+pub(crate) fn apply_weather_to_terrain(world: &mut TileMatrix, elevation_map: &[Vec<f64>], thresholds: &Thresholds, weather: &WeatherSettings) {
+    let (rain, snow) = (weather.rain(), weather.snow());
+    if rain == 0.0 && snow == 0.0 {
+        return;
+    }
+
+    let shifted = Thresholds {
+        threshold_shallow_water: thresholds.threshold_shallow_water + rain * (thresholds.threshold_sand - thresholds.threshold_shallow_water),
+        threshold_mountain: thresholds.threshold_mountain - snow * (thresholds.threshold_mountain - thresholds.threshold_hill),
+        ..*thresholds
+    };
+    let (min, max) = elevation_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), &v| (min.min(v), max.max(v)));
+    // the equalized thresholds are moved in the share of the tiles
+    let cuts = shifted.resolve(elevation_map, min, max).cuts(min, max);
+
+    for (tiles, values) in world.iter_mut().zip(elevation_map) {
+        for (tile, &value) in tiles.iter_mut().zip(values) {
+            match (&tile.tile_type, cuts.classify(value)) {
+                | (TileType::Sand, TileType::ShallowWater) => tile.tile_type = TileType::ShallowWater,
+                | (TileType::Mountain, TileType::Snow) => tile.tile_type = TileType::Snow,
+                | _ => {}
+            }
+        }
+    }
+}
+
+/// Scatters water on the free tiles that can hold it, up to a tile every hundred in a world of only rain
+pub(crate) fn spawn_weather_content(world: &mut TileMatrix, occupancy: &mut Occupancy, weather: &WeatherSettings) {
+    let size = world.len();
+    let number_of_spawn_points = (weather.rain() * usize::pow(size, 2) as f64 / 100.0) as usize;
+    if number_of_spawn_points == 0 {
+        return;
+    }
+
+    // the candidates are listed rather than drawn at random, the tile types holding water may be rare or missing
+    let mut candidates: Vec<Coordinate> = (0..size)
+        .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+        .filter(|c| occupancy.can_hold(*c, &Content::Water(0)) && occupancy.is_empty(*c))
+        .collect();
+    candidates.shuffle(&mut generation_rng());
+
+    let quantity = QuantityRange::full(&Content::Water(0));
+    for c in candidates.into_iter().take(number_of_spawn_points) {
+        occupancy.place(world, c, Content::Water(quantity.sample(&mut generation_rng())));
+    }
+}
@@ -0,0 +1,314 @@
+use nannou_core::math::{deg_to_rad, map_range};
+use noise::{NoiseFn, Perlin};
+use rand::Rng;
+
+use crate::utils::Coordinate;
+
+/// Grows a blob, a disc around the center whose border is moved in and out by a noise, over the allowed tiles.
+///
+/// The border is at `radius * (1 ± variation)` from the center, with a point every `step` degrees, see
+/// `blob_outline`; the tiles of the blob which are not allowed are removed, then only the tiles still connected to
+/// the center are kept. Without a step the noisy border is skipped, the blob is a plain disc. The blob must not cross
+/// the border of the world: keep the center at least `radius * (1 + variation) + 1` tiles from it.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use exclusion_zone::placement::blob::grow_blob;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let center = Coordinate::new(20, 20);
+/// let blob = grow_blob(&mut StdRng::seed_from_u64(42), center, 5.0, 0.1, Some(1), |c| c.row() >= 18);
+/// assert!(blob.contains(&center));
+/// assert!(blob.iter().all(|c| c.row() >= 18));
+/// # }
+/// ```
+pub fn grow_blob(rng: &mut impl Rng, center: Coordinate, radius: f32, variation: f32, step: Option<usize>, allowed: impl Fn(Coordinate) -> bool) -> Vec<Coordinate> {
+    // drawn even by the plain disc, so that the following draws do not depend on the step
+    let noise = Perlin::new(rng.gen());
+
+    let mut points = match step {
+        | Some(step) => {
+            let outline = blob_outline(&noise, center, radius, variation, step);
+            match outline_bounds(&outline) {
+                | Some(bounds) => fill_outline(&outline, center, bounds),
+                | None => Vec::new(),
+            }
+        }
+        | None => disc(center, radius),
+    };
+    retain_allowed(&mut points, allowed);
+    keep_connected_to_center(&mut points, center);
+    points
+}
+
+/// Returns the outline of a blob, a point every `step` degrees at a distance from the center moved by the noise
+/// between `radius * (1 - variation)` and `radius * (1 + variation)`; the outline is closed as long as the angle of
+/// a step covers one tile at most at the farthest point, `57 / radius` degrees
+pub fn blob_outline(noise: &Perlin, center: Coordinate, radius: f32, variation: f32, step: usize) -> Vec<Coordinate> {
+    stretched_blob_outline(noise, center, radius, variation, step, usize::MAX, |_, _| 1.0)
+}
+
+/// Returns the outline of a blob like `blob_outline`, with the distance of each point from the center multiplied by
+/// the stretch in its direction, given as the rows and the columns of a unit vector, to elongate the blob.
+///
+/// The points stretched past the border of a world of the given size are moved on the border, so the center only
+/// needs to be `radius * (1 + variation) + 1` tiles from it, as without stretch: the blob is then cut by the border
+/// instead of crossing it.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use noise::Perlin;
+/// use exclusion_zone::placement::blob::stretched_blob_outline;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// // twice as long towards the last column
+/// let outline = stretched_blob_outline(&Perlin::new(42), Coordinate::new(30, 30), 5.0, 0.1, 1, 100, |_, col| 1.0 + col.max(0.0));
+/// assert!(outline.iter().any(|c| c.col() > 37));
+/// assert!(outline.iter().all(|c| c.col() >= 24));
+///
+/// // cut by the last column of a smaller world
+/// let outline = stretched_blob_outline(&Perlin::new(42), Coordinate::new(30, 30), 5.0, 0.1, 1, 37, |_, col| 1.0 + col.max(0.0));
+/// assert!(outline.iter().any(|c| c.col() == 36));
+/// assert!(outline.iter().all(|c| c.col() < 37));
+/// # }
+/// ```
+pub fn stretched_blob_outline(noise: &Perlin, center: Coordinate, radius: f32, variation: f32, step: usize, size: usize, stretch: impl Fn(f32, f32) -> f32) -> Vec<Coordinate> {
+    // the last row and column of the world, the farthest a point can be moved
+    let last = (size - 1) as f32;
+    (0..=360)
+        .step_by(step)
+        .map(|i| {
+            // Map over an array of integers from 0 to 360 to represent the degrees in a circle.
+            // Convert each degree to radians.
+            let radian = deg_to_rad(i as f32);
+            // Get the sine of the radian to find the x co-ordinate of this point of the circle
+            // and multiply it by the radius.
+            let xoff = (radian.cos() + 1.0) as f64;
+            let yoff = (radian.sin() + 1.0) as f64;
+
+            let r = map_range(noise.get([xoff, yoff]), 0.0, 1.0, radius * (1. - variation), radius * (1. + variation)) * stretch(radian.sin(), radian.cos());
+            let relative_x = radian.cos() * r;
+            let relative_y = radian.sin() * r;
+
+            let border_x = (center.col as f32 + relative_x).clamp(0.0, last) as usize;
+            let border_y = (center.row as f32 + relative_y).clamp(0.0, last) as usize;
+
+            Coordinate {
+                row: border_y,
+                col: border_x,
+            }
+        })
+        .collect()
+}
+
+/// Returns the min row, the min column, the max row and the max column of the outline, `None` if it is empty
+#[inline(always)]
+pub fn outline_bounds(outline: &[Coordinate]) -> Option<(usize, usize, usize, usize)> {
+    let min_col = outline.iter().map(|f| f.col).min()?;
+    let max_col = outline.iter().map(|f| f.col).max()?;
+    let min_row = outline.iter().map(|f| f.row).min()?;
+    let max_row = outline.iter().map(|f| f.row).max()?;
+
+    Some((min_row, min_col, max_row, max_col))
+}
+
+/// Spreads from the center to the outline, returning the outline and the tiles inside it.
+///
+/// The bounds are the min row, the min column, the max row and the max column of the outline, as returned by
+/// `outline_bounds`; the center must be within them.
+pub fn fill_outline(outline: &[Coordinate], center: Coordinate, bounds: (usize, usize, usize, usize)) -> Vec<Coordinate> {
+    let (upper_border, left_border, lower_border, righter_border) = bounds;
+    let rect_width = righter_border - left_border + 1;
+    let rect_height = lower_border - upper_border + 1;
+    //marking `border_points` as already visited
+    let mut visited: Vec<Vec<bool>> = vec![vec![false; rect_width]; rect_height];
+
+    // set as contained and visited all the border points
+    for point in outline {
+        let x = point.col - left_border;
+        let y = point.row - upper_border;
+        visited[y][x] = true;
+    }
+
+    let mut stack: Vec<Coordinate> = Vec::new();
+    stack.push(Coordinate {
+        row: center.row - upper_border,
+        col: center.col - left_border,
+    });
+    // mark center as visited
+    visited[center.row - upper_border][center.col - left_border] = true;
+    while let Some(current) = stack.pop() {
+        let x = current.col;
+        let y = current.row;
+
+        // upper left
+        if x > 0 && y > 0 && !visited[y - 1][x - 1] && !visited[y - 1][x] && !visited[y][x - 1] {
+            visited[y - 1][x - 1] = true;
+            stack.push(Coordinate {
+                row: y - 1,
+                col: x - 1,
+            });
+        }
+        // upper center
+        if y > 0 && !visited[y - 1][x] {
+            visited[y - 1][x] = true;
+            stack.push(Coordinate { row: y - 1, col: x });
+        }
+        // upper right
+        if x < rect_width - 1 && y > 0 && !visited[y - 1][x + 1] && !visited[y - 1][x] && !visited[y][x + 1] {
+            visited[y - 1][x + 1] = true;
+            stack.push(Coordinate {
+                row: y - 1,
+                col: x + 1,
+            });
+        }
+        // right center
+        if x < rect_width - 1 && !visited[y][x + 1] {
+            visited[y][x + 1] = true;
+            stack.push(Coordinate { row: y, col: x + 1 });
+        }
+        // lower right
+        if x < rect_width - 1 && y < rect_height - 1 && !visited[y + 1][x + 1] && !visited[y + 1][x] && !visited[y][x + 1] {
+            visited[y + 1][x + 1] = true;
+            stack.push(Coordinate {
+                row: y + 1,
+                col: x + 1,
+            });
+        }
+        // lower center
+        if y < rect_height - 1 && !visited[y + 1][x] {
+            visited[y + 1][x] = true;
+            stack.push(Coordinate { row: y + 1, col: x });
+        }
+        // lower left
+        if x > 0 && y < rect_height - 1 && !visited[y + 1][x - 1] && !visited[y + 1][x] && !visited[y][x - 1] {
+            visited[y + 1][x - 1] = true;
+            stack.push(Coordinate {
+                row: y + 1,
+                col: x - 1,
+            });
+        }
+        // left center
+        if x > 0 && !visited[y][x - 1] {
+            visited[y][x - 1] = true;
+            stack.push(Coordinate { row: y, col: x - 1 });
+        }
+    }
+
+    let mut points = Vec::new();
+    for (y, row) in visited.iter().enumerate() {
+        for (x, visited) in row.iter().enumerate() {
+            if *visited {
+                points.push(Coordinate {
+                    row: y + upper_border,
+                    col: x + left_border,
+                });
+            }
+        }
+    }
+    points
+}
+
+/// Returns all the tiles within the radius from the center
+pub fn disc(center: Coordinate, radius: f32) -> Vec<Coordinate> {
+    stretched_disc(center, radius, radius, usize::MAX, |_, _| 1.0)
+}
+
+/// Returns all the tiles within the radius from the center, multiplied by the stretch in the direction of each tile
+/// like `stretched_blob_outline`; the reach is the farthest distance from the center, the radius times the highest
+/// stretch, and the tiles past the border of a world of the given size are left out
+pub fn stretched_disc(center: Coordinate, radius: f32, reach: f32, size: usize, stretch: impl Fn(f32, f32) -> f32) -> Vec<Coordinate> {
+    let mut points = Vec::new();
+    let r = reach.ceil() as usize;
+    for row in center.row.saturating_sub(r)..=(center.row + r).min(size - 1) {
+        for col in center.col.saturating_sub(r)..=(center.col + r).min(size - 1) {
+            let delta_row = row as f32 - center.row as f32;
+            let delta_col = col as f32 - center.col as f32;
+            let distance = (delta_row * delta_row + delta_col * delta_col).sqrt();
+            // the center has no direction, it is never stretched away
+            let (d_row, d_col) = if distance > 0.0 { (delta_row / distance, delta_col / distance) } else { (0.0, 0.0) };
+            if distance <= radius * stretch(d_row, d_col) {
+                points.push(Coordinate { row, col });
+            }
+        }
+    }
+    points
+}
+
+/// Removes the points which are not allowed, the last point takes the place of each removed one
+#[inline(always)]
+pub fn retain_allowed(points: &mut Vec<Coordinate>, allowed: impl Fn(Coordinate) -> bool) {
+    let mut i = 0;
+    while i < points.len() {
+        if !allowed(points[i]) {
+            points.swap_remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Keeps only the points reached from the center moving between the points sharing a side, so that after the points
+/// on the tiles which are not allowed are removed the blob grows only over the terrain around its center.
+///
+/// All the points are removed if the center is not one of them.
+pub fn keep_connected_to_center(points: &mut Vec<Coordinate>, center: Coordinate) {
+    const ABSENT: u8 = 0;
+    const IN_BLOB: u8 = 1;
+    const REACHED: u8 = 2;
+
+    let (Some(min_row), Some(min_col)) = (points.iter().map(|p| p.row).min(), points.iter().map(|p| p.col).min()) else {
+        return;
+    };
+    let max_row = points.iter().map(|p| p.row).max().unwrap_or(min_row);
+    let max_col = points.iter().map(|p| p.col).max().unwrap_or(min_col);
+    let (height, width) = (max_row - min_row + 1, max_col - min_col + 1);
+    let index = |c: &Coordinate| (c.row - min_row) * width + c.col - min_col;
+
+    let mut state = vec![ABSENT; width * height];
+    for point in points.iter() {
+        state[index(point)] = IN_BLOB;
+    }
+    if center.row < min_row || center.row > max_row || center.col < min_col || center.col > max_col || state[index(&center)] != IN_BLOB {
+        points.clear();
+        return;
+    }
+
+    state[index(&center)] = REACHED;
+    let mut stack = vec![center];
+    while let Some(current) = stack.pop() {
+        let neighbours = [
+            (current.row > min_row).then(|| Coordinate {
+                row: current.row - 1,
+                col: current.col,
+            }),
+            (current.row < max_row).then(|| Coordinate {
+                row: current.row + 1,
+                col: current.col,
+            }),
+            (current.col > min_col).then(|| Coordinate {
+                row: current.row,
+                col: current.col - 1,
+            }),
+            (current.col < max_col).then(|| Coordinate {
+                row: current.row,
+                col: current.col + 1,
+            }),
+        ];
+        for next in neighbours.into_iter().flatten() {
+            if state[index(&next)] == IN_BLOB {
+                state[index(&next)] = REACHED;
+                stack.push(next);
+            }
+        }
+    }
+    points.retain(|point| state[index(point)] == REACHED);
+}
@@ -0,0 +1,84 @@
+use crate::utils::Coordinate;
+
+/// Connects two tiles with a line segment using Bresenham's algorithm.
+///
+/// A tile is added at each diagonal step, so that consecutive tiles of the line always share a side;
+/// both ends are included.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use exclusion_zone::placement::line::bresenham_line;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let line = bresenham_line(Coordinate::new(0, 0), Coordinate::new(2, 2));
+/// assert_eq!(line.first(), Some(&Coordinate::new(0, 0)));
+/// assert_eq!(line.last(), Some(&Coordinate::new(2, 2)));
+/// assert!(line.windows(2).all(|pair| pair[0].row().abs_diff(pair[1].row()) + pair[0].col().abs_diff(pair[1].col()) == 1));
+/// # }
+/// ```
+pub fn bresenham_line(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let mut line_segments: Vec<Coordinate> = Vec::new();
+
+    let mut x = start.col as isize;
+    let mut y = start.row as isize;
+
+    let dx = (end.col as isize - start.col as isize).abs();
+    let dy = -(end.row as isize - start.row as isize).abs();
+
+    let sx = if start.col < end.col { 1 } else { -1 };
+    let sy = if start.row < end.row { 1 } else { -1 };
+
+    let mut err = dx + dy;
+
+    loop {
+        let next_step = Coordinate {
+            row: y as usize,
+            col: x as usize,
+        };
+
+        // add step between diagonal
+        if let Some(step) = add_step_between_diagonal(&line_segments, next_step) {
+            line_segments.push(step);
+        }
+
+        line_segments.push(next_step);
+
+        if x == end.col as isize && y == end.row as isize {
+            break;
+        }
+
+        let e2 = 2 * err;
+        if e2 >= dy {
+            if x != end.col as isize {
+                err += dy;
+            }
+            x += sx;
+        }
+        if e2 <= dx {
+            if y != end.row as isize {
+                err += dx;
+            }
+            y += sy;
+        }
+    }
+
+    line_segments
+}
+
+#[inline(always)]
+fn add_step_between_diagonal(segments: &[Coordinate], next_step: Coordinate) -> Option<Coordinate> {
+    let last = segments.last()?;
+    let is_diagonal = next_step.col != last.col && next_step.row != last.row;
+
+    if is_diagonal {
+        Some(Coordinate {
+            row: last.row,
+            col: next_step.col,
+        })
+    } else {
+        None
+    }
+}
@@ -0,0 +1,16 @@
+//! The pure placement algorithms behind the generator, free of file IO, clocks and global random number generators.
+//!
+//! Each algorithm takes the random number generator it draws from, as `&mut impl Rng`, and a closure telling which
+//! tiles are allowed instead of the world, so that the same seed always gives the same result: they can be tested
+//! in isolation and reused outside of the generator, like in constrained environments or in custom stages.
+//!
+//! The generator always uses them, the module is public only with the opt-in `placement` feature.
+
+/// Contains the growth of the blobs, the noisy discs of trees and fires
+pub mod blob;
+/// Contains the connection of two tiles with a line of tiles sharing a side
+pub mod line;
+/// Contains the probability matrices of the garbage piles
+pub mod probability;
+/// Contains the random sampling of tiles with a min spacing
+pub mod sampling;
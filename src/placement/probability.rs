@@ -0,0 +1,51 @@
+/// Generates the probability matrix of a garbage pile: concentric square rings, the outer ring with a probability of
+/// `1 - probability_step * rings` and each inner ring `probability_step` more likely than the one around it.
+///
+/// The sizes 2 and 3 are increased by one, the other sizes are kept; the matrix is indexed from its top left corner
+/// and the central tile of the odd sizes, which is not part of any ring, has a probability of 0.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use exclusion_zone::placement::probability::probability_matrix;
+///
+/// let matrix = probability_matrix(5, 0.2);
+/// assert_eq!(matrix.len(), 5);
+/// // the outer ring is less likely than the inner one
+/// assert!(matrix[0][0] < matrix[1][1]);
+/// # }
+/// ```
+#[allow(clippy::needless_range_loop)]
+pub fn probability_matrix(mut size: usize, probability_step: f64) -> Vec<Vec<f64>> {
+    // some edgy checks
+    if size == 0 {
+        return vec![vec![]];
+    } else if size / 2 == 1 {
+        size += 1; //we want the size to be odd
+    }
+
+    // initialize the matrix and calculate the total number of rings
+    let mut matrix = vec![vec![0.0; size]; size];
+    let total_rings = size / 2; // total number of ring
+
+    // iterate over the ring
+    for ring in 0..total_rings {
+        // assign the probability for the ring
+        let prob = 1. - probability_step * ((total_rings - ring) as f64);
+
+        // iterate over the first row of the ring
+        for col_index in ring..(size - ring) {
+            matrix[ring][col_index] = prob;
+            matrix[size - 1 - ring][col_index] = prob;
+        }
+
+        // iterate over the first column of the ring
+        for row_index in ring..(size - ring) {
+            matrix[row_index][ring] = prob;
+            matrix[row_index][size - 1 - ring] = prob;
+        }
+    }
+    matrix
+}
@@ -0,0 +1,121 @@
+use rand::Rng;
+
+use crate::utils::Coordinate;
+
+/// Attempts per sampled tile before giving up on the min spacing, as in the usual Poisson-disk sampling
+pub const SPACING_ATTEMPTS: usize = 30;
+
+/// Grid of buckets as wide as the min spacing, so that checking a tile only looks at the points of the 9 buckets around it.
+///
+/// The spacing is the Chebyshev distance, two points on adjacent tiles, diagonals included, are 1 tile apart.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use exclusion_zone::placement::sampling::SpacingGrid;
+/// use exclusion_zone::generator::Coordinate;
+///
+/// let mut grid = SpacingGrid::new(100, 5);
+/// grid.insert(Coordinate::new(10, 10));
+/// assert!(!grid.accepts(Coordinate::new(14, 12)));
+/// assert!(grid.accepts(Coordinate::new(15, 10)));
+/// # }
+/// ```
+pub struct SpacingGrid {
+    spacing: usize,
+    buckets_per_side: usize,
+    buckets: Vec<Vec<Coordinate>>,
+}
+
+impl SpacingGrid {
+    /// Creates an empty grid for a world of the given side, a spacing of 0 is the same as 1
+    pub fn new(size: usize, spacing: usize) -> Self {
+        let spacing = spacing.max(1);
+        let buckets_per_side = size.div_ceil(spacing).max(1);
+        SpacingGrid {
+            spacing,
+            buckets_per_side,
+            buckets: vec![Vec::new(); buckets_per_side * buckets_per_side],
+        }
+    }
+
+    /// Returns the min spacing between the points of the grid
+    pub fn spacing(&self) -> usize {
+        self.spacing
+    }
+
+    /// Returns true if no point of the grid is nearer than the spacing to the given tile
+    #[inline(always)]
+    pub fn accepts(&self, c: Coordinate) -> bool {
+        let (row, col) = (c.row / self.spacing, c.col / self.spacing);
+        (row.saturating_sub(1)..=(row + 1).min(self.buckets_per_side - 1)).all(|r| {
+            (col.saturating_sub(1)..=(col + 1).min(self.buckets_per_side - 1)).all(|c2| {
+                self.buckets[r * self.buckets_per_side + c2]
+                    .iter()
+                    .all(|p| p.row.abs_diff(c.row).max(p.col.abs_diff(c.col)) >= self.spacing)
+            })
+        })
+    }
+
+    /// Adds the point to the grid, without checking its spacing
+    #[inline(always)]
+    pub fn insert(&mut self, c: Coordinate) {
+        let bucket = (c.row / self.spacing) * self.buckets_per_side + c.col / self.spacing;
+        self.buckets[bucket].push(c);
+    }
+}
+
+/// Draws `count` random tiles of a `size` x `size` world for which `allowed` returns true.
+///
+/// With a min spacing the tiles are at least that far from each other; when the world is too crowded to fit
+/// all of them, the drawing stops after `SPACING_ATTEMPTS` attempts per tile and returns fewer tiles.
+/// Without it the same tile may be drawn more than once, and the drawing only ends once `count` tiles are found:
+/// `allowed` must accept at least one tile.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "placement")]
+/// # {
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use exclusion_zone::placement::sampling::sample_tiles;
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// // 10 tiles of the left half, at least 3 tiles from each other
+/// let tiles = sample_tiles(&mut rng, 50, 10, Some(3), |c| c.col() < 25);
+/// assert!(tiles.iter().all(|c| c.col() < 25));
+/// assert_eq!(tiles, sample_tiles(&mut StdRng::seed_from_u64(42), 50, 10, Some(3), |c| c.col() < 25));
+/// # }
+/// ```
+pub fn sample_tiles(rng: &mut impl Rng, size: usize, mut count: usize, min_spacing: Option<usize>, mut allowed: impl FnMut(Coordinate) -> bool) -> Vec<Coordinate> {
+    let mut tiles = Vec::with_capacity(count);
+    let mut grid = min_spacing.map(|spacing| SpacingGrid::new(size, spacing));
+    let mut attempts = count.saturating_mul(SPACING_ATTEMPTS);
+
+    while count > 0 {
+        let c = Coordinate {
+            row: rng.gen_range(0..size),
+            col: rng.gen_range(0..size),
+        };
+
+        if let Some(grid) = grid.as_mut() {
+            if attempts == 0 {
+                break;
+            }
+            attempts -= 1;
+            if !allowed(c) || !grid.accepts(c) {
+                continue;
+            }
+            grid.insert(c);
+            count -= 1;
+            tiles.push(c);
+        } else if allowed(c) {
+            count -= 1;
+            tiles.push(c);
+        }
+    }
+    tiles
+}
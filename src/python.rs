@@ -0,0 +1,145 @@
+use std::sync::Mutex;
+
+use image::RgbImage;
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use robotics_lib::world::tile::TileType;
+use serde_json::Value;
+
+use crate::generator::preview::tile_color;
+use crate::generator::{TileMatrix, WorldGenerator};
+use crate::utils::content_quantity;
+
+// the size used when the settings do not define one
+const DEFAULT_SIZE: usize = 1000;
+
+// the last world returned by `generate`, drawn by `render_png`
+static LAST_WORLD: Mutex<Option<TileMatrix>> = Mutex::new(None);
+
+// tile type codes, content indexes and content quantities
+type WorldArrays<'py> = (Bound<'py, PyArray2<u8>>, Bound<'py, PyArray2<u8>>, Bound<'py, PyArray2<u32>>);
+
+/// Python module exposing the generator, build it with `maturin build --features python`.
+///
+/// ```python
+/// import exclusion_zone
+///
+/// tile_type, content, quantity = exclusion_zone.generate({"size": 500, "seed": 42})
+/// exclusion_zone.render_png("world.png")
+/// ```
+#[pymodule]
+#[pyo3(name = "exclusion_zone")]
+fn exclusion_zone_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate, m)?)?;
+    m.add_function(wrap_pyfunction!(render_png, m)?)?;
+    Ok(())
+}
+
+/// Generates a world and returns three `size` x `size` numpy arrays: the tile type code (`uint8`),
+/// the content index (`uint8`) and the content quantity (`uint32`, the upper bound of the range for Bank, Bin and Crate).
+///
+/// Tile type codes follow the declaration order of `TileType`: DeepWater 0, ShallowWater 1, Sand 2, Grass 3, Street 4,
+/// Hill 5, Mountain 6, Snow 7, Lava 8, Teleport 9, Wall 10. Content indexes are the ones given by `Content::index`.
+///
+/// `settings` overrides the default settings for the given `size` (1000 if missing), any field of `WorldGenerator`
+/// can be set with the same structure it has when serialized, nested dictionaries are merged with the defaults.
+/// `seed` is a shortcut for the seed of the noise settings. Invalid settings raise a `ValueError` listing the issues.
+#[pyfunction]
+#[pyo3(signature = (settings = None))]
+fn generate<'py>(py: Python<'py>, settings: Option<&Bound<'py, PyDict>>) -> PyResult<WorldArrays<'py>> {
+    let mut generator = settings_from_dict(py, settings)?;
+    let ((world, ..), _) = py
+        .detach(move || generator.try_gen_with_artifacts())
+        .map_err(|issues| PyValueError::new_err(issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>().join("\n")))?;
+
+    let size = world.len();
+    let mut tile_types = Vec::with_capacity(size * size);
+    let mut contents = Vec::with_capacity(size * size);
+    let mut quantities = Vec::with_capacity(size * size);
+    for tile in world.iter().flatten() {
+        tile_types.push(tile_type_code(&tile.tile_type));
+        contents.push(tile.content.index() as u8);
+        quantities.push(content_quantity(&tile.content) as u32);
+    }
+
+    *LAST_WORLD.lock().map_err(|e| PyRuntimeError::new_err(e.to_string()))? = Some(world);
+
+    Ok((to_array(py, size, tile_types)?, to_array(py, size, contents)?, to_array(py, size, quantities)?))
+}
+
+/// Saves the last generated world as a PNG image, each tile is drawn as a `tile_size` x `tile_size` square
+/// with the color of its content, or of its tile type if empty.
+#[pyfunction]
+#[pyo3(signature = (path, tile_size = 1))]
+fn render_png(path: &str, tile_size: usize) -> PyResult<()> {
+    if tile_size == 0 {
+        return Err(PyValueError::new_err("tile_size must be greater than 0"));
+    }
+
+    let last_world = LAST_WORLD.lock().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let world = last_world.as_ref().ok_or_else(|| PyRuntimeError::new_err("No world to render, call generate first"))?;
+
+    let side = (world.len() * tile_size) as u32;
+    let image = RgbImage::from_fn(side, side, |x, y| {
+        let tile = &world[y as usize / tile_size][x as usize / tile_size];
+        image::Rgb(tile_color(tile))
+    });
+    image.save(path).map_err(|e| PyIOError::new_err(format!("Unable to save {path}: {e}")))
+}
+
+// build the settings overriding the defaults with the values of the dictionary
+fn settings_from_dict(py: Python<'_>, settings: Option<&Bound<'_, PyDict>>) -> PyResult<WorldGenerator> {
+    let Some(settings) = settings else {
+        return Ok(WorldGenerator::default(DEFAULT_SIZE));
+    };
+
+    let json: String = py.import("json")?.call_method1("dumps", (settings,))?.extract()?;
+    let mut overrides: Value = serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    let size = overrides.get("size").and_then(Value::as_u64).map_or(DEFAULT_SIZE, |s| s as usize);
+    let mut base = serde_json::to_value(WorldGenerator::default(size)).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    if let Some(seed) = overrides.as_object_mut().and_then(|o| o.remove("seed")) {
+        base["noise_settings"]["seed"] = seed;
+    }
+    merge(&mut base, overrides);
+
+    serde_json::from_value(base).map_err(|e| PyValueError::new_err(format!("Invalid settings: {e}")))
+}
+
+// recursively replace the values of base with the ones of overrides, keeping the missing keys
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        | (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        | (base, overrides) => *base = overrides,
+    }
+}
+
+fn to_array<'py, T: numpy::Element>(py: Python<'py>, size: usize, values: Vec<T>) -> PyResult<Bound<'py, PyArray2<T>>> {
+    Array2::from_shape_vec((size, size), values)
+        .map(|a| a.into_pyarray(py))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+#[inline(always)]
+fn tile_type_code(tile_type: &TileType) -> u8 {
+    match tile_type {
+        | TileType::DeepWater => 0,
+        | TileType::ShallowWater => 1,
+        | TileType::Sand => 2,
+        | TileType::Grass => 3,
+        | TileType::Street => 4,
+        | TileType::Hill => 5,
+        | TileType::Mountain => 6,
+        | TileType::Snow => 7,
+        | TileType::Lava => 8,
+        | TileType::Teleport(_) => 9,
+        | TileType::Wall => 10,
+    }
+}
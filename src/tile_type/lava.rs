@@ -1,18 +1,49 @@
-use std::cmp::min;
+use std::cmp::{min, Ordering};
+use std::collections::{BinaryHeap, HashSet};
 use std::ops::Range;
 
 use rand::seq::SliceRandom;
-use robotics_lib::world::tile::TileType;
+use robotics_lib::world::tile::{Content, TileType};
 use serde::{Deserialize, Serialize};
 
+use crate::generator::reconcile::{reconcile_content, ContentRelocation};
+use crate::generator::validation::{Issues, SettingsIssue};
 use crate::generator::TileMatrix;
-use crate::utils::Coordinate;
+use crate::utils::{generation_rng, Coordinate};
+
+/// What the lava does when its flow reaches a water tile
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum WaterContact {
+    /// the lava flows on, turning the water into lava
+    Flow,
+    /// the flow stops at the water, which is left untouched
+    #[default]
+    Stop,
+    /// the flow stops and the water tile it reached cools the lava into Mountain, a barrier of rock raised by the steam
+    SteamBarrier,
+    /// the flow stops and the water tile it reached is tagged as steam, see `TileTags::STEAM`
+    SteamTag,
+}
+
+/// What the lava does when its flow reaches a local minimum, a tile with no lower neighbour
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum LavaBasin {
+    /// the flow climbs out of the basin to its lowest neighbour and goes on, until it comes back to a tile it already
+    /// covered; from there it would only go around the same tiles, so it stops
+    #[default]
+    Climb,
+    /// the flow stops at the bottom of the basin
+    Stop,
+    /// the rest of the flow fills the basin as a lava lake, covering the lowest tile around the lake at each step;
+    /// when that tile is lower than the lake the lava spills over it and flows on from there
+    Pool,
+}
 
 /// Settings defining the behavior of lava generation within the world.
 ///
-/// This struct represents the configuration for lava, including the number of spawn points
-/// and the range of lava flow.
-#[derive(Serialize, Deserialize, Clone)]
+/// This struct represents the configuration for lava, including the number of spawn points,
+/// the range of lava flow and how the lava interacts with the surrounding tiles.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct LavaSettings {
     /// The number of spawn points for lava within the world.
     pub number_of_spawn_points: usize,
@@ -21,6 +52,17 @@ pub struct LavaSettings {
     /// This range defines the minimum and maximum possible distance that lava can flow from
     /// its source point.
     pub lava_flow_range: Range<usize>,
+    /// Converts the tiles bordering the lava to Mountain, the cooled rock, leaving water and streets untouched.
+    pub cooled_border: bool,
+    /// Sets on fire the Grass tiles and the trees next to the lava, or next to the cooled rock if `cooled_border` is set.
+    pub ignite_neighbours: bool,
+    /// What the flow does when it reaches ShallowWater or DeepWater, by default it stops there.
+    pub water_contact: WaterContact,
+    /// What the flow does at the bottom of a basin, by default it climbs out of it.
+    ///
+    /// Each flow keeps track of the tiles it covered, so it never goes back and forth between the same tiles until its
+    /// range runs out.
+    pub basin: LavaBasin,
 }
 
 impl LavaSettings {
@@ -30,11 +72,15 @@ impl LavaSettings {
         LavaSettings {
             number_of_spawn_points: usize::pow(size,2)/ 500,
             lava_flow_range: 1..usize::pow(size,2) / 25,
+            cooled_border: false,
+            ignite_neighbours: false,
+            water_contact: WaterContact::default(),
+            basin: LavaBasin::default(),
         }
     }
 
     /// Creates a new instance of `LavaSettings` with the given number of spawn points
-    /// and lava flow range.
+    /// and lava flow range, without any interaction with the surrounding tiles.
     ///
     /// # Arguments
     ///
@@ -54,90 +100,295 @@ impl LavaSettings {
         LavaSettings {
             number_of_spawn_points: spawn_points,
             lava_flow_range: flow_range,
+            cooled_border: false,
+            ignite_neighbours: false,
+            water_contact: WaterContact::default(),
+            basin: LavaBasin::default(),
         }
     }
+
+    /// Sets what the flow does when it reaches a water tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::lava::{LavaSettings, WaterContact};
+    ///
+    /// // the lava flowing into the lakes leaves a shore of rock
+    /// let settings = LavaSettings::default(300).with_water_contact(WaterContact::SteamBarrier);
+    /// ```
+    pub fn with_water_contact(mut self, water_contact: WaterContact) -> Self {
+        self.water_contact = water_contact;
+        self
+    }
+
+    /// Sets what the flow does when it reaches the bottom of a basin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::lava::{LavaBasin, LavaSettings};
+    ///
+    /// // the lava gathers in lakes in the valleys
+    /// let settings = LavaSettings::default(300).with_basin(LavaBasin::Pool);
+    /// ```
+    pub fn with_basin(mut self, basin: LavaBasin) -> Self {
+        self.basin = basin;
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::lava::LavaSettings;
+    ///
+    /// // the flow range is reversed
+    /// assert!(!LavaSettings::new(5, 15..1).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.fits("LavaSettings::number_of_spawn_points", self.number_of_spawn_points, size);
+        // the flow shortens the end until it meets the start
+        let range = &self.lava_flow_range;
+        issues.check(range.start <= range.end, "LavaSettings::lava_flow_range", || format!("the start of {range:?} is after its end"));
+        issues.0
+    }
 }
 
-pub(crate) fn spawn_lava(world: &mut TileMatrix, elevation_map: &Vec<Vec<f64>>, lava_settings: LavaSettings) {
+/// Flows the lava from random Mountain tiles, returns the water tiles the flows stopped at which are tagged as steam
+pub(crate) fn spawn_lava(world: &mut TileMatrix, elevation_map: &Vec<Vec<f64>>, lava_settings: LavaSettings) -> Vec<Coordinate> {
     let possible_spawn_points = get_yx_mountain_tiles(world);
     let min = min(lava_settings.number_of_spawn_points, possible_spawn_points.len());
+    let mut steam = Vec::new();
+    let size = world.len();
     for i in 0..min {
         let spawn_coordinate = possible_spawn_points[i];
-        let range = lava_settings.lava_flow_range.clone();
-        flow_from(world, elevation_map, spawn_coordinate, range);
+        flow_lava(world, elevation_map, spawn_coordinate, lava_settings.lava_flow_range.len(), &lava_settings, &mut steam, |c| side_neighbours(c, size));
     }
+    steam
 }
 
-//for each x,y flow the lava to the lower neighbour
-/// fatina ricorsina
+/// Applies the contact rule if the tile the lava flows into is water, returns true if the flow stops there; the water
+/// tiles tagged as steam are added to `steam`
 #[inline(always)]
-fn flow_from(world: &mut TileMatrix, elevation_map: &Vec<Vec<f64>>, spawn_coordinate: Coordinate, remaining_range: Range<usize>) -> usize {
-    //debug_println!("flowing from {},{} with range {}..{}", x,y, remaining_range.start, remaining_range.end);
-    world[spawn_coordinate.row][spawn_coordinate.col].tile_type = TileType::Lava;
-    if remaining_range.start == remaining_range.end {
-        0
-    } else {
-        // if there is a neighbour with a lower height, flow to it
-        let lowest_neighbour = get_lowest_neighbour(elevation_map, spawn_coordinate);
-        flow_from(world, elevation_map, lowest_neighbour, remaining_range.start..remaining_range.end - 1)
-        // if elevation_map[lowest_neighbour_y][lowest_neighbour_x] < elevation_map[y][x] {
-        //     return flow_from(world, elevation_map, lowest_neighbour_y, lowest_neighbour_x, remaining_range.start..remaining_range.end - 1);
-        // }
-        // else {
-        //     return remaining_range.end - remaining_range.start;
-        // }
-    }
-}
-
-// return the coordinates of the lowest neighbour
+pub(crate) fn meets_water(world: &mut TileMatrix, c: Coordinate, water_contact: WaterContact, steam: &mut Vec<Coordinate>) -> bool {
+    let tile = &mut world[c.row][c.col];
+    if water_contact == WaterContact::Flow || !matches!(tile.tile_type, TileType::DeepWater | TileType::ShallowWater) {
+        return false;
+    }
+    match water_contact {
+        | WaterContact::SteamBarrier => {
+            tile.tile_type = TileType::Mountain;
+            tile.content = Content::None;
+        }
+        // the flows often end in the same pool
+        | WaterContact::SteamTag if !steam.contains(&c) => steam.push(c),
+        | _ => {}
+    }
+    true
+}
+
+/// Makes the lava interact with the surrounding tiles, once the content has been spawned.
+///
+/// The cooled border is the ring of tiles touching the lava, converted to Mountain, the content Mountain cannot hold is
+/// moved to a nearby tile or removed, as returned. The ignition sets on fire the Grass tiles and the trees touching the
+/// lava, or touching the cooled border when it is enabled.
+pub(crate) fn spawn_lava_boundary(world: &mut TileMatrix, lava_settings: &LavaSettings) -> Vec<ContentRelocation> {
+    let mut relocations = Vec::new();
+    if !lava_settings.cooled_border && !lava_settings.ignite_neighbours {
+        return relocations;
+    }
+
+    let mut heat_sources: Vec<Vec<bool>> = world.iter().map(|row| row.iter().map(|tile| tile.tile_type == TileType::Lava).collect()).collect();
+    let mut hot = tiles_next_to(world, &heat_sources);
+    if lava_settings.cooled_border {
+        for c in hot.iter() {
+            world[c.row][c.col].tile_type = TileType::Mountain;
+            heat_sources[c.row][c.col] = true;
+        }
+        relocations = reconcile_content(world, &hot);
+        // the heat goes past the cooled rock
+        hot = tiles_next_to(world, &heat_sources);
+    }
+
+    if lava_settings.ignite_neighbours {
+        for c in hot {
+            let tile = &mut world[c.row][c.col];
+            let flammable = tile.tile_type == TileType::Grass || matches!(tile.content, Content::Tree(_));
+            if flammable && tile.tile_type.properties().can_hold(&Content::Fire) {
+                tile.content = Content::Fire;
+            }
+        }
+    }
+    relocations
+}
+
+// the tiles sharing a side with a heat source, excluding the sources themselves, water, streets and walls
 #[inline(always)]
-fn get_lowest_neighbour(elevation_map: &Vec<Vec<f64>>, start: Coordinate) -> Coordinate {
-    let mut neighbour_heights = Vec::new();
-    if start.row != 0 {
-        neighbour_heights.push((
-            elevation_map[start.row - 1][start.col],
-            Coordinate {
-                row: start.row - 1,
-                col: start.col,
-            },
-        ));
-    }
-    if start.row != elevation_map.len() - 1 {
-        neighbour_heights.push((
-            elevation_map[start.row + 1][start.col],
-            Coordinate {
-                row: start.row + 1,
-                col: start.col,
-            },
-        ));
-    }
-    if start.col != 0 {
-        neighbour_heights.push((
-            elevation_map[start.row][start.col - 1],
-            Coordinate {
-                row: start.row,
-                col: start.col - 1,
-            },
-        ));
-    }
-    if start.col != elevation_map[0].len() - 1 {
-        neighbour_heights.push((
-            elevation_map[start.row][start.col + 1],
-            Coordinate {
-                row: start.row,
-                col: start.col + 1,
+fn tiles_next_to(world: &TileMatrix, heat_sources: &[Vec<bool>]) -> Vec<Coordinate> {
+    let size = world.len();
+    let mut tiles = Vec::new();
+    for (row, tiles_row) in world.iter().enumerate() {
+        for (col, tile) in tiles_row.iter().enumerate() {
+            let affected = !matches!(tile.tile_type, TileType::Lava | TileType::DeepWater | TileType::ShallowWater | TileType::Street | TileType::Wall | TileType::Teleport(_));
+            if !affected || heat_sources[row][col] {
+                continue;
+            }
+            let neighbours = [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)];
+            if neighbours.iter().any(|&(r, c)| r < size && c < size && heat_sources[r][c]) {
+                tiles.push(Coordinate { row, col });
+            }
+        }
+    }
+    tiles
+}
+
+/// Flows the lava from the start for the given number of steps, to the lowest of the neighbours given by `neighbours`
+/// at each step, returns the steps left unused.
+///
+/// The flow keeps track of the tiles it covered: going back to one of them, or reaching the bottom of a basin, ends it
+/// or pools the lava as set by `LavaSettings::basin`.
+pub(crate) fn flow_lava<I: Iterator<Item = Coordinate>>(
+    world: &mut TileMatrix,
+    elevation_map: &[Vec<f64>],
+    start: Coordinate,
+    mut steps: usize,
+    settings: &LavaSettings,
+    steam: &mut Vec<Coordinate>,
+    neighbours: impl Fn(Coordinate) -> I,
+) -> usize {
+    let mut flow = Flow {
+        elevation_map,
+        settings,
+        covered: HashSet::new(),
+        shore: BinaryHeap::new(),
+    };
+    let mut current = start;
+    loop {
+        if meets_water(world, current, settings.water_contact, steam) {
+            return steps;
+        }
+        flow.cover(world, current, &neighbours);
+        if steps == 0 {
+            return 0;
+        }
+
+        // the walls, like the cliffs framing the world, stop the lava
+        let Some(lowest) = neighbours(current).filter(|n| world[n.row][n.col].tile_type != TileType::Wall).min_by(|a, b| flow.height(*a).total_cmp(&flow.height(*b))) else {
+            return steps;
+        };
+        let bottom = flow.height(lowest) >= flow.height(current);
+        let covered = flow.covered.contains(&lowest);
+        current = match settings.basin {
+            | LavaBasin::Climb if covered => return steps,
+            | LavaBasin::Stop if bottom || covered => return steps,
+            | LavaBasin::Pool if bottom || covered => match flow.fill_basin(world, current, &mut steps, steam, &neighbours) {
+                | Some(spill) => spill,
+                | None => return steps,
             },
-        ));
+            | _ => {
+                steps -= 1;
+                lowest
+            }
+        };
+    }
+}
+
+// the tiles covered by a flow and, when it pools, the shore of the lava around them
+struct Flow<'a> {
+    elevation_map: &'a [Vec<f64>],
+    settings: &'a LavaSettings,
+    covered: HashSet<Coordinate>,
+    shore: BinaryHeap<Shore>,
+}
+
+impl Flow<'_> {
+    #[inline(always)]
+    fn height(&self, c: Coordinate) -> f64 {
+        self.elevation_map[c.row][c.col]
+    }
+
+    // turns the tile into lava
+    #[inline(always)]
+    fn cover<I: Iterator<Item = Coordinate>>(&mut self, world: &mut TileMatrix, c: Coordinate, neighbours: &impl Fn(Coordinate) -> I) {
+        world[c.row][c.col].tile_type = TileType::Lava;
+        // the lava buries the rubble of the ruined streets
+        world[c.row][c.col].content = Content::None;
+        self.covered.insert(c);
+        if self.settings.basin == LavaBasin::Pool {
+            let shore: Vec<Shore> = neighbours(c).filter(|n| !self.covered.contains(n) && world[n.row][n.col].tile_type != TileType::Wall).map(|n| Shore(self.height(n), n)).collect();
+            self.shore.extend(shore);
+        }
+    }
+
+    // fills the basin with lava, a step per tile, from the lowest tile of its shore up; returns the tile the lava spills
+    // over, lower than the lake, or None if the steps run out or the lava has no shore left
+    fn fill_basin<I: Iterator<Item = Coordinate>>(
+        &mut self,
+        world: &mut TileMatrix,
+        bottom: Coordinate,
+        steps: &mut usize,
+        steam: &mut Vec<Coordinate>,
+        neighbours: &impl Fn(Coordinate) -> I,
+    ) -> Option<Coordinate> {
+        let mut level = self.height(bottom);
+        while *steps > 0 {
+            let Shore(elevation, c) = self.shore.pop()?;
+            if self.covered.contains(&c) {
+                continue;
+            }
+            *steps -= 1;
+            if elevation < level {
+                return Some(c);
+            }
+            level = elevation;
+            // the water the lava stops at is not part of the lake
+            if meets_water(world, c, self.settings.water_contact, steam) {
+                continue;
+            }
+            self.cover(world, c, neighbours);
+        }
+        None
+    }
+}
+
+// a tile on the shore of the lava, the lowest one comes first out of the heap
+struct Shore(f64, Coordinate);
+
+impl PartialEq for Shore {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Shore {}
+
+impl PartialOrd for Shore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
-    // sort by height
-    neighbour_heights.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    // return coordinates of the lowest neighbour
-    neighbour_heights[0].1
+}
+
+impl Ord for Shore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+// the tiles sharing a side with the given one, in the world of the given size
+#[inline(always)]
+fn side_neighbours(c: Coordinate, size: usize) -> impl Iterator<Item = Coordinate> {
+    [(c.row.wrapping_sub(1), c.col), (c.row + 1, c.col), (c.row, c.col.wrapping_sub(1)), (c.row, c.col + 1)]
+        .into_iter()
+        .filter(move |&(row, col)| row < size && col < size)
+        .map(|(row, col)| Coordinate { row, col })
 }
 
 // return vector with the coordinates of the mountain tiles in range
 #[inline(always)]
-fn get_yx_mountain_tiles(wordl: &mut TileMatrix) -> Vec<Coordinate> {
+pub(crate) fn get_yx_mountain_tiles(wordl: &mut TileMatrix) -> Vec<Coordinate> {
     let mut tiles_in_range = Vec::new();
     for (y, row) in wordl.iter().enumerate() {
         for (x, tile) in row.iter().enumerate() {
@@ -146,6 +397,6 @@ fn get_yx_mountain_tiles(wordl: &mut TileMatrix) -> Vec<Coordinate> {
             }
         }
     }
-    tiles_in_range.as_mut_slice().shuffle(&mut rand::thread_rng());
+    tiles_in_range.as_mut_slice().shuffle(&mut generation_rng());
     tiles_in_range
 }
@@ -1,13 +1,305 @@
-use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 
+use rand::Rng;
+use robotics_lib::world::tile::{Content, TileType};
+use serde::{Deserialize, Serialize};
 use voronator::delaunator::Point;
 use voronator::VoronoiDiagram;
 
-use crate::utils::{slice_vec_2d, Coordinate, Slice};
+use crate::placement::line::bresenham_line;
+use crate::generator::occupancy::Occupancy;
+use crate::generator::validation::{Issues, SettingsIssue};
+use crate::generator::TileMatrix;
+use crate::utils::{generation_rng, slice_vec_2d, Coordinate, Slice};
 
-// TODO doc street
+/// Tiles at each end of a street, next to its junctions, never broken by a gap
+const GAP_MARGIN: usize = 3;
+/// Shortest and longest gap, in tiles, breaking a degraded street
+const GAP_LENGTH: (usize, usize) = (2, 5);
+/// Probability that a tile of a gap holds rubble
+const RUBBLE_PROBABILITY: f64 = 0.4;
+/// Cost of routing a street over a tile the robots cannot walk but a street can be laid on, like the deep water of a
+/// bridge, twice the cost of walking a Mountain
+const UNWALKABLE_COST: usize = 20;
+
+/// Settings defining the behavior of street generation.
+///
+/// Streets are the edges of the Voronoi diagram built over the local maxima of the elevation map,
+/// drawn over the terrain after its classification.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StreetSettings {
+    /// the number of slices per side in which the elevation map is divided to look for local maxima,
+    /// each local maximum is a potential street junction
+    pub n_slice_side: usize,
+    /// the elevation below which local maxima are ignored
+    pub lower_threshold: f64,
+    /// allow streets to cross deep water as bridges, otherwise they are re-routed around it
+    pub bridges: bool,
+    /// the side of the square of streets, 2 or 3 tiles, into which the junctions are widened, 0 disables the plazas
+    pub plaza_size: usize,
+    /// the dead-end stubs shorter than this number of tiles are removed, 0 keeps every stub
+    pub min_dead_end_length: usize,
+    /// the rows of trees planted along the major streets, `None` plants no tree along the streets
+    pub tree_lining: Option<LiningSettings>,
+    /// the probability, between 0 and 1, that a street between two junctions is broken by a gap of ruins, 0 keeps every street whole
+    pub degradation: f64,
+    /// the max number of street tiles, the least important streets are dropped to stay within it, `None` keeps them all
+    pub max_street_tiles: Option<usize>,
+    /// how the streets between two junctions are routed over the terrain
+    pub routing: StreetRouting,
+}
+
+/// The cost model routing the streets between two junctions
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreetRouting {
+    /// the straight line between the junctions, the edge of the Voronoi diagram, whatever the terrain it crosses
+    #[default]
+    Straight,
+    /// the path between the junctions a robot walks with the least energy, each tile costing the energy of walking
+    /// it, as given by the properties of its tile type: 0 for the Street, 1 for the Grass, 3 for the Sand, 5 for the
+    /// ShallowWater and 10 for the Mountain; the tiles of the streets routed before cost 0, so the streets share
+    /// their tiles where they can.
+    WalkCost,
+}
+
+/// Settings of the rows of trees planted along both sides of the major streets, like avenues
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LiningSettings {
+    /// the number of street tiles between two trees of the same row
+    pub spacing: usize,
+    /// the distance, in tiles, of the trees from the street
+    pub distance: usize,
+    /// the min length, in tiles, of a street between two junctions to be lined with trees
+    pub min_street_length: usize,
+}
+
+impl Default for LiningSettings {
+    /// Provides an instance of `LiningSettings` with a tree every 4 tiles next to the streets at least 30 tiles long
+    fn default() -> Self {
+        LiningSettings {
+            spacing: 4,
+            distance: 1,
+            min_street_length: 30,
+        }
+    }
+}
+
+impl LiningSettings {
+    /// Creates a new instance of `LiningSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `spacing` - The number of street tiles between two trees of the same row.
+    /// * `distance` - The distance, in tiles, of the trees from the street, 1 plants them next to it.
+    /// * `min_street_length` - The min length, in tiles, of a street between two junctions to be lined with trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::{LiningSettings, StreetSettings};
+    ///
+    /// // a tree every 3 tiles, two tiles away from the streets at least 50 tiles long
+    /// let settings = StreetSettings::default().with_tree_lining(LiningSettings::new(3, 2, 50));
+    /// ```
+    pub fn new(spacing: usize, distance: usize, min_street_length: usize) -> Self {
+        LiningSettings {
+            spacing,
+            distance,
+            min_street_length,
+        }
+    }
+}
+
+impl Default for StreetSettings {
+    /// Provides an instance of `StreetSettings` with the default parameters, bridges, plazas and dead-end trimming are disabled
+    fn default() -> Self {
+        StreetSettings {
+            n_slice_side: 10,
+            lower_threshold: 0.0,
+            bridges: false,
+            plaza_size: 0,
+            min_dead_end_length: 0,
+            tree_lining: None,
+            degradation: 0.0,
+            max_street_tiles: None,
+            routing: StreetRouting::Straight,
+        }
+    }
+}
+
+impl StreetSettings {
+    /// Creates a new instance of `StreetSettings` with the given parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_slice_side` - The number of slices per side used to look for local maxima.
+    /// * `lower_threshold` - The elevation below which local maxima are ignored.
+    /// * `bridges` - Allow streets to cross deep water.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::StreetSettings;
+    ///
+    /// let settings = StreetSettings::new(10, 0.0, true);
+    /// ```
+    pub fn new(n_slice_side: usize, lower_threshold: f64, bridges: bool) -> Self {
+        StreetSettings {
+            n_slice_side,
+            lower_threshold,
+            bridges,
+            plaza_size: 0,
+            min_dead_end_length: 0,
+            tree_lining: None,
+            degradation: 0.0,
+            max_street_tiles: None,
+            routing: StreetRouting::Straight,
+        }
+    }
+
+    /// Sets how the street network is cleaned up once drawn: the junctions are widened into plazas and the
+    /// short dead-end stubs left by the Voronoi edges are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `plaza_size` - The side of the plazas, 2 or 3 tiles, 0 disables them.
+    /// * `min_dead_end_length` - The length below which dead-end stubs are removed, 0 keeps them all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::StreetSettings;
+    ///
+    /// // 3x3 plazas at the junctions and no spur shorter than 5 tiles
+    /// let settings = StreetSettings::default().with_cleanup(3, 5);
+    /// ```
+    pub fn with_cleanup(mut self, plaza_size: usize, min_dead_end_length: usize) -> Self {
+        self.plaza_size = plaza_size;
+        self.min_dead_end_length = min_dead_end_length;
+        self
+    }
+
+    /// Plants rows of trees along both sides of the major streets, once the terrain is final and before the
+    /// content of the spawn order, so that the spawners place their content around them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::{LiningSettings, StreetSettings};
+    ///
+    /// let settings = StreetSettings::default().with_tree_lining(LiningSettings::default());
+    /// ```
+    pub fn with_tree_lining(mut self, lining: LiningSettings) -> Self {
+        self.tree_lining = Some(lining);
+        self
+    }
+
+    /// Breaks the streets with gaps of ruins, decades of abandonment turning a few tiles of a street back into the
+    /// grass or the sand it was paved over, with some rubble, a small Rock, on them.
+    ///
+    /// Each street between two junctions closing a loop is broken with the given probability, by a gap of 2 to 5 tiles
+    /// away from its ends, while the streets of a spanning tree of the network are kept, so the junctions stay
+    /// connected by streets; only the tiles paved over Grass or Sand are restored, which are walkable, so the robot can
+    /// still walk along the whole network, crossings and bridges are never broken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::StreetSettings;
+    ///
+    /// // a third of the streets closing a loop broken by ruins
+    /// let settings = StreetSettings::default().with_degradation(0.33);
+    /// ```
+    pub fn with_degradation(mut self, degradation: f64) -> Self {
+        self.degradation = degradation;
+        self
+    }
+
+    /// Limits the number of street tiles, since the number of streets grows with the number of slices and can
+    /// cover a large share of the big worlds.
+    ///
+    /// The streets between two junctions are ranked by importance, their length times their mean elevation, so the
+    /// long streets across the high ground, around the largest local maxima, are the most important. The least
+    /// important streets closing a loop are dropped first, then the least important dead ends, so the network stays
+    /// connected while it shrinks. The budget counts the tiles of the straight streets: the detours around the tiles
+    /// which cannot be paved and the plazas can add a few tiles more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::StreetSettings;
+    ///
+    /// // many junctions, but no more than 5000 tiles of streets
+    /// let settings = StreetSettings::new(40, 0.0, false).with_max_street_tiles(5000);
+    /// ```
+    pub fn with_max_street_tiles(mut self, max_street_tiles: usize) -> Self {
+        self.max_street_tiles = Some(max_street_tiles);
+        self
+    }
+
+    /// Sets the cost model routing the streets between two junctions, see `StreetRouting`.
+    ///
+    /// With `StreetRouting::WalkCost` each street follows the path a robot walks with the least energy between its
+    /// junctions, going around the mountains and the water rather than over them and joining the streets routed
+    /// before, so the robots travel between the junctions, the hubs of the network, spending the least energy. The
+    /// streets are routed over the terrain before any content, they are longer than the straight ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::{StreetRouting, StreetSettings};
+    ///
+    /// let settings = StreetSettings::default().with_routing(StreetRouting::WalkCost);
+    /// ```
+    pub fn with_routing(mut self, routing: StreetRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Checks the settings for the given world size, returning the issues found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exclusion_zone::tile_type::street::StreetSettings;
+    ///
+    /// assert!(StreetSettings::default().validate(100).is_empty());
+    /// assert!(!StreetSettings::new(0, 0.0, false).validate(100).is_empty());
+    /// ```
+    pub fn validate(&self, size: usize) -> Vec<SettingsIssue> {
+        let mut issues = Issues::default();
+        issues.check((1..=size).contains(&self.n_slice_side), "StreetSettings::n_slice_side", || {
+            format!("{} slices per side do not fit a world side of {size} tiles, use a value between 1 and {size}", self.n_slice_side)
+        });
+        issues.check(matches!(self.plaza_size, 0 | 2 | 3), "StreetSettings::plaza_size", || {
+            format!("plazas of {0}x{0} tiles are not supported, use 2, 3 or 0 to disable them", self.plaza_size)
+        });
+        issues.probability("StreetSettings::degradation", self.degradation);
+        issues.check(self.max_street_tiles != Some(0), "StreetSettings::max_street_tiles", || "a budget of 0 tiles leaves no street, disable the streets instead".to_string());
+        if let Some(lining) = &self.tree_lining {
+            issues.check(lining.spacing > 0, "LiningSettings::spacing", || "the trees of a row must be at least 1 tile apart".to_string());
+            issues.check(lining.distance > 0, "LiningSettings::distance", || {
+                "the trees cannot be planted on the street, use a distance of at least 1".to_string()
+            });
+            issues.check(lining.min_street_length > 1, "LiningSettings::min_street_length", || {
+                format!("{} tiles do not make a street with a direction, use a length of at least 2", lining.min_street_length)
+            });
+        }
+        issues.0
+    }
+
+    // whether a street can be drawn over the given tile type
+    #[inline(always)]
+    pub(crate) fn can_pave(&self, tile_type: TileType) -> bool {
+        match tile_type {
+            | TileType::DeepWater => self.bridges,
+            | TileType::Lava | TileType::Wall => false,
+            | _ => true,
+        }
+    }
+}
 
 #[derive(Debug, Eq, Clone)]
 struct Edge {
@@ -64,26 +356,525 @@ impl Ord for Edge {
     }
 }
 
-pub(crate) fn street_spawn(elevation_map: &[Vec<f64>], n_slice_side: usize, lower_threshold: f64) -> Vec<Vec<Coordinate>> {
+pub(crate) fn street_spawn(elevation_map: &[Vec<f64>], n_slice_side: usize, lower_threshold: f64, small_world: bool) -> Vec<Vec<Coordinate>> {
     // get local maxima
     let mut local_maxima: Vec<Coordinate> = get_local_maxima(elevation_map, n_slice_side, lower_threshold);
 
-    // combine near local maxima
-    let combined_local_maxima: Vec<Coordinate> = combine_local_maxima(elevation_map, &mut local_maxima, n_slice_side, elevation_map.len() / 100);
+    // combine near local maxima, the band around the slices is empty on a small world
+    let combined_local_maxima: Vec<Coordinate> = if small_world {
+        local_maxima
+    } else {
+        combine_local_maxima(elevation_map, &mut local_maxima, n_slice_side, elevation_map.len() / 100)
+    };
 
-    // get voronoi diagram
-    let diagram = get_voronoi_diagram(elevation_map, &combined_local_maxima);
+    // get voronoi diagram, none if the maxima are too few or aligned, as on a small world
+    let Some(diagram) = get_voronoi_diagram(elevation_map, &combined_local_maxima) else {
+        return Vec::new();
+    };
 
     // get unique edges extremes from diagram
     let unique_extremes: HashSet<Edge> = get_edges_extremes_from_diagram(diagram);
 
-    // fix edges extremes
-    let fixed_extremes = fix_extremes(unique_extremes, elevation_map.len() - 1);
+    // fix edges extremes, sorted so that the duplicates always keep the same direction
+    let mut fixed_extremes = fix_extremes(unique_extremes, elevation_map.len() - 1);
+    fixed_extremes.sort();
 
-    // remove duplicates
-    let unique_edges = remove_duplicates(fixed_extremes);
+    // remove duplicates, sorted so that the streets are always listed in the same order for the stages walking them
+    let mut unique_edges = remove_duplicates(fixed_extremes);
+    unique_edges.sort();
 
-    unique_edges.iter().map(|edge| connect_points(edge.start, edge.end)).collect()
+    unique_edges.iter().map(|edge| bresenham_line(edge.start, edge.end)).collect()
+}
+
+/// Drops the least important streets until the streets cover at most `max_tiles` tiles, see `with_max_street_tiles`.
+///
+/// The streets closing a loop go first: the most important streets linking all the junctions they can, like a
+/// maximum spanning tree, form the backbone of the network, which only loses its dead ends.
+pub(crate) fn prune_streets(polygons: Vec<Vec<Coordinate>>, elevation_map: &[Vec<f64>], max_tiles: usize) -> Vec<Vec<Coordinate>> {
+    // how many streets cover each tile, the junctions are shared
+    let mut covered: HashMap<Coordinate, usize> = HashMap::new();
+    for c in polygons.iter().flatten() {
+        *covered.entry(*c).or_insert(0) += 1;
+    }
+    if covered.len() <= max_tiles {
+        return polygons;
+    }
+
+    let (min, max) = elevation_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), &value| (min.min(value), max.max(value)));
+    let range = if max > min { max - min } else { 1.0 };
+    let importance: Vec<f64> = polygons
+        .iter()
+        .map(|polygon| {
+            let mean = polygon.iter().map(|c| (elevation_map[c.row][c.col] - min) / range).sum::<f64>() / polygon.len().max(1) as f64;
+            polygon.len() as f64 * (1.0 + mean)
+        })
+        .collect();
+
+    // the backbone, built with the most important streets first, each joining two parts of the network; the streets
+    // as important are ordered by their ends, so that the same network is always pruned in the same way
+    let mut order: Vec<usize> = (0..polygons.len()).collect();
+    order.sort_by(|a, b| importance[*b].total_cmp(&importance[*a]).then_with(|| (polygons[*a].first(), polygons[*a].last()).cmp(&(polygons[*b].first(), polygons[*b].last()))));
+    let backbone = spanning_streets(&polygons, &order);
+    // the number of backbone streets ending at each junction
+    let mut degree: HashMap<Coordinate, usize> = HashMap::new();
+    for polygon in polygons.iter().enumerate().filter(|(i, _)| backbone[*i]).map(|(_, polygon)| polygon) {
+        for end in [polygon[0], polygon[polygon.len() - 1]] {
+            *degree.entry(end).or_insert(0) += 1;
+        }
+    }
+
+    let mut kept = vec![true; polygons.len()];
+    while covered.len() > max_tiles {
+        let is_leaf = |i: usize| polygons[i].first().is_some_and(|c| degree[c] == 1) || polygons[i].last().is_some_and(|c| degree[c] == 1);
+        let loops = order.iter().rev().copied().find(|&i| kept[i] && !backbone[i]);
+        let Some(drop) = loops.or_else(|| order.iter().rev().copied().find(|&i| kept[i] && is_leaf(i))) else {
+            break;
+        };
+
+        kept[drop] = false;
+        for c in &polygons[drop] {
+            if let Some(count) = covered.get_mut(c) {
+                *count -= 1;
+                if *count == 0 {
+                    covered.remove(c);
+                }
+            }
+        }
+        if backbone[drop] {
+            for end in [polygons[drop][0], polygons[drop][polygons[drop].len() - 1]] {
+                degree.entry(end).and_modify(|degree| *degree -= 1);
+            }
+        }
+    }
+
+    polygons.into_iter().zip(kept).filter(|(_, kept)| *kept).map(|(polygon, _)| polygon).collect()
+}
+
+// the streets of a spanning tree of the network, true for the streets which, taken in the given order, join two parts
+// of the network, false for the ones closing a loop
+#[inline(always)]
+fn spanning_streets(polygons: &[Vec<Coordinate>], order: &[usize]) -> Vec<bool> {
+    let mut parts: HashMap<Coordinate, Coordinate> = HashMap::new();
+    let mut spanning = vec![false; polygons.len()];
+    for &i in order {
+        let (Some(start), Some(end)) = (polygons[i].first(), polygons[i].last()) else {
+            continue;
+        };
+        let (start, end) = (find_part(&mut parts, *start), find_part(&mut parts, *end));
+        if start != end {
+            parts.insert(start, end);
+            spanning[i] = true;
+        }
+    }
+    spanning
+}
+
+// the representative of the part of the network the junction belongs to
+#[inline(always)]
+fn find_part(parts: &mut HashMap<Coordinate, Coordinate>, c: Coordinate) -> Coordinate {
+    let mut root = c;
+    while let Some(&parent) = parts.get(&root) {
+        root = parent;
+    }
+    // the junctions on the way point straight to the representative, for the next searches
+    let mut current = c;
+    while let Some(parent) = parts.insert(current, root) {
+        if parent == root {
+            break;
+        }
+        current = parent;
+    }
+    if current == root {
+        parts.remove(&root);
+    }
+    root
+}
+
+/// Routes each street between its junctions with the least energy a robot spends walking the terrain, see
+/// `StreetRouting::WalkCost`; the streets are routed in order, each one reusing the tiles of the ones before for free.
+///
+/// The ends of a street on tiles that cannot be paved are trimmed like in `place_streets`, a street whose junctions
+/// cannot be joined keeps its straight line, which `place_streets` interrupts where it cannot be paved.
+pub(crate) fn route_streets(world: &TileMatrix, mut polygons: Vec<Vec<Coordinate>>, settings: &StreetSettings) -> Vec<Vec<Coordinate>> {
+    // sorted, so that the same world is always routed in the same way
+    polygons.sort_by_key(|polygon| (polygon.first().copied(), polygon.last().copied()));
+    let mut routed: HashSet<Coordinate> = HashSet::new();
+    polygons
+        .into_iter()
+        .map(|polygon| {
+            let first = polygon.iter().find(|c| settings.can_pave(world[c.row][c.col].tile_type));
+            let last = polygon.iter().rfind(|c| settings.can_pave(world[c.row][c.col].tile_type));
+            let path = match (first, last) {
+                | (Some(first), Some(last)) => find_cheapest_path(world, *first, *last, &routed, settings),
+                | _ => None,
+            };
+            match path {
+                | Some(path) => {
+                    routed.extend(path.iter().copied());
+                    path
+                }
+                | None => polygon,
+            }
+        })
+        .collect()
+}
+
+// the energy a robot spends walking onto the tile, None if no street can be laid on it
+#[inline(always)]
+fn walk_cost(world: &TileMatrix, c: Coordinate, routed: &HashSet<Coordinate>, settings: &StreetSettings) -> Option<usize> {
+    let tile_type = world[c.row][c.col].tile_type;
+    if !settings.can_pave(tile_type) {
+        None
+    } else if routed.contains(&c) {
+        Some(0)
+    } else if tile_type.properties().walk() {
+        Some(tile_type.properties().cost())
+    } else {
+        Some(UNWALKABLE_COST)
+    }
+}
+
+// dijkstra search of the cheapest 4-connected path of paveable tiles between two points, limited to a window around
+// them to keep the search local
+#[inline(always)]
+fn find_cheapest_path(world: &TileMatrix, from: Coordinate, to: Coordinate, routed: &HashSet<Coordinate>, settings: &StreetSettings) -> Option<Vec<Coordinate>> {
+    let margin = ((from.row.abs_diff(to.row) + from.col.abs_diff(to.col)) / 2).max(10);
+    let top = from.row.min(to.row).saturating_sub(margin);
+    let left = from.col.min(to.col).saturating_sub(margin);
+    let bottom = (from.row.max(to.row) + margin).min(world.len() - 1);
+    let right = (from.col.max(to.col) + margin).min(world.len() - 1);
+
+    let width = right - left + 1;
+    let height = bottom - top + 1;
+    let index = |c: Coordinate| (c.row - top) * width + (c.col - left);
+
+    let mut costs: Vec<usize> = vec![usize::MAX; width * height];
+    let mut parents: Vec<Option<Coordinate>> = vec![None; width * height];
+    // the ties are broken by the coordinates, so that the same world is always routed in the same way
+    let mut queue = BinaryHeap::new();
+    costs[index(from)] = 0;
+    queue.push(Reverse((0, from)));
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if current == to {
+            let mut path = vec![current];
+            let mut step = current;
+            while step != from {
+                step = parents[index(step)].unwrap();
+                path.push(step);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if cost > costs[index(current)] {
+            continue;
+        }
+
+        let neighbours = [
+            (current.row > top).then(|| Coordinate::new(current.row - 1, current.col)),
+            (current.row < bottom).then(|| Coordinate::new(current.row + 1, current.col)),
+            (current.col > left).then(|| Coordinate::new(current.row, current.col - 1)),
+            (current.col < right).then(|| Coordinate::new(current.row, current.col + 1)),
+        ];
+        for next in neighbours.into_iter().flatten() {
+            let Some(step) = walk_cost(world, next, routed, settings) else {
+                continue;
+            };
+            if cost + step < costs[index(next)] {
+                costs[index(next)] = cost + step;
+                parents[index(next)] = Some(current);
+                queue.push(Reverse((cost + step, next)));
+            }
+        }
+    }
+    None
+}
+
+/// Draws the streets over the terrain, skipping the tiles that cannot be paved.
+///
+/// When a street crosses a run of tiles that cannot be paved, the run is replaced with a detour
+/// around it, if no detour exists the street is interrupted there. Streets starting or ending on
+/// tiles that cannot be paved are shortened to the first and last paved tile.
+///
+/// Once the whole network is drawn, the dead-end stubs shorter than `min_dead_end_length` are removed,
+/// restoring the tiles they covered, then the junctions are widened into plazas of `plaza_size` tiles;
+/// last, the streets are broken by the gaps of ruins of `degradation`.
+///
+/// Returns the bridges, the tiles of the streets laid over water, sorted.
+pub(crate) fn place_streets(world: &mut TileMatrix, polygons: &[Vec<Coordinate>], settings: &StreetSettings) -> Vec<Coordinate> {
+    // the tile type of each paved tile before the streets, to restore the trimmed stubs
+    let mut paved: HashMap<Coordinate, TileType> = HashMap::new();
+    let mut pave = |world: &mut TileMatrix, c: Coordinate| {
+        paved.entry(c).or_insert(world[c.row][c.col].tile_type);
+        world[c.row][c.col].tile_type = TileType::Street;
+    };
+
+    for polygon in polygons {
+        // trim the parts of the street which start or end in tiles that cannot be paved
+        let first = polygon.iter().position(|c| settings.can_pave(world[c.row][c.col].tile_type));
+        let last = polygon.iter().rposition(|c| settings.can_pave(world[c.row][c.col].tile_type));
+        let (first, last) = match (first, last) {
+            | (Some(first), Some(last)) => (first, last),
+            | _ => continue,
+        };
+
+        let mut i = first;
+        while i <= last {
+            let c = polygon[i];
+            if settings.can_pave(world[c.row][c.col].tile_type) {
+                pave(world, c);
+                i += 1;
+                continue;
+            }
+
+            // find the end of the run of tiles that cannot be paved, `last` can always be paved
+            let mut end = i;
+            while !settings.can_pave(world[polygon[end].row][polygon[end].col].tile_type) {
+                end += 1;
+            }
+
+            if let Some(detour) = find_detour(world, polygon[i - 1], polygon[end], settings) {
+                for c in detour {
+                    pave(world, c);
+                }
+            }
+            i = end;
+        }
+    }
+
+    if settings.min_dead_end_length > 0 {
+        trim_dead_ends(world, &paved, settings.min_dead_end_length);
+    }
+    if settings.plaza_size > 1 {
+        build_plazas(world, settings);
+    }
+    if settings.degradation > 0.0 {
+        degrade_streets(world, polygons, &paved, settings.degradation);
+    }
+
+    let mut bridges: Vec<Coordinate> = paved
+        .into_iter()
+        .filter(|(c, tile_type)| matches!(tile_type, TileType::DeepWater | TileType::ShallowWater) && world[c.row][c.col].tile_type == TileType::Street)
+        .map(|(c, _)| c)
+        .collect();
+    bridges.sort();
+    bridges
+}
+
+/// Plants a Tree every `spacing` tiles on both sides of the streets at least `min_street_length` tiles long,
+/// `distance` tiles away from them across their direction; returns the number of trees planted.
+///
+/// The streets are the straight lines between two junctions returned by `street_spawn`, their tiles which are not
+/// streets anymore, like the ones covered by lava, are skipped. The trees are placed through the occupancy, so that
+/// the spawners running later see them, and only on empty tiles that are not streets and can hold them.
+pub(crate) fn line_streets(world: &mut TileMatrix, occupancy: &mut Occupancy, polygons: &[Vec<Coordinate>], settings: &LiningSettings) -> usize {
+    let size = world.len() as f64;
+    let mut planted = 0;
+
+    for polygon in polygons.iter().filter(|polygon| polygon.len() >= settings.min_street_length) {
+        let (first, last) = (polygon[0], polygon[polygon.len() - 1]);
+        let (d_row, d_col) = (last.row as f64 - first.row as f64, last.col as f64 - first.col as f64);
+        let length = d_row.hypot(d_col);
+        if length == 0.0 {
+            continue;
+        }
+        // the offset of the trees from the street, across its direction
+        let (offset_row, offset_col) = (-d_col / length * settings.distance as f64, d_row / length * settings.distance as f64);
+
+        for c in polygon.iter().step_by(settings.spacing) {
+            if world[c.row][c.col].tile_type != TileType::Street {
+                continue;
+            }
+            for side in [-1.0, 1.0] {
+                let (row, col) = ((c.row as f64 + side * offset_row).round(), (c.col as f64 + side * offset_col).round());
+                if row < 0.0 || col < 0.0 || row >= size || col >= size {
+                    continue;
+                }
+                let tree = Coordinate {
+                    row: row as usize,
+                    col: col as usize,
+                };
+                if world[tree.row][tree.col].tile_type != TileType::Street && occupancy.is_empty(tree) && occupancy.can_hold(tree, &Content::Tree(0)) && occupancy.place(world, tree, Content::Tree(0))
+                {
+                    planted += 1;
+                }
+            }
+        }
+    }
+    planted
+}
+
+// breaks each street closing a loop with the given probability by a gap, restoring the grass and the sand it was paved
+// over, leaving rubble on some of the restored tiles; the streets of a spanning tree are kept, so that the junctions
+// stay connected by streets
+#[inline(always)]
+fn degrade_streets(world: &mut TileMatrix, polygons: &[Vec<Coordinate>], paved: &HashMap<Coordinate, TileType>, degradation: f64) {
+    let spanning = spanning_streets(polygons, &(0..polygons.len()).collect::<Vec<_>>());
+    let mut rng = generation_rng();
+    for polygon in polygons.iter().zip(spanning).filter(|(_, spanning)| !spanning).map(|(polygon, _)| polygon) {
+        let length = rng.gen_range(GAP_LENGTH.0..=GAP_LENGTH.1);
+        if polygon.len() < length + 2 * GAP_MARGIN || !rng.gen_bool(degradation) {
+            continue;
+        }
+
+        let start = rng.gen_range(GAP_MARGIN..=polygon.len() - GAP_MARGIN - length);
+        for &c in &polygon[start..start + length] {
+            let Some(&original) = paved.get(&c).filter(|original| matches!(original, TileType::Grass | TileType::Sand)) else {
+                continue;
+            };
+            // the crossings with other streets are kept
+            if world[c.row][c.col].tile_type != TileType::Street || street_neighbours(world, c).len() > 2 {
+                continue;
+            }
+            world[c.row][c.col].tile_type = original;
+            if rng.gen_bool(RUBBLE_PROBABILITY) {
+                world[c.row][c.col].content = Content::Rock(rng.gen_range(1..=2));
+            }
+        }
+    }
+}
+
+// the street tiles sharing a side with the given one
+#[inline(always)]
+fn street_neighbours(world: &TileMatrix, c: Coordinate) -> Vec<Coordinate> {
+    let size = world.len();
+    [(c.row.wrapping_sub(1), c.col), (c.row + 1, c.col), (c.row, c.col.wrapping_sub(1)), (c.row, c.col + 1)]
+        .into_iter()
+        .filter(|&(row, col)| row < size && col < size && world[row][col].tile_type == TileType::Street)
+        .map(|(row, col)| Coordinate { row, col })
+        .collect()
+}
+
+// removes the stubs going from a dead end to a junction shorter than the given length, the isolated streets are kept
+#[inline(always)]
+fn trim_dead_ends(world: &mut TileMatrix, paved: &HashMap<Coordinate, TileType>, min_length: usize) {
+    // sorted, so that the same world is always trimmed in the same way
+    let mut dead_ends: Vec<Coordinate> = paved.keys().copied().filter(|c| street_neighbours(world, *c).len() == 1).collect();
+    dead_ends.sort();
+
+    for dead_end in dead_ends {
+        // a stub removed before may have changed the surroundings
+        if world[dead_end.row][dead_end.col].tile_type != TileType::Street || street_neighbours(world, dead_end).len() != 1 {
+            continue;
+        }
+
+        // walk from the dead end along the stub until the junction
+        let mut stub = vec![dead_end];
+        let mut previous: Option<Coordinate> = None;
+        let mut current = dead_end;
+        let reaches_junction = loop {
+            let Some(next) = street_neighbours(world, current).into_iter().find(|c| Some(*c) != previous) else {
+                break false;
+            };
+            let neighbours = street_neighbours(world, next).len();
+            if neighbours >= 3 {
+                break true;
+            }
+            if neighbours < 2 || stub.len() >= min_length {
+                break false;
+            }
+            stub.push(next);
+            previous = Some(current);
+            current = next;
+        };
+
+        if reaches_junction && stub.len() < min_length {
+            for c in stub {
+                world[c.row][c.col].tile_type = paved[&c];
+            }
+        }
+    }
+}
+
+// widens the junctions, the streets with at least three streets around, into squares of streets
+#[inline(always)]
+fn build_plazas(world: &mut TileMatrix, settings: &StreetSettings) {
+    let size = world.len();
+    let junctions: Vec<Coordinate> = (0..size)
+        .flat_map(|row| (0..size).map(move |col| Coordinate { row, col }))
+        .filter(|c| world[c.row][c.col].tile_type == TileType::Street && street_neighbours(world, *c).len() >= 3)
+        .collect();
+
+    // a plaza of 2 tiles grows toward the south east, a plaza of 3 tiles is centered on the junction
+    let before = (settings.plaza_size - 1) / 2;
+    for junction in junctions {
+        let rows = junction.row.saturating_sub(before)..(junction.row.saturating_sub(before) + settings.plaza_size).min(size);
+        let cols = junction.col.saturating_sub(before)..(junction.col.saturating_sub(before) + settings.plaza_size).min(size);
+        for row in rows {
+            for col in cols.clone() {
+                if settings.can_pave(world[row][col].tile_type) {
+                    world[row][col].tile_type = TileType::Street;
+                }
+            }
+        }
+    }
+}
+
+// breadth first search of the shortest 4-connected path of paveable tiles between two points,
+// limited to a window around them to keep the search local
+#[inline(always)]
+fn find_detour(world: &TileMatrix, from: Coordinate, to: Coordinate, settings: &StreetSettings) -> Option<Vec<Coordinate>> {
+    let margin = (from.row.abs_diff(to.row) + from.col.abs_diff(to.col)).max(10);
+    let top = from.row.min(to.row).saturating_sub(margin);
+    let left = from.col.min(to.col).saturating_sub(margin);
+    let bottom = (from.row.max(to.row) + margin).min(world.len() - 1);
+    let right = (from.col.max(to.col) + margin).min(world.len() - 1);
+
+    let width = right - left + 1;
+    let height = bottom - top + 1;
+    let index = |c: Coordinate| (c.row - top) * width + (c.col - left);
+
+    let mut parents: Vec<Option<Coordinate>> = vec![None; width * height];
+    let mut queue = VecDeque::new();
+    parents[index(from)] = Some(from);
+    queue.push_back(from);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![current];
+            let mut step = current;
+            while step != from {
+                step = parents[index(step)].unwrap();
+                path.push(step);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let mut neighbours = Vec::with_capacity(4);
+        if current.row > top {
+            neighbours.push(Coordinate {
+                row: current.row - 1,
+                col: current.col,
+            });
+        }
+        if current.row < bottom {
+            neighbours.push(Coordinate {
+                row: current.row + 1,
+                col: current.col,
+            });
+        }
+        if current.col > left {
+            neighbours.push(Coordinate {
+                row: current.row,
+                col: current.col - 1,
+            });
+        }
+        if current.col < right {
+            neighbours.push(Coordinate {
+                row: current.row,
+                col: current.col + 1,
+            });
+        }
+
+        for next in neighbours {
+            if parents[index(next)].is_none() && settings.can_pave(world[next.row][next.col].tile_type) {
+                parents[index(next)] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
 }
 
 #[inline(always)]
@@ -115,12 +906,12 @@ fn get_edges_extremes_from_diagram(diagram: VoronoiDiagram<Point>) -> HashSet<Ed
 }
 
 #[inline(always)]
-fn get_voronoi_diagram(elevation_map: &[Vec<f64>], centers: &[Coordinate]) -> VoronoiDiagram<Point> {
+fn get_voronoi_diagram(elevation_map: &[Vec<f64>], centers: &[Coordinate]) -> Option<VoronoiDiagram<Point>> {
     // convert centers to (f64,f64)
     let points: Vec<(f64, f64)> = centers.iter().map(|c| (c.col as f64, c.row as f64)).collect();
 
     // voronoi diagram
-    VoronoiDiagram::<Point>::from_tuple(&(0., 0.), &((elevation_map.len() - 1) as f64, (elevation_map.len() - 1) as f64), &points).unwrap()
+    VoronoiDiagram::<Point>::from_tuple(&(0., 0.), &((elevation_map.len() - 1) as f64, (elevation_map.len() - 1) as f64), &points)
 }
 
 #[inline(always)]
@@ -140,76 +931,6 @@ fn are_extremes_on_border(e1: Coordinate, e2: Coordinate, size: usize) -> bool {
     (e1.col == 0 && e2.col == 0) || (e1.col == size && e2.col == size) || (e1.row == 0 && e2.row == 0) || (e1.row == size && e2.row == size)
 }
 
-// Function to connect two points with a line segment using Bresenham's algorithm
-#[inline(always)]
-fn connect_points(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
-    let mut line_segments: Vec<Coordinate> = Vec::new();
-
-    let mut x = start.col as isize;
-    let mut y = start.row as isize;
-
-    let dx = (end.col as isize - start.col as isize).abs();
-    let dy = -(end.row as isize - start.row as isize).abs();
-
-    let sx = if start.col < end.col { 1 } else { -1 };
-    let sy = if start.row < end.row { 1 } else { -1 };
-
-    let mut err = dx + dy;
-
-    loop {
-        let next_step = Coordinate {
-            row: y as usize,
-            col: x as usize,
-        };
-
-        // add step between diagonal
-        if let Some(step) = add_step_between_diagonal(&line_segments, next_step) {
-            line_segments.push(step);
-        }
-
-        line_segments.push(next_step);
-
-        if x == end.col as isize && y == end.row as isize {
-            break;
-        }
-
-        let e2 = 2 * err;
-        if e2 >= dy {
-            if x != end.col as isize {
-                err += dy;
-            }
-            x += sx;
-        }
-        if e2 <= dx {
-            if y != end.row as isize {
-                err += dx;
-            }
-            y += sy;
-        }
-    }
-
-    line_segments
-}
-
-#[inline(always)]
-fn add_step_between_diagonal(segments: &[Coordinate], next_step: Coordinate) -> Option<Coordinate> {
-    if segments.is_empty() {
-        return None;
-    }
-
-    let last = segments.last().unwrap();
-    let is_diagonal = next_step.col != last.col && next_step.row != last.row;
-
-    if is_diagonal {
-        Some(Coordinate {
-            row: last.row,
-            col: next_step.col,
-        })
-    } else {
-        None
-    }
-}
-
 #[inline(always)]
 fn combine_local_maxima(elevation_map: &[Vec<f64>], all_local_maxima: &mut [Coordinate], n_slice_per_side: usize, band_width: usize) -> Vec<Coordinate> {
     let mut hs: HashSet<Coordinate> = HashSet::new();
@@ -346,7 +1067,7 @@ fn get_vertical_slice(map_len: usize, col: usize, qnt_per_slice: usize, band_wid
 }
 
 #[inline(always)]
-fn get_local_maxima(elevation_map: &[Vec<f64>], n_slice_side: usize, lower_threshold: f64) -> Vec<Coordinate> {
+pub(crate) fn get_local_maxima(elevation_map: &[Vec<f64>], n_slice_side: usize, lower_threshold: f64) -> Vec<Coordinate> {
     let mut local_maxima: Vec<Coordinate> = Vec::new();
     let mut found_local_maximum;
     let slices = slice_vec_2d(elevation_map, n_slice_side);
@@ -1,23 +1,60 @@
+use std::cell::RefCell;
+#[cfg(feature = "io")]
 use std::fs::File;
-use std::io::{self, Read};
+#[cfg(feature = "io")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "io")]
+use std::path::Path;
+
+#[cfg(feature = "io")]
+use chrono::Utc;
 
 use noise::Perlin;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::iter::IntoParallelIterator;
 use robotics_lib::world::tile::{Content, TileType};
 use serde::{Deserialize, Serialize};
-use zstd::stream::copy_encode;
+use tracing::warn;
+#[cfg(feature = "io")]
 use zstd::stream::read::Decoder;
-
-use crate::generator::TileMatrix;
+#[cfg(feature = "io")]
+use zstd::stream::write::Encoder;
+
+use crate::placement::sampling::sample_tiles;
+use crate::generator::occupancy::Occupancy;
+#[cfg(feature = "io")]
+use crate::generator::save::{tile_dump, ColumnarWorld, JsonWorld, SaveFormat, SaveOptions, SaveReport, COLUMNAR_MAGIC};
+use crate::generator::spawn_report::SkipReason;
+#[cfg(feature = "io")]
+use crate::generator::summary::SaveSummary;
+#[cfg(feature = "io")]
 use crate::generator::{GenResult, WorldGenerator};
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// A position in the tile matrix, `row` is the first index and `col` the second one
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct Coordinate {
     pub(crate) row: usize,
     pub(crate) col: usize,
 }
 
 impl Coordinate {
+    /// Creates a new `Coordinate` given its row and column
+    pub fn new(row: usize, col: usize) -> Self {
+        Coordinate { row, col }
+    }
+
+    /// Returns the row of the coordinate, the first index of the tile matrix
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the column of the coordinate, the second index of the tile matrix
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
     #[inline(always)]
     #[allow(dead_code)]
     pub(crate) fn is_neighbor(&self, other: &Coordinate) -> bool {
@@ -28,6 +65,86 @@ impl Coordinate {
     }
 }
 
+/// A position in the world, with explicit accessors for both conventions used around the tile matrix:
+/// `row` and `col` index it as `world[row][col]`, `x` is the column and `y` is the row.
+///
+/// The robot spawn point of a `GenResult` is a `(row, col)` tuple, the order used by robotics_lib to place the robot,
+/// convert it with `Position::from_spawn` and `Position::spawn` rather than destructuring it.
+///
+/// # Examples
+///
+/// ```
+/// use robotics_lib::world::world_generator::Generator;
+/// use exclusion_zone::generator::mini::MiniWorldGenerator;
+/// use exclusion_zone::generator::Position;
+///
+/// let (world, spawn, ..) = MiniWorldGenerator::new(20).gen();
+/// let spawn = Position::from_spawn(spawn);
+/// assert_eq!(spawn, Position::from_xy(spawn.x(), spawn.y()));
+/// assert!(world[spawn.row()][spawn.col()].tile_type.properties().walk());
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct Position(Coordinate);
+
+impl Position {
+    /// Creates a new `Position` given its row and column
+    pub fn from_row_col(row: usize, col: usize) -> Self {
+        Position(Coordinate { row, col })
+    }
+
+    /// Creates a new `Position` given its x, the column, and its y, the row
+    pub fn from_xy(x: usize, y: usize) -> Self {
+        Position(Coordinate { row: y, col: x })
+    }
+
+    /// Creates a new `Position` from the `(row, col)` robot spawn point of a `GenResult`
+    pub fn from_spawn(spawn: (usize, usize)) -> Self {
+        Position::from_row_col(spawn.0, spawn.1)
+    }
+
+    /// Returns the position as the `(row, col)` robot spawn point of a `GenResult`
+    pub fn spawn(&self) -> (usize, usize) {
+        (self.0.row, self.0.col)
+    }
+
+    /// Returns the row of the position, the first index of the tile matrix
+    pub fn row(&self) -> usize {
+        self.0.row
+    }
+
+    /// Returns the column of the position, the second index of the tile matrix
+    pub fn col(&self) -> usize {
+        self.0.col
+    }
+
+    /// Returns the x of the position, the column
+    pub fn x(&self) -> usize {
+        self.0.col
+    }
+
+    /// Returns the y of the position, the row
+    pub fn y(&self) -> usize {
+        self.0.row
+    }
+
+    /// Returns the position as a `Coordinate`
+    pub fn coordinate(&self) -> Coordinate {
+        self.0
+    }
+}
+
+impl From<Coordinate> for Position {
+    fn from(c: Coordinate) -> Self {
+        Position(c)
+    }
+}
+
+impl From<Position> for Coordinate {
+    fn from(p: Position) -> Self {
+        p.0
+    }
+}
+
 impl PartialOrd for Coordinate {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -147,45 +264,140 @@ pub(crate) fn map_value_to_range(value: f64, from: std::ops::Range<f64>, to: std
     (value - from_min) * (to_max - to_min) / (from_max - from_min) + to_min
 }
 
+thread_local! {
+    static GENERATION_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Handle to the random number generator used while generating a world.
+///
+/// Works like `ThreadRng`, but the generator behind it can be re-seeded with `seed_generation_rng`,
+/// so that the same settings always produce the same world, and it does not depend on the OS
+/// entropy source once seeded, which is not available on every target (e.g. wasm32-unknown-unknown).
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct GenerationRng;
+
+impl RngCore for GenerationRng {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        GENERATION_RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        GENERATION_RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        GENERATION_RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    #[inline(always)]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        GENERATION_RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
 #[inline(always)]
-pub(crate) fn spawn_content_randomly(world: &mut TileMatrix, mut number_of_spawn_points: usize, content: Content) -> Vec<Coordinate> {
-    let mut rng = rand::thread_rng();
+pub(crate) fn generation_rng() -> GenerationRng {
+    GenerationRng
+}
 
-    let mut spawn_points = Vec::with_capacity(number_of_spawn_points);
+// every random choice made by the current thread from now on depends only on the seed
+#[inline(always)]
+pub(crate) fn seed_generation_rng(seed: u64) {
+    GENERATION_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
 
-    while number_of_spawn_points > 0 {
-        let c = Coordinate {
-            row: rng.gen_range(0..world.len()),
-            col: rng.gen_range(0..world.len()),
-        };
+// iterate in parallel with rayon when the parallel feature is enabled, sequentially otherwise
+#[cfg(feature = "parallel")]
+#[inline(always)]
+pub(crate) fn into_maybe_par_iter<I: IntoParallelIterator>(iter: I) -> I::Iter {
+    iter.into_par_iter()
+}
 
-        if world[c.row][c.col].tile_type.properties().can_hold(&content){
-            number_of_spawn_points -= 1;
-            spawn_points.push(c);
-        } else {
-            //println!("filtered {:?} on {:?}",content ,world[c.row][c.col].tile_type)
-        }
+#[cfg(not(feature = "parallel"))]
+#[inline(always)]
+pub(crate) fn into_maybe_par_iter<I: IntoIterator>(iter: I) -> I::IntoIter {
+    iter.into_iter()
+}
+
+// max share of the free tiles which can hold a content that a single spawn stage may fill
+const MAX_CAPACITY_FILL: f64 = 0.5;
+
+/// Draws random tiles which can hold the content.
+///
+/// The number of spawn points is clamped to `MAX_CAPACITY_FILL` of the free tiles which can hold the content, so that
+/// the counts of the default settings of large worlds, or of worlds with few eligible tiles, do not flood the map
+/// or keep drawing forever; a warning reports the clamped counts.
+///
+/// With a min spacing the tiles are at least that far from each other; when the world is too crowded to fit
+/// all of them, the drawing stops after `SPACING_ATTEMPTS` attempts per spawn point and returns fewer tiles,
+/// see `sample_tiles`. The spawn points left out are recorded in the spawn report of the occupancy.
+#[inline(always)]
+pub(crate) fn spawn_content_randomly(occupancy: &mut Occupancy, mut number_of_spawn_points: usize, content: Content, min_spacing: Option<usize>) -> Vec<Coordinate> {
+    let capacity = occupancy.free_capacity(&content);
+    let max_spawn_points = (capacity as f64 * MAX_CAPACITY_FILL) as usize;
+    if number_of_spawn_points > max_spawn_points {
+        warn!(
+            content = ?content,
+            requested = number_of_spawn_points,
+            spawned = max_spawn_points,
+            capacity,
+            "SpawnPointsClamped: only the capacity of free tiles can hold the content"
+        );
+        occupancy.skip(SkipReason::CapacityClamped, number_of_spawn_points - max_spawn_points);
+        number_of_spawn_points = max_spawn_points;
+    }
+
+    let spawn_points = sample_tiles(&mut generation_rng(), occupancy.size(), number_of_spawn_points, min_spacing, |c| occupancy.can_hold(c, &content));
+    if let Some(spacing) = min_spacing.filter(|_| spawn_points.len() < number_of_spawn_points) {
+        warn!(
+            content = ?content,
+            skipped = number_of_spawn_points - spawn_points.len(),
+            min_spacing = spacing.max(1),
+            "MinSpacingNotSatisfied: not enough room for the min spacing"
+        );
+        occupancy.skip(SkipReason::MinSpacing, number_of_spawn_points - spawn_points.len());
     }
     spawn_points
 }
 
+// the quantity held by the content, the upper bound of the range for Bank, Bin and Crate
+#[inline(always)]
+pub(crate) fn content_quantity(content: &Content) -> usize {
+    match content.get_value() {
+        | (Some(value), _) => value,
+        | (_, Some(range)) => range.end,
+        | _ => 0,
+    }
+}
+
 #[inline(always)]
 pub(crate) fn get_random_seeded_noise() -> Perlin {
     // setting noise with random seed
-    let mut rng = rand::thread_rng();
+    let mut rng = generation_rng();
     Perlin::new(rng.gen())
 }
 
+#[cfg(feature = "io")]
 #[derive(Serialize, Deserialize)]
 pub(crate) struct SerializedWorld {
     pub(crate) world: GenResult,
     pub(crate) settings: WorldGenerator,
 }
 
+#[cfg(feature = "io")]
 impl SerializedWorld {
     #[inline(always)]
-    pub(crate) fn serialize(&self, file_path: &str, compression_level: i32) -> Result<(), String> {
-        let serialized = match bincode::serialize(self) {
+    pub(crate) fn serialize(&self, file_path: &str, options: &SaveOptions) -> Result<SaveReport, String> {
+        let start = Utc::now();
+        let serialized = match options.format {
+            | SaveFormat::Bincode => bincode::serialize(self),
+            | SaveFormat::Columnar => bincode::serialize(&ColumnarWorld::encode(self)).map(|columnar| [COLUMNAR_MAGIC.as_slice(), &columnar].concat()),
+            | SaveFormat::JsonPretty => return self.serialize_json(file_path),
+        };
+        let serialized = match serialized {
             | Ok(r) => r,
             | Err(e) => {
                 return Err(format!("{e}"));
@@ -199,25 +411,72 @@ impl SerializedWorld {
             }
         };
 
-        match copy_encode(&*serialized, &mut file, compression_level) {
-            | Ok(r) => r,
-            | Err(e) => {
-                return Err(format!("{e}"));
-            }
-        };
+        // the summary comes first, uncompressed, so that it can be read without decompressing the world
+        SaveSummary::new(&self.world, &self.settings).write_frame(&mut file)?;
 
-        Ok(())
+        // the world is split in blocks compressed by the worker threads, 1 thread compresses it on the calling thread
+        let threads = options.threads.max(1);
+        let mut encoder = Encoder::new(&mut file, options.compression_level).map_err(|e| format!("{e}"))?;
+        if threads > 1 {
+            encoder.multithread(threads as u32).map_err(|e| format!("{e}"))?;
+        }
+        encoder.write_all(&serialized).map_err(|e| format!("{e}"))?;
+        encoder.finish().map_err(|e| format!("{e}"))?;
+
+        let compressed_bytes = file.metadata().map_err(|e| format!("{e}"))?.len();
+        let elapsed_ms = (Utc::now() - start).num_milliseconds();
+        Ok(SaveReport {
+            uncompressed_bytes: serialized.len() as u64,
+            compressed_bytes,
+            threads,
+            elapsed_ms,
+            throughput: serialized.len() as f64 / 1_000_000.0 / (elapsed_ms.max(1) as f64 / 1000.0),
+        })
+    }
+
+    // the debug save, the world as indented JSON next to the dump of the tiles, both uncompressed
+    fn serialize_json(&self, file_path: &str) -> Result<SaveReport, String> {
+        let start = Utc::now();
+        let tiles_path = format!("{file_path}.tiles.txt");
+        let tiles_file = Path::new(&tiles_path).file_name().map_or_else(|| tiles_path.clone(), |name| name.to_string_lossy().into_owned());
+        let json = serde_json::to_string_pretty(&JsonWorld::new(self, tiles_file)).map_err(|e| format!("{e}"))?;
+        let tiles = tile_dump(&self.world.0);
+
+        std::fs::write(format!("{file_path}.json"), &json).map_err(|e| format!("{e}"))?;
+        std::fs::write(&tiles_path, &tiles).map_err(|e| format!("{e}"))?;
+
+        let bytes = (json.len() + tiles.len()) as u64;
+        let elapsed_ms = (Utc::now() - start).num_milliseconds();
+        Ok(SaveReport {
+            uncompressed_bytes: bytes,
+            compressed_bytes: bytes,
+            threads: 1,
+            elapsed_ms,
+            throughput: bytes as f64 / 1_000_000.0 / (elapsed_ms.max(1) as f64 / 1000.0),
+        })
     }
+
     #[inline(always)]
     pub(crate) fn deserialize(file_path: &str) -> io::Result<Self> {
-        let file = File::open(file_path)?;
-
-        let mut buffer = Vec::new();
-        let mut decoder = Decoder::new(file)?;
-        decoder.read_to_end(&mut buffer)?;
+        let buffer = read_compressed(file_path)?;
 
-        let deserialized: SerializedWorld = bincode::deserialize(&buffer).map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Deserialization failed: {}", e)))?;
+        let deserialized: Result<SerializedWorld, String> = match buffer.strip_prefix(COLUMNAR_MAGIC.as_slice()) {
+            | Some(columnar) => bincode::deserialize::<ColumnarWorld>(columnar).map_err(|e| format!("{e}")).and_then(ColumnarWorld::decode),
+            | None => bincode::deserialize(&buffer).map_err(|e| format!("{e}")),
+        };
 
-        Ok(deserialized)
+        deserialized.map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Deserialization failed: {}", e)))
     }
 }
+
+// the decompressed content of a save, the skippable frame of the summary is skipped by the decoder
+#[cfg(feature = "io")]
+#[inline(always)]
+pub(crate) fn read_compressed(file_path: &str) -> io::Result<Vec<u8>> {
+    let file = File::open(file_path)?;
+
+    let mut buffer = Vec::new();
+    let mut decoder = Decoder::new(file)?;
+    decoder.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}